@@ -0,0 +1,105 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use futures::executor::block_on;
+use simulacrum::SimulatorStore;
+use sui_json_rpc::error::Error;
+use sui_json_rpc::{get_balance_changes_from_effect, get_object_changes, ObjectProvider};
+use sui_json_rpc_types::{BalanceChange, ObjectChange};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::object::Object;
+use sui_types::transaction::InputObjectKind;
+
+/// The objects a transaction read and wrote, indexed by ID and version.
+///
+/// Object and balance changes are computed by looking up the input and output versions of every
+/// object touched by a transaction. These are gathered up front, so every lookup made while
+/// computing the changes is served from memory, and the futures doing so complete without ever
+/// suspending, which is why it is fine to block on them.
+pub(crate) struct TransactionObjects(BTreeMap<(ObjectID, SequenceNumber), Object>);
+
+impl TransactionObjects {
+    /// Gathers the objects touched by the transaction with `effects`. Output objects are read from
+    /// `written` if they are there (e.g. for transactions that were only dry run), and from
+    /// `store` otherwise.
+    pub(crate) fn new(
+        store: &dyn SimulatorStore,
+        effects: &TransactionEffects,
+        written: &BTreeMap<ObjectID, Object>,
+    ) -> Self {
+        let inputs = effects.modified_at_versions();
+        let outputs = effects
+            .all_changed_objects()
+            .into_iter()
+            .map(|((id, version, _), _, _)| (id, version));
+
+        let mut objects = BTreeMap::new();
+        for (id, version) in inputs.into_iter().chain(outputs) {
+            let object = written
+                .get(&id)
+                .filter(|o| o.version() == version)
+                .cloned()
+                .or_else(|| store.get_object_at_version(&id, version));
+
+            if let Some(object) = object {
+                objects.insert((id, version), object);
+            }
+        }
+
+        Self(objects)
+    }
+
+    pub(crate) fn object_changes(
+        &self,
+        sender: SuiAddress,
+        effects: &TransactionEffects,
+    ) -> Result<Vec<ObjectChange>, Error> {
+        block_on(get_object_changes(
+            self,
+            sender,
+            effects.modified_at_versions(),
+            effects.all_changed_objects(),
+            effects.all_removed_objects(),
+        ))
+    }
+
+    pub(crate) fn balance_changes(
+        &self,
+        effects: &TransactionEffects,
+        input_objects: Vec<InputObjectKind>,
+    ) -> Result<Vec<BalanceChange>, Error> {
+        block_on(get_balance_changes_from_effect(
+            self,
+            effects,
+            input_objects,
+            None,
+        ))
+    }
+}
+
+#[async_trait]
+impl ObjectProvider for TransactionObjects {
+    type Error = Error;
+
+    async fn get_object(&self, id: &ObjectID, version: &SequenceNumber) -> Result<Object, Error> {
+        self.0.get(&(*id, *version)).cloned().ok_or_else(|| {
+            Error::UnexpectedError(format!("Object {id} at version {version} was not found"))
+        })
+    }
+
+    async fn find_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Option<Object>, Error> {
+        Ok(self
+            .0
+            .range((*id, SequenceNumber::MIN)..=(*id, *version))
+            .next_back()
+            .map(|(_, object)| object.clone()))
+    }
+}
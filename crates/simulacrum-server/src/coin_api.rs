@@ -0,0 +1,246 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use move_core_types::language_storage::TypeTag;
+use simulacrum::SimulatorStore;
+use sui_json_rpc::coin_api::parse_to_type_tag;
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_api::{cap_page_limit, CoinReadApiOpenRpc, CoinReadApiServer};
+use sui_json_rpc_types::{Balance, Coin, CoinPage, SuiCoinMetadata};
+use sui_open_rpc::Module;
+use sui_types::balance::Supply;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+use crate::{unsupported, SharedSimulacrum};
+
+pub struct CoinReadApi<R, S> {
+    simulacrum: SharedSimulacrum<R, S>,
+}
+
+impl<R, S> CoinReadApi<R, S> {
+    pub fn new(simulacrum: SharedSimulacrum<R, S>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+/// All coins owned by `owner`, optionally restricted to `coin_type`, ordered by coin type and
+/// then by ID, which is the same order that a full node returns them in.
+fn owned_coins(
+    store: &dyn SimulatorStore,
+    owner: SuiAddress,
+    coin_type: Option<&TypeTag>,
+) -> Vec<(TypeTag, Coin)> {
+    let mut coins: Vec<_> = store
+        .owned_objects(owner)
+        .filter_map(|object| {
+            let type_ = object.coin_type_maybe()?;
+            if coin_type.is_some_and(|t| t != &type_) {
+                return None;
+            }
+
+            let (coin_object_id, version, digest) = object.compute_object_reference();
+            let coin = Coin {
+                coin_type: type_.to_string(),
+                coin_object_id,
+                version,
+                digest,
+                balance: object.get_coin_value_unsafe(),
+                previous_transaction: object.previous_transaction,
+            };
+            Some((type_, coin))
+        })
+        .collect();
+
+    coins.sort_by(|(_, a), (_, b)| {
+        (&a.coin_type, a.coin_object_id).cmp(&(&b.coin_type, b.coin_object_id))
+    });
+    coins
+}
+
+fn coin_page(
+    coins: Vec<(TypeTag, Coin)>,
+    cursor: Option<ObjectID>,
+    limit: Option<usize>,
+) -> CoinPage {
+    let limit = cap_page_limit(limit);
+    let start = cursor
+        .and_then(|cursor| {
+            coins
+                .iter()
+                .position(|(_, coin)| coin.coin_object_id == cursor)
+        })
+        .map_or(0, |position| position + 1);
+
+    let mut data: Vec<_> = coins
+        .into_iter()
+        .skip(start)
+        .take(limit + 1)
+        .map(|(_, coin)| coin)
+        .collect();
+
+    let has_next_page = data.len() > limit;
+    data.truncate(limit);
+    let next_cursor = data.last().map(|coin| coin.coin_object_id);
+
+    CoinPage {
+        data,
+        next_cursor,
+        has_next_page,
+    }
+}
+
+fn balance(coin_type: &TypeTag, coins: &[Coin]) -> Balance {
+    Balance {
+        coin_type: coin_type.to_string(),
+        coin_object_count: coins.len(),
+        total_balance: coins.iter().map(|coin| coin.balance as u128).sum(),
+        locked_balance: Default::default(),
+    }
+}
+
+#[async_trait]
+impl<R, S> CoinReadApiServer for CoinReadApi<R, S>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    async fn get_coins(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<CoinPage> {
+        let coin_type = parse_to_type_tag(coin_type)?;
+        let simulacrum = self.simulacrum.read().await;
+        let coins = owned_coins(simulacrum.store(), owner, Some(&coin_type));
+        Ok(coin_page(coins, cursor, limit))
+    }
+
+    async fn get_all_coins(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<CoinPage> {
+        let simulacrum = self.simulacrum.read().await;
+        let coins = owned_coins(simulacrum.store(), owner, None);
+        Ok(coin_page(coins, cursor, limit))
+    }
+
+    async fn get_balance(&self, owner: SuiAddress, coin_type: Option<String>) -> RpcResult<Balance> {
+        let coin_type = parse_to_type_tag(coin_type)?;
+        let simulacrum = self.simulacrum.read().await;
+        let coins: Vec<_> = owned_coins(simulacrum.store(), owner, Some(&coin_type))
+            .into_iter()
+            .map(|(_, coin)| coin)
+            .collect();
+        Ok(balance(&coin_type, &coins))
+    }
+
+    async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
+        let simulacrum = self.simulacrum.read().await;
+        let mut coins_by_type: BTreeMap<String, (TypeTag, Vec<Coin>)> = BTreeMap::new();
+        for (coin_type, coin) in owned_coins(simulacrum.store(), owner, None) {
+            coins_by_type
+                .entry(coin.coin_type.clone())
+                .or_insert_with(|| (coin_type, vec![]))
+                .1
+                .push(coin);
+        }
+
+        Ok(coins_by_type
+            .values()
+            .map(|(coin_type, coins)| balance(coin_type, coins))
+            .collect())
+    }
+
+    async fn get_coin_metadata(&self, _coin_type: String) -> RpcResult<Option<SuiCoinMetadata>> {
+        unsupported("suix_getCoinMetadata")
+    }
+
+    async fn get_total_supply(&self, _coin_type: String) -> RpcResult<Supply> {
+        unsupported("suix_getTotalSupply")
+    }
+}
+
+impl<R, S> SuiRpcModule for CoinReadApi<R, S>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        CoinReadApiOpenRpc::module_doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_types::base_types::{ObjectDigest, SequenceNumber, TransactionDigest};
+    use sui_types::gas_coin::GAS;
+
+    use super::*;
+
+    fn coins(count: usize) -> Vec<(TypeTag, Coin)> {
+        let mut coins: Vec<_> = (0..count)
+            .map(|_| {
+                let coin = Coin {
+                    coin_type: GAS::type_tag().to_string(),
+                    coin_object_id: ObjectID::random(),
+                    version: SequenceNumber::new(),
+                    digest: ObjectDigest::random(),
+                    balance: 1,
+                    previous_transaction: TransactionDigest::random(),
+                };
+                (GAS::type_tag(), coin)
+            })
+            .collect();
+        coins.sort_by_key(|(_, coin)| coin.coin_object_id);
+        coins
+    }
+
+    #[test]
+    fn paginate_coins() {
+        let coins = coins(5);
+        let ids: Vec<_> = coins.iter().map(|(_, c)| c.coin_object_id).collect();
+
+        let first = coin_page(coins.clone(), None, Some(2));
+        assert_eq!(
+            first.data.iter().map(|c| c.coin_object_id).collect::<Vec<_>>(),
+            ids[..2]
+        );
+        assert!(first.has_next_page);
+
+        let second = coin_page(coins.clone(), first.next_cursor, Some(2));
+        assert_eq!(
+            second.data.iter().map(|c| c.coin_object_id).collect::<Vec<_>>(),
+            ids[2..4]
+        );
+        assert!(second.has_next_page);
+
+        let last = coin_page(coins, second.next_cursor, Some(2));
+        assert_eq!(
+            last.data.iter().map(|c| c.coin_object_id).collect::<Vec<_>>(),
+            ids[4..]
+        );
+        assert!(!last.has_next_page);
+        assert_eq!(last.next_cursor, Some(ids[4]));
+    }
+
+    #[test]
+    fn balances() {
+        let coins: Vec<_> = coins(3).into_iter().map(|(_, coin)| coin).collect();
+        let balance = balance(&GAS::type_tag(), &coins);
+        assert_eq!(balance.coin_object_count, 3);
+        assert_eq!(balance.total_balance, 3);
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::SocketAddr;
+
+use axum::{
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use http::{Method, StatusCode};
+use simulacrum::SimulatorStore;
+use sui_faucet::{CoinInfo, FaucetError, FaucetRequest, FaucetResponse, FixedAmountRequest};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::object::Owner;
+use tower::ServiceBuilder;
+use tower_http::cors::{Any, CorsLayer};
+
+use crate::SharedSimulacrum;
+
+/// Amount of MIST handed out by the faucet per request.
+const FAUCET_AMOUNT: u64 = sui_faucet::DEFAULT_AMOUNT;
+
+/// Serve a faucet for `simulacrum` on `listen_address`, speaking the same protocol as the `/gas`
+/// endpoint of `sui-faucet`.
+///
+/// Every request is paid out of the simulacrum's first genesis account, and sealed into a
+/// checkpoint of its own.
+pub async fn start_faucet<R, S>(
+    simulacrum: SharedSimulacrum<R, S>,
+    listen_address: SocketAddr,
+) -> anyhow::Result<()>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    let cors = CorsLayer::new()
+        .allow_methods(vec![Method::GET, Method::POST])
+        .allow_headers(Any)
+        .allow_origin(Any);
+
+    let app = Router::new()
+        .route("/", get(health))
+        .route("/gas", post(faucet_request::<R, S>))
+        .layer(
+            ServiceBuilder::new()
+                .layer(cors)
+                .layer(Extension(simulacrum))
+                .into_inner(),
+        );
+
+    axum::Server::bind(&listen_address)
+        .serve(app.into_make_service())
+        .await?;
+
+    Ok(())
+}
+
+async fn health() -> &'static str {
+    "OK"
+}
+
+async fn faucet_request<R, S>(
+    Extension(simulacrum): Extension<SharedSimulacrum<R, S>>,
+    Json(payload): Json<FaucetRequest>,
+) -> impl IntoResponse
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    let FaucetRequest::FixedAmountRequest(FixedAmountRequest { recipient }) = payload else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(FaucetResponse::from(FaucetError::Internal(
+                "Input Error.".to_string(),
+            ))),
+        );
+    };
+
+    let mut simulacrum = simulacrum.write().await;
+    let effects = match simulacrum.request_gas(recipient, FAUCET_AMOUNT) {
+        Ok(effects) => effects,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(FaucetResponse::from(FaucetError::Internal(e.to_string()))),
+            )
+        }
+    };
+    simulacrum.create_checkpoint();
+
+    let transferred_gas_objects = effects
+        .created()
+        .into_iter()
+        .filter(|(_, owner)| *owner == Owner::AddressOwner(recipient))
+        .map(|((id, _, _), _)| CoinInfo {
+            amount: FAUCET_AMOUNT,
+            id,
+            transfer_tx_digest: *effects.transaction_digest(),
+        })
+        .collect();
+
+    (
+        StatusCode::CREATED,
+        Json(FaucetResponse {
+            transferred_gas_objects,
+            error: None,
+        }),
+    )
+}
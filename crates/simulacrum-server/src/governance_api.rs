@@ -0,0 +1,80 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use simulacrum::SimulatorStore;
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_api::{GovernanceReadApiOpenRpc, GovernanceReadApiServer};
+use sui_json_rpc_types::{DelegatedStake, SuiCommittee, ValidatorApys};
+use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::sui_serde::BigInt;
+use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+
+use crate::{unsupported, SharedSimulacrum};
+
+pub struct GovernanceReadApi<R, S> {
+    simulacrum: SharedSimulacrum<R, S>,
+}
+
+impl<R, S> GovernanceReadApi<R, S> {
+    pub fn new(simulacrum: SharedSimulacrum<R, S>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+#[async_trait]
+impl<R, S> GovernanceReadApiServer for GovernanceReadApi<R, S>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    async fn get_stakes_by_ids(
+        &self,
+        _staked_sui_ids: Vec<ObjectID>,
+    ) -> RpcResult<Vec<DelegatedStake>> {
+        unsupported("suix_getStakesByIds")
+    }
+
+    async fn get_stakes(&self, _owner: SuiAddress) -> RpcResult<Vec<DelegatedStake>> {
+        unsupported("suix_getStakes")
+    }
+
+    async fn get_committee_info(&self, _epoch: Option<BigInt<u64>>) -> RpcResult<SuiCommittee> {
+        unsupported("suix_getCommitteeInfo")
+    }
+
+    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary> {
+        let simulacrum = self.simulacrum.read().await;
+        Ok(simulacrum
+            .store()
+            .get_system_state()
+            .into_sui_system_state_summary())
+    }
+
+    async fn get_reference_gas_price(&self) -> RpcResult<BigInt<u64>> {
+        let simulacrum = self.simulacrum.read().await;
+        Ok(simulacrum.reference_gas_price().into())
+    }
+
+    async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
+        unsupported("suix_getValidatorsApy")
+    }
+}
+
+impl<R, S> SuiRpcModule for GovernanceReadApi<R, S>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        GovernanceReadApiOpenRpc::module_doc()
+    }
+}
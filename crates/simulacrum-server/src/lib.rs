@@ -0,0 +1,178 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A JSON-RPC front-end for a [`Simulacrum`].
+//!
+//! This serves the parts of the Sui full node JSON-RPC API that are needed to read objects,
+//! balances and checkpoints and to execute transactions, straight out of a `Simulacrum` instance,
+//! so that tools and SDKs which speak JSON-RPC can be pointed at a simulated (or forked) chain.
+//! Methods that cannot be answered from a `Simulacrum` return an "unsupported feature" error.
+//!
+//! Every executed transaction is immediately sealed into a checkpoint of its own, so a given
+//! sequence of transactions always produces the same sequence of checkpoints.
+//!
+//! [`Simulacrum`]: simulacrum::Simulacrum
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::ModuleId;
+use prometheus::Registry;
+use simulacrum::{Simulacrum, SimulatorStore};
+use sui_json_rpc::error::Error;
+use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
+use sui_json_rpc_types::{
+    SuiObjectData, SuiObjectDataOptions, SuiObjectResponse, SuiTransactionBlock,
+    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_types::base_types::ObjectID;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
+use sui_types::error::{SuiError, SuiObjectResponseError};
+use sui_types::object::Object;
+use sui_types::storage::get_module_by_id;
+use sui_types::transaction::{SenderSignedData, TransactionDataAPI};
+use tokio::sync::RwLock;
+
+use crate::changes::TransactionObjects;
+
+pub use coin_api::CoinReadApi;
+pub use faucet::start_faucet;
+pub use governance_api::GovernanceReadApi;
+pub use node_state::SharedNodeState;
+pub use read_api::ReadApi;
+pub use write_api::WriteApi;
+
+mod changes;
+mod coin_api;
+mod faucet;
+mod governance_api;
+mod node_state;
+mod read_api;
+mod write_api;
+
+/// A `Simulacrum` that is shared between the RPC server and whoever else is driving the chain.
+pub type SharedSimulacrum<R, S> = Arc<RwLock<Simulacrum<R, S>>>;
+
+/// Start serving JSON-RPC requests for `simulacrum` on `listen_address`.
+pub async fn start_rpc_server<R, S>(
+    simulacrum: SharedSimulacrum<R, S>,
+    listen_address: SocketAddr,
+) -> anyhow::Result<ServerHandle>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    let registry = Registry::new();
+    let mut builder = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), &registry);
+
+    builder.register_module(ReadApi::new(simulacrum.clone()))?;
+    builder.register_module(CoinReadApi::new(simulacrum.clone()))?;
+    builder.register_module(GovernanceReadApi::new(simulacrum.clone()))?;
+    builder.register_module(WriteApi::new(simulacrum))?;
+
+    Ok(builder.start(listen_address, None, None).await?)
+}
+
+pub(crate) fn unsupported<T>(method: &str) -> jsonrpsee::core::RpcResult<T> {
+    Err(Error::UnsupportedFeature(format!(
+        "{method} is not supported by the simulacrum RPC server"
+    ))
+    .into())
+}
+
+/// Resolves modules out of the packages in a [`SimulatorStore`].
+pub(crate) struct StoreModuleResolver<'a>(pub &'a dyn SimulatorStore);
+
+impl GetModule for StoreModuleResolver<'_> {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        get_module_by_id(self.0, id)
+    }
+}
+
+/// Builds the response for a transaction that has been executed, with the parts requested in
+/// `options`.
+pub(crate) fn transaction_block_response(
+    store: &dyn SimulatorStore,
+    transaction: &SenderSignedData,
+    effects: TransactionEffects,
+    events: TransactionEvents,
+    options: &SuiTransactionBlockResponseOptions,
+) -> Result<SuiTransactionBlockResponse, Error> {
+    let digest = *effects.transaction_digest();
+    let tx_data = transaction.transaction_data();
+    let mut response = SuiTransactionBlockResponse::new(digest);
+
+    if options.show_input {
+        response.transaction = Some(SuiTransactionBlock::try_from(
+            transaction.clone(),
+            &StoreModuleResolver(store),
+        )?);
+    }
+
+    if options.show_raw_input {
+        response.raw_transaction = bcs::to_bytes(transaction)?;
+    }
+
+    if options.show_events {
+        response.events = Some(SuiTransactionBlockEvents::try_from_using_module_resolver(
+            events,
+            digest,
+            None,
+            &StoreModuleResolver(store),
+        )?);
+    }
+
+    if options.show_object_changes || options.show_balance_changes {
+        let objects = TransactionObjects::new(store, &effects, &BTreeMap::new());
+        if options.show_object_changes {
+            response.object_changes = Some(objects.object_changes(tx_data.sender(), &effects)?);
+        }
+        if options.show_balance_changes {
+            response.balance_changes =
+                Some(objects.balance_changes(&effects, tx_data.input_objects()?)?);
+        }
+    }
+
+    if options.show_effects {
+        response.effects = Some(effects.try_into()?);
+    }
+
+    Ok(response)
+}
+
+pub(crate) fn object_response(
+    store: &dyn SimulatorStore,
+    object_id: ObjectID,
+    object: Option<Object>,
+    options: SuiObjectDataOptions,
+) -> anyhow::Result<SuiObjectResponse> {
+    let Some(object) = object else {
+        return Ok(SuiObjectResponse::new_with_error(
+            SuiObjectResponseError::NotExists { object_id },
+        ));
+    };
+
+    Ok(SuiObjectResponse::new_with_data(object_data(
+        store, object, options,
+    )?))
+}
+
+pub(crate) fn object_data(
+    store: &dyn SimulatorStore,
+    object: Object,
+    options: SuiObjectDataOptions,
+) -> anyhow::Result<SuiObjectData> {
+    let layout = if options.show_bcs || options.show_content {
+        object.get_layout(&StoreModuleResolver(store))?
+    } else {
+        None
+    };
+
+    SuiObjectData::try_from((object.compute_object_reference(), object, layout, options))
+}
@@ -0,0 +1,98 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use simulacrum::{Simulacrum, SimulatorStore};
+use sui_rest_api::node_state_getter::NodeStateGetter;
+use sui_types::base_types::{ObjectID, VersionNumber};
+use sui_types::digests::{TransactionDigest, TransactionEventsDigest};
+use sui_types::effects::{TransactionEffects, TransactionEvents};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages_checkpoint::{
+    CheckpointContents, CheckpointContentsDigest, CheckpointSequenceNumber, VerifiedCheckpoint,
+};
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+use sui_types::transaction::VerifiedTransaction;
+use tokio::sync::RwLockReadGuard;
+
+use crate::SharedSimulacrum;
+
+/// Exposes a [`SharedSimulacrum`] to the REST API, which is what the indexer behind the GraphQL
+/// service reads checkpoints from.
+pub struct SharedNodeState<R, S>(pub SharedSimulacrum<R, S>);
+
+impl<R, S> SharedNodeState<R, S>
+where
+    R: Send + Sync,
+    S: SimulatorStore + Send + Sync,
+{
+    /// `NodeStateGetter` is synchronous but called from within the runtime, so the lock has to be
+    /// acquired outside of it.
+    fn read(&self) -> RwLockReadGuard<'_, Simulacrum<R, S>> {
+        tokio::task::block_in_place(|| self.0.blocking_read())
+    }
+}
+
+impl<R, S> NodeStateGetter for SharedNodeState<R, S>
+where
+    R: Send + Sync,
+    S: SimulatorStore + Send + Sync,
+{
+    fn get_verified_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<VerifiedCheckpoint> {
+        NodeStateGetter::get_verified_checkpoint_by_sequence_number(&*self.read(), sequence_number)
+    }
+
+    fn get_latest_checkpoint_sequence_number(&self) -> SuiResult<CheckpointSequenceNumber> {
+        NodeStateGetter::get_latest_checkpoint_sequence_number(&*self.read())
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        content_digest: CheckpointContentsDigest,
+    ) -> SuiResult<CheckpointContents> {
+        NodeStateGetter::get_checkpoint_contents(&*self.read(), content_digest)
+    }
+
+    fn multi_get_transaction_blocks(
+        &self,
+        tx_digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<VerifiedTransaction>>> {
+        NodeStateGetter::multi_get_transaction_blocks(&*self.read(), tx_digests)
+    }
+
+    fn multi_get_executed_effects(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<TransactionEffects>>> {
+        NodeStateGetter::multi_get_executed_effects(&*self.read(), digests)
+    }
+
+    fn multi_get_events(
+        &self,
+        event_digests: &[TransactionEventsDigest],
+    ) -> SuiResult<Vec<Option<TransactionEvents>>> {
+        NodeStateGetter::multi_get_events(&*self.read(), event_digests)
+    }
+
+    fn multi_get_object_by_key(
+        &self,
+        object_keys: &[ObjectKey],
+    ) -> Result<Vec<Option<Object>>, SuiError> {
+        NodeStateGetter::multi_get_object_by_key(&*self.read(), object_keys)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, SuiError> {
+        NodeStateGetter::get_object_by_key(&*self.read(), object_id, version)
+    }
+
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        NodeStateGetter::get_object(&*self.read(), object_id)
+    }
+}
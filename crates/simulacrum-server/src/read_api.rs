@@ -0,0 +1,349 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use simulacrum::SimulatorStore;
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_api::{
+    validate_limit, ReadApiOpenRpc, ReadApiServer, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
+};
+use sui_json_rpc_types::{
+    Checkpoint, CheckpointId, CheckpointPage, ProtocolConfigResponse, SuiEvent,
+    SuiGetPastObjectRequest, SuiLoadedChildObjectsResponse, SuiObjectDataOptions,
+    SuiObjectResponse, SuiPastObjectResponse, SuiTransactionBlockEvents,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_open_rpc::Module;
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::digests::ChainIdentifier;
+use sui_types::error::{SuiError, UserInputError};
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint};
+use sui_types::sui_serde::BigInt;
+
+use crate::{
+    object_data, object_response, transaction_block_response, unsupported, SharedSimulacrum,
+    StoreModuleResolver,
+};
+
+pub struct ReadApi<R, S> {
+    simulacrum: SharedSimulacrum<R, S>,
+}
+
+impl<R, S> ReadApi<R, S> {
+    pub fn new(simulacrum: SharedSimulacrum<R, S>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+fn executed_transaction_block_response(
+    store: &dyn SimulatorStore,
+    digest: TransactionDigest,
+    options: &SuiTransactionBlockResponseOptions,
+) -> Result<SuiTransactionBlockResponse, Error> {
+    let transaction = store
+        .get_transaction(&digest)
+        .ok_or(SuiError::TransactionNotFound { digest })?;
+    let effects = store
+        .get_transaction_effects(&digest)
+        .ok_or(SuiError::TransactionNotFound { digest })?;
+    let events = store
+        .get_transaction_events_by_tx_digest(&digest)
+        .unwrap_or_default();
+
+    transaction_block_response(store, transaction.data(), effects, events, options)
+}
+
+fn checkpoint(store: &dyn SimulatorStore, summary: VerifiedCheckpoint) -> Result<Checkpoint, Error> {
+    let contents = store
+        .get_checkpoint_contents(&summary.content_digest)
+        .ok_or_else(|| {
+            Error::UnexpectedError(format!(
+                "Contents of checkpoint {} not found",
+                summary.sequence_number()
+            ))
+        })?;
+    let signature = summary.get_validator_signature();
+    let (_, summary) = summary.into_summary_and_sequence();
+    Ok(Checkpoint::from((summary, contents, signature)))
+}
+
+fn past_object_response(
+    store: &dyn SimulatorStore,
+    object_id: ObjectID,
+    version: SequenceNumber,
+    options: SuiObjectDataOptions,
+) -> Result<SuiPastObjectResponse, Error> {
+    if let Some(object) = store.get_object_at_version(&object_id, version) {
+        return Ok(SuiPastObjectResponse::VersionFound(object_data(
+            store, object, options,
+        )?));
+    }
+
+    Ok(match store.get_object(&object_id) {
+        None => SuiPastObjectResponse::ObjectNotExists(object_id),
+        Some(latest) if latest.version() < version => SuiPastObjectResponse::VersionTooHigh {
+            object_id,
+            asked_version: version,
+            latest_version: latest.version(),
+        },
+        Some(_) => SuiPastObjectResponse::VersionNotFound(object_id, version),
+    })
+}
+
+#[async_trait]
+impl<R, S> ReadApiServer for ReadApi<R, S>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    async fn get_transaction_block(
+        &self,
+        digest: TransactionDigest,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<SuiTransactionBlockResponse> {
+        let simulacrum = self.simulacrum.read().await;
+        Ok(executed_transaction_block_response(
+            simulacrum.store(),
+            digest,
+            &options.unwrap_or_default(),
+        )?)
+    }
+
+    async fn multi_get_transaction_blocks(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionBlockResponse>> {
+        let simulacrum = self.simulacrum.read().await;
+        let options = options.unwrap_or_default();
+        Ok(digests
+            .into_iter()
+            .map(|digest| executed_transaction_block_response(simulacrum.store(), digest, &options))
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiObjectResponse> {
+        let simulacrum = self.simulacrum.read().await;
+        let store = simulacrum.store();
+        Ok(object_response(
+            store,
+            object_id,
+            store.get_object(&object_id),
+            options.unwrap_or_default(),
+        )
+        .map_err(Error::from)?)
+    }
+
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectResponse>> {
+        let simulacrum = self.simulacrum.read().await;
+        let store = simulacrum.store();
+        let options = options.unwrap_or_default();
+        Ok(object_ids
+            .into_iter()
+            .map(|id| object_response(store, id, store.get_object(&id), options.clone()))
+            .collect::<Result<_, _>>()
+            .map_err(Error::from)?)
+    }
+
+    async fn try_get_past_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        let simulacrum = self.simulacrum.read().await;
+        Ok(past_object_response(
+            simulacrum.store(),
+            object_id,
+            version,
+            options.unwrap_or_default(),
+        )?)
+    }
+
+    async fn try_multi_get_past_objects(
+        &self,
+        past_objects: Vec<SuiGetPastObjectRequest>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiPastObjectResponse>> {
+        let simulacrum = self.simulacrum.read().await;
+        let options = options.unwrap_or_default();
+        Ok(past_objects
+            .into_iter()
+            .map(|request| {
+                past_object_response(
+                    simulacrum.store(),
+                    request.object_id,
+                    request.version,
+                    options.clone(),
+                )
+            })
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn get_loaded_child_objects(
+        &self,
+        _digest: TransactionDigest,
+    ) -> RpcResult<SuiLoadedChildObjectsResponse> {
+        unsupported("sui_getLoadedChildObjects")
+    }
+
+    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
+        let simulacrum = self.simulacrum.read().await;
+        let store = simulacrum.store();
+        let summary = match id {
+            CheckpointId::SequenceNumber(sequence_number) => store
+                .get_checkpoint_by_sequence_number(sequence_number)
+                .ok_or(UserInputError::VerifiedCheckpointNotFound(sequence_number)),
+            CheckpointId::Digest(digest) => store
+                .get_checkpoint_by_digest(&digest)
+                .ok_or_else(|| UserInputError::VerifiedCheckpointDigestNotFound(digest.to_string())),
+        }
+        .map_err(Error::from)?;
+        Ok(checkpoint(store, summary)?)
+    }
+
+    async fn get_checkpoints(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)
+            .map_err(|e| Error::from(SuiRpcInputError::from(e)))?;
+
+        let simulacrum = self.simulacrum.read().await;
+        let store = simulacrum.store();
+        let latest = *store
+            .get_highest_checkpint()
+            .expect("genesis checkpoint must exist")
+            .sequence_number();
+
+        let sequence_numbers: Vec<CheckpointSequenceNumber> = match (cursor, descending_order) {
+            (None, false) => (0..=latest).take(limit + 1).collect(),
+            (Some(cursor), false) => (*cursor + 1..=latest).take(limit + 1).collect(),
+            (None, true) => (0..=latest).rev().take(limit + 1).collect(),
+            (Some(cursor), true) => (0..(*cursor).min(latest + 1))
+                .rev()
+                .take(limit + 1)
+                .collect(),
+        };
+
+        let mut data = sequence_numbers
+            .into_iter()
+            .filter_map(|sequence_number| store.get_checkpoint_by_sequence_number(sequence_number))
+            .map(|summary| checkpoint(store, summary))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+        let next_cursor = if has_next_page {
+            data.last().map(|c| c.sequence_number.into())
+        } else {
+            None
+        };
+
+        Ok(CheckpointPage {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    async fn get_checkpoints_deprecated_limit(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<BigInt<u64>>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        self.get_checkpoints(cursor, limit.map(|l| *l as usize), descending_order)
+            .await
+    }
+
+    async fn get_events(&self, transaction_digest: TransactionDigest) -> RpcResult<Vec<SuiEvent>> {
+        let simulacrum = self.simulacrum.read().await;
+        let store = simulacrum.store();
+        let events = store
+            .get_transaction_events_by_tx_digest(&transaction_digest)
+            .unwrap_or_default();
+        Ok(SuiTransactionBlockEvents::try_from_using_module_resolver(
+            events,
+            transaction_digest,
+            None,
+            &StoreModuleResolver(store),
+        )
+        .map_err(Error::from)?
+        .data)
+    }
+
+    async fn get_total_transaction_blocks(&self) -> RpcResult<BigInt<u64>> {
+        let simulacrum = self.simulacrum.read().await;
+        let checkpoint = simulacrum
+            .store()
+            .get_highest_checkpint()
+            .expect("genesis checkpoint must exist");
+        Ok(checkpoint.network_total_transactions.into())
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<BigInt<u64>> {
+        let simulacrum = self.simulacrum.read().await;
+        let checkpoint = simulacrum
+            .store()
+            .get_highest_checkpint()
+            .expect("genesis checkpoint must exist");
+        Ok((*checkpoint.sequence_number()).into())
+    }
+
+    async fn get_protocol_config(
+        &self,
+        version: Option<BigInt<u64>>,
+    ) -> RpcResult<ProtocolConfigResponse> {
+        let simulacrum = self.simulacrum.read().await;
+        let config = match version {
+            Some(version) => {
+                ProtocolConfig::get_for_version_if_supported((*version).into(), Chain::Unknown)
+                    .ok_or(SuiRpcInputError::ProtocolVersionUnsupported(
+                        ProtocolVersion::MIN.as_u64(),
+                        ProtocolVersion::MAX.as_u64(),
+                    ))
+                    .map_err(Error::from)?
+            }
+            None => simulacrum.protocol_config().clone(),
+        };
+        Ok(config.into())
+    }
+
+    async fn get_chain_identifier(&self) -> RpcResult<String> {
+        let simulacrum = self.simulacrum.read().await;
+        let genesis = simulacrum
+            .store()
+            .get_checkpoint_by_sequence_number(0)
+            .expect("genesis checkpoint must exist");
+        Ok(ChainIdentifier::from(*genesis.digest()).to_string())
+    }
+}
+
+impl<R, S> SuiRpcModule for ReadApi<R, S>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        ReadApiOpenRpc::module_doc()
+    }
+}
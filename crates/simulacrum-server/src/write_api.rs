@@ -0,0 +1,141 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
+use fastcrypto::traits::ToFromBytes;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use simulacrum::SimulatorStore;
+use sui_json_rpc::error::Error;
+use sui_json_rpc::SuiRpcModule;
+use sui_json_rpc_api::{WriteApiOpenRpc, WriteApiServer};
+use sui_json_rpc_types::{
+    DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockData,
+    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_open_rpc::Module;
+use sui_types::base_types::SuiAddress;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::signature::GenericSignature;
+use sui_types::sui_serde::BigInt;
+use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
+
+use crate::changes::TransactionObjects;
+use crate::{transaction_block_response, unsupported, SharedSimulacrum, StoreModuleResolver};
+
+pub struct WriteApi<R, S> {
+    simulacrum: SharedSimulacrum<R, S>,
+}
+
+impl<R, S> WriteApi<R, S> {
+    pub fn new(simulacrum: SharedSimulacrum<R, S>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+#[async_trait]
+impl<R, S> WriteApiServer for WriteApi<R, S>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    /// Executes the transaction immediately and seals it into its own checkpoint, so the request
+    /// type is irrelevant: results are always visible to subsequent reads by the time this
+    /// returns.
+    async fn execute_transaction_block(
+        &self,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+        options: Option<SuiTransactionBlockResponseOptions>,
+        _request_type: Option<ExecuteTransactionRequestType>,
+    ) -> RpcResult<SuiTransactionBlockResponse> {
+        let options = options.unwrap_or_default();
+        let tx_data: TransactionData =
+            bcs::from_bytes(&tx_bytes.to_vec().map_err(Error::from)?).map_err(Error::from)?;
+
+        let mut sigs = Vec::with_capacity(signatures.len());
+        for sig in signatures {
+            sigs.push(
+                GenericSignature::from_bytes(&sig.to_vec().map_err(Error::from)?)
+                    .map_err(Error::from)?,
+            );
+        }
+        let transaction = Transaction::from_generic_sig_data(tx_data, sigs);
+
+        let mut simulacrum = self.simulacrum.write().await;
+        let (effects, _) = simulacrum
+            .execute_transaction(transaction.clone())
+            .map_err(Error::from)?;
+        let checkpoint = simulacrum.create_checkpoint();
+
+        let store = simulacrum.store();
+        let events = store
+            .get_transaction_events_by_tx_digest(effects.transaction_digest())
+            .unwrap_or_default();
+        let mut response =
+            transaction_block_response(store, transaction.data(), effects, events, &options)?;
+
+        response.timestamp_ms = Some(checkpoint.timestamp_ms);
+        response.confirmed_local_execution = Some(true);
+        response.checkpoint = Some(*checkpoint.sequence_number());
+        Ok(response)
+    }
+
+    async fn dev_inspect_transaction_block(
+        &self,
+        _sender_address: SuiAddress,
+        _tx_bytes: Base64,
+        _gas_price: Option<BigInt<u64>>,
+        _epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<DevInspectResults> {
+        unsupported("sui_devInspectTransactionBlock")
+    }
+
+    async fn dry_run_transaction_block(
+        &self,
+        tx_bytes: Base64,
+    ) -> RpcResult<DryRunTransactionBlockResponse> {
+        let tx_data: TransactionData =
+            bcs::from_bytes(&tx_bytes.to_vec().map_err(Error::from)?).map_err(Error::from)?;
+        let sender = tx_data.sender();
+        let input_objects = tx_data.input_objects().map_err(Error::from)?;
+
+        let simulacrum = self.simulacrum.read().await;
+        let store = simulacrum.store();
+        let (inner_temporary_store, effects, _) = simulacrum
+            .dry_run_transaction(tx_data.clone())
+            .map_err(Error::from)?;
+
+        let resolver = StoreModuleResolver(store);
+        let objects = TransactionObjects::new(store, &effects, &inner_temporary_store.written);
+        Ok(DryRunTransactionBlockResponse {
+            object_changes: objects.object_changes(sender, &effects)?,
+            balance_changes: objects.balance_changes(&effects, input_objects)?,
+            events: SuiTransactionBlockEvents::try_from_using_module_resolver(
+                inner_temporary_store.events,
+                *effects.transaction_digest(),
+                None,
+                &resolver,
+            )
+            .map_err(Error::from)?,
+            input: SuiTransactionBlockData::try_from(tx_data, &resolver).map_err(Error::from)?,
+            effects: effects.try_into().map_err(Error::from)?,
+        })
+    }
+}
+
+impl<R, S> SuiRpcModule for WriteApi<R, S>
+where
+    R: Send + Sync + 'static,
+    S: SimulatorStore + Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        WriteApiOpenRpc::module_doc()
+    }
+}
@@ -0,0 +1,295 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use rand::rngs::OsRng;
+use simulacrum::{InMemoryStore, Simulacrum};
+use simulacrum_server::{start_faucet, start_rpc_server, SharedSimulacrum};
+use sui_config::local_ip_utils::new_local_tcp_socket_for_testing;
+use sui_faucet::{FaucetRequest, FaucetResponse};
+use sui_json_rpc::ServerHandle;
+use sui_json_rpc_types::{
+    SuiExecutionStatus, SuiObjectDataOptions, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_types::base_types::SuiAddress;
+use sui_types::gas_coin::GAS;
+use sui_types::transaction::TransactionDataAPI;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+struct TestServer {
+    simulacrum: SharedSimulacrum<OsRng, InMemoryStore>,
+    client: SuiClient,
+    _handle: ServerHandle,
+}
+
+async fn start_server() -> TestServer {
+    let simulacrum = Arc::new(RwLock::new(Simulacrum::new()));
+    let address = new_local_tcp_socket_for_testing();
+    let handle = start_rpc_server(simulacrum.clone(), address).await.unwrap();
+    let client = SuiClientBuilder::default()
+        .build(format!("http://{address}"))
+        .await
+        .unwrap();
+
+    TestServer {
+        simulacrum,
+        client,
+        _handle: handle,
+    }
+}
+
+async fn request_gas(address: SocketAddr, recipient: SuiAddress) -> FaucetResponse {
+    let client = reqwest::Client::new();
+    let url = format!("http://{address}/gas");
+
+    // The faucet is started in the background, so it may not be listening yet.
+    for _ in 0..50 {
+        if let Ok(response) = client
+            .post(&url)
+            .json(&FaucetRequest::new_fixed_amount_request(recipient))
+            .send()
+            .await
+        {
+            return response.json().await.unwrap();
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("faucet did not start listening on {address}");
+}
+
+#[tokio::test]
+async fn test_execute_and_read() {
+    let server = start_server().await;
+    let recipient = SuiAddress::random_for_testing_only();
+    let (transaction, amount) = server.simulacrum.write().await.transfer_txn(recipient);
+    let sender = transaction.data().transaction_data().sender();
+    let latest = server
+        .client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await
+        .unwrap();
+
+    let response = server
+        .client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            transaction.clone(),
+            SuiTransactionBlockResponseOptions::full_content(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(&response.digest, transaction.digest());
+    assert_eq!(
+        response.effects.as_ref().unwrap().status(),
+        &SuiExecutionStatus::Success
+    );
+    assert_eq!(response.confirmed_local_execution, Some(true));
+    assert_eq!(response.checkpoint, Some(latest + 1));
+
+    // The transaction and its outputs are visible as soon as execution returns.
+    let read = server
+        .client
+        .read_api()
+        .get_transaction_with_options(
+            response.digest,
+            SuiTransactionBlockResponseOptions::new().with_effects(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(read.effects, response.effects);
+    assert_eq!(read.checkpoint, response.checkpoint);
+
+    let balance = server
+        .client
+        .coin_read_api()
+        .get_balance(recipient, None)
+        .await
+        .unwrap();
+    assert_eq!(balance.total_balance, amount as u128);
+
+    let created = response.effects.as_ref().unwrap().created();
+    assert_eq!(created.len(), 1);
+    let object = server
+        .client
+        .read_api()
+        .get_object_with_options(
+            created[0].object_id(),
+            SuiObjectDataOptions::new().with_owner().with_type(),
+        )
+        .await
+        .unwrap()
+        .into_object()
+        .unwrap();
+    assert_eq!(
+        object.owner.unwrap().get_owner_address().unwrap(),
+        recipient
+    );
+    assert!(object.type_.unwrap().is_gas_coin());
+
+    let gas = response
+        .balance_changes
+        .unwrap()
+        .into_iter()
+        .find(|change| change.owner.get_owner_address().unwrap() == sender)
+        .unwrap();
+    assert_eq!(gas.coin_type, GAS::type_tag());
+    assert!(gas.amount < -(amount as i128));
+}
+
+#[tokio::test]
+async fn test_dry_run_does_not_commit() {
+    let server = start_server().await;
+    let recipient = SuiAddress::random_for_testing_only();
+    let (transaction, amount) = server.simulacrum.write().await.transfer_txn(recipient);
+    let latest = server
+        .client
+        .read_api()
+        .get_latest_checkpoint_sequence_number()
+        .await
+        .unwrap();
+
+    let response = server
+        .client
+        .read_api()
+        .dry_run_transaction_block(transaction.data().transaction_data().clone())
+        .await
+        .unwrap();
+
+    assert_eq!(response.effects.status(), &SuiExecutionStatus::Success);
+    assert_eq!(response.effects.transaction_digest(), transaction.digest());
+    let received = response
+        .balance_changes
+        .iter()
+        .find(|change| change.owner.get_owner_address().unwrap() == recipient)
+        .unwrap();
+    assert_eq!(received.amount, amount as i128);
+
+    // Nothing was executed or checkpointed.
+    let balance = server
+        .client
+        .coin_read_api()
+        .get_balance(recipient, None)
+        .await
+        .unwrap();
+    assert_eq!(balance.total_balance, 0);
+    assert_eq!(
+        server
+            .client
+            .read_api()
+            .get_latest_checkpoint_sequence_number()
+            .await
+            .unwrap(),
+        latest
+    );
+}
+
+#[tokio::test]
+async fn test_get_checkpoints_pagination() {
+    let server = start_server().await;
+    for _ in 0..4 {
+        let (transaction, _) = server
+            .simulacrum
+            .write()
+            .await
+            .transfer_txn(SuiAddress::random_for_testing_only());
+        server
+            .client
+            .quorum_driver_api()
+            .execute_transaction_block(transaction, SuiTransactionBlockResponseOptions::new(), None)
+            .await
+            .unwrap();
+    }
+
+    // Genesis, plus one checkpoint per transaction.
+    let read_api = server.client.read_api();
+    let latest = read_api
+        .get_latest_checkpoint_sequence_number()
+        .await
+        .unwrap();
+    assert_eq!(latest, 4);
+
+    for descending in [false, true] {
+        let mut cursor = None;
+        let mut sequence_numbers = vec![];
+        loop {
+            let page = read_api
+                .get_checkpoints(cursor, Some(2), descending)
+                .await
+                .unwrap();
+            assert!(page.data.len() <= 2);
+            sequence_numbers.extend(page.data.iter().map(|c| c.sequence_number));
+            if !page.has_next_page {
+                assert_eq!(page.next_cursor, None);
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+
+        let mut expected: Vec<_> = (0..=latest).collect();
+        if descending {
+            expected.reverse();
+        }
+        assert_eq!(sequence_numbers, expected);
+    }
+}
+
+#[tokio::test]
+async fn test_faucet() {
+    let server = start_server().await;
+    let faucet_address = new_local_tcp_socket_for_testing();
+    tokio::spawn(start_faucet(server.simulacrum.clone(), faucet_address));
+
+    let recipient = SuiAddress::random_for_testing_only();
+    let response = request_gas(faucet_address, recipient).await;
+    assert_eq!(response.error, None);
+    assert!(!response.transferred_gas_objects.is_empty());
+
+    let coins = server
+        .client
+        .coin_read_api()
+        .get_coins(recipient, None, None, None)
+        .await
+        .unwrap();
+    let mut received: Vec<_> = coins
+        .data
+        .iter()
+        .map(|c| (c.coin_object_id, c.balance))
+        .collect();
+    let mut expected: Vec<_> = response
+        .transferred_gas_objects
+        .iter()
+        .map(|coin| (coin.id, coin.amount))
+        .collect();
+    received.sort();
+    expected.sort();
+    assert_eq!(received, expected);
+
+    // The faucet transaction was sealed into a checkpoint like any other.
+    let digest = response.transferred_gas_objects[0].transfer_tx_digest;
+    let transaction = server
+        .client
+        .read_api()
+        .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+        .await
+        .unwrap();
+    assert_eq!(
+        transaction.checkpoint,
+        Some(
+            server
+                .client
+                .read_api()
+                .get_latest_checkpoint_sequence_number()
+                .await
+                .unwrap()
+        )
+    );
+}
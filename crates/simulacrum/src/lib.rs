@@ -10,21 +10,24 @@
 //!
 //! [`Simulacrum`]: crate::Simulacrum
 
+use std::collections::BTreeMap;
 use std::num::NonZeroUsize;
 
 use anyhow::{anyhow, Result};
 use fastcrypto::traits::Signer;
+use move_core_types::language_storage::TypeTag;
 use rand::rngs::OsRng;
 use sui_config::{genesis, transaction_deny_config::TransactionDenyConfig};
-use sui_protocol_config::ProtocolVersion;
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
 use sui_swarm_config::genesis_config::AccountConfig;
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_swarm_config::network_config_builder::ConfigBuilder;
 use sui_types::base_types::{AuthorityName, ObjectID, VersionNumber};
+use sui_types::coin::Coin;
 use sui_types::crypto::AuthoritySignature;
-use sui_types::digests::ConsensusCommitDigest;
+use sui_types::digests::{ConsensusCommitDigest, TransactionDigest};
 use sui_types::error::SuiError;
-use sui_types::object::Object;
+use sui_types::object::{MoveObject, Object, Owner, OBJECT_START_VERSION};
 use sui_types::storage::ObjectStore;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemState;
 use sui_types::transaction::EndOfEpochTransactionKind;
@@ -41,6 +44,7 @@ use sui_types::{
 };
 
 use self::epoch_state::EpochState;
+pub use self::store::forked_store::{ForkSource, ForkedStore};
 pub use self::store::in_mem_store::InMemoryStore;
 use self::store::in_mem_store::KeyStore;
 pub use self::store::SimulatorStore;
@@ -134,6 +138,29 @@ where
     }
 }
 
+impl<R, F> Simulacrum<R, ForkedStore<F>>
+where
+    R: rand::RngCore + rand::CryptoRng,
+    F: ForkSource,
+{
+    /// Create a new Simulacrum whose state is forked from `source`.
+    ///
+    /// The chain starts from a fresh local genesis, so the committee, system objects and funded
+    /// accounts are all controlled locally, while any other object is lazily fetched from
+    /// `source` the first time it is read. `chain_start_timestamp_ms` should usually be the
+    /// timestamp of the fork point, so that the `Clock` seen by transactions agrees with the forked
+    /// state.
+    pub fn new_forked(mut rng: R, chain_start_timestamp_ms: u64, source: F) -> Self {
+        let config = ConfigBuilder::new_with_temp_dir()
+            .rng(&mut rng)
+            .with_chain_start_timestamp_ms(chain_start_timestamp_ms)
+            .deterministic_committee_size(NonZeroUsize::new(1).unwrap())
+            .build();
+        let store = ForkedStore::new(&config.genesis, source);
+        Self::new_with_network_config_store(&config, rng, store)
+    }
+}
+
 impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
     pub fn new_with_network_config_store(config: &NetworkConfig, rng: R, store: S) -> Self {
         let keystore = KeyStore::from_network_config(config);
@@ -169,7 +196,48 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         transaction: Transaction,
     ) -> anyhow::Result<(TransactionEffects, Option<ExecutionError>)> {
         let transaction = transaction.verify(&VerifyParams::default())?;
+        self.execute_verified_transaction(transaction)
+    }
 
+    /// Executes `transaction_data` as if it had been signed by its sender (and gas sponsor, if
+    /// any), without needing their keys.
+    ///
+    /// Apart from skipping signature verification, the transaction goes through the same checks as
+    /// [`Self::execute_transaction`], so the sender still needs to own its owned object inputs.
+    pub fn execute_transaction_impersonating(
+        &mut self,
+        transaction_data: TransactionData,
+    ) -> anyhow::Result<(TransactionEffects, Option<ExecutionError>)> {
+        let transaction = VerifiedTransaction::new_unchecked(
+            Transaction::from_generic_sig_data(transaction_data, vec![]),
+        );
+        self.execute_verified_transaction(transaction)
+    }
+
+    /// Executes `transaction_data` against the current state without committing anything, so that
+    /// its effects can be inspected. As with [`Self::execute_transaction_impersonating`], the
+    /// transaction does not need to be signed.
+    pub fn dry_run_transaction(
+        &self,
+        transaction_data: TransactionData,
+    ) -> anyhow::Result<(
+        InnerTemporaryStore,
+        TransactionEffects,
+        Option<ExecutionError>,
+    )> {
+        let transaction = VerifiedTransaction::new_unchecked(
+            Transaction::from_generic_sig_data(transaction_data, vec![]),
+        );
+        let (inner_temporary_store, effects, execution_error_opt) = self
+            .epoch_state
+            .execute_transaction(&self.store, &self.deny_config, &transaction)?;
+        Ok((inner_temporary_store, effects, execution_error_opt.err()))
+    }
+
+    fn execute_verified_transaction(
+        &mut self,
+        transaction: VerifiedTransaction,
+    ) -> anyhow::Result<(TransactionEffects, Option<ExecutionError>)> {
         let (inner_temporary_store, effects, execution_error_opt) = self
             .epoch_state
             .execute_transaction(&self.store, &self.deny_config, &transaction)?;
@@ -304,6 +372,11 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
         &mut self.rng
     }
 
+    /// Return the protocol config for the current epoch
+    pub fn protocol_config(&self) -> &ProtocolConfig {
+        self.epoch_state.protocol_config()
+    }
+
     /// Return the reference gas price for the current epoch
     pub fn reference_gas_price(&self) -> u64 {
         self.epoch_state.reference_gas_price()
@@ -360,6 +433,86 @@ impl<R, S: store::SimulatorStore> Simulacrum<R, S> {
 
         self.execute_transaction(tx).map(|x| x.0)
     }
+
+    /// Writes `object` directly into the store, replacing any existing object with the same ID.
+    ///
+    /// No transaction is executed, so it is up to the caller to keep the rest of the chain's state
+    /// consistent with the object. When replacing an object, the new object's version should be
+    /// higher than the old one's, as it would be if it had been written by a transaction.
+    pub fn set_object(&mut self, object: Object) {
+        self.store
+            .update_objects(BTreeMap::from([(object.id(), object)]), vec![]);
+    }
+
+    /// Removes the object with `id` from the live object set, returning it if it existed.
+    ///
+    /// Like [`Self::set_object`], no transaction is executed. Previous versions of the object are
+    /// still readable.
+    pub fn remove_object(&mut self, id: &ObjectID) -> Option<Object> {
+        let object = store::SimulatorStore::get_object(&self.store, id)?;
+        let (id, version, digest) = object.compute_object_reference();
+
+        // Write the object before deleting it, so that stores which fall back to another source
+        // for objects they have never seen (like `ForkedStore`) know that it has been removed.
+        self.store
+            .update_objects(BTreeMap::from([(id, object.clone())]), vec![]);
+        self.store
+            .update_objects(BTreeMap::new(), vec![(id, version, digest)]);
+        Some(object)
+    }
+
+    /// Creates a `Coin<coin_type>` worth `amount`, owned by `owner`, returning its ID.
+    ///
+    /// The coin is written directly into the store, so the coin type's total supply (as recorded
+    /// by its `TreasuryCap`) is not updated.
+    pub fn mint_coin(&mut self, owner: SuiAddress, coin_type: TypeTag, amount: u64) -> ObjectID
+    where
+        R: rand::RngCore + rand::CryptoRng,
+    {
+        let id = ObjectID::random_from_rng(&mut self.rng);
+        let coin = MoveObject::new_coin(
+            Coin::type_(coin_type).into(),
+            OBJECT_START_VERSION,
+            id,
+            amount,
+        );
+        self.set_object(Object::new_move(
+            coin,
+            Owner::AddressOwner(owner),
+            TransactionDigest::genesis_marker(),
+        ));
+        id
+    }
+}
+
+/// The state of a [`Simulacrum`] at a point in time, see [`Simulacrum::snapshot`].
+pub struct Snapshot<S> {
+    store: S,
+    checkpoint_builder: MockCheckpointBuilder,
+    epoch_state: EpochState,
+}
+
+impl<R, S: store::SimulatorStore + Clone> Simulacrum<R, S> {
+    /// Takes a snapshot of the chain's state, which can later be restored with
+    /// [`Self::revert_to`].
+    ///
+    /// The snapshot includes the store, the pending (not yet checkpointed) transactions and the
+    /// current epoch, but not the state of the RNG.
+    pub fn snapshot(&self) -> Snapshot<S> {
+        Snapshot {
+            store: self.store.clone(),
+            checkpoint_builder: self.checkpoint_builder.clone(),
+            epoch_state: self.epoch_state.clone(),
+        }
+    }
+
+    /// Restores the chain to the state it was in when `snapshot` was taken, discarding everything
+    /// that has happened since. The same snapshot can be reverted to any number of times.
+    pub fn revert_to(&mut self, snapshot: &Snapshot<S>) {
+        self.store = snapshot.store.clone();
+        self.checkpoint_builder = snapshot.checkpoint_builder.clone();
+        self.epoch_state = snapshot.epoch_state.clone();
+    }
 }
 
 pub struct CommitteeWithKeys<'a> {
@@ -442,11 +595,15 @@ impl Simulacrum {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::time::Duration;
 
     use rand::{rngs::StdRng, SeedableRng};
     use sui_types::{
-        base_types::SuiAddress, effects::TransactionEffectsAPI, gas_coin::GasCoin,
+        base_types::SuiAddress,
+        crypto::{get_key_pair, AccountKeyPair},
+        effects::TransactionEffectsAPI,
+        gas_coin::{GasCoin, GAS},
         transaction::TransactionDataAPI,
     };
 
@@ -552,4 +709,141 @@ mod tests {
         assert_eq!(&checkpoint.epoch_rolling_gas_cost_summary, gas_summary);
         assert_eq!(checkpoint.network_total_transactions, 2); // genesis + 1 txn
     }
+
+    #[test]
+    fn forked_transfer() {
+        let (sender, key): (_, AccountKeyPair) = get_key_pair();
+        let remote_coin = Object::new_gas_with_balance_and_owner_for_testing(
+            10 * MIST_PER_SUI,
+            sender,
+        );
+        let coin_id = remote_coin.id();
+        let remote: BTreeMap<_, _> = [(coin_id, remote_coin.clone())].into_iter().collect();
+
+        let rng = StdRng::from_seed([9; 32]);
+        let mut sim = Simulacrum::new_forked(rng, 1, remote);
+
+        // The coin is only known to the fork source until it is read.
+        assert!(!sim.store.local().contains_object_history(&coin_id));
+        assert_eq!(
+            store::SimulatorStore::get_object(sim.store(), &coin_id),
+            Some(remote_coin.clone())
+        );
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let tx_data = TransactionData::new_transfer_sui(
+            recipient,
+            sender,
+            Some(MIST_PER_SUI),
+            remote_coin.compute_object_reference(),
+            MIST_PER_SUI,
+            sim.reference_gas_price(),
+        );
+        let tx = Transaction::from_data_and_signer(tx_data, vec![&key]);
+        let (effects, error) = sim.execute_transaction(tx).unwrap();
+        assert!(error.is_none(), "{error:?}");
+        assert!(effects.status().is_ok());
+
+        // The mutated coin now lives in the local store and shadows the fork source, while the
+        // forked version remains readable.
+        let local_coin = store::SimulatorStore::get_object(sim.store(), &coin_id).unwrap();
+        assert!(local_coin.version() > remote_coin.version());
+        assert!(sim.store.local().contains_object_history(&coin_id));
+        assert_eq!(
+            store::SimulatorStore::get_object_at_version(
+                sim.store(),
+                &coin_id,
+                remote_coin.version()
+            ),
+            Some(remote_coin)
+        );
+
+        assert_eq!(
+            MIST_PER_SUI,
+            sim.store()
+                .owned_objects(recipient)
+                .next()
+                .and_then(|object| GasCoin::try_from(&object).ok())
+                .unwrap()
+                .value()
+        );
+    }
+
+    #[test]
+    fn impersonate_minted_coin() {
+        let mut sim = Simulacrum::new();
+        let sender = SuiAddress::random_for_testing_only();
+        let coin_id = sim.mint_coin(sender, GAS::type_tag(), 10 * MIST_PER_SUI);
+        let coin = store::SimulatorStore::get_object(sim.store(), &coin_id).unwrap();
+        assert!(coin.is_gas_coin());
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let tx_data = TransactionData::new_transfer_sui(
+            recipient,
+            sender,
+            Some(MIST_PER_SUI),
+            coin.compute_object_reference(),
+            MIST_PER_SUI,
+            sim.reference_gas_price(),
+        );
+
+        // `sender` has no key, so the transaction can only be executed by impersonating them.
+        let tx = Transaction::from_generic_sig_data(tx_data.clone(), vec![]);
+        assert!(sim.execute_transaction(tx).is_err());
+
+        let (effects, error) = sim.execute_transaction_impersonating(tx_data).unwrap();
+        assert!(error.is_none(), "{error:?}");
+        assert!(effects.status().is_ok());
+        assert_eq!(
+            MIST_PER_SUI,
+            sim.store()
+                .owned_objects(recipient)
+                .next()
+                .and_then(|object| GasCoin::try_from(&object).ok())
+                .unwrap()
+                .value()
+        );
+    }
+
+    #[test]
+    fn snapshot_and_revert() {
+        let mut sim = Simulacrum::new();
+        let recipient = SuiAddress::random_for_testing_only();
+        let snapshot = sim.snapshot();
+        let start = sim.store().get_highest_checkpint().unwrap();
+
+        for _ in 0..2 {
+            let (tx, _) = sim.transfer_txn(recipient);
+            sim.execute_transaction(tx).unwrap();
+            sim.advance_clock(Duration::from_millis(1));
+            sim.create_checkpoint();
+            assert_eq!(sim.store().owned_objects(recipient).count(), 1);
+
+            sim.revert_to(&snapshot);
+            assert_eq!(sim.store().owned_objects(recipient).count(), 0);
+            assert_eq!(sim.store().get_highest_checkpint().unwrap(), start);
+        }
+    }
+
+    #[test]
+    fn set_and_remove_objects() {
+        let mut sim = Simulacrum::new();
+        let owner = SuiAddress::random_for_testing_only();
+        let object = Object::new_gas_with_balance_and_owner_for_testing(MIST_PER_SUI, owner);
+        let id = object.id();
+
+        sim.set_object(object.clone());
+        assert_eq!(
+            store::SimulatorStore::get_object(sim.store(), &id),
+            Some(object.clone())
+        );
+
+        assert_eq!(sim.remove_object(&id), Some(object.clone()));
+        assert_eq!(store::SimulatorStore::get_object(sim.store(), &id), None);
+        assert_eq!(
+            store::SimulatorStore::get_object_at_version(sim.store(), &id, object.version()),
+            Some(object)
+        );
+        assert_eq!(sim.remove_object(&id), None);
+    }
 }
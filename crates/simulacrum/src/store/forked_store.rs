@@ -0,0 +1,436 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use sui_config::genesis;
+use sui_types::storage::{get_module, load_package_object_from_object_store, PackageObject};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    committee::{Committee, EpochId},
+    digests::{ObjectDigest, TransactionDigest, TransactionEventsDigest},
+    effects::{TransactionEffects, TransactionEvents},
+    error::SuiError,
+    messages_checkpoint::{
+        CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
+        VerifiedCheckpoint,
+    },
+    object::{Object, Owner},
+    storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync},
+    transaction::VerifiedTransaction,
+};
+use tracing::warn;
+
+use super::in_mem_store::InMemoryStore;
+use super::SimulatorStore;
+
+/// A source of objects that a [`ForkedStore`] falls back to for objects it has not seen locally.
+///
+/// Implementations are typically backed by a remote network and are expected to serve objects as
+/// of a single, fixed point in that network's history (the fork point), so that every read made
+/// by the forked chain observes a consistent snapshot.
+pub trait ForkSource: Send + Sync {
+    /// Fetch the object with `id` as it was at the fork point, or `None` if it was not live then.
+    fn get_object(&self, id: &ObjectID) -> anyhow::Result<Option<Object>>;
+
+    /// Fetch `version` of the object with `id`, or `None` if that version did not exist at or
+    /// before the fork point.
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> anyhow::Result<Option<Object>>;
+}
+
+/// A fixed set of objects can act as a fork source, which is mostly useful in tests.
+impl ForkSource for BTreeMap<ObjectID, Object> {
+    fn get_object(&self, id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        Ok(self.get(id).cloned())
+    }
+
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> anyhow::Result<Option<Object>> {
+        Ok(self.get(id).filter(|o| o.version() == version).cloned())
+    }
+}
+
+/// A [`SimulatorStore`] layered on top of a [`ForkSource`].
+///
+/// All chain data (checkpoints, transactions, committees) and every object written by local
+/// execution lives in an [`InMemoryStore`]. Reads of objects that have never been written locally
+/// are served by lazily fetching them from the fork source, and the results (including misses)
+/// are cached so that each object is fetched at most once.
+///
+/// Objects written locally always shadow the fork source, this includes the objects created by
+/// the local genesis (system packages, the system state, the clock, etc.), which allows the forked
+/// chain to be driven by a locally controlled committee.
+///
+/// Cloning a `ForkedStore` clones its local state, while the fork source is shared.
+pub struct ForkedStore<F> {
+    local: InMemoryStore,
+    source: Arc<F>,
+    remote_objects: RwLock<HashMap<ObjectID, Option<Object>>>,
+    remote_versioned_objects: RwLock<HashMap<(ObjectID, SequenceNumber), Option<Object>>>,
+}
+
+impl<F: ForkSource> ForkedStore<F> {
+    pub fn new(genesis: &genesis::Genesis, source: F) -> Self {
+        let mut store = Self {
+            local: InMemoryStore::default(),
+            source: Arc::new(source),
+            remote_objects: RwLock::default(),
+            remote_versioned_objects: RwLock::default(),
+        };
+        store.init_with_genesis(genesis);
+        store
+    }
+
+    pub fn source(&self) -> &F {
+        &self.source
+    }
+
+    /// The store holding everything that has been written by the forked chain itself.
+    pub fn local(&self) -> &InMemoryStore {
+        &self.local
+    }
+
+    pub fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        if let Some(object) = self.local.get_object(id) {
+            return Some(object.clone());
+        }
+
+        // The object was written locally but is no longer live, so it must have been deleted
+        // since the fork.
+        if self.local.contains_object_history(id) {
+            return None;
+        }
+
+        self.get_remote_object(id)
+    }
+
+    pub fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        if let Some(object) = self.local.get_object_at_version(id, version) {
+            return Some(object.clone());
+        }
+
+        let remote = self.get_remote_object(id)?;
+        if remote.version() == version {
+            return Some(remote);
+        } else if remote.version() < version {
+            return None;
+        }
+
+        if let Some(object) = self
+            .remote_versioned_objects
+            .read()
+            .unwrap()
+            .get(&(*id, version))
+        {
+            return object.clone();
+        }
+
+        let object = match self.source.get_object_at_version(id, version) {
+            Ok(object) => object,
+            Err(e) => {
+                warn!("Unable to fetch object {id} at version {version} from fork source: {e}");
+                return None;
+            }
+        };
+
+        self.remote_versioned_objects
+            .write()
+            .unwrap()
+            .insert((*id, version), object.clone());
+        object
+    }
+
+    /// Objects owned by `owner`.
+    ///
+    /// A fork source cannot be queried by owner, so this only includes objects that have been
+    /// written locally or that have already been fetched from the fork source.
+    pub fn owned_objects(&self, owner: SuiAddress) -> impl Iterator<Item = Object> + '_ {
+        let is_owned_by =
+            move |object: &Object| matches!(object.owner, Owner::AddressOwner(addr) if addr == owner);
+
+        let remote: Vec<_> = self
+            .remote_objects
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(id, _)| !self.local.contains_object_history(id))
+            .filter_map(|(_, object)| object.clone())
+            .filter(is_owned_by)
+            .collect();
+
+        self.local
+            .owned_objects(owner)
+            .cloned()
+            .chain(remote)
+    }
+
+    fn get_remote_object(&self, id: &ObjectID) -> Option<Object> {
+        if let Some(object) = self.remote_objects.read().unwrap().get(id) {
+            return object.clone();
+        }
+
+        let object = match self.source.get_object(id) {
+            Ok(object) => object,
+            Err(e) => {
+                // Failures are not cached so that transient errors can be retried.
+                warn!("Unable to fetch object {id} from fork source: {e}");
+                return None;
+            }
+        };
+
+        self.remote_objects
+            .write()
+            .unwrap()
+            .insert(*id, object.clone());
+        object
+    }
+}
+
+impl<F> Clone for ForkedStore<F> {
+    fn clone(&self) -> Self {
+        Self {
+            local: self.local.clone(),
+            source: self.source.clone(),
+            remote_objects: RwLock::new(self.remote_objects.read().unwrap().clone()),
+            remote_versioned_objects: RwLock::new(
+                self.remote_versioned_objects.read().unwrap().clone(),
+            ),
+        }
+    }
+}
+
+impl<F: ForkSource> BackingPackageStore for ForkedStore<F> {
+    fn get_package_object(
+        &self,
+        package_id: &ObjectID,
+    ) -> sui_types::error::SuiResult<Option<PackageObject>> {
+        load_package_object_from_object_store(self, package_id)
+    }
+}
+
+impl<F: ForkSource> ChildObjectResolver for ForkedStore<F> {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        let child_object = match self.get_object(child) {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner,
+            });
+        }
+
+        if child_object.version() > child_version_upper_bound {
+            return Err(SuiError::UnsupportedFeatureError {
+                error: "TODO ForkedStore::read_child_object does not yet support bounded reads"
+                    .to_owned(),
+            });
+        }
+
+        Ok(Some(child_object))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        _epoch_id: EpochId,
+    ) -> sui_types::error::SuiResult<Option<Object>> {
+        let recv_object = match self.get_object(receiving_object_id) {
+            None => return Ok(None),
+            Some(obj) => obj,
+        };
+        if recv_object.owner != Owner::AddressOwner((*owner).into()) {
+            return Ok(None);
+        }
+
+        if recv_object.version() != receive_object_at_version {
+            return Ok(None);
+        }
+        Ok(Some(recv_object))
+    }
+}
+
+impl<F: ForkSource> GetModule for ForkedStore<F> {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self
+            .get_module(id)?
+            .map(|bytes| CompiledModule::deserialize_with_defaults(&bytes).unwrap()))
+    }
+}
+
+impl<F: ForkSource> ModuleResolver for ForkedStore<F> {
+    type Error = SuiError;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        get_module(self, module_id)
+    }
+}
+
+impl<F: ForkSource> ObjectStore for ForkedStore<F> {
+    fn get_object(
+        &self,
+        object_id: &ObjectID,
+    ) -> Result<Option<Object>, sui_types::error::SuiError> {
+        Ok(self.get_object(object_id))
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Result<Option<Object>, sui_types::error::SuiError> {
+        Ok(self.get_object_at_version(object_id, version))
+    }
+}
+
+impl<F: ForkSource> ParentSync for ForkedStore<F> {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        _object_id: ObjectID,
+    ) -> sui_types::error::SuiResult<Option<sui_types::base_types::ObjectRef>> {
+        panic!("Never called in newer protocol versions")
+    }
+}
+
+impl<F: ForkSource> SimulatorStore for ForkedStore<F> {
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Option<VerifiedCheckpoint> {
+        self.local
+            .get_checkpoint_by_sequence_number(sequence_number)
+            .cloned()
+    }
+
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint> {
+        self.local.get_checkpoint_by_digest(digest).cloned()
+    }
+
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint> {
+        self.local.get_highest_checkpint().cloned()
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Option<CheckpointContents> {
+        self.local.get_checkpoint_contents(digest).cloned()
+    }
+
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee> {
+        self.local.get_committee_by_epoch(epoch).cloned()
+    }
+
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction> {
+        self.local.get_transaction(digest).cloned()
+    }
+
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects> {
+        self.local.get_transaction_effects(digest).cloned()
+    }
+
+    fn get_transaction_events(
+        &self,
+        digest: &TransactionEventsDigest,
+    ) -> Option<TransactionEvents> {
+        self.local.get_transaction_events(digest).cloned()
+    }
+
+    fn get_transaction_events_by_tx_digest(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Option<TransactionEvents> {
+        SimulatorStore::get_transaction_events_by_tx_digest(&self.local, tx_digest)
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        self.get_object(id)
+    }
+
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.get_object_at_version(id, version)
+    }
+
+    fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
+        self.local.get_system_state()
+    }
+
+    fn get_clock(&self) -> sui_types::clock::Clock {
+        self.local.get_clock()
+    }
+
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_> {
+        Box::new(self.owned_objects(owner))
+    }
+
+    fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
+        self.local.insert_checkpoint(checkpoint)
+    }
+
+    fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        self.local.insert_checkpoint_contents(contents)
+    }
+
+    fn insert_committee(&mut self, committee: Committee) {
+        self.local.insert_committee(committee)
+    }
+
+    fn insert_executed_transaction(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+    ) {
+        self.local
+            .insert_executed_transaction(transaction, effects, events, written_objects)
+    }
+
+    fn insert_transaction(&mut self, transaction: VerifiedTransaction) {
+        self.local.insert_transaction(transaction)
+    }
+
+    fn insert_transaction_effects(&mut self, effects: TransactionEffects) {
+        self.local.insert_transaction_effects(effects)
+    }
+
+    fn insert_events(&mut self, tx_digest: &TransactionDigest, events: TransactionEvents) {
+        self.local.insert_events(tx_digest, events)
+    }
+
+    fn update_objects(
+        &mut self,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) {
+        self.local.update_objects(written_objects, deleted_objects)
+    }
+
+    fn backing_store(&self) -> &dyn sui_types::storage::BackingStore {
+        self
+    }
+}
@@ -25,7 +25,7 @@ use sui_types::{
 
 use super::SimulatorStore;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct InMemoryStore {
     // Checkpoint data
     checkpoints: BTreeMap<CheckpointSequenceNumber, VerifiedCheckpoint>,
@@ -115,6 +115,12 @@ impl InMemoryStore {
             .and_then(|versions| versions.get(&version))
     }
 
+    /// Returns true if any version of the object with `id` has been written to this store, even if
+    /// the object has since been deleted.
+    pub fn contains_object_history(&self, id: &ObjectID) -> bool {
+        self.objects.contains_key(id)
+    }
+
     pub fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
         sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
     }
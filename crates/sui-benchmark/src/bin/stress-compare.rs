@@ -0,0 +1,72 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use clap::*;
+use std::path::{Path, PathBuf};
+use sui_benchmark::drivers::report::{BenchmarkReport, RegressionThresholds, ReportComparison};
+
+/// Compare two reports written by `stress --report-path`, and flag the metrics that regressed.
+/// Exits with a non-zero status if there are any regressions, so it can gate CI runs:
+/// ```cargo run --release --package sui-benchmark
+/// --bin stress-compare -- /tmp/baseline.json /tmp/candidate.json \
+/// --significance 0.01 \
+/// --min-change 0.1
+/// ```
+#[derive(Parser)]
+#[clap(name = "Stress Report Comparison")]
+struct Args {
+    /// Report of the baseline run.
+    old: PathBuf,
+    /// Report of the run being checked for regressions.
+    new: PathBuf,
+    /// Changes are only regressions if they are statistically significant at this level. Metrics
+    /// that the reports don't have enough samples to test are compared on `min_change` alone.
+    #[clap(long, default_value = "0.05")]
+    significance: f64,
+    /// Changes are only regressions if they are worse by at least this fraction of the old value.
+    #[clap(long, default_value = "0.05")]
+    min_change: f64,
+    /// Print the comparison as JSON instead of a table.
+    #[clap(long, action)]
+    json: bool,
+}
+
+fn read_report(path: &Path) -> Result<BenchmarkReport> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read report {}", path.display()))?;
+    serde_json::from_str(&data)
+        .with_context(|| format!("Failed to parse report {}", path.display()))
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let old = read_report(&args.old)?;
+    let new = read_report(&args.new)?;
+
+    let comparison = ReportComparison::new(
+        &old,
+        &new,
+        RegressionThresholds {
+            significance: args.significance,
+            min_change: args.min_change,
+        },
+    );
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&comparison)?);
+    } else {
+        println!("{}", comparison.to_table());
+    }
+
+    let regressions = comparison.regressions().count();
+    if regressions > 0 {
+        eprintln!(
+            "{regressions} regression(s) in {} relative to {}",
+            args.new.display(),
+            args.old.display()
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -14,6 +14,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use sui_benchmark::drivers::bench_driver::BenchDriver;
 use sui_benchmark::drivers::driver::Driver;
+use sui_benchmark::drivers::report::{BenchmarkConfig, BenchmarkReport};
 use sui_benchmark::drivers::BenchmarkCmp;
 use sui_benchmark::drivers::BenchmarkStats;
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
@@ -130,6 +131,8 @@ async fn main() -> Result<()> {
         .unwrap();
     let prev_benchmark_stats_path = opts.compare_with.clone();
     let curr_benchmark_stats_path = opts.benchmark_stats_path.clone();
+    let report_path = opts.report_path.clone();
+    let report_config = BenchmarkConfig::new(&opts);
     let registry_clone = registry.clone();
     let handle = std::thread::spawn(move || {
         client_runtime.block_on(async move {
@@ -202,6 +205,16 @@ async fn main() -> Result<()> {
                         let serialized = serde_json::to_string(&benchmark_stats)?;
                         std::fs::write(curr_benchmark_stats_path, serialized)?;
                     }
+                    if !report_path.is_empty() {
+                        let report = BenchmarkReport::new(
+                            report_config,
+                            &benchmark_stats,
+                            stress_stat_collection.then_some(&stress_stats),
+                        );
+                        let serialized = serde_json::to_string_pretty(&report)?;
+                        std::fs::write(&report_path, serialized)?;
+                        eprintln!("Benchmark report written to {report_path}");
+                    }
                 }
                 Err(e) => eprintln!("{e}"),
             },
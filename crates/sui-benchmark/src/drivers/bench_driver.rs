@@ -150,6 +150,7 @@ impl BenchMetrics {
 #[derive(Default)]
 struct Stats {
     pub id: usize,
+    pub workload: String,
     pub num_no_gas: u64,
     pub num_submitted: u64,
     pub num_in_flight: u64,
@@ -166,14 +167,25 @@ enum NextOp {
         num_commands: u16,
         /// Gas used in the executed transction
         gas_used: u64,
+        /// Whether the transaction executed successfully, as opposed to aborting
+        is_ok: bool,
         /// The payload updated with the effects of the transaction
         payload: Box<dyn Payload>,
     },
-    // The transaction failed and could not be retried
-    Failure,
+    // The transaction failed with an error of the given kind, and could not be retried
+    Failure(String),
+    // The transaction failed, and should be retried
     Retry(RetryType),
 }
 
+/// A short name for the kind of error `err` is, used to break errors down in benchmark reports.
+fn error_kind(err: &anyhow::Error) -> String {
+    match err.downcast_ref::<QuorumDriverError>() {
+        Some(err) => err.as_ref().to_string(),
+        None => "Other".to_string(),
+    }
+}
+
 async fn print_and_start_benchmark() -> &'static Instant {
     static ONCE: OnceCell<Instant> = OnceCell::const_new();
     ONCE.get_or_init(|| async move {
@@ -185,6 +197,7 @@ async fn print_and_start_benchmark() -> &'static Instant {
 
 pub struct BenchWorker {
     pub id: u64,
+    pub workload: String,
     pub target_qps: u64,
     pub payload: Vec<Box<dyn Payload>>,
     pub proxy: Arc<dyn ValidatorProxy + Send + Sync>,
@@ -196,8 +209,8 @@ impl Debug for BenchWorker {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str(
             format!(
-                "BenchWorker id:{}, group:{}, duration:{}, target_qps:{}",
-                self.id, self.group, self.duration, self.target_qps
+                "BenchWorker id:{}, workload:{}, group:{}, duration:{}, target_qps:{}",
+                self.id, self.workload, self.group, self.duration, self.target_qps
             )
             .as_str(),
         )
@@ -264,6 +277,10 @@ impl BenchDriver {
             .workload
             .make_test_payloads(proxy.clone(), system_state_observer.clone())
             .await;
+        let workload = payloads
+            .first()
+            .map(|payload| payload.to_string())
+            .unwrap_or_default();
         let mut total_workers = workload_info.workload_params.num_workers;
         while total_workers > 0 {
             let target_qps = qps / total_workers;
@@ -272,6 +289,7 @@ impl BenchDriver {
                 let remaining = payloads.split_off(chunk_size);
                 workers.push(BenchWorker {
                     id: *id,
+                    workload: workload.clone(),
                     target_qps,
                     payload: payloads,
                     proxy: proxy.clone(),
@@ -378,6 +396,7 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 latency_ms: HistogramWrapper {
                     histogram: hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3).unwrap(),
                 },
+                ..Default::default()
             };
             let mut stat_collection: BTreeMap<usize, Stats> = BTreeMap::new();
             let mut counter = 0;
@@ -386,6 +405,7 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
             while let Some(
                 sample_stat @ Stats {
                     id,
+                    workload: _,
                     num_no_gas: _,
                     num_in_flight: _,
                     num_submitted: _,
@@ -400,9 +420,22 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                     continue;
                 }
 
-                benchmark_stat.update(start.elapsed(), &sample_stat.bench_stats);
+                let elapsed = start.elapsed();
+                benchmark_stat.update(elapsed, &sample_stat.bench_stats);
+                benchmark_stat
+                    .workloads
+                    .entry(sample_stat.workload.clone())
+                    .or_insert_with(|| BenchmarkStats {
+                        latency_ms: HistogramWrapper {
+                            histogram: hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3)
+                                .unwrap(),
+                        },
+                        ..Default::default()
+                    })
+                    .update(elapsed, &sample_stat.bench_stats);
                 stat_collection.insert(id, sample_stat);
 
+                let mut workload_qps: BTreeMap<&str, f64> = BTreeMap::new();
                 let mut total_qps: f32 = 0.0;
                 let mut total_cps: f32 = 0.0;
                 let mut num_success_txes: u64 = 0;
@@ -423,6 +456,8 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                     }
 
                     total_qps += v.bench_stats.num_success_txes as f32 / duration;
+                    *workload_qps.entry(v.workload.as_str()).or_default() +=
+                        v.bench_stats.num_success_txes as f64 / duration as f64;
                     total_cps += v.bench_stats.num_success_cmds as f32 / duration;
                     num_success_txes += v.bench_stats.num_success_txes;
                     num_error_txes += v.bench_stats.num_error_txes;
@@ -442,6 +477,12 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 };
                 counter += 1;
                 if counter % num_workers == 0 {
+                    benchmark_stat.tps_samples.push(total_qps as f64);
+                    for (workload, qps) in workload_qps {
+                        if let Some(stats) = benchmark_stat.workloads.get_mut(workload) {
+                            stats.tps_samples.push(qps);
+                        }
+                    }
                     stat = format!("TPS = {}, CPS = {}, latency_ms(min/p50/p99/max) = {}/{}/{}/{}, num_success_tx = {}, num_error_tx = {}, num_success_cmds = {}, no_gas = {}, submitted = {}, in_flight = {}", total_qps, total_cps, latency_histogram.min(), latency_histogram.value_at_quantile(0.5), latency_histogram.value_at_quantile(0.99), latency_histogram.max(), num_success_txes, num_error_txes, num_success_cmds, num_no_gas, num_submitted, num_in_flight);
                     if show_progress {
                         eprintln!("{}", stat);
@@ -686,6 +727,7 @@ async fn run_bench_worker(
     let mut num_in_flight: u64 = 0;
     let mut num_submitted = 0;
     let mut worker_gas_used = 0;
+    let mut errors: BTreeMap<String, u64> = BTreeMap::new();
 
     let mut latency_histogram = hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3).unwrap();
     let mut request_interval = time::interval(Duration::from_micros(request_delay_micros));
@@ -756,10 +798,12 @@ async fn run_bench_worker(
                     num_commands,
                     payload,
                     gas_used: effects.gas_used(),
+                    is_ok: effects.is_ok(),
                 }
             }
             Err(err) => {
                 error!("{}", err);
+                let kind = error_kind(&err);
                 if err
                     .downcast::<QuorumDriverError>()
                     .and_then(|err| {
@@ -774,7 +818,7 @@ async fn run_bench_worker(
                     })
                     .is_err()
                 {
-                    NextOp::Failure
+                    NextOp::Failure(kind)
                 } else {
                     metrics_cloned
                         .num_error
@@ -828,6 +872,7 @@ async fn run_bench_worker(
                 if tx_cloned
                     .try_send(Stats {
                         id: worker.id as usize,
+                        workload: worker.workload.clone(),
                         num_no_gas,
                         num_in_flight,
                         num_submitted,
@@ -839,21 +884,27 @@ async fn run_bench_worker(
                             latency_ms:HistogramWrapper{
                                 histogram:latency_histogram.clone()
                             },
-                            total_gas_used: worker_gas_used
+                            total_gas_used: worker_gas_used,
+                            errors: errors.clone(),
+                            ..Default::default()
                         },
                     })
                     .is_err()
                 {
+                    // Keep accumulating, so that these stats are reported with the next update
+                    // instead of being lost.
                     debug!("Failed to update stat!");
+                } else {
+                    num_success_txes = 0;
+                    num_error_txes = 0;
+                    num_success_cmds = 0;
+                    num_no_gas = 0;
+                    num_submitted = 0;
+                    worker_gas_used = 0;
+                    errors.clear();
+                    stat_start_time = Instant::now();
+                    latency_histogram.reset();
                 }
-                num_success_txes = 0;
-                num_error_txes = 0;
-                num_success_cmds = 0;
-                num_no_gas = 0;
-                num_submitted = 0;
-                worker_gas_used = 0;
-                stat_start_time = Instant::now();
-                latency_histogram.reset();
             }
             _ = request_interval.tick() => {
 
@@ -913,16 +964,20 @@ async fn run_bench_worker(
                             break;
                         }
                     }
-                    NextOp::Failure => {
+                    NextOp::Failure(kind) => {
                         error!("Permanent failure to execute payload. May result in gas objects being leaked");
                         num_error_txes += 1;
+                        *errors.entry(kind).or_default() += 1;
                         // Update total benchmark progress
                         if update_progress(1) {
                             break;
                         }
                     }
-                    NextOp::Response { latency, num_commands, payload, gas_used } => {
+                    NextOp::Response { latency, num_commands, payload, gas_used, is_ok } => {
                         num_success_txes += 1;
+                        if !is_ok {
+                            *errors.entry("ExecutionFailure".to_string()).or_default() += 1;
+                        }
                         num_success_cmds += num_commands as u64;
                         num_in_flight -= 1;
                         worker_gas_used += gas_used;
@@ -945,6 +1000,7 @@ async fn run_bench_worker(
     if tx_cloned
         .try_send(Stats {
             id: worker.id as usize,
+            workload: worker.workload.clone(),
             num_no_gas,
             num_in_flight,
             num_submitted,
@@ -957,6 +1013,8 @@ async fn run_bench_worker(
                 latency_ms: HistogramWrapper {
                     histogram: latency_histogram,
                 },
+                errors,
+                ..Default::default()
             },
         })
         .is_err()
@@ -972,7 +1030,7 @@ async fn run_bench_worker(
     );
     while let Some(result) = futures.next().await {
         let p = match result {
-            NextOp::Failure => {
+            NextOp::Failure(_) => {
                 error!(
                     "Permanent failure to execute payload. May result in gas objects being leaked"
                 );
@@ -982,6 +1040,7 @@ async fn run_bench_worker(
                 latency: _,
                 num_commands: _,
                 gas_used: _,
+                is_ok: _,
                 payload,
             } => payload,
             NextOp::Retry(b) => b.1,
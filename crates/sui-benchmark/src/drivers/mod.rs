@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use duration_str::parse;
+use std::collections::BTreeMap;
 use std::fmt::Formatter;
 use std::{str::FromStr, time::Duration};
 
 pub mod bench_driver;
 pub mod driver;
+pub mod report;
 use comfy_table::{Cell, Color, ContentArrangement, Row, Table};
 use hdrhistogram::{serialization::Serializer, Histogram};
 
@@ -131,6 +133,16 @@ pub struct BenchmarkStats {
     /// Total gas used
     pub total_gas_used: u64,
     pub latency_ms: HistogramWrapper,
+    /// Number of failed transactions, by the kind of failure. Transactions that were executed but
+    /// aborted are counted here as well, even though they count as successes above.
+    #[serde(default)]
+    pub errors: BTreeMap<String, u64>,
+    /// TPS across all workers, sampled once per stat collection interval.
+    #[serde(default)]
+    pub tps_samples: Vec<f64>,
+    /// Statistics for each workload, by name.
+    #[serde(default)]
+    pub workloads: BTreeMap<String, BenchmarkStats>,
 }
 
 impl BenchmarkStats {
@@ -144,6 +156,9 @@ impl BenchmarkStats {
             .histogram
             .add(&sample_stat.latency_ms.histogram)
             .unwrap();
+        for (kind, count) in &sample_stat.errors {
+            *self.errors.entry(kind.clone()).or_default() += count;
+        }
     }
     pub fn to_table(&self) -> Table {
         let mut table = Table::new();
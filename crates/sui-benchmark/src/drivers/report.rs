@@ -0,0 +1,726 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Machine-readable reports of benchmark runs, and comparisons between them that flag
+//! statistically significant regressions.
+
+use crate::drivers::{BenchmarkStats, StressStats};
+use crate::options::Opts;
+use comfy_table::{Cell, Color, Row, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::{System, SystemExt};
+
+/// Name that the statistics across all workloads are reported under in comparisons.
+pub const TOTAL: &str = "total";
+
+/// Everything measured during a benchmark run, along with the configuration it ran with.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BenchmarkReport {
+    pub config: BenchmarkConfig,
+    /// Statistics across all workloads.
+    pub total: WorkloadReport,
+    /// Statistics for each workload, by name.
+    pub workloads: BTreeMap<String, WorkloadReport>,
+    /// Client CPU usage, in percent, if stress stat collection was enabled.
+    pub cpu_usage: Option<PercentilesReport>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BenchmarkConfig {
+    /// Version of the stress client.
+    pub version: String,
+    /// When the report was produced, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub committee_size: u64,
+    pub num_server_threads: u64,
+    pub num_client_threads: u64,
+    pub local: bool,
+    pub use_fullnode_for_execution: bool,
+    pub run_duration: String,
+    pub protocol_version: Option<u64>,
+    /// The workloads' configuration, as passed on the command line.
+    pub run_spec: String,
+    pub host: HostConfig,
+}
+
+/// The machine the stress client ran on.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HostConfig {
+    pub os: Option<String>,
+    pub num_cpus: usize,
+    pub total_memory_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WorkloadReport {
+    pub duration_secs: f64,
+    pub num_success_txes: u64,
+    pub num_error_txes: u64,
+    pub num_success_cmds: u64,
+    pub tps: f64,
+    pub cps: f64,
+    pub error_rate: f64,
+    /// TPS sampled once per stat collection interval, used to tell how noisy `tps` is.
+    pub tps_samples: Vec<f64>,
+    pub latency_ms: LatencyReport,
+    pub gas: GasReport,
+    /// Number of failed transactions, by the kind of failure.
+    pub errors: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LatencyReport {
+    pub count: u64,
+    pub mean: f64,
+    pub stdev: f64,
+    pub min: u64,
+    pub p25: u64,
+    pub p50: u64,
+    pub p75: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub p999: u64,
+    pub max: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GasReport {
+    pub total_gas_used: u64,
+    pub gas_per_tx: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PercentilesReport {
+    pub p50: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl BenchmarkConfig {
+    pub fn new(opts: &Opts) -> Self {
+        let system = System::new_all();
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            committee_size: opts.committee_size,
+            num_server_threads: opts.num_server_threads,
+            num_client_threads: opts.num_client_threads,
+            local: opts.local,
+            use_fullnode_for_execution: opts.use_fullnode_for_execution,
+            run_duration: opts.run_duration.to_string(),
+            protocol_version: opts.protocol_version,
+            run_spec: format!("{:?}", opts.run_spec),
+            host: HostConfig {
+                os: system.long_os_version(),
+                num_cpus: system.cpus().len(),
+                total_memory_bytes: system.total_memory(),
+            },
+        }
+    }
+}
+
+impl BenchmarkReport {
+    pub fn new(
+        config: BenchmarkConfig,
+        benchmark_stats: &BenchmarkStats,
+        stress_stats: Option<&StressStats>,
+    ) -> Self {
+        Self {
+            config,
+            total: WorkloadReport::from(benchmark_stats),
+            workloads: benchmark_stats
+                .workloads
+                .iter()
+                .map(|(name, stats)| (name.clone(), WorkloadReport::from(stats)))
+                .collect(),
+            cpu_usage: stress_stats.map(|stats| {
+                let histogram = &stats.cpu_usage.histogram;
+                PercentilesReport {
+                    p50: histogram.value_at_quantile(0.5),
+                    p99: histogram.value_at_quantile(0.99),
+                    max: histogram.max(),
+                }
+            }),
+        }
+    }
+
+    /// The report for the workload called `name`, or the report across all workloads for
+    /// [`TOTAL`].
+    pub fn workload(&self, name: &str) -> Option<&WorkloadReport> {
+        if name == TOTAL {
+            Some(&self.total)
+        } else {
+            self.workloads.get(name)
+        }
+    }
+}
+
+impl From<&BenchmarkStats> for WorkloadReport {
+    fn from(stats: &BenchmarkStats) -> Self {
+        let duration_secs = stats.duration.as_secs_f64();
+        let per_sec = |n: u64| {
+            if duration_secs > 0.0 {
+                n as f64 / duration_secs
+            } else {
+                0.0
+            }
+        };
+        let num_txes = stats.num_success_txes + stats.num_error_txes;
+        let histogram = &stats.latency_ms.histogram;
+
+        Self {
+            duration_secs,
+            num_success_txes: stats.num_success_txes,
+            num_error_txes: stats.num_error_txes,
+            num_success_cmds: stats.num_success_cmds,
+            tps: per_sec(stats.num_success_txes),
+            cps: per_sec(stats.num_success_cmds),
+            error_rate: ratio(stats.num_error_txes as f64, num_txes as f64),
+            tps_samples: stats.tps_samples.clone(),
+            latency_ms: LatencyReport {
+                count: histogram.len(),
+                mean: histogram.mean(),
+                stdev: histogram.stdev(),
+                min: histogram.min(),
+                p25: histogram.value_at_quantile(0.25),
+                p50: histogram.value_at_quantile(0.5),
+                p75: histogram.value_at_quantile(0.75),
+                p90: histogram.value_at_quantile(0.9),
+                p99: histogram.value_at_quantile(0.99),
+                p999: histogram.value_at_quantile(0.999),
+                max: histogram.max(),
+            },
+            gas: GasReport {
+                total_gas_used: stats.total_gas_used,
+                gas_per_tx: ratio(stats.total_gas_used as f64, stats.num_success_txes as f64),
+            },
+            errors: stats.errors.clone(),
+        }
+    }
+}
+
+fn ratio(n: f64, d: f64) -> f64 {
+    if d > 0.0 {
+        n / d
+    } else {
+        0.0
+    }
+}
+
+/// Thresholds for flagging a change between two reports as a regression.
+#[derive(Clone, Copy, Debug)]
+pub struct RegressionThresholds {
+    /// Changes are only regressions if a test of the hypothesis that nothing changed gives a
+    /// p-value below this. Metrics that there isn't enough information to test are exempt.
+    pub significance: f64,
+    /// Changes are only regressions if they are worse by at least this fraction of the old value.
+    pub min_change: f64,
+}
+
+impl Default for RegressionThresholds {
+    fn default() -> Self {
+        Self {
+            significance: 0.05,
+            min_change: 0.05,
+        }
+    }
+}
+
+/// How one metric of one workload changed between two reports.
+#[derive(Serialize, Debug, Clone)]
+pub struct MetricComparison {
+    pub workload: String,
+    pub metric: String,
+    pub old: f64,
+    pub new: f64,
+    /// Change relative to the old value, positive if the metric increased.
+    pub change: f64,
+    /// Two-sided p-value for the hypothesis that the metric didn't change, if the reports have
+    /// enough information to test it.
+    pub p_value: Option<f64>,
+    /// Whether the metric got worse by more than the thresholds allow.
+    pub regression: bool,
+}
+
+/// A comparison of every metric of every workload that two reports have in common.
+#[derive(Serialize, Debug)]
+pub struct ReportComparison {
+    pub metrics: Vec<MetricComparison>,
+}
+
+impl ReportComparison {
+    pub fn new(
+        old: &BenchmarkReport,
+        new: &BenchmarkReport,
+        thresholds: RegressionThresholds,
+    ) -> Self {
+        let workloads = std::iter::once(TOTAL)
+            .chain(old.workloads.keys().map(String::as_str))
+            .filter(|name| new.workload(name).is_some());
+
+        let mut metrics = vec![];
+        for name in workloads {
+            let (old, new) = (old.workload(name).unwrap(), new.workload(name).unwrap());
+            let mut compare = |metric: &str, old: f64, new: f64, p_value, higher_is_better| {
+                metrics.push(compare_metric(
+                    name,
+                    metric,
+                    old,
+                    new,
+                    p_value,
+                    higher_is_better,
+                    thresholds,
+                ))
+            };
+
+            compare(
+                "tps",
+                old.tps,
+                new.tps,
+                samples_p_value(&old.tps_samples, &new.tps_samples),
+                true,
+            );
+            compare(
+                "error_rate",
+                old.error_rate,
+                new.error_rate,
+                proportions_p_value(
+                    old.num_error_txes,
+                    old.num_success_txes + old.num_error_txes,
+                    new.num_error_txes,
+                    new.num_success_txes + new.num_error_txes,
+                ),
+                false,
+            );
+            compare(
+                "mean_latency_ms",
+                old.latency_ms.mean,
+                new.latency_ms.mean,
+                welch_p_value(
+                    (old.latency_ms.mean, old.latency_ms.stdev.powi(2)),
+                    old.latency_ms.count as f64,
+                    (new.latency_ms.mean, new.latency_ms.stdev.powi(2)),
+                    new.latency_ms.count as f64,
+                ),
+                false,
+            );
+            compare(
+                "p50_latency_ms",
+                old.latency_ms.p50 as f64,
+                new.latency_ms.p50 as f64,
+                None,
+                false,
+            );
+            compare(
+                "p99_latency_ms",
+                old.latency_ms.p99 as f64,
+                new.latency_ms.p99 as f64,
+                None,
+                false,
+            );
+            compare(
+                "gas_per_tx",
+                old.gas.gas_per_tx,
+                new.gas.gas_per_tx,
+                None,
+                false,
+            );
+        }
+
+        Self { metrics }
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &MetricComparison> {
+        self.metrics.iter().filter(|m| m.regression)
+    }
+
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        table.set_header(vec![
+            "workload",
+            "metric",
+            "old",
+            "new",
+            "change",
+            "p-value",
+            "regression",
+        ]);
+        for cmp in &self.metrics {
+            let color = if cmp.regression {
+                Color::Red
+            } else {
+                Color::Reset
+            };
+            let mut row = Row::new();
+            row.add_cell(Cell::new(&cmp.workload));
+            row.add_cell(Cell::new(&cmp.metric));
+            row.add_cell(Cell::new(format!("{:.2}", cmp.old)));
+            row.add_cell(Cell::new(format!("{:.2}", cmp.new)));
+            row.add_cell(Cell::new(format!("{:+.2}%", cmp.change * 100.0)).fg(color));
+            row.add_cell(Cell::new(
+                cmp.p_value.map_or("-".to_string(), |p| format!("{p:.4}")),
+            ));
+            row.add_cell(Cell::new(if cmp.regression { "yes" } else { "no" }).fg(color));
+            table.add_row(row);
+        }
+        table
+    }
+}
+
+fn compare_metric(
+    workload: &str,
+    metric: &str,
+    old: f64,
+    new: f64,
+    p_value: Option<f64>,
+    higher_is_better: bool,
+    thresholds: RegressionThresholds,
+) -> MetricComparison {
+    let change = if old != 0.0 {
+        (new - old) / old
+    } else if new == 0.0 {
+        0.0
+    } else {
+        f64::INFINITY.copysign(new)
+    };
+
+    let worse = if higher_is_better { -change } else { change };
+    let significant = p_value.map_or(true, |p| p < thresholds.significance);
+
+    MetricComparison {
+        workload: workload.to_string(),
+        metric: metric.to_string(),
+        old,
+        new,
+        change,
+        p_value,
+        regression: worse >= thresholds.min_change && significant,
+    }
+}
+
+/// Two-sided p-value of Welch's t-test on two sets of samples.
+fn samples_p_value(old: &[f64], new: &[f64]) -> Option<f64> {
+    fn mean_and_variance(samples: &[f64]) -> Option<(f64, f64)> {
+        let n = samples.len() as f64;
+        if samples.len() < 2 {
+            return None;
+        }
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Some((mean, variance))
+    }
+
+    welch_p_value(
+        mean_and_variance(old)?,
+        old.len() as f64,
+        mean_and_variance(new)?,
+        new.len() as f64,
+    )
+}
+
+/// Two-sided p-value of Welch's t-test, given the mean and variance of two sets of `n1` and `n2`
+/// samples.
+fn welch_p_value((m1, v1): (f64, f64), n1: f64, (m2, v2): (f64, f64), n2: f64) -> Option<f64> {
+    if n1 < 2.0 || n2 < 2.0 {
+        return None;
+    }
+
+    let (se1, se2) = (v1 / n1, v2 / n2);
+    let se = se1 + se2;
+    if se == 0.0 {
+        return Some(if m1 == m2 { 1.0 } else { 0.0 });
+    }
+
+    let t = (m2 - m1) / se.sqrt();
+    let df = se.powi(2) / (se1.powi(2) / (n1 - 1.0) + se2.powi(2) / (n2 - 1.0));
+    Some(incomplete_beta(df / 2.0, 0.5, df / (df + t * t)))
+}
+
+/// Two-sided p-value of a z-test for whether `k1` out of `n1` and `k2` out of `n2` are samples
+/// from the same proportion.
+fn proportions_p_value(k1: u64, n1: u64, k2: u64, n2: u64) -> Option<f64> {
+    if n1 == 0 || n2 == 0 {
+        return None;
+    }
+
+    let (k1, n1, k2, n2) = (k1 as f64, n1 as f64, k2 as f64, n2 as f64);
+    let pooled = (k1 + k2) / (n1 + n2);
+    let se = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+    if se == 0.0 {
+        return Some(1.0);
+    }
+
+    let z = (k2 / n2 - k1 / n1) / se;
+    Some(erfc(z.abs() / std::f64::consts::SQRT_2))
+}
+
+/// Complementary error function, with a fractional error below 1.2e-7 (Numerical Recipes,
+/// `erfcc`).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let ans = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 {
+        ans
+    } else {
+        2.0 - ans
+    }
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`.
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    // The continued fraction converges quickly on this side of the mean, otherwise use the
+    // symmetry I_x(a, b) = 1 - I_{1-x}(b, a).
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction for the incomplete beta function, by the modified Lentz method (Numerical
+/// Recipes, `betacf`).
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 200;
+    const EPSILON: f64 = 1e-12;
+    const TINY: f64 = 1e-300;
+
+    let clamp = |v: f64| if v.abs() < TINY { TINY } else { v };
+
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - qab * x / qap);
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 / clamp(1.0 + aa * d);
+        c = clamp(1.0 + aa / c);
+        h *= d * c;
+
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 / clamp(1.0 + aa * d);
+        c = clamp(1.0 + aa / c);
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+/// Natural log of the gamma function, by the Lanczos approximation (Numerical Recipes,
+/// `gammln`).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    let mut y = x;
+    for c in COEFFICIENTS {
+        y += 1.0;
+        series += c / y;
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_erfc() {
+        // Reference values from `math.erfc`, to the approximation's 1.2e-7 fractional error.
+        for (x, expected) in [
+            (0.0, 1.0),
+            (0.5, 0.479_500_122_186_953_5),
+            (1.0, 0.157_299_207_050_285_13),
+            (2.0, 0.004_677_734_981_047_266),
+            (-1.0, 1.842_700_792_949_714_8),
+        ] {
+            assert_close(erfc(x), expected, expected * 1.2e-7);
+        }
+    }
+
+    #[test]
+    fn test_ln_gamma() {
+        for (x, expected) in [
+            (1.0, 0.0),
+            (2.0, 0.0),
+            // ln(sqrt(pi))
+            (0.5, 0.572_364_942_924_700_1),
+            (3.5, 1.200_973_602_347_074),
+            // ln(9!)
+            (10.0, 12.801_827_480_081_469),
+        ] {
+            assert_close(ln_gamma(x), expected, 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_incomplete_beta() {
+        assert_eq!(incomplete_beta(2.0, 3.0, 0.0), 0.0);
+        assert_eq!(incomplete_beta(2.0, 3.0, 1.0), 1.0);
+
+        for (a, b, x, expected) in [
+            // I_x(1, 1) = x
+            (1.0, 1.0, 0.3, 0.3),
+            // I_x(a, 1) = x^a
+            (2.0, 1.0, 0.3, 0.09),
+            // I_x(1, b) = 1 - (1 - x)^b
+            (1.0, 3.0, 0.2, 0.488),
+            // Binomial tail: P(Bin(4, 0.4) >= 2)
+            (2.0, 3.0, 0.4, 0.5248),
+            // Symmetric about the mean
+            (7.5, 7.5, 0.5, 0.5),
+        ] {
+            assert_close(incomplete_beta(a, b, x), expected, 1e-9);
+        }
+
+        // I_x(a, b) = 1 - I_{1-x}(b, a), across both branches of the continued fraction.
+        assert_close(
+            incomplete_beta(2.5, 0.5, 0.9),
+            1.0 - incomplete_beta(0.5, 2.5, 0.1),
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn test_welch_p_value() {
+        // t = 1 with 2 degrees of freedom, where the two-sided p-value is 1 - t / sqrt(t^2 + 2).
+        assert_close(
+            welch_p_value((0.0, 1.0), 2.0, (1.0, 1.0), 2.0).unwrap(),
+            1.0 - 1.0 / 3f64.sqrt(),
+            1e-9,
+        );
+
+        // t = 2 with 1 degree of freedom (Cauchy), where it is 1 - 2 atan(t) / pi.
+        assert_close(
+            welch_p_value((0.0, 8.0), 2.0, (4.0, 0.0), 10.0).unwrap(),
+            1.0 - 2.0 * 2f64.atan() / std::f64::consts::PI,
+            1e-9,
+        );
+
+        // [1, 2, 3, 4, 5] against [2, 4, 6, 8, 10]: t = 1.897, df = 5.882.
+        let p = samples_p_value(&[1.0, 2.0, 3.0, 4.0, 5.0], &[2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_close(p.unwrap(), 0.107_531_194_930_324_37, 1e-6);
+
+        // The test is symmetric, and identical samples are indistinguishable.
+        assert_eq!(
+            welch_p_value((1.0, 2.0), 5.0, (3.0, 4.0), 6.0),
+            welch_p_value((3.0, 4.0), 6.0, (1.0, 2.0), 5.0)
+        );
+        assert_close(
+            welch_p_value((3.0, 2.0), 5.0, (3.0, 2.0), 5.0).unwrap(),
+            1.0,
+            1e-12,
+        );
+
+        // Without any variance the means either match or they don't.
+        assert_eq!(welch_p_value((3.0, 0.0), 5.0, (3.0, 0.0), 5.0), Some(1.0));
+        assert_eq!(welch_p_value((3.0, 0.0), 5.0, (4.0, 0.0), 5.0), Some(0.0));
+
+        // Too few samples to estimate a variance.
+        assert_eq!(welch_p_value((3.0, 1.0), 1.0, (4.0, 1.0), 5.0), None);
+        assert_eq!(samples_p_value(&[1.0], &[1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn test_proportions_p_value() {
+        // 50/100 against 60/100: z = 1.4213.
+        assert_close(
+            proportions_p_value(50, 100, 60, 100).unwrap(),
+            0.155_218_489_684_684_02,
+            1e-6,
+        );
+        assert_eq!(
+            proportions_p_value(50, 100, 60, 100),
+            proportions_p_value(60, 100, 50, 100)
+        );
+        assert_close(proportions_p_value(5, 100, 10, 200).unwrap(), 1.0, 1e-9);
+
+        // All or nothing on both sides.
+        assert_eq!(proportions_p_value(0, 100, 0, 50), Some(1.0));
+        assert_eq!(proportions_p_value(100, 100, 50, 50), Some(1.0));
+
+        assert_eq!(proportions_p_value(0, 0, 1, 10), None);
+        assert_eq!(proportions_p_value(1, 10, 0, 0), None);
+    }
+
+    #[test]
+    fn test_compare_metric() {
+        let thresholds = RegressionThresholds::default();
+        let compare = |old, new, p_value, higher_is_better| {
+            compare_metric("w", "m", old, new, p_value, higher_is_better, thresholds)
+        };
+
+        // Higher is better: a significant 10% drop is a regression, a rise is not.
+        let cmp = compare(100.0, 90.0, Some(0.01), true);
+        assert_close(cmp.change, -0.1, 1e-12);
+        assert!(cmp.regression);
+        assert!(!compare(100.0, 110.0, Some(0.01), true).regression);
+
+        // Lower is better: the other way around.
+        assert!(compare(100.0, 110.0, Some(0.01), false).regression);
+        assert!(!compare(100.0, 90.0, Some(0.01), false).regression);
+
+        // Not significant, or too small a change.
+        assert!(!compare(100.0, 90.0, Some(0.2), true).regression);
+        assert!(!compare(100.0, 98.0, Some(0.01), true).regression);
+
+        // Metrics that can't be tested are judged on the change alone.
+        assert!(compare(100.0, 90.0, None, true).regression);
+
+        // Changes from zero.
+        assert_eq!(compare(0.0, 0.0, None, false).change, 0.0);
+        let cmp = compare(0.0, 0.5, None, false);
+        assert_eq!(cmp.change, f64::INFINITY);
+        assert!(cmp.regression);
+        assert!(!compare(0.0, 0.5, None, true).regression);
+    }
+}
@@ -6,6 +6,7 @@ use clap::*;
 use strum_macros::EnumString;
 
 use crate::drivers::Interval;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Parser)]
@@ -90,6 +91,10 @@ pub struct Opts {
     /// Path where previous benchmark stats is stored to use for comparison
     #[clap(long, default_value = "", global = true)]
     pub compare_with: String,
+    /// Path to write a JSON report of the run to, with per-workload statistics and the
+    /// configuration it ran with. Reports can be compared with `stress-compare`.
+    #[clap(long, default_value = "", global = true)]
+    pub report_path: String,
     // Stat collection interval seconds
     #[clap(long, default_value = "10", global = true)]
     pub stat_collection_interval: u64,
@@ -178,6 +183,15 @@ pub enum RunSpec {
         // relative weight of adversarial transactions in the benchmark workload
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
         shared_deletion: Vec<u32>,
+        // relative weight of AMM swap transactions in the benchmark workload
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        amm_swap: Vec<u32>,
+        // relative weight of DeepBook order placement transactions in the benchmark workload
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        deepbook: Vec<u32>,
+        // relative weight of transactions from the workload described by `plugin_spec`
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [0])]
+        plugin: Vec<u32>,
 
         // --- workload-specific options --- (TODO: use subcommands or similar)
         // 100 for max hotness i.e all requests target
@@ -207,6 +221,18 @@ pub enum RunSpec {
         // Default is (0-0.5) implying random load at 50% load. See `AdversarialPayloadType` enum for `adversarial_type`
         #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = ["0-1.0".to_string()])]
         adversarial_cfg: Vec<String>,
+        // The number of AMM pools to create. Every swap goes to one of them at random, so fewer
+        // pools means more contention, with a single pool being the hottest possible.
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [1])]
+        amm_swap_num_pools: Vec<u64>,
+        // The number of DeepBook pools to create. Every order is placed on one of them at random.
+        #[clap(long, num_args(1..), value_delimiter = ',', default_values_t = [1])]
+        deepbook_num_pools: Vec<u64>,
+        // Path to a spec (YAML or JSON) describing a workload as a Move package to publish, and
+        // weighted programmable transaction templates that call into it. See `workloads::plugin`
+        // for the format, and `workloads/data/plugins` for an example.
+        #[clap(long, num_args(1..), value_delimiter = ',')]
+        plugin_spec: Option<Vec<PathBuf>>,
 
         // --- generic options ---
         // Target qps
@@ -0,0 +1,269 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::Interval;
+use crate::in_memory_wallet::move_call_pt_impl;
+use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{BenchMoveCallArg, ExecutionEffects, ValidatorProxy};
+use async_trait::async_trait;
+use futures::future::join_all;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::crypto::get_key_pair;
+use sui_types::object::Owner;
+use sui_types::transaction::{Transaction, TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE};
+use tracing::{error, info};
+
+/// Reserves of each asset that every pool starts with.
+const INITIAL_RESERVES: u64 = 1_000_000_000_000;
+
+/// Swaps are for a random amount of up to this much of the input asset. Payloads alternate
+/// between swapping X for Y and Y for X, so that reserves stay roughly balanced over time.
+const MAX_SWAP_AMOUNT: u64 = 1_000_000;
+
+#[derive(Debug)]
+pub struct AmmSwapTestPayload {
+    package_id: ObjectID,
+    pool_id: ObjectID,
+    pool_initial_shared_version: SequenceNumber,
+    x_to_y: bool,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for AmmSwapTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "amm_swap")
+    }
+}
+
+impl Payload for AmmSwapTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("AMM swap tx failed... Status: {:?}", effects.status());
+        }
+        self.gas.0 = effects.gas_object().0;
+        self.x_to_y = !self.x_to_y;
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        let rgp = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        let amount_in = rand::thread_rng().gen_range(1..=MAX_SWAP_AMOUNT);
+        move_call_pt_impl(
+            self.gas.1,
+            &self.gas.2,
+            self.package_id,
+            "amm",
+            "swap",
+            vec![],
+            vec![
+                BenchMoveCallArg::Shared((self.pool_id, self.pool_initial_shared_version, true)),
+                amount_in.into(),
+                self.x_to_y.into(),
+            ],
+            &self.gas.0,
+            rgp * TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+            rgp,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct AmmSwapWorkloadBuilder {
+    num_pools: u64,
+    num_payloads: u64,
+}
+
+impl AmmSwapWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        num_pools: u64,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 || num_pools == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                group,
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+            };
+            let workload_builder =
+                Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(AmmSwapWorkloadBuilder {
+                    num_pools,
+                    num_payloads: max_ops,
+                }));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for AmmSwapWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        // Gas coin for publishing the AMM package, followed by one for creating each pool
+        (0..=self.num_pools)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        (0..self.num_payloads)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(AmmSwapWorkload {
+            package_id: None,
+            pools: vec![],
+            init_gas,
+            payload_gas,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct AmmSwapWorkload {
+    pub package_id: Option<ObjectID>,
+    pub pools: Vec<(ObjectID, SequenceNumber)>,
+    pub init_gas: Vec<Gas>,
+    pub payload_gas: Vec<Gas>,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for AmmSwapWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.package_id.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let (head, tail) = self
+            .init_gas
+            .split_first()
+            .expect("Not enough gas to initialize AMM swap workload");
+
+        info!("Publishing AMM package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/workloads/data/amm");
+        let transaction = TestTransactionBuilder::new(head.1, head.0, gas_price)
+            .publish(path)
+            .build_and_sign(head.2.as_ref());
+        let effects = proxy.execute_transaction_block(transaction).await.unwrap();
+        let package_id = effects
+            .created()
+            .iter()
+            .find(|(_, owner)| matches!(owner, Owner::Immutable))
+            .map(|((id, _, _), _)| *id)
+            .expect("Publishing the AMM package should create a package object");
+        self.package_id = Some(package_id);
+        info!("AMM package id {:?}", package_id);
+
+        let futures = tail.iter().map(|(gas, sender, keypair)| {
+            let transaction = move_call_pt_impl(
+                *sender,
+                keypair,
+                package_id,
+                "amm",
+                "create_pool",
+                vec![],
+                vec![INITIAL_RESERVES.into(), INITIAL_RESERVES.into()],
+                gas,
+                gas_price * TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+                gas_price,
+            );
+            let proxy = proxy.clone();
+            async move {
+                let effects = proxy
+                    .execute_transaction_block(transaction)
+                    .await
+                    .expect("Failed to create AMM pool!");
+                effects
+                    .created()
+                    .into_iter()
+                    .find_map(|((id, _, _), owner)| match owner {
+                        Owner::Shared {
+                            initial_shared_version,
+                        } => Some((id, initial_shared_version)),
+                        _ => None,
+                    })
+                    .expect("Creating an AMM pool should create a shared object")
+            }
+        });
+        self.pools = join_all(futures).await;
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!("Creating AMM swap payloads, hang tight..");
+        let mut rng = rand::thread_rng();
+        self.payload_gas
+            .iter()
+            .map(|gas| {
+                // pick a random pool to swap against
+                let (pool_id, pool_initial_shared_version) = *self
+                    .pools
+                    .choose(&mut rng)
+                    .expect("Failed to get a random AMM pool");
+                Box::<dyn Payload>::from(Box::new(AmmSwapTestPayload {
+                    package_id: self.package_id.unwrap(),
+                    pool_id,
+                    pool_initial_shared_version,
+                    x_to_y: rng.gen(),
+                    gas: gas.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A constant product market maker over virtual reserves. Pools don't hold any coins, so that
+/// swapping only costs the swapper gas, but every swap reads and writes the pool's reserves the
+/// same way a real AMM would, which makes each pool a hot shared object.
+module amm::amm {
+    use sui::event;
+    use sui::object::{Self, ID, UID};
+    use sui::transfer;
+    use sui::tx_context::TxContext;
+
+    /// Trying to swap nothing, or more than the pool can pay out.
+    const EInvalidSwap: u64 = 0;
+
+    /// Fee taken by the pool, in basis points.
+    const FEE_BPS: u128 = 30;
+
+    struct Pool has key {
+        id: UID,
+        reserve_x: u64,
+        reserve_y: u64,
+        swaps: u64,
+    }
+
+    struct Swapped has copy, drop {
+        pool: ID,
+        x_to_y: bool,
+        amount_in: u64,
+        amount_out: u64,
+    }
+
+    public fun create_pool(reserve_x: u64, reserve_y: u64, ctx: &mut TxContext) {
+        transfer::share_object(Pool {
+            id: object::new(ctx),
+            reserve_x,
+            reserve_y,
+            swaps: 0,
+        })
+    }
+
+    /// Swap `amount_in` of X for Y if `x_to_y`, or `amount_in` of Y for X otherwise, returning
+    /// the amount paid out.
+    public fun swap(pool: &mut Pool, amount_in: u64, x_to_y: bool): u64 {
+        let (reserve_in, reserve_out) = if (x_to_y) {
+            (pool.reserve_x, pool.reserve_y)
+        } else {
+            (pool.reserve_y, pool.reserve_x)
+        };
+
+        let amount_in_after_fee = (amount_in as u128) * (10_000 - FEE_BPS);
+        let amount_out = (
+            amount_in_after_fee * (reserve_out as u128)
+                / ((reserve_in as u128) * 10_000 + amount_in_after_fee)
+        as u64);
+        assert!(amount_in > 0 && amount_out > 0 && amount_out < reserve_out, EInvalidSwap);
+
+        if (x_to_y) {
+            pool.reserve_x = pool.reserve_x + amount_in;
+            pool.reserve_y = pool.reserve_y - amount_out;
+        } else {
+            pool.reserve_y = pool.reserve_y + amount_in;
+            pool.reserve_x = pool.reserve_x - amount_out;
+        };
+        pool.swaps = pool.swaps + 1;
+
+        event::emit(Swapped {
+            pool: object::id(pool),
+            x_to_y,
+            amount_in,
+            amount_out,
+        });
+        amount_out
+    }
+}
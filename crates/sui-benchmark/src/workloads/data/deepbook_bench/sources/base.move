@@ -0,0 +1,8 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Base asset for the DeepBook pools created by the benchmark. Orders are only ever placed on the
+/// bid side, paid for in SUI, so the base asset never needs to be minted.
+module deepbook_bench::base {
+    struct BASE has drop {}
+}
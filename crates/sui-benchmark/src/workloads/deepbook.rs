@@ -0,0 +1,430 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::drivers::Interval;
+use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{ExecutionEffects, ProgrammableTransactionBuilder, ValidatorProxy};
+use async_trait::async_trait;
+use futures::future::join_all;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
+use sui_types::crypto::{get_key_pair, AccountKeyPair};
+use sui_types::gas_coin::{GAS, MIST_PER_SUI};
+use sui_types::object::Owner;
+use sui_types::transaction::{
+    Argument, Command, ObjectArg, Transaction, TransactionData,
+    TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+};
+use sui_types::utils::to_sender_signed_transaction;
+use sui_types::{DEEPBOOK_PACKAGE_ID, SUI_CLOCK_OBJECT_ID, SUI_CLOCK_OBJECT_SHARED_VERSION};
+use tracing::{error, info};
+
+/// Fee charged by DeepBook for creating a pool.
+const POOL_CREATION_FEE: u64 = 100 * MIST_PER_SUI;
+
+/// Pools are created with these tick and lot sizes, and orders are always for a multiple of them.
+const TICK_SIZE: u64 = 1_000_000;
+const LOT_SIZE: u64 = 1_000;
+
+/// Bids are placed at a random price of up to this many ticks, for this many lots.
+const MAX_PRICE_TICKS: u64 = 100;
+const ORDER_LOTS: u64 = 100;
+
+/// Amount of SUI each account deposits into the pool to pay for its bids.
+const QUOTE_DEPOSIT: u64 = 10 * MIST_PER_SUI;
+
+/// After placing this many orders, an account cancels all of its open orders, so that the order
+/// book doesn't grow without bound over the course of the benchmark.
+const ORDERS_BEFORE_CANCEL: u64 = 10;
+
+// Constants from `deepbook::clob_v2`
+const CANCEL_OLDEST: u8 = 0;
+const NO_RESTRICTION: u8 = 0;
+
+#[derive(Debug)]
+pub struct DeepBookTestPayload {
+    pool_id: ObjectID,
+    pool_initial_shared_version: SequenceNumber,
+    /// `Pool<BASE, SUI>`'s type arguments
+    type_arguments: Vec<TypeTag>,
+    /// Set once the account has been created and funded by the first transaction.
+    account_cap: Option<ObjectRef>,
+    orders_placed: u64,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for DeepBookTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "deepbook")
+    }
+}
+
+impl DeepBookTestPayload {
+    fn clob_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        function: &str,
+        arguments: Vec<Argument>,
+    ) -> Argument {
+        builder.programmable_move_call(
+            DEEPBOOK_PACKAGE_ID,
+            Identifier::new("clob_v2").unwrap(),
+            Identifier::new(function).unwrap(),
+            self.type_arguments.clone(),
+            arguments,
+        )
+    }
+
+    /// Create an account for the payload's sender, and fund it with SUI to bid with.
+    fn create_account(&self, builder: &mut ProgrammableTransactionBuilder, pool: Argument) {
+        let account_cap = builder.programmable_move_call(
+            DEEPBOOK_PACKAGE_ID,
+            Identifier::new("clob_v2").unwrap(),
+            Identifier::new("create_account").unwrap(),
+            vec![],
+            vec![],
+        );
+        let amount = builder.pure(QUOTE_DEPOSIT).unwrap();
+        let coin = builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+        self.clob_call(builder, "deposit_quote", vec![pool, coin, account_cap]);
+        builder.transfer_arg(self.gas.1, account_cap);
+    }
+
+    /// Place a resting bid, cancelling all of the account's previous orders every so often.
+    fn place_order(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        pool: Argument,
+        account_cap: ObjectRef,
+    ) {
+        let price = rand::thread_rng().gen_range(1..=MAX_PRICE_TICKS) * TICK_SIZE;
+        let arguments = vec![
+            pool,
+            builder.pure(self.orders_placed).unwrap(),
+            builder.pure(price).unwrap(),
+            builder.pure(ORDER_LOTS * LOT_SIZE).unwrap(),
+            builder.pure(CANCEL_OLDEST).unwrap(),
+            builder.pure(true).unwrap(),
+            builder.pure(u64::MAX).unwrap(),
+            builder.pure(NO_RESTRICTION).unwrap(),
+            builder
+                .obj(ObjectArg::SharedObject {
+                    id: SUI_CLOCK_OBJECT_ID,
+                    initial_shared_version: SUI_CLOCK_OBJECT_SHARED_VERSION,
+                    mutable: false,
+                })
+                .unwrap(),
+            builder
+                .obj(ObjectArg::ImmOrOwnedObject(account_cap))
+                .unwrap(),
+        ];
+        let account_cap = arguments[9];
+        self.clob_call(builder, "place_limit_order", arguments);
+
+        if self.orders_placed % ORDERS_BEFORE_CANCEL == ORDERS_BEFORE_CANCEL - 1 {
+            self.clob_call(builder, "cancel_all_orders", vec![pool, account_cap]);
+        }
+    }
+}
+
+impl Payload for DeepBookTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!("DeepBook tx failed... Status: {:?}", effects.status());
+        }
+        self.gas.0 = effects.gas_object().0;
+
+        let sender = Owner::AddressOwner(self.gas.1);
+        match self.account_cap {
+            // The account cap is only created by the first transaction, and is the only object
+            // besides the gas coin that the sender ends up owning.
+            None => {
+                self.account_cap = effects
+                    .created()
+                    .into_iter()
+                    .find(|(_, owner)| *owner == sender)
+                    .map(|(obj_ref, _)| obj_ref);
+            }
+            // Taking the account cap as an input bumps its version, even if the order failed.
+            Some((id, _, _)) => {
+                if let Some((obj_ref, _)) = effects
+                    .mutated()
+                    .into_iter()
+                    .find(|((mutated, _, _), _)| *mutated == id)
+                {
+                    self.account_cap = Some(obj_ref);
+                }
+                if effects.is_ok() {
+                    self.orders_placed += 1;
+                }
+            }
+        }
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        let rgp = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let pool = builder
+            .obj(ObjectArg::SharedObject {
+                id: self.pool_id,
+                initial_shared_version: self.pool_initial_shared_version,
+                mutable: true,
+            })
+            .unwrap();
+        match self.account_cap {
+            None => self.create_account(&mut builder, pool),
+            Some(account_cap) => self.place_order(&mut builder, pool, account_cap),
+        }
+
+        let data = TransactionData::new_programmable(
+            self.gas.1,
+            vec![self.gas.0],
+            builder.finish(),
+            rgp * TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+            rgp,
+        );
+        to_sender_signed_transaction(data, self.gas.2.as_ref())
+    }
+}
+
+#[derive(Debug)]
+pub struct DeepBookWorkloadBuilder {
+    num_pools: u64,
+    num_payloads: u64,
+}
+
+impl DeepBookWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        num_pools: u64,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 || num_pools == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                group,
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+            };
+            let workload_builder =
+                Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(DeepBookWorkloadBuilder {
+                    num_pools,
+                    num_payloads: max_ops,
+                }));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for DeepBookWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        // Gas coin for publishing the base asset's package, followed by one for creating each
+        // pool, which also pays the pool creation fee.
+        (0..=self.num_pools)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        (0..self.num_payloads)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(DeepBookWorkload {
+            base_type: None,
+            pools: vec![],
+            init_gas,
+            payload_gas,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct DeepBookWorkload {
+    /// Type of the pools' base asset, from the package published during init.
+    pub base_type: Option<TypeTag>,
+    pub pools: Vec<(ObjectID, SequenceNumber)>,
+    pub init_gas: Vec<Gas>,
+    pub payload_gas: Vec<Gas>,
+}
+
+impl DeepBookWorkload {
+    fn type_arguments(&self) -> Vec<TypeTag> {
+        vec![self.base_type.clone().unwrap(), GAS::type_tag()]
+    }
+
+    async fn create_pool(
+        &self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        gas: ObjectRef,
+        sender: SuiAddress,
+        keypair: &AccountKeyPair,
+        gas_price: u64,
+    ) -> (ObjectID, SequenceNumber) {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let fee = builder.pure(POOL_CREATION_FEE).unwrap();
+        let creation_fee = builder.command(Command::SplitCoins(Argument::GasCoin, vec![fee]));
+        let arguments = vec![
+            builder.pure(TICK_SIZE).unwrap(),
+            builder.pure(LOT_SIZE).unwrap(),
+            creation_fee,
+        ];
+        builder.programmable_move_call(
+            DEEPBOOK_PACKAGE_ID,
+            Identifier::new("clob_v2").unwrap(),
+            Identifier::new("create_pool").unwrap(),
+            self.type_arguments(),
+            arguments,
+        );
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![gas],
+            builder.finish(),
+            gas_price * TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+            gas_price,
+        );
+
+        let effects = proxy
+            .execute_transaction_block(to_sender_signed_transaction(data, keypair))
+            .await
+            .expect("Failed to create DeepBook pool!");
+        assert!(
+            effects.is_ok(),
+            "Failed to create DeepBook pool: {}",
+            effects.status()
+        );
+        effects
+            .created()
+            .into_iter()
+            .find_map(|((id, _, _), owner)| match owner {
+                Owner::Shared {
+                    initial_shared_version,
+                } => Some((id, initial_shared_version)),
+                _ => None,
+            })
+            .expect("Creating a DeepBook pool should create a shared object")
+    }
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for DeepBookWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.base_type.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let (head, tail) = self
+            .init_gas
+            .split_first()
+            .expect("Not enough gas to initialize DeepBook workload");
+
+        info!("Publishing DeepBook benchmark package");
+        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("src/workloads/data/deepbook_bench");
+        let transaction = TestTransactionBuilder::new(head.1, head.0, gas_price)
+            .publish(path)
+            .build_and_sign(head.2.as_ref());
+        let effects = proxy.execute_transaction_block(transaction).await.unwrap();
+        let package_id = effects
+            .created()
+            .iter()
+            .find(|(_, owner)| matches!(owner, Owner::Immutable))
+            .map(|((id, _, _), _)| *id)
+            .expect("Publishing the DeepBook benchmark package should create a package object");
+        self.base_type = Some(TypeTag::Struct(Box::new(StructTag {
+            address: package_id.into(),
+            module: Identifier::new("base").unwrap(),
+            name: Identifier::new("BASE").unwrap(),
+            type_params: vec![],
+        })));
+
+        let this = &*self;
+        let futures = tail.iter().map(|(gas, sender, keypair)| {
+            this.create_pool(proxy.clone(), *gas, *sender, keypair, gas_price)
+        });
+        self.pools = join_all(futures).await;
+        info!("Created {} DeepBook pools", self.pools.len());
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!("Creating DeepBook payloads, hang tight..");
+        self.payload_gas
+            .iter()
+            .map(|gas| {
+                // pick a random pool to place orders on
+                let (pool_id, pool_initial_shared_version) = *self
+                    .pools
+                    .choose(&mut rand::thread_rng())
+                    .expect("Failed to get a random DeepBook pool");
+                Box::<dyn Payload>::from(Box::new(DeepBookTestPayload {
+                    pool_id,
+                    pool_initial_shared_version,
+                    type_arguments: self.type_arguments(),
+                    account_cap: None,
+                    orders_placed: 0,
+                    gas: gas.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
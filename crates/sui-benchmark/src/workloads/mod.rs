@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod adversarial;
+pub mod amm_swap;
 pub mod batch_payment;
+pub mod deepbook;
 pub mod delegation;
 pub mod payload;
+pub mod plugin;
 pub mod shared_counter;
 pub mod shared_object_deletion;
 pub mod transfer_object;
@@ -0,0 +1,819 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Workloads defined by a spec file rather than in Rust.
+//!
+//! A spec names a Move package, which is published when the workload is initialized, some setup
+//! calls to make once the package is published (e.g. to create the shared objects that the
+//! benchmark contends on), and a set of weighted programmable transaction templates. Every
+//! transaction the workload sends is built from one of the templates, chosen at random according
+//! to their weights. For example:
+//!
+//! ```yaml
+//! name: amm_swap
+//! package: ../amm
+//! setup:
+//!   - module: amm
+//!     function: create_pool
+//!     arguments: [{ u64: 1000000000000 }, { u64: 1000000000000 }]
+//!     repeat: 4
+//! transactions:
+//!   - weight: 1
+//!     commands:
+//!       - module: amm
+//!         function: swap
+//!         arguments:
+//!           - shared: { type: "$package::amm::Pool" }
+//!           - random_u64: { min: 1, max: 1000000 }
+//!           - bool: true
+//! ```
+//!
+//! The package's path is relative to the spec. Wherever a type is expected, `$package` stands for
+//! the ID of the published package.
+
+use crate::drivers::Interval;
+use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{ExecutionEffects, ProgrammableTransactionBuilder, ValidatorProxy};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use async_trait::async_trait;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use sui_test_transaction_builder::TestTransactionBuilder;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
+use sui_types::crypto::{get_key_pair, AccountKeyPair};
+use sui_types::object::Owner;
+use sui_types::transaction::{
+    Argument, ObjectArg, Transaction, TransactionData,
+    TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+};
+use sui_types::utils::to_sender_signed_transaction;
+use sui_types::{
+    parse_sui_struct_tag, parse_sui_type_tag, SUI_CLOCK_OBJECT_ID, SUI_CLOCK_OBJECT_SHARED_VERSION,
+};
+use tracing::{error, info};
+
+/// Placeholder for the published package's ID in the types mentioned by a spec.
+const PACKAGE_PLACEHOLDER: &str = "$package";
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WorkloadPluginSpec {
+    /// Name of the workload, used to label its transactions in metrics.
+    pub name: String,
+    /// Path to the Move package to publish.
+    pub package: PathBuf,
+    /// Calls to make, in order, once the package is published.
+    #[serde(default)]
+    pub setup: Vec<SetupCall>,
+    /// Templates for the transactions sent by the workload.
+    pub transactions: Vec<TransactionTemplate>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SetupCall {
+    #[serde(flatten)]
+    pub call: MoveCallTemplate,
+    /// Number of times to make the call.
+    #[serde(default = "default_repeat")]
+    pub repeat: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionTemplate {
+    /// Relative weight of this template among the spec's templates.
+    pub weight: u32,
+    pub commands: Vec<MoveCallTemplate>,
+    /// Indices of commands whose results are transferred back to the sender.
+    #[serde(default)]
+    pub transfer_to_sender: Vec<u16>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MoveCallTemplate {
+    /// Package to call into, defaults to the published package.
+    pub package: Option<ObjectID>,
+    pub module: String,
+    pub function: String,
+    #[serde(default)]
+    pub type_arguments: Vec<String>,
+    #[serde(default)]
+    pub arguments: Vec<ArgumentTemplate>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgumentTemplate {
+    Bool(bool),
+    U8(u8),
+    U64(u64),
+    U128(u128),
+    Address(SuiAddress),
+    /// The address of the transaction's sender.
+    Sender,
+    /// A `u64` chosen uniformly at random from `min..=max`, every time a transaction is built.
+    RandomU64 {
+        min: u64,
+        max: u64,
+    },
+    /// One of the shared objects of type `type` created during setup, chosen at random.
+    Shared {
+        #[serde(rename = "type")]
+        type_: String,
+        #[serde(default = "default_mutable")]
+        mutable: bool,
+    },
+    /// The `0x6::clock::Clock` shared object.
+    Clock,
+    GasCoin,
+    /// The result of an earlier command in the same transaction.
+    Result(u16),
+    NestedResult(u16, u16),
+}
+
+fn default_repeat() -> u64 {
+    1
+}
+
+fn default_mutable() -> bool {
+    true
+}
+
+impl WorkloadPluginSpec {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workload spec {}", path.display()))?;
+        let mut spec: WorkloadPluginSpec = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse workload spec {}", path.display()))?;
+
+        if spec.package.is_relative() {
+            if let Some(dir) = path.parent() {
+                spec.package = dir.join(&spec.package);
+            }
+        }
+
+        spec.validate()
+            .with_context(|| format!("Invalid workload spec {}", path.display()))?;
+        Ok(spec)
+    }
+
+    /// Load the spec for each of `num_groups` benchmark groups, from `paths` (one per group) if
+    /// any were given.
+    pub fn load_for_groups(
+        paths: Option<&[PathBuf]>,
+        num_groups: u32,
+    ) -> Result<Vec<Option<Self>>> {
+        let Some(paths) = paths else {
+            return Ok(vec![None; num_groups as usize]);
+        };
+
+        ensure!(
+            paths.len() == num_groups as usize,
+            "Expected one --plugin-spec per benchmark group ({num_groups}), got {}",
+            paths.len()
+        );
+        paths
+            .iter()
+            .map(|path| Self::load(path).map(Some))
+            .collect()
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.transactions.iter().any(|t| t.weight > 0),
+            "At least one transaction template needs a non-zero weight"
+        );
+
+        for call in &self.setup {
+            call.call.validate(0)?;
+        }
+
+        for template in &self.transactions {
+            ensure!(
+                !template.commands.is_empty(),
+                "Transaction templates need at least one command"
+            );
+            for (i, call) in template.commands.iter().enumerate() {
+                call.validate(i as u16)?;
+            }
+            for i in &template.transfer_to_sender {
+                ensure!(
+                    (*i as usize) < template.commands.len(),
+                    "Cannot transfer the result of command {i}, which does not exist"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MoveCallTemplate {
+    /// Check that the call is well-formed, as the `index`-th command of its transaction.
+    fn validate(&self, index: u16) -> Result<()> {
+        Identifier::new(self.module.as_str())?;
+        Identifier::new(self.function.as_str())?;
+        for type_ in &self.type_arguments {
+            resolve_type_tag(type_, ObjectID::ZERO)?;
+        }
+
+        for argument in &self.arguments {
+            match argument {
+                ArgumentTemplate::Result(i) | ArgumentTemplate::NestedResult(i, _) => ensure!(
+                    *i < index,
+                    "Command {index} can only use the results of earlier commands, not {i}"
+                ),
+                ArgumentTemplate::RandomU64 { min, max } => {
+                    ensure!(min <= max, "Empty range for random argument: {min}..={max}")
+                }
+                ArgumentTemplate::Shared { type_, .. } => {
+                    resolve_struct_tag(type_, ObjectID::ZERO)?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn resolve_type_tag(type_: &str, package_id: ObjectID) -> Result<TypeTag> {
+    parse_sui_type_tag(&type_.replace(PACKAGE_PLACEHOLDER, &package_id.to_hex_uncompressed()))
+}
+
+fn resolve_struct_tag(type_: &str, package_id: ObjectID) -> Result<StructTag> {
+    parse_sui_struct_tag(&type_.replace(PACKAGE_PLACEHOLDER, &package_id.to_hex_uncompressed()))
+}
+
+/// Whether an object of type `tag` can be used where `pattern` is expected. Patterns without type
+/// parameters match any instantiation of their type.
+fn matches_type(pattern: &StructTag, tag: &StructTag) -> bool {
+    pattern.address == tag.address
+        && pattern.module == tag.module
+        && pattern.name == tag.name
+        && (pattern.type_params.is_empty() || pattern.type_params == tag.type_params)
+}
+
+/// A spec's Move call, with its types resolved against the published package.
+#[derive(Debug, Clone)]
+struct ResolvedCall {
+    package: ObjectID,
+    module: Identifier,
+    function: Identifier,
+    type_arguments: Vec<TypeTag>,
+    arguments: Vec<ArgumentTemplate>,
+}
+
+impl ResolvedCall {
+    fn new(call: &MoveCallTemplate, package_id: ObjectID) -> Result<Self> {
+        Ok(Self {
+            package: call.package.unwrap_or(package_id),
+            module: Identifier::new(call.module.as_str())?,
+            function: Identifier::new(call.function.as_str())?,
+            type_arguments: call
+                .type_arguments
+                .iter()
+                .map(|type_| resolve_type_tag(type_, package_id))
+                .collect::<Result<_>>()?,
+            arguments: call.arguments.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ResolvedTemplate {
+    commands: Vec<ResolvedCall>,
+    transfer_to_sender: Vec<u16>,
+}
+
+/// Everything a payload needs to build transactions from the spec's templates.
+#[derive(Debug)]
+struct ResolvedSpec {
+    name: String,
+    templates: Vec<ResolvedTemplate>,
+    weights: WeightedIndex<u32>,
+    /// Shared objects created during setup, by the type string that the spec refers to them by.
+    shared_objects: BTreeMap<String, Vec<(ObjectID, SequenceNumber)>>,
+}
+
+impl ResolvedSpec {
+    /// Add `call` to `builder`, on behalf of `sender`.
+    fn add_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        call: &ResolvedCall,
+        sender: SuiAddress,
+    ) -> Result<Argument> {
+        let mut rng = rand::thread_rng();
+        let mut arguments = Vec::with_capacity(call.arguments.len());
+        for argument in &call.arguments {
+            arguments.push(match argument {
+                ArgumentTemplate::Bool(b) => builder.pure(b)?,
+                ArgumentTemplate::U8(n) => builder.pure(n)?,
+                ArgumentTemplate::U64(n) => builder.pure(n)?,
+                ArgumentTemplate::U128(n) => builder.pure(n)?,
+                ArgumentTemplate::Address(a) => builder.pure(a)?,
+                ArgumentTemplate::Sender => builder.pure(sender)?,
+                ArgumentTemplate::RandomU64 { min, max } => {
+                    builder.pure(rng.gen_range(*min..=*max))?
+                }
+                ArgumentTemplate::Shared { type_, mutable } => {
+                    let (id, initial_shared_version) = *self
+                        .shared_objects
+                        .get(type_)
+                        .and_then(|objects| objects.choose(&mut rng))
+                        .ok_or_else(|| anyhow!("No shared objects of type {type_}"))?;
+                    builder.obj(ObjectArg::SharedObject {
+                        id,
+                        initial_shared_version,
+                        mutable: *mutable,
+                    })?
+                }
+                ArgumentTemplate::Clock => builder.obj(ObjectArg::SharedObject {
+                    id: SUI_CLOCK_OBJECT_ID,
+                    initial_shared_version: SUI_CLOCK_OBJECT_SHARED_VERSION,
+                    mutable: false,
+                })?,
+                ArgumentTemplate::GasCoin => Argument::GasCoin,
+                ArgumentTemplate::Result(i) => Argument::Result(*i),
+                ArgumentTemplate::NestedResult(i, j) => Argument::NestedResult(*i, *j),
+            });
+        }
+
+        Ok(builder.programmable_move_call(
+            call.package,
+            call.module.clone(),
+            call.function.clone(),
+            call.type_arguments.clone(),
+            arguments,
+        ))
+    }
+
+    fn make_transaction(
+        &self,
+        template: &ResolvedTemplate,
+        gas: &ObjectRef,
+        sender: SuiAddress,
+        keypair: &AccountKeyPair,
+        gas_price: u64,
+    ) -> Result<Transaction> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let mut results = Vec::with_capacity(template.commands.len());
+        for call in &template.commands {
+            results.push(self.add_call(&mut builder, call, sender)?);
+        }
+
+        let transferred: Vec<_> = template
+            .transfer_to_sender
+            .iter()
+            .map(|i| results[*i as usize])
+            .collect();
+        if !transferred.is_empty() {
+            builder.transfer_args(sender, transferred);
+        }
+
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![*gas],
+            builder.finish(),
+            gas_price * TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+            gas_price,
+        );
+        Ok(to_sender_signed_transaction(data, keypair))
+    }
+}
+
+#[derive(Debug)]
+pub struct PluginTestPayload {
+    spec: Arc<ResolvedSpec>,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for PluginTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.spec.name)
+    }
+}
+
+impl Payload for PluginTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        if !effects.is_ok() {
+            effects.print_gas_summary();
+            error!(
+                "{} tx failed... Status: {:?}",
+                self.spec.name,
+                effects.status()
+            );
+        }
+        self.gas.0 = effects.gas_object().0;
+    }
+
+    fn make_transaction(&mut self) -> Transaction {
+        let rgp = self
+            .system_state_observer
+            .state
+            .borrow()
+            .reference_gas_price;
+        let template = &self.spec.templates[self.spec.weights.sample(&mut rand::thread_rng())];
+        self.spec
+            .make_transaction(template, &self.gas.0, self.gas.1, &self.gas.2, rgp)
+            .expect("Failed to build transaction from workload spec")
+    }
+}
+
+#[derive(Debug)]
+pub struct PluginWorkloadBuilder {
+    spec: WorkloadPluginSpec,
+    num_payloads: u64,
+}
+
+impl PluginWorkloadBuilder {
+    pub fn from(
+        spec: WorkloadPluginSpec,
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        duration: Interval,
+        group: u32,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                group,
+                target_qps,
+                num_workers,
+                max_ops,
+                duration,
+            };
+            let workload_builder =
+                Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(PluginWorkloadBuilder {
+                    spec,
+                    num_payloads: max_ops,
+                }));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for PluginWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        // Gas coin for publishing the package and making the setup calls
+        let (address, keypair) = get_key_pair();
+        vec![GasCoinConfig {
+            amount: MAX_GAS_FOR_TESTING,
+            address,
+            keypair: Arc::new(keypair),
+        }]
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        (0..self.num_payloads)
+            .map(|_| {
+                let (address, keypair) = get_key_pair();
+                GasCoinConfig {
+                    amount: MAX_GAS_FOR_TESTING,
+                    address,
+                    keypair: Arc::new(keypair),
+                }
+            })
+            .collect()
+    }
+
+    async fn build(
+        &self,
+        mut init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(PluginWorkload {
+            spec: self.spec.clone(),
+            resolved: None,
+            init_gas: init_gas.pop().unwrap(),
+            payload_gas,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct PluginWorkload {
+    spec: WorkloadPluginSpec,
+    /// Set once the spec's package has been published and its setup calls made.
+    resolved: Option<Arc<ResolvedSpec>>,
+    pub init_gas: Gas,
+    pub payload_gas: Vec<Gas>,
+}
+
+impl PluginWorkload {
+    /// Publish the spec's package, make its setup calls, and resolve its templates against them.
+    async fn setup(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        gas_price: u64,
+    ) -> Result<ResolvedSpec> {
+        let (mut gas, sender, keypair) = self.init_gas.clone();
+
+        info!("Publishing {} package", self.spec.name);
+        let transaction = TestTransactionBuilder::new(sender, gas, gas_price)
+            .publish(self.spec.package.clone())
+            .build_and_sign(keypair.as_ref());
+        let effects = proxy.execute_transaction_block(transaction).await?;
+        ensure!(effects.is_ok(), "Publish failed: {}", effects.status());
+        gas = effects.gas_object().0;
+        let package_id = effects
+            .created()
+            .iter()
+            .find(|(_, owner)| matches!(owner, Owner::Immutable))
+            .map(|((id, _, _), _)| *id)
+            .ok_or_else(|| anyhow!("Publish did not create a package"))?;
+        info!("{} package id {:?}", self.spec.name, package_id);
+
+        let mut resolved = ResolvedSpec {
+            name: self.spec.name.clone(),
+            templates: vec![],
+            weights: WeightedIndex::new(self.spec.transactions.iter().map(|t| t.weight))?,
+            shared_objects: BTreeMap::new(),
+        };
+
+        // Setup calls are made one after the other, because later calls may use the shared
+        // objects created by earlier ones.
+        let mut created = vec![];
+        for SetupCall { call, repeat } in &self.spec.setup {
+            let call = ResolvedCall::new(call, package_id)?;
+            let template = ResolvedTemplate {
+                commands: vec![call],
+                transfer_to_sender: vec![],
+            };
+            for _ in 0..*repeat {
+                let transaction =
+                    resolved.make_transaction(&template, &gas, sender, &keypair, gas_price)?;
+                let effects = proxy.execute_transaction_block(transaction).await?;
+                ensure!(
+                    effects.is_ok(),
+                    "Setup call {}::{} failed: {}",
+                    template.commands[0].module,
+                    template.commands[0].function,
+                    effects.status()
+                );
+                gas = effects.gas_object().0;
+
+                for ((id, _, _), owner) in effects.created() {
+                    if let Owner::Shared {
+                        initial_shared_version,
+                    } = owner
+                    {
+                        let object = proxy.get_object(id).await?;
+                        if let Some(tag) = object.struct_tag() {
+                            created.push((tag, id, initial_shared_version));
+                        }
+                    }
+                }
+                resolved.shared_objects = shared_objects_by_type(&self.spec, package_id, &created)?;
+            }
+        }
+        self.init_gas.0 = gas;
+
+        for template in &self.spec.transactions {
+            resolved.templates.push(ResolvedTemplate {
+                commands: template
+                    .commands
+                    .iter()
+                    .map(|call| ResolvedCall::new(call, package_id))
+                    .collect::<Result<_>>()?,
+                transfer_to_sender: template.transfer_to_sender.clone(),
+            });
+        }
+
+        // Every shared object type that the transactions use needs at least one object to use.
+        for call in resolved.templates.iter().flat_map(|t| &t.commands) {
+            for argument in &call.arguments {
+                if let ArgumentTemplate::Shared { type_, .. } = argument {
+                    if !resolved.shared_objects.contains_key(type_) {
+                        bail!("Setup did not create any shared objects of type {type_}");
+                    }
+                }
+            }
+        }
+
+        Ok(resolved)
+    }
+}
+
+/// Group the shared objects `created` during setup by the type strings that `spec` uses to refer
+/// to them.
+fn shared_objects_by_type(
+    spec: &WorkloadPluginSpec,
+    package_id: ObjectID,
+    created: &[(StructTag, ObjectID, SequenceNumber)],
+) -> Result<BTreeMap<String, Vec<(ObjectID, SequenceNumber)>>> {
+    let calls = spec
+        .setup
+        .iter()
+        .map(|s| &s.call)
+        .chain(spec.transactions.iter().flat_map(|t| &t.commands));
+
+    let mut objects = BTreeMap::new();
+    for argument in calls.flat_map(|c| &c.arguments) {
+        let ArgumentTemplate::Shared { type_, .. } = argument else {
+            continue;
+        };
+
+        let pattern = resolve_struct_tag(type_, package_id)?;
+        let matching: Vec<_> = created
+            .iter()
+            .filter(|(tag, _, _)| matches_type(&pattern, tag))
+            .map(|(_, id, version)| (*id, *version))
+            .collect();
+        if !matching.is_empty() {
+            objects.insert(type_.clone(), matching);
+        }
+    }
+
+    Ok(objects)
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for PluginWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.resolved.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let resolved = self
+            .setup(proxy, gas_price)
+            .await
+            .unwrap_or_else(|e| panic!("Failed to set up {} workload: {e:?}", self.spec.name));
+        self.resolved = Some(Arc::new(resolved));
+    }
+
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!("Creating {} payloads, hang tight..", self.spec.name);
+        let spec = self
+            .resolved
+            .clone()
+            .expect("Workload must be initialized before creating payloads");
+        self.payload_gas
+            .iter()
+            .map(|gas| {
+                Box::<dyn Payload>::from(Box::new(PluginTestPayload {
+                    spec: spec.clone(),
+                    gas: gas.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_spec_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src/workloads/data/plugins/amm_swap.yaml")
+    }
+
+    fn validate(yaml: &str) -> Result<()> {
+        serde_yaml::from_str::<WorkloadPluginSpec>(yaml)?.validate()
+    }
+
+    fn spec_with_transactions(transactions: &str) -> String {
+        format!("name: test\npackage: ../test\ntransactions:\n{transactions}")
+    }
+
+    #[test]
+    fn test_load_example_spec() {
+        let path = example_spec_path();
+        let spec = WorkloadPluginSpec::load(&path).unwrap();
+
+        assert_eq!(spec.name, "amm_swap_plugin");
+        assert_eq!(spec.package, path.parent().unwrap().join("../amm"));
+        assert!(spec.package.join("Move.toml").exists());
+
+        assert_eq!(spec.setup.len(), 1);
+        assert_eq!(spec.setup[0].call.function, "create_pool");
+        assert_eq!(spec.setup[0].repeat, 4);
+
+        let weights: Vec<_> = spec.transactions.iter().map(|t| t.weight).collect();
+        assert_eq!(weights, vec![3, 1]);
+        assert_eq!(spec.transactions[1].commands.len(), 2);
+        assert!(matches!(
+            spec.transactions[0].commands[0].arguments[..],
+            [
+                ArgumentTemplate::Shared { mutable: true, .. },
+                ArgumentTemplate::RandomU64 {
+                    min: 1,
+                    max: 1000000
+                },
+                ArgumentTemplate::Bool(true),
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_load_missing_spec() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("does_not_exist.yaml");
+        let err = WorkloadPluginSpec::load(&path).unwrap_err();
+        assert!(err.to_string().contains("Failed to read workload spec"));
+    }
+
+    #[test]
+    fn test_validate() {
+        let call = "{ module: m, function: f }";
+        validate(&spec_with_transactions(&format!(
+            "  - {{ weight: 1, commands: [{call}] }}"
+        )))
+        .unwrap();
+
+        // Unknown fields are rejected, rather than silently ignored.
+        assert!(validate(&spec_with_transactions(&format!(
+            "  - {{ weight: 1, commands: [{call}], wieght: 2 }}"
+        )))
+        .is_err());
+
+        for (transactions, error) in [
+            (
+                format!("  - {{ weight: 0, commands: [{call}] }}"),
+                "non-zero weight",
+            ),
+            ("  - { weight: 1, commands: [] }".to_string(), "at least one command"),
+            (
+                "  - { weight: 1, commands: [{ module: m, function: f, arguments: [result: 0] }] }"
+                    .to_string(),
+                "earlier commands",
+            ),
+            (
+                format!("  - {{ weight: 1, commands: [{call}], transfer_to_sender: [1] }}"),
+                "does not exist",
+            ),
+            (
+                "  - { weight: 1, commands: [{ module: m, function: f, arguments: [random_u64: { min: 2, max: 1 }] }] }"
+                    .to_string(),
+                "Empty range",
+            ),
+        ] {
+            let err = validate(&spec_with_transactions(&transactions)).unwrap_err();
+            assert!(
+                err.to_string().contains(error),
+                "expected {error:?} in {err:?}"
+            );
+        }
+
+        // Identifiers and types are checked up front.
+        for command in [
+            "{ module: not-an-identifier, function: f }",
+            "{ module: m, function: f, type_arguments: [\"$package::m::\"] }",
+            "{ module: m, function: f, arguments: [shared: { type: u64 }] }",
+        ] {
+            assert!(validate(&spec_with_transactions(&format!(
+                "  - {{ weight: 1, commands: [{command}] }}"
+            )))
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_load_for_groups() {
+        let specs = WorkloadPluginSpec::load_for_groups(None, 2).unwrap();
+        assert_eq!(specs.len(), 2);
+        assert!(specs.iter().all(Option::is_none));
+
+        let paths = vec![example_spec_path(), example_spec_path()];
+        let specs = WorkloadPluginSpec::load_for_groups(Some(&paths), 2).unwrap();
+        assert!(specs.iter().all(Option::is_some));
+
+        // Fewer (or more) specs than groups is an error, rather than a panic.
+        let err = WorkloadPluginSpec::load_for_groups(Some(&paths[..1]), 2).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("one --plugin-spec per benchmark group"));
+        assert!(WorkloadPluginSpec::load_for_groups(Some(&paths), 1).is_err());
+    }
+}
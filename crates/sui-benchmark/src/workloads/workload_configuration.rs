@@ -5,12 +5,15 @@ use crate::bank::BenchmarkBank;
 use crate::drivers::Interval;
 use crate::options::{Opts, RunSpec};
 use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::amm_swap::AmmSwapWorkloadBuilder;
 use crate::workloads::batch_payment::BatchPaymentWorkloadBuilder;
+use crate::workloads::deepbook::DeepBookWorkloadBuilder;
 use crate::workloads::delegation::DelegationWorkloadBuilder;
+use crate::workloads::plugin::{PluginWorkloadBuilder, WorkloadPluginSpec};
 use crate::workloads::shared_counter::SharedCounterWorkloadBuilder;
 use crate::workloads::transfer_object::TransferObjectWorkloadBuilder;
 use crate::workloads::{GroupID, WorkloadBuilderInfo, WorkloadInfo};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -39,11 +42,17 @@ impl WorkloadConfiguration {
                 delegation,
                 batch_payment,
                 adversarial,
+                amm_swap,
+                deepbook,
+                plugin,
                 shared_counter_hotness_factor,
                 num_shared_counters,
                 shared_counter_max_tip,
                 batch_payment_size,
                 adversarial_cfg,
+                amm_swap_num_pools,
+                deepbook_num_pools,
+                plugin_spec,
                 target_qps,
                 num_workers,
                 in_flight_ratio,
@@ -54,10 +63,19 @@ impl WorkloadConfiguration {
                     num_of_benchmark_groups
                 );
 
+                let mut plugin_specs = WorkloadPluginSpec::load_for_groups(
+                    plugin_spec.as_deref(),
+                    num_of_benchmark_groups,
+                )?;
+
                 // Creating the workload builders for each benchmark group. The workloads for each
                 // benchmark group will run in the same time for the same duration.
                 for workload_group in 0..num_of_benchmark_groups {
                     let i = workload_group as usize;
+                    let plugin_spec = plugin_specs[i].take();
+                    if plugin[i] > 0 && plugin_spec.is_none() {
+                        bail!("A plugin workload needs a spec, passed with --plugin-spec");
+                    }
                     let builders = Self::create_workload_builders(
                         workload_group,
                         num_workers[i],
@@ -68,11 +86,17 @@ impl WorkloadConfiguration {
                         batch_payment[i],
                         shared_deletion[i],
                         adversarial[i],
+                        amm_swap[i],
+                        deepbook[i],
+                        plugin[i],
                         AdversarialPayloadCfg::from_str(&adversarial_cfg[i]).unwrap(),
                         batch_payment_size[i],
                         shared_counter_hotness_factor[i],
                         num_shared_counters.as_ref().map(|n| n[i]),
                         shared_counter_max_tip[i],
+                        amm_swap_num_pools[i],
+                        deepbook_num_pools[i],
+                        plugin_spec,
                         target_qps[i],
                         in_flight_ratio[i],
                         duration[i],
@@ -145,11 +169,17 @@ impl WorkloadConfiguration {
         batch_payment_weight: u32,
         shared_deletion_weight: u32,
         adversarial_weight: u32,
+        amm_swap_weight: u32,
+        deepbook_weight: u32,
+        plugin_weight: u32,
         adversarial_cfg: AdversarialPayloadCfg,
         batch_payment_size: u32,
         shared_counter_hotness_factor: u32,
         num_shared_counters: Option<u64>,
         shared_counter_max_tip: u64,
+        amm_swap_num_pools: u64,
+        deepbook_num_pools: u64,
+        plugin_spec: Option<WorkloadPluginSpec>,
         target_qps: u64,
         in_flight_ratio: u64,
         duration: Interval,
@@ -159,7 +189,10 @@ impl WorkloadConfiguration {
             + transfer_object_weight
             + delegation_weight
             + batch_payment_weight
-            + adversarial_weight;
+            + adversarial_weight
+            + amm_swap_weight
+            + deepbook_weight
+            + plugin_weight;
         let reference_gas_price = system_state_observer.state.borrow().reference_gas_price;
         let mut workload_builders = vec![];
         let shared_workload = SharedCounterWorkloadBuilder::from(
@@ -226,6 +259,38 @@ impl WorkloadConfiguration {
             workload_group,
         );
         workload_builders.push(adversarial_workload);
+        let amm_swap_workload = AmmSwapWorkloadBuilder::from(
+            amm_swap_weight as f32 / total_weight as f32,
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            amm_swap_num_pools,
+            duration,
+            workload_group,
+        );
+        workload_builders.push(amm_swap_workload);
+        let deepbook_workload = DeepBookWorkloadBuilder::from(
+            deepbook_weight as f32 / total_weight as f32,
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            deepbook_num_pools,
+            duration,
+            workload_group,
+        );
+        workload_builders.push(deepbook_workload);
+        if let Some(spec) = plugin_spec {
+            let plugin_workload = PluginWorkloadBuilder::from(
+                spec,
+                plugin_weight as f32 / total_weight as f32,
+                target_qps,
+                num_workers,
+                in_flight_ratio,
+                duration,
+                workload_group,
+            );
+            workload_builders.push(plugin_workload);
+        }
 
         workload_builders
     }
@@ -551,6 +551,9 @@ mod test {
         let delegation_weight = 1;
         let batch_payment_weight = 1;
         let shared_object_deletion_weight = 1;
+        let amm_swap_weight = 1;
+        let deepbook_weight = 1;
+        let plugin_weight = 0;
 
         // Run random payloads at 100% load
         let adversarial_cfg = AdversarialPayloadCfg::from_str("0-1.0").unwrap();
@@ -563,6 +566,8 @@ mod test {
         let shared_counter_hotness_factor = 50;
         let num_shared_counters = Some(1);
         let shared_counter_max_tip = 0;
+        let amm_swap_num_pools = 1;
+        let deepbook_num_pools = 1;
         let gas_request_chunk_size = 100;
 
         let workloads_builders = WorkloadConfiguration::create_workload_builders(
@@ -575,11 +580,17 @@ mod test {
             batch_payment_weight,
             shared_object_deletion_weight,
             adversarial_weight,
+            amm_swap_weight,
+            deepbook_weight,
+            plugin_weight,
             adversarial_cfg,
             batch_payment_size,
             shared_counter_hotness_factor,
             num_shared_counters,
             shared_counter_max_tip,
+            amm_swap_num_pools,
+            deepbook_num_pools,
+            None,
             target_qps,
             in_flight_ratio,
             duration,
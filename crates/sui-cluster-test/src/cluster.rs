@@ -364,6 +364,7 @@ pub async fn new_wallet_context_from_cluster(
             alias: "localnet".to_string(),
             rpc: fullnode_url.into(),
             ws: None,
+            ..Default::default()
         }],
         active_address: Some(address),
         active_env: Some("localnet".to_string()),
@@ -0,0 +1,118 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// `RemoteForkSource` drives its requests with `block_in_place`, which needs a real multi-threaded
+// runtime.
+#![cfg(not(msim))]
+
+use rand::rngs::OsRng;
+use simulacrum::{ForkSource, Simulacrum};
+use sui_json_rpc_types::{SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions};
+use sui_replay::fork_source::RemoteForkSource;
+use sui_types::base_types::ObjectID;
+use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use test_cluster::{TestCluster, TestClusterBuilder};
+use tokio::time::{sleep, Duration};
+
+/// Wait until the transaction with `digest` has been included in a checkpoint, and return it.
+async fn checkpoint_of(test_cluster: &TestCluster, digest: TransactionDigest) -> u64 {
+    let client = test_cluster.wallet.get_client().await.unwrap();
+    loop {
+        let response = client
+            .read_api()
+            .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+            .await
+            .unwrap();
+        if let Some(checkpoint) = response.checkpoint {
+            return checkpoint;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Transfer one MIST from address 0 to address 1, paying for gas with `gas`, and return the
+/// transaction's digest and the IDs of the objects it created.
+async fn transfer_with_gas(
+    test_cluster: &TestCluster,
+    gas: ObjectID,
+) -> (TransactionDigest, Vec<ObjectID>) {
+    let sender = test_cluster.get_address_0();
+    let gas = test_cluster.get_latest_object_ref(&gas).await;
+    let tx_data = test_cluster
+        .test_transaction_builder_with_gas_object(sender, gas)
+        .await
+        .transfer_sui(Some(1), test_cluster.get_address_1())
+        .build();
+    let response = test_cluster.sign_and_execute_transaction(&tx_data).await;
+    let created = response
+        .effects
+        .as_ref()
+        .unwrap()
+        .created()
+        .iter()
+        .map(|created| created.object_id())
+        .collect();
+    (response.digest, created)
+}
+
+async fn fork_source(
+    test_cluster: &TestCluster,
+    checkpoint: CheckpointSequenceNumber,
+) -> RemoteForkSource {
+    RemoteForkSource::new(test_cluster.rpc_url(), Some(checkpoint))
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_reads_objects_at_pre_fork_versions() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let sender = test_cluster.get_address_0();
+    let (coin, _, _) = test_cluster
+        .wallet
+        .get_one_gas_object_owned_by_address(sender)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Fork after one transaction touching the coin, then touch it again.
+    let (before, _) = transfer_with_gas(&test_cluster, coin).await;
+    let fork_checkpoint = checkpoint_of(&test_cluster, before).await;
+    let pre_fork = test_cluster.get_latest_object_ref(&coin).await;
+
+    let (after, created_after) = transfer_with_gas(&test_cluster, coin).await;
+    assert!(checkpoint_of(&test_cluster, after).await > fork_checkpoint);
+    let post_fork = test_cluster.get_latest_object_ref(&coin).await;
+    assert!(post_fork.1 > pre_fork.1);
+
+    let source = fork_source(&test_cluster, fork_checkpoint).await;
+    assert_eq!(source.checkpoint(), fork_checkpoint);
+
+    // The latest version is newer than the fork, so the source walks back to the live one.
+    let object = source.get_object(&coin).unwrap().unwrap();
+    assert_eq!(object.compute_object_reference(), pre_fork);
+
+    // Versions written after the fork are not visible, earlier ones are.
+    assert!(source
+        .get_object_at_version(&coin, post_fork.1)
+        .unwrap()
+        .is_none());
+    let object = source
+        .get_object_at_version(&coin, pre_fork.1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(object.compute_object_reference(), pre_fork);
+
+    // Objects created after the fork did not exist at the fork checkpoint.
+    for id in created_after {
+        assert!(source.get_object(&id).unwrap().is_none());
+    }
+
+    // A simulacrum forked from the source (as `sui-test-validator --fork-url` sets up) sees the
+    // same pre-fork state.
+    let timestamp_ms = source.checkpoint_timestamp_ms().await.unwrap();
+    let simulacrum = Simulacrum::new_forked(OsRng, timestamp_ms, source);
+    let object = simulacrum.store().get_object(&coin).unwrap();
+    assert_eq!(object.compute_object_reference(), pre_fork);
+}
@@ -0,0 +1,205 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::future::join_all;
+use futures::StreamExt;
+use sui_json_rpc_types::SuiTransactionBlockEffectsAPI;
+use sui_keys::keystore::AccountKeystore;
+use sui_macros::sim_test;
+use sui_sdk::gas_pool::{GasPool, GasPoolConfig};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, SuiKeyPair};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::ProgrammableTransaction;
+use test_cluster::{TestCluster, TestClusterBuilder};
+
+fn owner_keypair(test_cluster: &TestCluster) -> SuiKeyPair {
+    let address = test_cluster.get_address_0();
+    let keypair = test_cluster
+        .wallet
+        .config
+        .keystore
+        .get_key(&address)
+        .unwrap();
+    SuiKeyPair::decode_base64(&keypair.encode_base64()).unwrap()
+}
+
+fn transfer_one_mist(recipient: SuiAddress) -> ProgrammableTransaction {
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(recipient, Some(1));
+    builder.finish()
+}
+
+async fn count_coins(test_cluster: &TestCluster, owner: SuiAddress) -> usize {
+    test_cluster
+        .wallet
+        .get_client()
+        .await
+        .unwrap()
+        .coin_read_api()
+        .get_coins_stream(owner, None)
+        .count()
+        .await
+}
+
+#[sim_test]
+async fn test_gas_pool_concurrent_transactions() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let recipient = test_cluster.get_address_1();
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let client = test_cluster.wallet.get_client().await?;
+    let config = GasPoolConfig {
+        num_coins: 4,
+        ..Default::default()
+    };
+    let pool = GasPool::new(client, owner_keypair(&test_cluster), config).await?;
+    assert_eq!(pool.size(), 4);
+    assert_eq!(pool.available(), 4);
+
+    // More transactions than coins, they wait for each other's coins to be released.
+    let responses =
+        join_all((0..12).map(|_| pool.execute(transfer_one_mist(recipient), rgp * 10_000))).await;
+    for response in responses {
+        let effects = response?.effects.unwrap();
+        assert!(effects.status().is_ok());
+    }
+    assert_eq!(pool.size(), 4);
+    assert_eq!(pool.available(), 4);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_gas_pool_recovers_leases() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let owner = test_cluster.get_address_0();
+    let temp_dir = tempfile::tempdir()?;
+    let config = GasPoolConfig {
+        num_coins: 3,
+        journal_path: Some(temp_dir.path().join("gas_pool.json")),
+        ..Default::default()
+    };
+
+    let pool = GasPool::new(
+        test_cluster.wallet.get_client().await?,
+        owner_keypair(&test_cluster),
+        config.clone(),
+    )
+    .await?;
+    let _lease1 = pool.lease().await?;
+    let _lease2 = pool.lease().await?;
+    assert_eq!(pool.available(), 1);
+    // The process holding the leases goes away without releasing them.
+    drop(pool);
+    let coins = count_coins(&test_cluster, owner).await;
+
+    let pool = GasPool::new(
+        test_cluster.wallet.get_client().await?,
+        owner_keypair(&test_cluster),
+        config,
+    )
+    .await?;
+    assert_eq!(pool.size(), 3);
+    assert_eq!(pool.available(), 3);
+    // The coins of the journal are reused rather than split again.
+    assert_eq!(count_coins(&test_cluster, owner).await, coins);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_gas_pool_merges_dust() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let owner = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let rgp = test_cluster.get_reference_gas_price().await;
+    // Any coin that paid for a transaction is below the minimum balance.
+    let config = GasPoolConfig {
+        num_coins: 2,
+        coin_balance: 1_000_000_000,
+        min_coin_balance: 1_000_000_000,
+        ..Default::default()
+    };
+    let pool = GasPool::new(
+        test_cluster.wallet.get_client().await?,
+        owner_keypair(&test_cluster),
+        config,
+    )
+    .await?;
+    let coins = count_coins(&test_cluster, owner).await;
+
+    pool.execute(transfer_one_mist(recipient), rgp * 10_000)
+        .await?;
+    pool.merge_dust().await?;
+    assert_eq!(pool.size(), 1);
+    assert_eq!(count_coins(&test_cluster, owner).await, coins - 1);
+
+    pool.replenish().await?;
+    assert_eq!(pool.size(), 2);
+    assert_eq!(pool.available(), 2);
+    assert_eq!(count_coins(&test_cluster, owner).await, coins);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_gas_pool_ignores_stale_release() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let config = GasPoolConfig {
+        num_coins: 1,
+        lease_duration: std::time::Duration::ZERO,
+        ..Default::default()
+    };
+    let pool = GasPool::new(
+        test_cluster.wallet.get_client().await?,
+        owner_keypair(&test_cluster),
+        config,
+    )
+    .await?;
+
+    // The first lease expires and is reclaimed, then the coin is leased again.
+    let stale = pool.lease().await?;
+    pool.reclaim_expired_leases().await?;
+    assert_eq!(pool.available(), 1);
+    let current = pool.lease().await?;
+    assert_eq!(stale.coin.0, current.coin.0);
+
+    // Releasing the reclaimed lease does not free the coin of the current one.
+    pool.release(stale).await?;
+    assert_eq!(pool.available(), 0);
+    pool.release(current).await?;
+    assert_eq!(pool.available(), 1);
+    Ok(())
+}
+
+#[cfg(msim)]
+#[sim_test]
+async fn test_gas_pool_execute_survives_failed_refresh() -> Result<(), anyhow::Error> {
+    use sui_macros::register_fail_point_if;
+
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let recipient = test_cluster.get_address_1();
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let config = GasPoolConfig {
+        num_coins: 1,
+        lease_duration: std::time::Duration::ZERO,
+        ..Default::default()
+    };
+    let pool = GasPool::new(
+        test_cluster.wallet.get_client().await?,
+        owner_keypair(&test_cluster),
+        config,
+    )
+    .await?;
+
+    // The transaction is executed, but the coin paying for it cannot be read back.
+    register_fail_point_if("gas-pool-fetch-coin-error", || true);
+    let response = pool
+        .execute(transfer_one_mist(recipient), rgp * 10_000)
+        .await?;
+    assert!(response.effects.unwrap().status().is_ok());
+    assert_eq!(pool.available(), 0);
+
+    // The lease is left to expire, and the coin is reclaimed once it can be read again.
+    register_fail_point_if("gas-pool-fetch-coin-error", || false);
+    pool.reclaim_expired_leases().await?;
+    assert_eq!(pool.available(), 1);
+    Ok(())
+}
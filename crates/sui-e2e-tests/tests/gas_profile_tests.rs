@@ -0,0 +1,40 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Gas profiles are only written by debug builds, and `LocalExec` drives some of its requests with
+// `block_on`, which needs a real multi-threaded runtime.
+#![cfg(all(debug_assertions, not(msim)))]
+
+use sui_types::transaction::TransactionDataAPI;
+use test_cluster::TestClusterBuilder;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_profile_dry_run() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let recipient = test_cluster.get_address_1();
+    let tx_data = test_cluster
+        .test_transaction_builder()
+        .await
+        .transfer_sui(Some(1), recipient)
+        .build();
+    let gas = tx_data.gas()[0];
+
+    let profile_dir = tempfile::tempdir().unwrap();
+    sui_replay::profile_dry_run(
+        &test_cluster.rpc_url(),
+        tx_data,
+        profile_dir.path().to_owned(),
+    )
+    .await
+    .unwrap();
+
+    let profiles: Vec<_> = std::fs::read_dir(profile_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(profiles.len(), 1);
+    assert!(profiles[0].starts_with("gas_profile_"));
+
+    // Nothing was executed on the network.
+    assert_eq!(test_cluster.get_latest_object_ref(&gas.0).await, gas);
+}
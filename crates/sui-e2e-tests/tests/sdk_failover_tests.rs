@@ -0,0 +1,57 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_json_rpc_api::ReadApiClient;
+use sui_macros::sim_test;
+use sui_sdk::error::Error;
+use sui_sdk::SuiClientBuilder;
+use test_cluster::TestClusterBuilder;
+
+// Nothing listens on this port.
+const UNREACHABLE_URL: &str = "http://127.0.0.1:1";
+
+#[sim_test]
+async fn test_failover_to_fallback_url() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let client = SuiClientBuilder::default()
+        .fallback_urls([test_cluster.rpc_url()])
+        .build(UNREACHABLE_URL)
+        .await?;
+
+    let health = client.endpoint_health();
+    assert_eq!(health[0].url, UNREACHABLE_URL);
+    assert!(!health[0].healthy);
+    assert!(health[1].healthy);
+
+    let balance = client.coin_read_api().get_balance(address, None).await?;
+    assert!(balance.total_balance > 0);
+    assert_eq!(client.endpoint_health()[1].consecutive_failures, 0);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_quorum_read() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let client = SuiClientBuilder::default()
+        .fallback_urls([test_cluster.rpc_url()])
+        .read_quorum(2)
+        .build(test_cluster.rpc_url())
+        .await?;
+
+    let chain_id = client
+        .quorum_read(2, |http| http.get_chain_identifier())
+        .await?;
+    assert_eq!(chain_id, client.read_api().get_chain_identifier().await?);
+
+    // Balances are critical reads, they go to both endpoints.
+    let balance = client.coin_read_api().get_balance(address, None).await?;
+    assert!(balance.total_balance > 0);
+
+    let result = client
+        .quorum_read(3, |http| http.get_chain_identifier())
+        .await;
+    assert!(matches!(result, Err(Error::DataError(_))));
+    Ok(())
+}
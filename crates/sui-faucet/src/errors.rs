@@ -37,6 +37,12 @@ pub enum FaucetError {
     #[error("Request consumer queue closed.")]
     ChannelClosed,
 
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
+    #[error("Missing or invalid admin token")]
+    Unauthorized,
+
     #[error("Coin amounts sent are incorrect:`{0}`")]
     CoinAmountTransferredIncorrect(String),
 
@@ -6,11 +6,16 @@ use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use uuid::Uuid;
 
+mod request_history;
 mod simple_faucet;
 mod write_ahead_log;
+pub use self::request_history::RequestStatus;
 pub use self::simple_faucet::SimpleFaucet;
 use clap::Parser;
-use std::{net::Ipv4Addr, path::PathBuf};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FaucetReceipt {
@@ -44,6 +49,38 @@ pub enum BatchSendStatusType {
     DISCARDED,
 }
 
+/// A request received by the faucet, as reported by its request history.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FaucetRequestRecord {
+    pub id: String,
+    pub recipient: SuiAddress,
+    pub ip: Option<IpAddr>,
+    pub timestamp_ms: u64,
+    pub status: RequestStatus,
+}
+
+impl From<request_history::RequestRecord> for FaucetRequestRecord {
+    fn from(record: request_history::RequestRecord) -> Self {
+        Self {
+            id: Uuid::from_bytes(record.uuid).to_string(),
+            recipient: record.recipient,
+            ip: record.ip,
+            timestamp_ms: record.timestamp_ms,
+            status: record.status,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RefillReceipt {
+    /// Coins owned by the faucet that were added to its gas coin pool.
+    pub added: Vec<ObjectID>,
+    /// Number of coins tracked by the gas coin pool after the refill, including coins in use.
+    pub pool_size: usize,
+}
+
 #[async_trait]
 pub trait Faucet {
     /// Send `Coin<SUI>` of the specified amount to the recipient
@@ -68,6 +105,8 @@ pub trait Faucet {
 
 pub const DEFAULT_AMOUNT: u64 = 1_000_000_000;
 pub const DEFAULT_NUM_OF_COINS: usize = 1;
+pub const DEFAULT_RATE_LIMIT_WINDOW_SECS: u64 = 24 * 60 * 60;
+pub const DEFAULT_REQUEST_HISTORY_RETENTION_SECS: u64 = 7 * 24 * 60 * 60;
 
 #[derive(Parser, Clone)]
 #[clap(
@@ -114,6 +153,41 @@ pub struct FaucetConfig {
 
     #[clap(long, action = clap::ArgAction::Set, default_value_t = false)]
     pub batch_enabled: bool,
+
+    /// Path to the database recording the requests received by the faucet. Defaults to a
+    /// `.history` sibling of the write-ahead log.
+    #[clap(long)]
+    pub request_history: Option<PathBuf>,
+
+    /// Maximum number of requests served to the same recipient address within the rate limit
+    /// window. Unlimited if unset.
+    #[clap(long)]
+    pub max_requests_per_address: Option<u64>,
+
+    /// Maximum number of requests served to the same client IP within the rate limit window.
+    /// Unlimited if unset.
+    #[clap(long)]
+    pub max_requests_per_ip: Option<u64>,
+
+    /// Length of the sliding window that per-address and per-IP quotas apply to.
+    #[clap(long, default_value_t = DEFAULT_RATE_LIMIT_WINDOW_SECS)]
+    pub rate_limit_window_secs: u64,
+
+    /// How long requests are kept in the request history. Never shorter than the rate limit
+    /// window.
+    #[clap(long, default_value_t = DEFAULT_REQUEST_HISTORY_RETENTION_SECS)]
+    pub request_history_retention_secs: u64,
+
+    /// Number of proxies in front of the faucet that append the address they received a request
+    /// from to the `X-Forwarded-For` header. When set, clients are identified by the entry that
+    /// many positions from the right of the header, the one added by the outermost proxy, rather
+    /// than by the address of the connection. Entries further left are set by the client.
+    #[clap(long, default_value_t = 0)]
+    pub trusted_proxy_hops: usize,
+
+    /// Bearer token required by the `/admin` endpoints. The endpoints are disabled if unset.
+    #[clap(long)]
+    pub admin_token: Option<String>,
 }
 
 impl Default for FaucetConfig {
@@ -132,6 +206,13 @@ impl Default for FaucetConfig {
             batch_request_size: 500,
             ttl_expiration: 300,
             batch_enabled: false,
+            request_history: None,
+            max_requests_per_address: None,
+            max_requests_per_ip: None,
+            rate_limit_window_secs: DEFAULT_RATE_LIMIT_WINDOW_SECS,
+            request_history_retention_secs: DEFAULT_REQUEST_HISTORY_RETENTION_SECS,
+            trusted_proxy_hops: 0,
+            admin_token: None,
         }
     }
 }
@@ -0,0 +1,565 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::IpAddr;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::Map;
+use typed_store::{rocks::DBMap, TypedStoreError};
+
+use typed_store_derive::DBMapUtils;
+use uuid::Uuid;
+
+/// Persistent record of the requests received by the faucet. It backs the per-address and per-IP
+/// quotas (which count the accepted requests that fall within a sliding window), and the request
+/// history served by the admin API.
+///
+/// Requests are stored by ID, with secondary indices ordered by the time they were received, so
+/// that the requests within a window (or the most recent ones) can be found with a range scan.
+/// The per-address and per-IP indices also hold the status of each request, so that quotas are
+/// counted from the index alone.
+#[derive(DBMapUtils, Clone)]
+pub struct RequestHistory {
+    pub requests: DBMap<uuid::Bytes, RequestRecord>,
+    pub by_time: DBMap<(u64, uuid::Bytes), ()>,
+    pub by_address: DBMap<(SuiAddress, u64, uuid::Bytes), RequestStatus>,
+    pub by_ip: DBMap<(IpAddr, u64, uuid::Bytes), RequestStatus>,
+}
+
+/// Maximum number of rate limited requests recorded for the same address or IP within a quota
+/// window. Further rejected requests are not recorded, so that a client retrying while over quota
+/// grows neither the history nor the cost of checking its quota.
+const MAX_RATE_LIMITED_PER_KEY: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct RequestRecord {
+    pub uuid: uuid::Bytes,
+    pub recipient: SuiAddress,
+    pub ip: Option<IpAddr>,
+    pub timestamp_ms: u64,
+    pub status: RequestStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RequestStatus {
+    /// The request was within quota and was handed to the faucet. Only these requests count
+    /// towards a quota.
+    Accepted,
+    /// The request was accepted, but the faucet failed to serve it.
+    Failed,
+    /// The request was rejected because its address or IP was over quota.
+    RateLimited,
+}
+
+/// The maximum number of requests allowed from the same address or IP in any window of
+/// `window_ms` milliseconds. `None` means the corresponding requests are not limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestQuota {
+    pub max_requests_per_address: Option<u64>,
+    pub max_requests_per_ip: Option<u64>,
+    pub window_ms: u64,
+}
+
+/// Outcome of [`RequestHistory::admit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Accepted,
+    /// The recipient has used its quota, and can request again after `retry_after_ms`.
+    AddressQuotaExceeded {
+        limit: u64,
+        retry_after_ms: u64,
+    },
+    /// The client IP has used its quota, and can request again after `retry_after_ms`.
+    IpQuotaExceeded {
+        limit: u64,
+        retry_after_ms: u64,
+    },
+}
+
+impl RequestHistory {
+    pub(crate) fn open(path: &Path) -> Self {
+        Self::open_tables_read_write(
+            path.to_path_buf(),
+            typed_store::rocks::MetricConf::new("faucet_request_history"),
+            None,
+            None,
+        )
+    }
+
+    /// Check whether a request from `ip` for `recipient`, received at `now_ms`, fits within
+    /// `quota`, and record it in the history -- as accepted if it does, or rate limited if it
+    /// does not and fewer than `MAX_RATE_LIMITED_PER_KEY` requests were already rejected for the
+    /// same reason within the window.
+    pub(crate) fn admit(
+        &mut self,
+        uuid: Uuid,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+        now_ms: u64,
+        quota: &RequestQuota,
+    ) -> Result<Admission, TypedStoreError> {
+        let since_ms = now_ms.saturating_sub(quota.window_ms);
+        let mut admission = Admission::Accepted;
+        let mut rate_limited = 0;
+
+        if let Some(limit) = quota.max_requests_per_address {
+            let usage = window_usage(
+                &self.by_address,
+                (recipient, since_ms, [0; 16]),
+                (recipient, u64::MAX, [u8::MAX; 16]),
+            )?;
+            if usage.accepted.len() as u64 >= limit {
+                admission = Admission::AddressQuotaExceeded {
+                    limit,
+                    retry_after_ms: retry_after_ms(&usage.accepted, limit, now_ms, quota.window_ms),
+                };
+                rate_limited = usage.rate_limited;
+            }
+        }
+
+        if let (Admission::Accepted, Some(ip), Some(limit)) =
+            (admission, ip, quota.max_requests_per_ip)
+        {
+            let usage = window_usage(
+                &self.by_ip,
+                (ip, since_ms, [0; 16]),
+                (ip, u64::MAX, [u8::MAX; 16]),
+            )?;
+            if usage.accepted.len() as u64 >= limit {
+                admission = Admission::IpQuotaExceeded {
+                    limit,
+                    retry_after_ms: retry_after_ms(&usage.accepted, limit, now_ms, quota.window_ms),
+                };
+                rate_limited = usage.rate_limited;
+            }
+        }
+
+        let status = if admission == Admission::Accepted {
+            RequestStatus::Accepted
+        } else if rate_limited < MAX_RATE_LIMITED_PER_KEY {
+            RequestStatus::RateLimited
+        } else {
+            return Ok(admission);
+        };
+
+        self.insert(RequestRecord {
+            uuid: *uuid.as_bytes(),
+            recipient,
+            ip,
+            timestamp_ms: now_ms,
+            status,
+        })?;
+
+        Ok(admission)
+    }
+
+    /// Mark the request `uuid` as failed, so that it no longer counts towards its quotas. Requests
+    /// that were not recorded in the history are ignored.
+    pub(crate) fn mark_failed(&mut self, uuid: Uuid) -> Result<(), TypedStoreError> {
+        let key = *uuid.as_bytes();
+        if let Some(mut record) = self.requests.get(&key)? {
+            record.status = RequestStatus::Failed;
+            self.insert(record)?;
+        }
+        Ok(())
+    }
+
+    /// Return up to `limit` of the most recent requests, newest first, optionally restricted to
+    /// the requests for `recipient` and/or the requests from `ip`.
+    pub(crate) fn recent(
+        &self,
+        recipient: Option<SuiAddress>,
+        ip: Option<IpAddr>,
+        limit: usize,
+    ) -> Result<Vec<RequestRecord>, TypedStoreError> {
+        let uuids: Box<dyn Iterator<Item = Result<uuid::Bytes, TypedStoreError>> + '_> =
+            match (recipient, ip) {
+                (Some(recipient), _) => {
+                    let upper = (recipient, u64::MAX, [u8::MAX; 16]);
+                    Box::new(
+                        self.by_address
+                            .safe_range_iter((recipient, 0, [0; 16])..=upper)
+                            .skip_prior_to(&upper)?
+                            .reverse()
+                            .map(|item| item.map(|((_, _, uuid), _)| uuid)),
+                    )
+                }
+
+                (None, Some(ip)) => {
+                    let upper = (ip, u64::MAX, [u8::MAX; 16]);
+                    Box::new(
+                        self.by_ip
+                            .safe_range_iter((ip, 0, [0; 16])..=upper)
+                            .skip_prior_to(&upper)?
+                            .reverse()
+                            .map(|item| item.map(|((_, _, uuid), _)| uuid)),
+                    )
+                }
+
+                (None, None) => Box::new(
+                    self.by_time
+                        .safe_iter()
+                        .skip_to_last()
+                        .reverse()
+                        .map(|item| item.map(|((_, uuid), _)| uuid)),
+                ),
+            };
+
+        let mut records = vec![];
+        for uuid in uuids {
+            if records.len() >= limit {
+                break;
+            }
+            let Some(record) = self.requests.get(&uuid?)? else {
+                continue;
+            };
+            if ip.map_or(true, |ip| record.ip == Some(ip)) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Remove all requests received before `cutoff_ms` from the history. Returns the number of
+    /// requests removed.
+    pub(crate) fn prune(&mut self, cutoff_ms: u64) -> Result<usize, TypedStoreError> {
+        let expired = self
+            .by_time
+            .safe_range_iter(..(cutoff_ms, [0; 16]))
+            .map(|item| item.map(|(key, _)| key))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut records = Vec::with_capacity(expired.len());
+        for (_, uuid) in &expired {
+            if let Some(record) = self.requests.get(uuid)? {
+                records.push(record);
+            }
+        }
+
+        let mut batch = self.requests.batch();
+        batch.delete_batch(&self.requests, expired.iter().map(|(_, uuid)| *uuid))?;
+        batch.delete_batch(&self.by_time, expired.iter().copied())?;
+        batch.delete_batch(
+            &self.by_address,
+            records
+                .iter()
+                .map(|r| (r.recipient, r.timestamp_ms, r.uuid)),
+        )?;
+        batch.delete_batch(
+            &self.by_ip,
+            records
+                .iter()
+                .filter_map(|r| Some((r.ip?, r.timestamp_ms, r.uuid))),
+        )?;
+        batch.write()?;
+
+        Ok(expired.len())
+    }
+
+    fn insert(&mut self, record: RequestRecord) -> Result<(), TypedStoreError> {
+        let RequestRecord {
+            uuid,
+            recipient,
+            ip,
+            timestamp_ms,
+            status,
+        } = record;
+
+        let mut batch = self.requests.batch();
+        batch.insert_batch(&self.requests, [(uuid, &record)])?;
+        batch.insert_batch(&self.by_time, [((timestamp_ms, uuid), ())])?;
+        batch.insert_batch(
+            &self.by_address,
+            [((recipient, timestamp_ms, uuid), status)],
+        )?;
+        if let Some(ip) = ip {
+            batch.insert_batch(&self.by_ip, [((ip, timestamp_ms, uuid), status)])?;
+        }
+        batch.write()
+    }
+}
+
+/// The requests recorded in an index range.
+struct WindowUsage {
+    /// Timestamps of the accepted requests, in ascending order.
+    accepted: Vec<u64>,
+    /// Number of rate limited requests.
+    rate_limited: usize,
+}
+
+/// The requests recorded in the index range `[lower, upper]`.
+fn window_usage<K>(
+    index: &DBMap<(K, u64, uuid::Bytes), RequestStatus>,
+    lower: (K, u64, uuid::Bytes),
+    upper: (K, u64, uuid::Bytes),
+) -> Result<WindowUsage, TypedStoreError>
+where
+    K: Serialize + serde::de::DeserializeOwned,
+{
+    let mut usage = WindowUsage {
+        accepted: vec![],
+        rate_limited: 0,
+    };
+    for item in index.safe_range_iter(lower..=upper) {
+        match item? {
+            ((_, timestamp_ms, _), RequestStatus::Accepted) => usage.accepted.push(timestamp_ms),
+            (_, RequestStatus::RateLimited) => usage.rate_limited += 1,
+            (_, RequestStatus::Failed) => {}
+        }
+    }
+    Ok(usage)
+}
+
+/// How long until enough of the `accepted` requests (sorted by timestamp) leave the window for a
+/// new request to fit within `limit`.
+fn retry_after_ms(accepted: &[u64], limit: u64, now_ms: u64, window_ms: u64) -> u64 {
+    let excess = accepted.len() - limit.min(accepted.len() as u64) as usize;
+    accepted
+        .get(excess)
+        .map_or(0, |oldest| (oldest + window_ms).saturating_sub(now_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const WINDOW_MS: u64 = 60_000;
+
+    fn quota(per_address: Option<u64>, per_ip: Option<u64>) -> RequestQuota {
+        RequestQuota {
+            max_requests_per_address: per_address,
+            max_requests_per_ip: per_ip,
+            window_ms: WINDOW_MS,
+        }
+    }
+
+    #[tokio::test]
+    async fn address_quota_slides_with_window() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut history = RequestHistory::open(&tmp.path().join("history"));
+        let quota = quota(Some(2), None);
+        let recipient = SuiAddress::random_for_testing_only();
+
+        for now_ms in [1_000, 2_000] {
+            let admission = history
+                .admit(Uuid::new_v4(), recipient, None, now_ms, &quota)
+                .unwrap();
+            assert_eq!(admission, Admission::Accepted);
+        }
+
+        // Third request inside the window is rejected until the first one leaves it.
+        let admission = history
+            .admit(Uuid::new_v4(), recipient, None, 3_000, &quota)
+            .unwrap();
+        assert_eq!(
+            admission,
+            Admission::AddressQuotaExceeded {
+                limit: 2,
+                retry_after_ms: 1_000 + WINDOW_MS - 3_000,
+            }
+        );
+
+        // Other addresses are unaffected.
+        let admission = history
+            .admit(
+                Uuid::new_v4(),
+                SuiAddress::random_for_testing_only(),
+                None,
+                3_000,
+                &quota,
+            )
+            .unwrap();
+        assert_eq!(admission, Admission::Accepted);
+
+        // Once the first request falls out of the window, there is room for one more.
+        let admission = history
+            .admit(
+                Uuid::new_v4(),
+                recipient,
+                None,
+                1_000 + WINDOW_MS + 1,
+                &quota,
+            )
+            .unwrap();
+        assert_eq!(admission, Admission::Accepted);
+    }
+
+    #[tokio::test]
+    async fn ip_quota_spans_addresses() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut history = RequestHistory::open(&tmp.path().join("history"));
+        let quota = quota(None, Some(1));
+        let ip = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+
+        let admission = history
+            .admit(
+                Uuid::new_v4(),
+                SuiAddress::random_for_testing_only(),
+                ip,
+                1_000,
+                &quota,
+            )
+            .unwrap();
+        assert_eq!(admission, Admission::Accepted);
+
+        let admission = history
+            .admit(
+                Uuid::new_v4(),
+                SuiAddress::random_for_testing_only(),
+                ip,
+                2_000,
+                &quota,
+            )
+            .unwrap();
+        assert!(matches!(
+            admission,
+            Admission::IpQuotaExceeded { limit: 1, .. }
+        ));
+
+        // Requests without a known IP are only subject to the address quota.
+        let admission = history
+            .admit(
+                Uuid::new_v4(),
+                SuiAddress::random_for_testing_only(),
+                None,
+                2_000,
+                &quota,
+            )
+            .unwrap();
+        assert_eq!(admission, Admission::Accepted);
+    }
+
+    #[tokio::test]
+    async fn failed_requests_do_not_count() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut history = RequestHistory::open(&tmp.path().join("history"));
+        let quota = quota(Some(1), None);
+        let recipient = SuiAddress::random_for_testing_only();
+
+        let uuid = Uuid::new_v4();
+        history.admit(uuid, recipient, None, 1_000, &quota).unwrap();
+        history.mark_failed(uuid).unwrap();
+
+        let admission = history
+            .admit(Uuid::new_v4(), recipient, None, 2_000, &quota)
+            .unwrap();
+        assert_eq!(admission, Admission::Accepted);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_requests_are_capped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut history = RequestHistory::open(&tmp.path().join("history"));
+        let quota = quota(Some(1), None);
+        let recipient = SuiAddress::random_for_testing_only();
+
+        for now_ms in 1_000..1_000 + 2 * MAX_RATE_LIMITED_PER_KEY as u64 {
+            history
+                .admit(Uuid::new_v4(), recipient, None, now_ms, &quota)
+                .unwrap();
+        }
+
+        // Requests beyond the cap are still rejected, but no longer recorded.
+        let admission = history
+            .admit(Uuid::new_v4(), recipient, None, 5_000, &quota)
+            .unwrap();
+        assert!(matches!(
+            admission,
+            Admission::AddressQuotaExceeded { limit: 1, .. }
+        ));
+        let recorded = history.recent(Some(recipient), None, usize::MAX).unwrap();
+        assert_eq!(recorded.len(), 1 + MAX_RATE_LIMITED_PER_KEY);
+        assert_eq!(
+            recorded
+                .iter()
+                .filter(|r| r.status == RequestStatus::RateLimited)
+                .count(),
+            MAX_RATE_LIMITED_PER_KEY
+        );
+    }
+
+    #[tokio::test]
+    async fn recent_requests_newest_first() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut history = RequestHistory::open(&tmp.path().join("history"));
+        let quota = quota(Some(1), None);
+        let recipient = SuiAddress::random_for_testing_only();
+        let ip = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        history.admit(first, recipient, ip, 1_000, &quota).unwrap();
+        history
+            .admit(second, recipient, None, 2_000, &quota)
+            .unwrap();
+        history
+            .admit(
+                other,
+                SuiAddress::random_for_testing_only(),
+                ip,
+                3_000,
+                &quota,
+            )
+            .unwrap();
+
+        let all = history.recent(None, None, 10).unwrap();
+        let uuids: Vec<_> = all.iter().map(|r| Uuid::from_bytes(r.uuid)).collect();
+        assert_eq!(uuids, vec![other, second, first]);
+
+        let by_address = history.recent(Some(recipient), None, 10).unwrap();
+        assert_eq!(by_address.len(), 2);
+        assert_eq!(Uuid::from_bytes(by_address[0].uuid), second);
+        assert_eq!(by_address[0].status, RequestStatus::RateLimited);
+        assert_eq!(Uuid::from_bytes(by_address[1].uuid), first);
+        assert_eq!(by_address[1].status, RequestStatus::Accepted);
+
+        let by_ip = history.recent(None, ip, 1).unwrap();
+        assert_eq!(by_ip.len(), 1);
+        assert_eq!(Uuid::from_bytes(by_ip[0].uuid), other);
+
+        let by_both = history.recent(Some(recipient), ip, 10).unwrap();
+        assert_eq!(by_both.len(), 1);
+        assert_eq!(Uuid::from_bytes(by_both[0].uuid), first);
+    }
+
+    #[tokio::test]
+    async fn prune_removes_old_requests() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut history = RequestHistory::open(&tmp.path().join("history"));
+        let quota = quota(Some(1), Some(1));
+        let recipient = SuiAddress::random_for_testing_only();
+        let ip = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+        history
+            .admit(Uuid::new_v4(), recipient, ip, 1_000, &quota)
+            .unwrap();
+        let kept = Uuid::new_v4();
+        history
+            .admit(
+                kept,
+                SuiAddress::random_for_testing_only(),
+                None,
+                5_000,
+                &quota,
+            )
+            .unwrap();
+
+        assert_eq!(history.prune(2_000).unwrap(), 1);
+
+        let remaining = history.recent(None, None, 10).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(Uuid::from_bytes(remaining[0].uuid), kept);
+        assert!(history
+            .recent(Some(recipient), None, 10)
+            .unwrap()
+            .is_empty());
+        assert!(history.recent(None, ip, 10).unwrap().is_empty());
+    }
+}
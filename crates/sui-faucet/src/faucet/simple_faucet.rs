@@ -12,8 +12,10 @@ use std::collections::HashMap;
 #[cfg(test)]
 use std::collections::HashSet;
 use std::fmt;
+use std::net::IpAddr;
 use std::path::Path;
 use std::sync::{Arc, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use tap::tap::TapFallible;
 use tokio::sync::oneshot;
@@ -41,10 +43,11 @@ use tokio::time::{timeout, Duration};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use super::request_history::{Admission, RequestHistory, RequestQuota};
 use super::write_ahead_log::WriteAheadLog;
 use crate::{
     BatchFaucetReceipt, BatchSendStatus, BatchSendStatusType, CoinInfo, Faucet, FaucetConfig,
-    FaucetError, FaucetReceipt,
+    FaucetError, FaucetReceipt, FaucetRequestRecord, RefillReceipt,
 };
 
 pub struct SimpleFaucet {
@@ -56,6 +59,13 @@ pub struct SimpleFaucet {
     batch_consumer: Mutex<Receiver<ObjectID>>,
     pub metrics: FaucetMetrics,
     pub wal: Mutex<WriteAheadLog>,
+    pub request_history: Mutex<RequestHistory>,
+    request_quota: RequestQuota,
+    request_history_retention: Duration,
+    /// Coins in the gas coin pools, whether queued or in use, mapped to whether they belong to the
+    /// batch pool.
+    pooled_coins: parking_lot::Mutex<HashMap<ObjectID, bool>>,
+    min_coin_balance: u64,
     request_producer: Sender<(Uuid, SuiAddress, Vec<u64>)>,
     batch_request_size: u64,
     task_id_cache: Mutex<TtlCache<Uuid, BatchSendStatus>>,
@@ -94,6 +104,8 @@ const DEFAULT_GAS_COMPUTATION_BUCKET: u64 = 10_000_000;
 const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
 const RECV_TIMEOUT: Duration = Duration::from_secs(5);
 const BATCH_TIMEOUT: Duration = Duration::from_secs(10);
+// Room for coins added to the gas coin pools after start up, by refilling them.
+const MIN_GAS_POOL_CAPACITY: usize = 1_000;
 
 impl SimpleFaucet {
     pub async fn new(
@@ -107,6 +119,7 @@ impl SimpleFaucet {
             .map_err(|err| FaucetError::Wallet(err.to_string()))?;
         info!("SimpleFaucet::new with active address: {active_address}");
 
+        let min_coin_balance = config.amount * config.num_coins as u64;
         let coins = wallet
             .gas_objects(active_address)
             .await
@@ -114,15 +127,29 @@ impl SimpleFaucet {
             .iter()
             // Ok to unwrap() since `get_gas_objects` guarantees gas
             .map(|q| GasCoin::try_from(&q.1).unwrap())
-            .filter(|coin| coin.0.balance.value() >= (config.amount * config.num_coins as u64))
+            .filter(|coin| coin.0.balance.value() >= min_coin_balance)
             .collect::<Vec<GasCoin>>();
         let metrics = FaucetMetrics::new(prometheus_registry);
 
         let wal = WriteAheadLog::open(wal_path);
         let mut pending = vec![];
 
-        let (producer, consumer) = mpsc::channel(coins.len());
-        let (batch_producer, batch_consumer) = mpsc::channel(coins.len());
+        let request_history = RequestHistory::open(
+            &config
+                .request_history
+                .clone()
+                .unwrap_or_else(|| wal_path.with_extension("history")),
+        );
+        let request_quota = RequestQuota {
+            max_requests_per_address: config.max_requests_per_address,
+            max_requests_per_ip: config.max_requests_per_ip,
+            window_ms: config.rate_limit_window_secs * 1000,
+        };
+        let mut pooled_coins = HashMap::new();
+
+        let pool_capacity = coins.len().max(MIN_GAS_POOL_CAPACITY);
+        let (producer, consumer) = mpsc::channel(pool_capacity);
+        let (batch_producer, batch_consumer) = mpsc::channel(pool_capacity);
 
         let (sender, mut receiver) =
             mpsc::channel::<(Uuid, SuiAddress, Vec<u64>)>(config.max_request_queue_length as usize);
@@ -148,6 +175,7 @@ impl SimpleFaucet {
                 let uuid = Uuid::from_bytes(uuid);
                 info!(?uuid, ?recipient, ?coin_id, "Retrying txn from WAL.");
                 pending.push((uuid, recipient, coin_id, tx));
+                pooled_coins.insert(coin_id, false);
             } else if coins_processed < split_point {
                 producer
                     .send(coin_id)
@@ -158,6 +186,7 @@ impl SimpleFaucet {
                     })
                     .tap_err(|e| error!(?coin_id, "Failed to add coin to gas pools: {e:?}"))
                     .unwrap();
+                pooled_coins.insert(coin_id, false);
             } else {
                 batch_producer
                     .send(coin_id)
@@ -168,6 +197,7 @@ impl SimpleFaucet {
                     })
                     .tap_err(|e| error!(?coin_id, "Failed to add coin to batch gas pools: {e:?}"))
                     .unwrap();
+                pooled_coins.insert(coin_id, true);
             }
         }
         let (batch_transfer_shutdown, mut rx_batch_transfer_shutdown) = oneshot::channel();
@@ -181,6 +211,15 @@ impl SimpleFaucet {
             batch_consumer: Mutex::new(batch_consumer),
             metrics,
             wal: Mutex::new(wal),
+            request_history: Mutex::new(request_history),
+            request_quota,
+            request_history_retention: Duration::from_secs(
+                config
+                    .request_history_retention_secs
+                    .max(config.rate_limit_window_secs),
+            ),
+            pooled_coins: parking_lot::Mutex::new(pooled_coins),
+            min_coin_balance,
             request_producer: sender,
             batch_request_size: config.batch_request_size,
             // Max faucet requests times 10 minutes worth of requests to hold onto at max.
@@ -532,14 +571,14 @@ impl SimpleFaucet {
 
             GasCoinResponse::GasCoinWithInsufficientBalance(coin_id) => {
                 warn!(?uuid, ?coin_id, "Insufficient balance, removing from pool");
-                self.metrics.total_discarded_coins.inc();
+                self.discard_gas_coin(coin_id);
                 self.transfer_gases(amounts, recipient, uuid).await
             }
 
             GasCoinResponse::InvalidGasCoin(coin_id) => {
                 // The coin does not exist, or does not belong to the current active address.
                 warn!(?uuid, ?coin_id, "Invalid, removing from pool");
-                self.metrics.total_discarded_coins.inc();
+                self.discard_gas_coin(coin_id);
                 self.transfer_gases(amounts, recipient, uuid).await
             }
 
@@ -556,6 +595,7 @@ impl SimpleFaucet {
         producer
             .try_send(coin_id)
             .expect("unexpected - queue is large enough to hold all coins");
+        self.pooled_coins.lock().insert(coin_id, false);
         self.metrics.total_available_coins.inc();
         info!(?uuid, ?coin_id, "Recycled coin");
     }
@@ -569,10 +609,149 @@ impl SimpleFaucet {
         batch_producer
             .try_send(coin_id)
             .expect("unexpected - queue is large enough to hold all coins");
+        self.pooled_coins.lock().insert(coin_id, true);
         self.metrics.total_available_coins.inc();
         info!(?uuid, ?coin_id, "Recycled coin");
     }
 
+    /// Drop a coin that can no longer be used from the gas coin pools. It is added back by
+    /// [`Self::refill_coin_pool`] if it becomes usable again.
+    fn discard_gas_coin(&self, coin_id: ObjectID) {
+        self.pooled_coins.lock().remove(&coin_id);
+        self.metrics.total_discarded_coins.inc();
+    }
+
+    /// Add the coins owned by the faucet that can serve a request but are not in its gas coin
+    /// pools to the pools, e.g. after the faucet's address has been topped up.
+    pub async fn refill_coin_pool(&self) -> Result<RefillReceipt, FaucetError> {
+        let coins = self
+            .wallet
+            .gas_objects(self.active_address)
+            .await
+            .map_err(|e| FaucetError::Wallet(e.to_string()))?;
+
+        let producer = self.producer.lock().await;
+        let batch_producer = self.batch_producer.lock().await;
+        let mut pooled_coins = self.pooled_coins.lock();
+        let mut batch_count = pooled_coins
+            .values()
+            .filter(|for_batch| **for_batch)
+            .count();
+        let mut count = pooled_coins.len() - batch_count;
+
+        let mut added = vec![];
+        for (_, object) in coins {
+            let Ok(coin) = GasCoin::try_from(&object) else {
+                continue;
+            };
+            let coin_id = *coin.id();
+            if coin.value() < self.min_coin_balance || pooled_coins.contains_key(&coin_id) {
+                continue;
+            }
+
+            let for_batch = if count < producer.max_capacity() {
+                count += 1;
+                false
+            } else if batch_count < batch_producer.max_capacity() {
+                batch_count += 1;
+                true
+            } else {
+                warn!(?coin_id, "Gas coin pools are full, not adding coin");
+                break;
+            };
+
+            let sender = if for_batch {
+                &batch_producer
+            } else {
+                &producer
+            };
+            sender.try_send(coin_id).map_err(FaucetError::internal)?;
+            pooled_coins.insert(coin_id, for_batch);
+            self.metrics.total_available_coins.inc();
+            info!(?coin_id, for_batch, "Refilled gas pool with coin");
+            added.push(coin_id);
+        }
+
+        Ok(RefillReceipt {
+            added,
+            pool_size: pooled_coins.len(),
+        })
+    }
+
+    /// Check that a request for `recipient` from `ip` is within the faucet's per-address and
+    /// per-IP quotas, and record it in the request history.
+    pub async fn admit_request(
+        &self,
+        uuid: Uuid,
+        recipient: SuiAddress,
+        ip: Option<IpAddr>,
+    ) -> Result<(), FaucetError> {
+        let admission = self
+            .request_history
+            .lock()
+            .await
+            .admit(uuid, recipient, ip, now_ms(), &self.request_quota)
+            .map_err(FaucetError::internal)?;
+
+        let (requester, limit, retry_after_ms) = match admission {
+            Admission::Accepted => return Ok(()),
+            Admission::AddressQuotaExceeded {
+                limit,
+                retry_after_ms,
+            } => (format!("Address {recipient}"), limit, retry_after_ms),
+            Admission::IpQuotaExceeded {
+                limit,
+                retry_after_ms,
+            } => ("Client IP".to_string(), limit, retry_after_ms),
+        };
+
+        warn!(?uuid, ?recipient, ?ip, "Request is over quota");
+        self.metrics.total_requests_rate_limited.inc();
+        Err(FaucetError::TooManyRequests(format!(
+            "{requester} has reached its limit of {limit} requests every {}s, try again in {}s",
+            self.request_quota.window_ms / 1000,
+            (retry_after_ms + 999) / 1000,
+        )))
+    }
+
+    /// Record in the request history that request `uuid` was not served, so that it does not
+    /// count towards its quotas.
+    async fn mark_request_failed(&self, uuid: Uuid) {
+        if let Err(e) = self.request_history.lock().await.mark_failed(uuid) {
+            error!(?uuid, "Failed to mark request as failed in history: {e:?}");
+        }
+    }
+
+    /// Up to `limit` of the most recent requests received by the faucet, newest first, optionally
+    /// only those for `recipient` and/or from `ip`.
+    pub async fn recent_requests(
+        &self,
+        recipient: Option<SuiAddress>,
+        ip: Option<IpAddr>,
+        limit: usize,
+    ) -> Result<Vec<FaucetRequestRecord>, FaucetError> {
+        let records = self
+            .request_history
+            .lock()
+            .await
+            .recent(recipient, ip, limit)
+            .map_err(FaucetError::internal)?;
+        Ok(records.into_iter().map(FaucetRequestRecord::from).collect())
+    }
+
+    /// Remove requests older than the retention period from the request history.
+    pub async fn prune_request_history(&self) -> Result<(), FaucetError> {
+        let cutoff_ms = now_ms().saturating_sub(self.request_history_retention.as_millis() as u64);
+        let pruned = self
+            .request_history
+            .lock()
+            .await
+            .prune(cutoff_ms)
+            .map_err(FaucetError::internal)?;
+        info!("Pruned {pruned} requests from request history");
+        Ok(())
+    }
+
     async fn execute_pay_sui_txn_with_retries(
         &self,
         tx: &Transaction,
@@ -868,7 +1047,13 @@ impl Faucet for SimpleFaucet {
     ) -> Result<FaucetReceipt, FaucetError> {
         info!(?recipient, uuid = ?id, ?amounts, "Getting faucet requests");
 
-        let (digest, coin_ids) = self.transfer_gases(amounts, recipient, id).await?;
+        let (digest, coin_ids) = match self.transfer_gases(amounts, recipient, id).await {
+            Ok(res) => res,
+            Err(e) => {
+                self.mark_request_failed(id).await;
+                return Err(e);
+            }
+        };
 
         info!(uuid = ?id, ?recipient, ?digest, "PaySui txn succeeded");
         let mut sent = Vec::with_capacity(coin_ids.len());
@@ -921,6 +1106,7 @@ impl Faucet for SimpleFaucet {
             .try_send((id, recipient, amounts.to_vec()))
             .is_err()
         {
+            self.mark_request_failed(id).await;
             return Err(FaucetError::BatchSendQueueFull);
         }
         let mut task_map = self.task_id_cache.lock().await;
@@ -946,6 +1132,13 @@ impl Faucet for SimpleFaucet {
     }
 }
 
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before the UNIX epoch")
+        .as_millis() as u64
+}
+
 pub async fn batch_gather(
     request_consumer: &mut Receiver<(Uuid, SuiAddress, Vec<u64>)>,
     requests: &mut Vec<(Uuid, SuiAddress, Vec<u64>)>,
@@ -1060,13 +1253,13 @@ pub async fn batch_transfer_gases(
             GasCoinResponse::UnknownGasCoin(coin_id) => {
                 // Continue the loop to retry preparing the gas coin
                 warn!(?uuid, ?coin_id, "unknown gas coin.");
-                faucet.metrics.total_discarded_coins.inc();
+                faucet.discard_gas_coin(coin_id);
                 continue;
             }
 
             GasCoinResponse::GasCoinWithInsufficientBalance(coin_id) => {
                 warn!(?uuid, ?coin_id, "Insufficient balance, removing from pool");
-                faucet.metrics.total_discarded_coins.inc();
+                faucet.discard_gas_coin(coin_id);
                 // Continue the loop to retry preparing the gas coin
                 continue;
             }
@@ -1074,7 +1267,7 @@ pub async fn batch_transfer_gases(
             GasCoinResponse::InvalidGasCoin(coin_id) => {
                 // The coin does not exist, or does not belong to the current active address.
                 warn!(?uuid, ?coin_id, "Invalid, removing from pool");
-                faucet.metrics.total_discarded_coins.inc();
+                faucet.discard_gas_coin(coin_id);
                 // Continue the loop to retry preparing the gas coin
                 continue;
             }
@@ -1095,6 +1288,7 @@ mod tests {
     use test_cluster::TestClusterBuilder;
 
     use super::*;
+    use crate::RequestStatus;
 
     #[tokio::test]
     async fn simple_faucet_basic_interface_should_work() {
@@ -1111,7 +1305,7 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: None,
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: Some(10),
             serialize_unsigned_transaction: false,
@@ -1239,7 +1433,7 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: None,
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: Some(10),
             serialize_unsigned_transaction: false,
@@ -1398,7 +1592,7 @@ mod tests {
         let res = SuiClientCommands::PayAllSui {
             input_coins: vec![*bad_gas.id()],
             recipient: KeyIdentity::Address(SuiAddress::random_for_testing_only()),
-            gas_budget: 2_000_000,
+            gas_budget: Some(2_000_000),
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
         }
@@ -1521,7 +1715,7 @@ mod tests {
         let res = SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![tiny_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
@@ -1606,7 +1800,7 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![reasonable_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
@@ -1622,7 +1816,7 @@ mod tests {
             SuiClientCommands::TransferSui {
                 to: KeyIdentity::Address(destination_address),
                 sui_coin_object_id: *gas.id(),
-                gas_budget: 50000000,
+                gas_budget: Some(50000000),
                 amount: None,
                 serialize_unsigned_transaction: false,
                 serialize_signed_transaction: false,
@@ -1679,7 +1873,7 @@ mod tests {
         let _res = SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![tiny_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
@@ -1695,7 +1889,7 @@ mod tests {
             SuiClientCommands::TransferSui {
                 to: KeyIdentity::Address(destination_address),
                 sui_coin_object_id: *gas.id(),
-                gas_budget: 50000000,
+                gas_budget: Some(50000000),
                 amount: None,
                 serialize_unsigned_transaction: false,
                 serialize_signed_transaction: false,
@@ -1802,6 +1996,96 @@ mod tests {
         assert!(restarted_wal.log.is_empty())
     }
 
+    #[tokio::test]
+    async fn test_refill_coin_pool() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let context = test_cluster.wallet;
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let config = FaucetConfig::default();
+
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        // Every coin the faucet owns is already in the pool.
+        let receipt = faucet.refill_coin_pool().await.unwrap();
+        assert!(receipt.added.is_empty());
+        let pool_size = receipt.pool_size;
+
+        let uuid = Uuid::new_v4();
+        let GasCoinResponse::ValidGasCoin(coin_id) =
+            faucet.prepare_gas_coin(100, uuid, false).await
+        else {
+            panic!("prepare_gas_coin did not give a valid coin.")
+        };
+
+        // Drop the coin from the pool, as the faucet does with coins it can no longer use.
+        faucet.discard_gas_coin(coin_id);
+        let available = faucet.metrics.total_available_coins.get();
+
+        let receipt = faucet.refill_coin_pool().await.unwrap();
+        assert_eq!(receipt.added, vec![coin_id]);
+        assert_eq!(receipt.pool_size, pool_size);
+        assert_eq!(available + 1, faucet.metrics.total_available_coins.get());
+    }
+
+    #[tokio::test]
+    async fn test_request_quota() {
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let context = test_cluster.wallet;
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let config = FaucetConfig {
+            max_requests_per_address: Some(1),
+            ..Default::default()
+        };
+
+        let faucet = SimpleFaucet::new(
+            context,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+            config,
+        )
+        .await
+        .unwrap();
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let ip = Some(IpAddr::from([127, 0, 0, 1]));
+        let accepted = Uuid::new_v4();
+        faucet.admit_request(accepted, recipient, ip).await.unwrap();
+        faucet.send(accepted, recipient, &[1]).await.unwrap();
+
+        let rejected = Uuid::new_v4();
+        let res = faucet.admit_request(rejected, recipient, ip).await;
+        assert!(matches!(res, Err(FaucetError::TooManyRequests(_))));
+        assert_eq!(faucet.metrics.total_requests_rate_limited.get(), 1);
+
+        // Other recipients are not affected.
+        faucet
+            .admit_request(Uuid::new_v4(), SuiAddress::random_for_testing_only(), ip)
+            .await
+            .unwrap();
+
+        let history = faucet
+            .recent_requests(Some(recipient), None, 10)
+            .await
+            .unwrap();
+        let statuses: Vec<_> = history.iter().map(|r| (r.id.clone(), r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (rejected.to_string(), RequestStatus::RateLimited),
+                (accepted.to_string(), RequestStatus::Accepted),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_amounts_transferred_on_batch() {
         let test_cluster = TestClusterBuilder::new().build().await;
@@ -1813,7 +2097,7 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: None,
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: Some(10),
             serialize_unsigned_transaction: false,
@@ -3,15 +3,16 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
-    extract::Path,
+    extract::{ConnectInfo, Path, Query},
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
 };
 use clap::Parser;
-use http::Method;
+use http::{header, HeaderMap, Method};
 use mysten_metrics::spawn_monitored_task;
+use serde::Deserialize;
 use std::env;
 use std::{
     borrow::Cow,
@@ -22,15 +23,19 @@ use std::{
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
 use sui_faucet::{
     BatchFaucetResponse, BatchStatusFaucetResponse, Faucet, FaucetConfig, FaucetError,
-    FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet,
+    FaucetRequest, FaucetResponse, RefillFaucetResponse, RequestHistoryResponse,
+    RequestMetricsLayer, SimpleFaucet,
 };
 use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::SuiAddress;
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
 use uuid::Uuid;
 
 const CONCURRENCY_LIMIT: usize = 30;
+const DEFAULT_HISTORY_LIMIT: usize = 100;
+const MAX_HISTORY_LIMIT: usize = 1_000;
 
 struct AppState<F = Arc<SimpleFaucet>> {
     faucet: F,
@@ -88,26 +93,35 @@ async fn main() -> Result<(), anyhow::Error> {
         .allow_headers(Any)
         .allow_origin(Any);
 
-    let app = Router::new()
+    let mut router = Router::new()
         .route("/", get(health))
         .route("/gas", post(request_gas))
         .route("/v1/gas", post(batch_request_gas))
-        .route("/v1/status/:task_id", get(request_status))
-        .layer(
-            ServiceBuilder::new()
-                .layer(HandleErrorLayer::new(handle_error))
-                .layer(RequestMetricsLayer::new(&prometheus_registry))
-                .layer(cors)
-                .load_shed()
-                .buffer(request_buffer_size)
-                .layer(RateLimitLayer::new(
-                    max_request_per_second,
-                    Duration::from_secs(1),
-                ))
-                .concurrency_limit(max_concurrency)
-                .layer(Extension(app_state.clone()))
-                .into_inner(),
-        );
+        .route("/v1/status/:task_id", get(request_status));
+
+    if app_state.config.admin_token.is_some() {
+        router = router
+            .route("/admin/history", get(request_history))
+            .route("/admin/refill", post(refill_coin_pool));
+    } else {
+        info!("No admin token configured, admin endpoints are disabled.");
+    }
+
+    let app = router.layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(handle_error))
+            .layer(RequestMetricsLayer::new(&prometheus_registry))
+            .layer(cors)
+            .load_shed()
+            .buffer(request_buffer_size)
+            .layer(RateLimitLayer::new(
+                max_request_per_second,
+                Duration::from_secs(1),
+            ))
+            .concurrency_limit(max_concurrency)
+            .layer(Extension(app_state.clone()))
+            .into_inner(),
+    );
 
     spawn_monitored_task!(async move {
         info!("Starting task to clear WAL.");
@@ -115,13 +129,16 @@ async fn main() -> Result<(), anyhow::Error> {
             // Every config.wal_retry_interval (Default: 300 seconds) we try to clear the wal coins
             tokio::time::sleep(Duration::from_secs(wal_retry_interval)).await;
             app_state.faucet.retry_wal_coins().await.unwrap();
+            if let Err(e) = app_state.faucet.prune_request_history().await {
+                warn!("Failed to prune request history: {:?}", e);
+            }
         }
     });
 
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -134,6 +151,8 @@ async fn health() -> &'static str {
 /// handler for batch_request_gas requests
 async fn batch_request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     let id = Uuid::new_v4();
@@ -149,6 +168,16 @@ async fn batch_request_gas(
         );
     };
 
+    let ip = client_ip(&state.config, peer, &headers);
+    if let Err(e) = state
+        .faucet
+        .admit_request(id, request.recipient, Some(ip))
+        .await
+    {
+        warn!(uuid =?id, "Request was not admitted: {:?}", e);
+        return (rejection_status(&e), Json(BatchFaucetResponse::from(e)));
+    }
+
     if state.config.batch_enabled {
         let result = spawn_monitored_task!(async move {
             state
@@ -239,37 +268,46 @@ async fn request_status(
 /// handler for all the request_gas requests
 async fn request_gas(
     Extension(state): Extension<Arc<AppState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
-    let result = match payload {
-        FaucetRequest::FixedAmountRequest(requests) => {
-            // We spawn a tokio task for this such that connection drop will not interrupt
-            // it and impact the recycling of coins
-            spawn_monitored_task!(async move {
-                state
-                    .faucet
-                    .send(
-                        id,
-                        requests.recipient,
-                        &vec![state.config.amount; state.config.num_coins],
-                    )
-                    .await
-            })
-            .await
-            .unwrap()
-        }
-        _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(FaucetResponse::from(FaucetError::Internal(
-                    "Input Error.".to_string(),
-                ))),
-            )
-        }
+    let FaucetRequest::FixedAmountRequest(requests) = payload else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(FaucetResponse::from(FaucetError::Internal(
+                "Input Error.".to_string(),
+            ))),
+        );
     };
+
+    let ip = client_ip(&state.config, peer, &headers);
+    if let Err(e) = state
+        .faucet
+        .admit_request(id, requests.recipient, Some(ip))
+        .await
+    {
+        warn!(uuid =?id, "Request was not admitted: {:?}", e);
+        return (rejection_status(&e), Json(FaucetResponse::from(e)));
+    }
+
+    // We spawn a tokio task for this such that connection drop will not interrupt
+    // it and impact the recycling of coins
+    let result = spawn_monitored_task!(async move {
+        state
+            .faucet
+            .send(
+                id,
+                requests.recipient,
+                &vec![state.config.amount; state.config.num_coins],
+            )
+            .await
+    })
+    .await
+    .unwrap();
     match result {
         Ok(v) => {
             info!(uuid =?id, "Request is successfully served");
@@ -285,6 +323,115 @@ async fn request_gas(
     }
 }
 
+#[derive(Deserialize)]
+struct HistoryQuery {
+    address: Option<SuiAddress>,
+    ip: Option<IpAddr>,
+    limit: Option<usize>,
+}
+
+/// handler for admin requests for the faucet's request history
+async fn request_history(
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<HistoryQuery>,
+) -> impl IntoResponse {
+    if !is_admin(&state.config, &headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(RequestHistoryResponse::from(FaucetError::Unauthorized)),
+        );
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .min(MAX_HISTORY_LIMIT);
+    match state
+        .faucet
+        .recent_requests(query.address, query.ip, limit)
+        .await
+    {
+        Ok(v) => (StatusCode::OK, Json(RequestHistoryResponse::from(v))),
+        Err(v) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(RequestHistoryResponse::from(v)),
+        ),
+    }
+}
+
+/// handler for admin requests to add the faucet's unused coins to its gas coin pool
+async fn refill_coin_pool(
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_admin(&state.config, &headers) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(RefillFaucetResponse::from(FaucetError::Unauthorized)),
+        );
+    }
+
+    match state.faucet.refill_coin_pool().await {
+        Ok(v) => {
+            info!("Refilled gas coin pool with {} coins", v.added.len());
+            (StatusCode::OK, Json(RefillFaucetResponse::from(v)))
+        }
+        Err(v) => {
+            warn!("Failed to refill gas coin pool: {:?}", v);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(RefillFaucetResponse::from(v)),
+            )
+        }
+    }
+}
+
+/// The IP of the client making a request: the entry `trusted_proxy_hops` positions from the
+/// right of the `X-Forwarded-For` header, which the outermost trusted proxy appended, or the
+/// address of the connection if no proxy is trusted or the header has fewer entries.
+fn client_ip(config: &FaucetConfig, peer: SocketAddr, headers: &HeaderMap) -> IpAddr {
+    if config.trusted_proxy_hops == 0 {
+        return peer.ip();
+    }
+    let entries: Vec<_> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect();
+    entries
+        .len()
+        .checked_sub(config.trusted_proxy_hops)
+        .and_then(|index| entries[index].trim().parse().ok())
+        .unwrap_or_else(|| peer.ip())
+}
+
+fn is_admin(config: &FaucetConfig, headers: &HeaderMap) -> bool {
+    let Some(token) = &config.admin_token else {
+        return false;
+    };
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|bearer| constant_time_eq(bearer.as_bytes(), token.as_bytes()))
+}
+
+/// Compare `a` and `b` in a time that only depends on their lengths, so that response times do
+/// not reveal how much of the admin token a guess got right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && std::hint::black_box(a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y))) == 0
+}
+
+fn rejection_status(error: &FaucetError) -> StatusCode {
+    match error {
+        FaucetError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
@@ -309,3 +456,66 @@ async fn handle_error(error: BoxError) -> impl IntoResponse {
         Cow::from(format!("Unhandled internal error: {}", error)),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forwarded_for(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("x-forwarded-for", value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let peer: SocketAddr = "10.0.0.1:5003".parse().unwrap();
+        let headers = forwarded_for(&["1.1.1.1, 2.2.2.2", "3.3.3.3"]);
+        let ip = |hops| {
+            let config = FaucetConfig {
+                trusted_proxy_hops: hops,
+                ..Default::default()
+            };
+            client_ip(&config, peer, &headers).to_string()
+        };
+
+        // The header is ignored unless a proxy is trusted.
+        assert_eq!(ip(0), "10.0.0.1");
+        // Entries are counted from the right, those on the left may be spoofed by the client.
+        assert_eq!(ip(1), "3.3.3.3");
+        assert_eq!(ip(2), "2.2.2.2");
+        assert_eq!(ip(3), "1.1.1.1");
+        assert_eq!(ip(4), "10.0.0.1");
+
+        let config = FaucetConfig {
+            trusted_proxy_hops: 1,
+            ..Default::default()
+        };
+        let headers = forwarded_for(&["1.1.1.1, not an ip"]);
+        assert_eq!(client_ip(&config, peer, &headers).to_string(), "10.0.0.1");
+    }
+
+    #[test]
+    fn test_is_admin() {
+        let config = FaucetConfig {
+            admin_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let bearer = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::AUTHORIZATION, value.parse().unwrap());
+            headers
+        };
+        assert!(is_admin(&config, &bearer("Bearer secret")));
+        assert!(!is_admin(&config, &bearer("Bearer secreT")));
+        assert!(!is_admin(&config, &bearer("Bearer secret2")));
+        assert!(!is_admin(&config, &bearer("secret")));
+        assert!(!is_admin(&config, &HeaderMap::new()));
+        assert!(!is_admin(
+            &FaucetConfig::default(),
+            &bearer("Bearer secret")
+        ));
+    }
+}
@@ -28,6 +28,7 @@ pub struct FaucetMetrics {
     pub(crate) total_available_coins: IntGauge,
     pub(crate) total_discarded_coins: IntGauge,
     pub(crate) total_coin_requests_succeeded: IntGauge,
+    pub(crate) total_requests_rate_limited: IntCounter,
 }
 
 const LATENCY_SEC_BUCKETS: &[f64] = &[
@@ -112,6 +113,12 @@ impl FaucetMetrics {
                 registry,
             )
             .unwrap(),
+            total_requests_rate_limited: register_int_counter_with_registry!(
+                "total_requests_rate_limited",
+                "Total number of requests rejected because their address or IP was over quota",
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
@@ -90,3 +90,53 @@ impl From<BatchSendStatus> for BatchStatusFaucetResponse {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestHistoryResponse {
+    pub requests: Vec<FaucetRequestRecord>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for RequestHistoryResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            error: Some(e.to_string()),
+            requests: vec![],
+        }
+    }
+}
+
+impl From<Vec<FaucetRequestRecord>> for RequestHistoryResponse {
+    fn from(v: Vec<FaucetRequestRecord>) -> Self {
+        Self {
+            requests: v,
+            error: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RefillFaucetResponse {
+    pub refill: Option<RefillReceipt>,
+    pub error: Option<String>,
+}
+
+impl From<FaucetError> for RefillFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            error: Some(e.to_string()),
+            refill: None,
+        }
+    }
+}
+
+impl From<RefillReceipt> for RefillFaucetResponse {
+    fn from(v: RefillReceipt) -> Self {
+        Self {
+            refill: Some(v),
+            error: None,
+        }
+    }
+}
@@ -0,0 +1,773 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A file keystore that keeps its private keys encrypted at rest.
+//!
+//! The keystore file is a JSON envelope holding the scrypt parameters and salt used to derive
+//! a 256-bit key from the passphrase, and the AES-256-GCM encryption of the keys and their
+//! aliases. The envelope header is authenticated as associated data, so tampering with the KDF
+//! parameters is detected on decryption.
+//!
+//! The passphrase is read from the `SUI_KEYSTORE_PASSPHRASE` environment variable when it is set
+//! (e.g. on CI runners), and prompted for otherwise. To avoid prompting on every CLI invocation,
+//! [EncryptedFileKeystore::start_session] caches the derived key next to the keystore until a
+//! deadline, encrypted under a random session secret. The secret is never written to disk: it is
+//! handed back to the caller, and subsequent invocations read it from the `SUI_KEYSTORE_SESSION`
+//! environment variable, so the session file alone does not unlock the keystore.
+
+use crate::keystore::{AccountKeystore, Alias, InMemKeystore};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, ensure, Context};
+use fastcrypto::encoding::{Base64, Encoding, Hex};
+use inquire::Password;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::Intent;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair};
+use zeroize::Zeroize;
+
+/// Environment variable holding the passphrase of an encrypted keystore.
+pub const SUI_KEYSTORE_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_PASSPHRASE";
+/// Environment variable holding the passphrase to set when encrypting a keystore or changing
+/// its passphrase.
+pub const SUI_KEYSTORE_NEW_PASSPHRASE_ENV: &str = "SUI_KEYSTORE_NEW_PASSPHRASE";
+/// Environment variable holding the secret of an unlock session, as returned by
+/// [EncryptedFileKeystore::start_session].
+pub const SUI_KEYSTORE_SESSION_ENV: &str = "SUI_KEYSTORE_SESSION";
+
+const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+const KDF_NAME: &str = "scrypt";
+const CIPHER_NAME: &str = "aes-256-gcm";
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+/// Cost parameters of the scrypt key derivation.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for KdfParams {
+    /// N = 2^17, r = 8, p = 1, which takes 128 MiB of memory per derivation.
+    fn default() -> Self {
+        Self {
+            log_n: 17,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreHeader {
+    version: u8,
+    kdf: String,
+    kdf_params: KdfParams,
+    salt: String,
+    cipher: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    #[serde(flatten)]
+    header: EncryptedKeystoreHeader,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// The plaintext protected by the envelope.
+#[derive(Serialize, Deserialize, Default)]
+struct KeystoreContents {
+    keys: Vec<String>,
+    aliases: Vec<Alias>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UnlockSession {
+    expires_at_ms: u64,
+    /// The keystore key, encrypted under the session secret with the deadline as associated
+    /// data.
+    nonce: String,
+    ciphertext: String,
+}
+
+pub struct EncryptedFileKeystore {
+    keystore: InMemKeystore,
+    path: PathBuf,
+    kdf_params: KdfParams,
+    salt: Vec<u8>,
+    key: [u8; KEY_LENGTH],
+}
+
+impl Serialize for EncryptedFileKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileKeystore::unlock(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl Drop for EncryptedFileKeystore {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl AccountKeystore for EncryptedFileKeystore {
+    fn add_key(&mut self, alias: Option<String>, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        let alias = self.create_alias(alias)?;
+        self.keystore.add_key(Some(alias), keypair)?;
+        self.save()
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.keystore.keys()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        self.keystore.get_key(address)
+    }
+
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        self.keystore.sign_hashed(address, msg)
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        self.keystore.sign_secure(address, msg, intent)
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.keystore.addresses_with_alias()
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.keystore.aliases()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.keystore.aliases_mut()
+    }
+
+    fn get_alias_by_address(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        self.keystore.get_alias_by_address(address)
+    }
+
+    fn get_address_by_alias(&self, alias: String) -> Result<&SuiAddress, anyhow::Error> {
+        self.keystore.get_address_by_alias(alias)
+    }
+
+    fn create_alias(&self, alias: Option<String>) -> Result<String, anyhow::Error> {
+        self.keystore.create_alias(alias)
+    }
+
+    /// Updates an old alias to the new alias and re-encrypts the keystore.
+    /// If the new_alias is None, it will generate a new random alias.
+    fn update_alias(
+        &mut self,
+        old_alias: &str,
+        new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        let new_alias_name = self.update_alias_value(old_alias, new_alias)?;
+        self.save()?;
+        Ok(new_alias_name)
+    }
+}
+
+impl EncryptedFileKeystore {
+    /// Create a new, empty encrypted keystore at `path`, protected by `passphrase`.
+    pub fn new(
+        path: &Path,
+        passphrase: &str,
+        kdf_params: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        ensure!(
+            !path.exists(),
+            "Keystore file already exists: {}",
+            path.display()
+        );
+        let keystore = Self::empty(path, passphrase, kdf_params)?;
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Encrypt all keys and aliases of `keystore` into a new encrypted keystore at `path`.
+    /// An existing file at `path` is overwritten, which allows migrating a plaintext keystore
+    /// in place: it is only replaced once the encrypted keystore has been written and decrypts
+    /// with `passphrase` to the same keys and aliases.
+    pub fn from_keystore<K: AccountKeystore>(
+        keystore: &K,
+        path: &Path,
+        passphrase: &str,
+        kdf_params: KdfParams,
+    ) -> Result<Self, anyhow::Error> {
+        let mut encrypted = Self::empty(path, passphrase, kdf_params)?;
+        for (address, alias) in keystore.addresses_with_alias() {
+            let key = keystore.get_key(address)?;
+            let key = SuiKeyPair::decode_base64(&key.encode_base64())
+                .map_err(|e| anyhow!("Cannot copy key for address {address}. {e}"))?;
+            encrypted.keystore.add_key(Some(alias.alias.clone()), key)?;
+        }
+        write_private_checked(path, encrypted.serialize()?.as_bytes(), |written| {
+            let reopened = Self::open(written, passphrase)?;
+            ensure!(
+                reopened.addresses() == keystore.addresses()
+                    && reopened.alias_names() == keystore.alias_names(),
+                "The encrypted keystore does not hold the same keys as {}",
+                path.display()
+            );
+            Ok(())
+        })?;
+        Ok(encrypted)
+    }
+
+    /// Decrypt the keystore at `path` with `passphrase`.
+    pub fn open(path: &Path, passphrase: &str) -> Result<Self, anyhow::Error> {
+        let file = read_keystore_file(path)?;
+        let salt = decode(&file.header.salt, "salt")?;
+        let key = derive_key(passphrase, &salt, &file.header.kdf_params)?;
+        Self::decrypt(path, &file, key)
+    }
+
+    /// Decrypt the keystore at `path` with the key cached by an unexpired unlock session, using
+    /// the session secret returned by [Self::start_session].
+    pub fn open_session(path: &Path, secret: &str) -> Result<Self, anyhow::Error> {
+        let file = read_keystore_file(path)?;
+        let key = read_session(path, secret)?;
+        Self::decrypt(path, &file, key)
+    }
+
+    /// Decrypt the keystore at `path`, using the unlock session whose secret is in the
+    /// `SUI_KEYSTORE_SESSION` environment variable if there is one, or the passphrase from the
+    /// environment or a prompt otherwise.
+    pub fn unlock(path: &Path) -> Result<Self, anyhow::Error> {
+        if let Ok(secret) = std::env::var(SUI_KEYSTORE_SESSION_ENV) {
+            // A missing, expired or stale session (e.g. from before a passphrase change) fails to
+            // open, in which case fall back to asking for the passphrase.
+            if let Ok(keystore) = Self::open_session(path, &secret) {
+                return Ok(keystore);
+            }
+        }
+        let file = read_keystore_file(path)?;
+        let passphrase = read_passphrase(path)?;
+        let salt = decode(&file.header.salt, "salt")?;
+        let key = derive_key(&passphrase, &salt, &file.header.kdf_params)?;
+        Self::decrypt(path, &file, key)
+    }
+
+    /// Returns true if the file at `path` is an encrypted keystore.
+    pub fn is_encrypted(path: &Path) -> bool {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<EncryptedKeystoreFile>(&bytes).ok())
+            .is_some()
+    }
+
+    /// Re-encrypt the keystore under a new passphrase, with a fresh salt. Any unlock session
+    /// is ended.
+    pub fn change_passphrase(&mut self, new_passphrase: &str) -> Result<(), anyhow::Error> {
+        ensure!(!new_passphrase.is_empty(), "The passphrase cannot be empty");
+        let salt = random_bytes(SALT_LENGTH);
+        let mut key = derive_key(new_passphrase, &salt, &self.kdf_params)?;
+        std::mem::swap(&mut self.key, &mut key);
+        key.zeroize();
+        self.salt = salt;
+        self.save()?;
+        Self::lock(&self.path)
+    }
+
+    /// Cache the derived key next to the keystore, encrypted under a fresh random secret, so
+    /// that it can be unlocked without the passphrase until `timeout` has elapsed. Returns the
+    /// Base64 session secret, which is not stored anywhere and has to be passed back through
+    /// `SUI_KEYSTORE_SESSION` or [Self::open_session]. Starting a session replaces any previous
+    /// one.
+    pub fn start_session(&self, timeout: Duration) -> Result<String, anyhow::Error> {
+        let mut secret: [u8; KEY_LENGTH] = random_bytes(KEY_LENGTH)
+            .try_into()
+            .expect("random_bytes returns KEY_LENGTH bytes");
+        let expires_at_ms = now_ms().saturating_add(timeout.as_millis() as u64);
+        let nonce = random_bytes(NONCE_LENGTH);
+        let ciphertext = cipher(&secret)?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &self.key,
+                    aad: &expires_at_ms.to_le_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("Cannot encrypt the keystore session"))?;
+        let session = UnlockSession {
+            expires_at_ms,
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        };
+        let encoded = Base64::encode(secret);
+        secret.zeroize();
+        write_private(&session_path(&self.path), &serde_json::to_vec(&session)?)?;
+        Ok(encoded)
+    }
+
+    /// End the unlock session of the keystore at `path`, if any.
+    pub fn lock(path: &Path) -> Result<(), anyhow::Error> {
+        let session_path = session_path(path);
+        if session_path.exists() {
+            fs::remove_file(&session_path).with_context(|| {
+                format!("Cannot remove keystore session: {}", session_path.display())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Returns the deadline of the unlock session of the keystore at `path`, if one is active.
+    pub fn session_expiry(path: &Path) -> Option<SystemTime> {
+        let session: UnlockSession =
+            serde_json::from_slice(&fs::read(session_path(path)).ok()?).ok()?;
+        (session.expires_at_ms > now_ms())
+            .then(|| UNIX_EPOCH + Duration::from_millis(session.expires_at_ms))
+    }
+
+    /// Write the decrypted keys and aliases as a plaintext file keystore at `path`.
+    pub fn export(&self, path: &Path) -> Result<(), anyhow::Error> {
+        ensure!(
+            !path.exists(),
+            "Export destination already exists: {}",
+            path.display()
+        );
+        let contents = self.contents();
+        write_private(
+            path,
+            serde_json::to_string_pretty(&contents.keys)?.as_bytes(),
+        )?;
+        let mut aliases_path = path.to_path_buf();
+        aliases_path.set_extension("aliases");
+        write_private(
+            &aliases_path,
+            serde_json::to_string_pretty(&contents.aliases)?.as_bytes(),
+        )
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        write_private(&self.path, self.serialize()?.as_bytes())
+    }
+
+    fn serialize(&self) -> Result<String, anyhow::Error> {
+        let header = self.header();
+        let nonce = random_bytes(NONCE_LENGTH);
+        let plaintext = serde_json::to_vec(&self.contents())?;
+        let ciphertext = cipher(&self.key)?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &serde_json::to_vec(&header)?,
+                },
+            )
+            .map_err(|_| anyhow!("Cannot encrypt keystore: {}", self.path.display()))?;
+        let file = EncryptedKeystoreFile {
+            header,
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        };
+        serde_json::to_string_pretty(&file)
+            .with_context(|| format!("Cannot serialize keystore to file: {}", self.path.display()))
+    }
+
+    fn empty(path: &Path, passphrase: &str, kdf_params: KdfParams) -> Result<Self, anyhow::Error> {
+        ensure!(!passphrase.is_empty(), "The passphrase cannot be empty");
+        let salt = random_bytes(SALT_LENGTH);
+        let key = derive_key(passphrase, &salt, &kdf_params)?;
+        Ok(Self {
+            keystore: InMemKeystore::default(),
+            path: path.to_path_buf(),
+            kdf_params,
+            salt,
+            key,
+        })
+    }
+
+    fn decrypt(
+        path: &Path,
+        file: &EncryptedKeystoreFile,
+        key: [u8; KEY_LENGTH],
+    ) -> Result<Self, anyhow::Error> {
+        let nonce = decode(&file.nonce, "nonce")?;
+        ensure!(
+            nonce.len() == NONCE_LENGTH,
+            "Invalid nonce in keystore: {}",
+            path.display()
+        );
+        let plaintext = cipher(&key)?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &decode(&file.ciphertext, "ciphertext")?,
+                    aad: &serde_json::to_vec(&file.header)?,
+                },
+            )
+            .map_err(|_| {
+                anyhow!(
+                    "Cannot decrypt keystore {}: wrong passphrase or corrupted file",
+                    path.display()
+                )
+            })?;
+        let contents: KeystoreContents = serde_json::from_slice(&plaintext)
+            .with_context(|| format!("Cannot deserialize the keystore: {}", path.display()))?;
+
+        let keys = contents
+            .keys
+            .iter()
+            .map(|kpstr| {
+                let key = SuiKeyPair::decode_base64(kpstr);
+                key.map(|k| (SuiAddress::from(&k.public()), k))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid keystore file: {}. {}", path.display(), e))?;
+        let aliases = contents
+            .aliases
+            .into_iter()
+            .map(|alias| {
+                let key = PublicKey::decode_base64(&alias.public_key_base64);
+                key.map(|k| (Into::<SuiAddress>::into(&k), alias))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid aliases in keystore: {}. {}", path.display(), e))?;
+
+        Ok(Self {
+            keystore: InMemKeystore { aliases, keys },
+            path: path.to_path_buf(),
+            kdf_params: file.header.kdf_params,
+            salt: decode(&file.header.salt, "salt")?,
+            key,
+        })
+    }
+
+    fn header(&self) -> EncryptedKeystoreHeader {
+        EncryptedKeystoreHeader {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            kdf: KDF_NAME.to_string(),
+            kdf_params: self.kdf_params,
+            salt: Base64::encode(&self.salt),
+            cipher: CIPHER_NAME.to_string(),
+        }
+    }
+
+    fn contents(&self) -> KeystoreContents {
+        KeystoreContents {
+            keys: self
+                .keystore
+                .keys
+                .values()
+                .map(EncodeDecodeBase64::encode_base64)
+                .collect(),
+            aliases: self.keystore.aliases().into_iter().cloned().collect(),
+        }
+    }
+}
+
+/// Read the passphrase of the keystore at `path` from the environment, or prompt for it.
+pub fn read_passphrase(path: &Path) -> Result<String, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    Password::new(&format!("Passphrase for keystore {}:", path.display()))
+        .without_confirmation()
+        .prompt()
+        .map_err(|e| {
+            anyhow!(
+                "Cannot read the keystore passphrase: {e}. \
+                Set {SUI_KEYSTORE_PASSPHRASE_ENV} when running non-interactively."
+            )
+        })
+}
+
+/// Read a new keystore passphrase from the environment, or prompt for it twice.
+pub fn read_new_passphrase() -> Result<String, anyhow::Error> {
+    let passphrase = match std::env::var(SUI_KEYSTORE_NEW_PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) => Password::new("New keystore passphrase:")
+            .with_custom_confirmation_message("Confirm passphrase:")
+            .with_custom_confirmation_error_message("The passphrases don't match.")
+            .prompt()
+            .map_err(|e| {
+                anyhow!(
+                    "Cannot read the new keystore passphrase: {e}. \
+                    Set {SUI_KEYSTORE_NEW_PASSPHRASE_ENV} when running non-interactively."
+                )
+            })?,
+    };
+    ensure!(!passphrase.is_empty(), "The passphrase cannot be empty");
+    Ok(passphrase)
+}
+
+/// Encrypt `plaintext` under `passphrase`, in the envelope format of the encrypted keystore.
+/// This is used for other files holding secrets, like exported client environments.
+pub fn encrypt_with_passphrase(
+    plaintext: &[u8],
+    passphrase: &str,
+    kdf_params: KdfParams,
+) -> Result<Vec<u8>, anyhow::Error> {
+    ensure!(!passphrase.is_empty(), "The passphrase cannot be empty");
+    let salt = random_bytes(SALT_LENGTH);
+    let mut key = derive_key(passphrase, &salt, &kdf_params)?;
+    let header = EncryptedKeystoreHeader {
+        version: ENCRYPTED_KEYSTORE_VERSION,
+        kdf: KDF_NAME.to_string(),
+        kdf_params,
+        salt: Base64::encode(&salt),
+        cipher: CIPHER_NAME.to_string(),
+    };
+    let nonce = random_bytes(NONCE_LENGTH);
+    let ciphertext = cipher(&key)?
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &serde_json::to_vec(&header)?,
+            },
+        )
+        .map_err(|_| anyhow!("Cannot encrypt with the passphrase"));
+    key.zeroize();
+    let file = EncryptedKeystoreFile {
+        header,
+        nonce: Base64::encode(nonce),
+        ciphertext: Base64::encode(ciphertext?),
+    };
+    Ok(serde_json::to_vec_pretty(&file)?)
+}
+
+/// Decrypt an envelope produced by [encrypt_with_passphrase].
+pub fn decrypt_with_passphrase(
+    envelope: &[u8],
+    passphrase: &str,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let file: EncryptedKeystoreFile =
+        serde_json::from_slice(envelope).context("Cannot deserialize the encrypted file")?;
+    check_header(&file.header)?;
+    let mut key = derive_key(
+        passphrase,
+        &decode(&file.header.salt, "salt")?,
+        &file.header.kdf_params,
+    )?;
+    let nonce = decode(&file.nonce, "nonce")?;
+    ensure!(
+        nonce.len() == NONCE_LENGTH,
+        "Invalid nonce in encrypted file"
+    );
+    let plaintext = cipher(&key)?
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &decode(&file.ciphertext, "ciphertext")?,
+                aad: &serde_json::to_vec(&file.header)?,
+            },
+        )
+        .map_err(|_| anyhow!("Cannot decrypt: wrong passphrase or corrupted file"));
+    key.zeroize();
+    plaintext
+}
+
+/// Returns true if `bytes` is an envelope produced by [encrypt_with_passphrase].
+pub fn is_passphrase_encrypted(bytes: &[u8]) -> bool {
+    serde_json::from_slice::<EncryptedKeystoreFile>(bytes).is_ok()
+}
+
+fn read_keystore_file(path: &Path) -> Result<EncryptedKeystoreFile, anyhow::Error> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("Cannot open the keystore file: {}", path.display()))?;
+    let file: EncryptedKeystoreFile = serde_json::from_slice(&bytes).with_context(|| {
+        format!(
+            "Cannot deserialize the encrypted keystore file: {}",
+            path.display()
+        )
+    })?;
+    check_header(&file.header).with_context(|| format!("Cannot read {}", path.display()))?;
+    Ok(file)
+}
+
+fn check_header(header: &EncryptedKeystoreHeader) -> Result<(), anyhow::Error> {
+    if header.version != ENCRYPTED_KEYSTORE_VERSION
+        || header.kdf != KDF_NAME
+        || header.cipher != CIPHER_NAME
+    {
+        bail!(
+            "Unsupported encrypted keystore format (version {}, {} + {})",
+            header.version,
+            header.kdf,
+            header.cipher,
+        );
+    }
+    Ok(())
+}
+
+fn read_session(path: &Path, secret: &str) -> Result<[u8; KEY_LENGTH], anyhow::Error> {
+    let session_path = session_path(path);
+    let bytes = fs::read(&session_path)
+        .with_context(|| format!("No keystore session: {}", session_path.display()))?;
+    let session: UnlockSession = serde_json::from_slice(&bytes).with_context(|| {
+        format!(
+            "Cannot deserialize the keystore session: {}",
+            session_path.display()
+        )
+    })?;
+    if session.expires_at_ms <= now_ms() {
+        // Best effort, the session is rejected either way.
+        let _ = EncryptedFileKeystore::lock(path);
+        bail!("The keystore session has expired");
+    }
+    let mut secret: [u8; KEY_LENGTH] = Base64::decode(secret)
+        .ok()
+        .and_then(|secret| secret.try_into().ok())
+        .ok_or_else(|| anyhow!("Invalid keystore session secret"))?;
+    let nonce = decode(&session.nonce, "session nonce")?;
+    ensure!(
+        nonce.len() == NONCE_LENGTH,
+        "Invalid keystore session nonce"
+    );
+    let ciphertext = decode(&session.ciphertext, "session ciphertext")?;
+    let key = cipher(&secret)?.decrypt(
+        Nonce::from_slice(&nonce),
+        Payload {
+            msg: &ciphertext,
+            aad: &session.expires_at_ms.to_le_bytes(),
+        },
+    );
+    secret.zeroize();
+    let mut key = key.map_err(|_| anyhow!("Wrong keystore session secret"))?;
+    let result = key
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow!("Invalid keystore session key"));
+    key.zeroize();
+    result
+}
+
+fn session_path(path: &Path) -> PathBuf {
+    let mut session_path = path.to_path_buf();
+    session_path.set_extension("session");
+    session_path
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    kdf_params: &KdfParams,
+) -> Result<[u8; KEY_LENGTH], anyhow::Error> {
+    let params = scrypt::Params::new(kdf_params.log_n, kdf_params.r, kdf_params.p)
+        .map_err(|e| anyhow!("Invalid scrypt parameters: {e}"))?;
+    let mut key = [0u8; KEY_LENGTH];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| anyhow!("Cannot derive the keystore key: {e}"))?;
+    Ok(key)
+}
+
+fn cipher(key: &[u8; KEY_LENGTH]) -> Result<Aes256Gcm, anyhow::Error> {
+    Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("Invalid keystore key: {e}"))
+}
+
+fn decode(value: &str, what: &str) -> Result<Vec<u8>, anyhow::Error> {
+    Base64::decode(value).map_err(|e| anyhow!("Invalid {what} in encrypted keystore: {e}"))
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Write `contents` to `path`, readable by the current user only. The file is replaced
+/// atomically, so that it is never left truncated or half-written.
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), anyhow::Error> {
+    write_private_checked(path, contents, |_| Ok(()))
+}
+
+/// Like [write_private], but `check` is first run on the written contents, in a temporary file
+/// next to `path`, and `path` is left untouched if it fails.
+fn write_private_checked(
+    path: &Path,
+    contents: &[u8],
+    check: impl FnOnce(&Path) -> Result<(), anyhow::Error>,
+) -> Result<(), anyhow::Error> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid file path: {}", path.display()))?;
+    let tmp_path = dir.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        Hex::encode(random_bytes(8))
+    ));
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let result = options
+        .open(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .with_context(|| format!("Cannot write file: {}", tmp_path.display()))
+        .and_then(|()| check(&tmp_path))
+        .and_then(|()| {
+            fs::rename(&tmp_path, path)
+                .with_context(|| format!("Cannot write file: {}", path.display()))
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return result;
+    }
+    // Persist the rename itself.
+    #[cfg(unix)]
+    fs::File::open(dir)
+        .and_then(|dir| dir.sync_all())
+        .with_context(|| format!("Cannot sync directory: {}", dir.display()))?;
+    Ok(())
+}
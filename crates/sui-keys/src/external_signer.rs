@@ -0,0 +1,464 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A keystore backend that delegates signing to an external signer, so that private keys can
+//! live in an HSM, a secrets manager or a custody service instead of on disk.
+//!
+//! The signer is either a program that is spawned for every request, or a process listening on
+//! a Unix domain socket that accepts one request per connection. Requests and responses are
+//! single lines of JSON:
+//!
+//! ```text
+//! > {"method":"keys"}
+//! < {"keys":[{"public_key":"<Base64 flag || pk>","alias":"my-key"}]}
+//! > {"method":"sign","public_key":"<Base64 flag || pk>","msg":"<Base64>","intent_msg":"<Base64>"}
+//! < {"signature":"<Base64 flag || sig || pk>"}
+//! < {"error":"<reason>"}
+//! ```
+//!
+//! The signer must sign `msg` exactly as [Signature::new_hashed] would. For transactions and
+//! personal messages, `msg` is the Blake2b-256 digest of `intent_msg`, the BCS bytes of the
+//! intent message, which is passed along so the signer can inspect what it is asked to sign.
+//! Returned signatures are checked against the requested key before they are used.
+
+use crate::keystore::{AccountKeystore, Alias};
+use anyhow::{anyhow, bail, Context};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::time::Duration;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    DefaultHash, EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair, SuiSignature,
+};
+
+/// How long to wait for the signer to answer a request, by default.
+pub const EXTERNAL_SIGNER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How to reach the external signer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalSignerTransport {
+    /// Spawn `program` with `args` for every request, writing the request to its stdin and
+    /// reading the response from its stdout.
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// Connect to a Unix domain socket for every request.
+    Socket(PathBuf),
+}
+
+impl FromStr for ExternalSignerTransport {
+    type Err = anyhow::Error;
+
+    /// Parses `unix:<path>` as a socket, and anything else as a whitespace separated command
+    /// line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            return Ok(Self::Socket(PathBuf::from(path)));
+        }
+        let mut words = s.split_whitespace().map(str::to_string);
+        let program = words
+            .next()
+            .ok_or_else(|| anyhow!("The external signer command cannot be empty"))?;
+        Ok(Self::Command {
+            program,
+            args: words.collect(),
+        })
+    }
+}
+
+impl Display for ExternalSignerTransport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Command { program, args } => {
+                write!(f, "{program}")?;
+                for arg in args {
+                    write!(f, " {arg}")?;
+                }
+                Ok(())
+            }
+            Self::Socket(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum ExternalSignerRequest {
+    /// List the public keys the signer can sign for.
+    Keys,
+    /// Sign `msg` with the key of `public_key`.
+    Sign {
+        /// Base64 encoded `flag || pk`.
+        public_key: String,
+        /// Base64 encoded bytes to sign.
+        msg: String,
+        /// Base64 encoded BCS bytes of the intent message `msg` is the digest of, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        intent_msg: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalSignerResponse {
+    Keys(Vec<ExternalKey>),
+    /// Base64 encoded `flag || sig || pk`.
+    Signature(String),
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ExternalKey {
+    /// Base64 encoded `flag || pk`.
+    pub public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+pub struct ExternalKeystore {
+    transport: ExternalSignerTransport,
+    timeout: Duration,
+    keys: BTreeMap<SuiAddress, PublicKey>,
+    aliases: BTreeMap<SuiAddress, Alias>,
+}
+
+impl Serialize for ExternalKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.transport.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExternalKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        ExternalKeystore::new(ExternalSignerTransport::deserialize(deserializer)?)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for ExternalKeystore {
+    fn add_key(
+        &mut self,
+        _alias: Option<String>,
+        _keypair: SuiKeyPair,
+    ) -> Result<(), anyhow::Error> {
+        bail!("Keys of an external signer are managed by the signer itself")
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.keys.values().cloned().collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        bail!("The private key of [{address}] is held by the external signer and cannot be read")
+    }
+
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        self.sign(address, msg, None)
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let intent_msg = IntentMessage::new(intent, msg);
+        let bytes = bcs::to_bytes(&intent_msg).map_err(signature::Error::from_source)?;
+        let mut hasher = DefaultHash::default();
+        hasher.update(&bytes);
+        let sig = self.sign(address, &hasher.finalize().digest, Some(&bytes))?;
+        sig.verify_secure(&intent_msg, *address, sig.scheme())
+            .map_err(signature::Error::from_source)?;
+        Ok(sig)
+    }
+
+    fn addresses_with_alias(&self) -> Vec<(&SuiAddress, &Alias)> {
+        self.aliases.iter().collect::<Vec<_>>()
+    }
+
+    fn aliases(&self) -> Vec<&Alias> {
+        self.aliases.values().collect()
+    }
+
+    fn aliases_mut(&mut self) -> Vec<&mut Alias> {
+        self.aliases.values_mut().collect()
+    }
+
+    fn get_alias_by_address(&self, address: &SuiAddress) -> Result<String, anyhow::Error> {
+        match self.aliases.get(address) {
+            Some(alias) => Ok(alias.alias.clone()),
+            None => bail!("Cannot find alias for address {address}"),
+        }
+    }
+
+    fn get_address_by_alias(&self, alias: String) -> Result<&SuiAddress, anyhow::Error> {
+        self.addresses_with_alias()
+            .iter()
+            .find(|x| x.1.alias == alias)
+            .ok_or_else(|| anyhow!("Cannot resolve alias {alias} to an address"))
+            .map(|x| x.0)
+    }
+
+    fn create_alias(&self, _alias: Option<String>) -> Result<String, anyhow::Error> {
+        bail!("Aliases of an external signer are managed by the signer itself")
+    }
+
+    fn update_alias(
+        &mut self,
+        _old_alias: &str,
+        _new_alias: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        bail!("Aliases of an external signer are managed by the signer itself")
+    }
+}
+
+impl ExternalKeystore {
+    /// Connect to the external signer and load the keys it can sign for.
+    pub fn new(transport: ExternalSignerTransport) -> Result<Self, anyhow::Error> {
+        Self::new_with_timeout(transport, EXTERNAL_SIGNER_TIMEOUT)
+    }
+
+    /// Like [Self::new], but giving up on requests the signer has not answered within
+    /// `timeout`.
+    pub fn new_with_timeout(
+        transport: ExternalSignerTransport,
+        timeout: Duration,
+    ) -> Result<Self, anyhow::Error> {
+        let keys = match request(&transport, &ExternalSignerRequest::Keys, timeout)? {
+            ExternalSignerResponse::Keys(keys) => keys,
+            ExternalSignerResponse::Error(e) => {
+                bail!("External signer [{transport}] failed to list keys: {e}")
+            }
+            response => {
+                bail!("Unexpected response from external signer [{transport}]: {response:?}")
+            }
+        };
+
+        let mut public_keys = BTreeMap::new();
+        let mut aliases = BTreeMap::new();
+        for key in keys {
+            let public_key = PublicKey::decode_base64(&key.public_key).map_err(|e| {
+                anyhow!(
+                    "Invalid public key {} from external signer [{transport}]: {e}",
+                    key.public_key
+                )
+            })?;
+            let address = SuiAddress::from(&public_key);
+            // Fall back to a name derived from the address, so that it is stable across runs.
+            let alias = key
+                .alias
+                .unwrap_or_else(|| format!("external-{}", &address.to_string()[2..10]));
+            aliases.insert(
+                address,
+                Alias {
+                    alias,
+                    public_key_base64: key.public_key,
+                },
+            );
+            public_keys.insert(address, public_key);
+        }
+
+        Ok(Self {
+            transport,
+            timeout,
+            keys: public_keys,
+            aliases,
+        })
+    }
+
+    pub fn transport(&self) -> &ExternalSignerTransport {
+        &self.transport
+    }
+
+    fn sign(
+        &self,
+        address: &SuiAddress,
+        msg: &[u8],
+        intent_msg: Option<&[u8]>,
+    ) -> Result<Signature, signature::Error> {
+        let public_key = self.keys.get(address).ok_or_else(|| {
+            signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+        })?;
+        let sign_request = ExternalSignerRequest::Sign {
+            public_key: public_key.encode_base64(),
+            msg: Base64::encode(msg),
+            intent_msg: intent_msg.map(Base64::encode),
+        };
+        let sig = match request(&self.transport, &sign_request, self.timeout)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?
+        {
+            ExternalSignerResponse::Signature(sig) => {
+                Signature::decode_base64(&sig).map_err(|e| {
+                    signature::Error::from_source(format!(
+                        "Invalid signature from external signer: {e}"
+                    ))
+                })?
+            }
+            ExternalSignerResponse::Error(e) => {
+                return Err(signature::Error::from_source(format!(
+                    "External signer refused to sign for [{address}]: {e}"
+                )))
+            }
+            response => {
+                return Err(signature::Error::from_source(format!(
+                    "Unexpected response from external signer: {response:?}"
+                )))
+            }
+        };
+
+        let signer = PublicKey::try_from_bytes(sig.scheme(), sig.public_key_bytes())
+            .map(|pk| SuiAddress::from(&pk))
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        if signer != *address {
+            return Err(signature::Error::from_source(format!(
+                "External signer signed with [{signer}] instead of [{address}]"
+            )));
+        }
+        Ok(sig)
+    }
+}
+
+/// Send one request to the external signer and wait up to `timeout` for its response. A command
+/// signer that does not answer in time is killed.
+fn request(
+    transport: &ExternalSignerTransport,
+    request: &ExternalSignerRequest,
+    timeout: Duration,
+) -> Result<ExternalSignerResponse, anyhow::Error> {
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    let response = match transport {
+        ExternalSignerTransport::Command { program, args } => {
+            let mut child = Command::new(program)
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .with_context(|| format!("Cannot start external signer [{transport}]"))?;
+            child
+                .stdin
+                .take()
+                .ok_or_else(|| anyhow!("Cannot write to external signer [{transport}]"))?
+                .write_all(line.as_bytes())?;
+            // The output is read on another thread, so that waiting for it can time out.
+            let mut stdout = child
+                .stdout
+                .take()
+                .ok_or_else(|| anyhow!("Cannot read from external signer [{transport}]"))?;
+            let (sender, receiver) = mpsc::channel();
+            std::thread::spawn(move || {
+                let mut output = vec![];
+                let _ = sender.send(stdout.read_to_end(&mut output).map(|_| output));
+            });
+            let output = match receiver.recv_timeout(timeout) {
+                Ok(output) => output?,
+                Err(_) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    bail!("No response from external signer [{transport}] within {timeout:?}");
+                }
+            };
+            let status = child.wait()?;
+            if !status.success() {
+                bail!("External signer [{transport}] exited with {status}");
+            }
+            String::from_utf8(output)?
+        }
+        #[cfg(unix)]
+        ExternalSignerTransport::Socket(path) => {
+            let mut stream = std::os::unix::net::UnixStream::connect(path)
+                .with_context(|| format!("Cannot connect to external signer [{transport}]"))?;
+            stream.set_read_timeout(Some(timeout))?;
+            stream.set_write_timeout(Some(timeout))?;
+            stream.write_all(line.as_bytes())?;
+            let mut response = String::new();
+            BufReader::new(stream)
+                .read_line(&mut response)
+                .with_context(|| format!("No response from external signer [{transport}]"))?;
+            response
+        }
+        #[cfg(not(unix))]
+        ExternalSignerTransport::Socket(_) => {
+            bail!("Socket external signers are only supported on Unix")
+        }
+    };
+    let response = response.lines().next().unwrap_or_default();
+    serde_json::from_str(response)
+        .with_context(|| format!("Invalid response from external signer [{transport}]: {response}"))
+}
+
+/// Answer an external signer request with the keys of `keystore`. This is the signer side of
+/// the protocol, for mock signers in tests and for custom signers written in Rust.
+pub fn handle_external_signer_request<K: AccountKeystore>(
+    keystore: &K,
+    request: ExternalSignerRequest,
+) -> ExternalSignerResponse {
+    match request {
+        ExternalSignerRequest::Keys => ExternalSignerResponse::Keys(
+            keystore
+                .addresses_with_alias()
+                .into_iter()
+                .map(|(_, alias)| ExternalKey {
+                    public_key: alias.public_key_base64.clone(),
+                    alias: Some(alias.alias.clone()),
+                })
+                .collect(),
+        ),
+        ExternalSignerRequest::Sign {
+            public_key, msg, ..
+        } => {
+            let sig = PublicKey::decode_base64(&public_key)
+                .map_err(|e| e.to_string())
+                .and_then(|pk| {
+                    let msg = Base64::decode(&msg).map_err(|e| e.to_string())?;
+                    keystore
+                        .sign_hashed(&SuiAddress::from(&pk), &msg)
+                        .map_err(|e| e.to_string())
+                });
+            match sig {
+                Ok(sig) => ExternalSignerResponse::Signature(sig.encode_base64()),
+                Err(e) => ExternalSignerResponse::Error(e),
+            }
+        }
+    }
+}
+
+/// Serve the requests read line by line from `reader` with the keys of `keystore`, until the
+/// end of the input.
+pub fn serve_external_signer<K: AccountKeystore>(
+    keystore: &K,
+    reader: impl BufRead,
+    mut writer: impl Write,
+) -> Result<(), anyhow::Error> {
+    for line in reader.lines() {
+        let response = match serde_json::from_str(&line?) {
+            Ok(request) => handle_external_signer_request(keystore, request),
+            Err(e) => ExternalSignerResponse::Error(format!("Invalid request: {e}")),
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
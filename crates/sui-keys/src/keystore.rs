@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::encrypted_keystore::EncryptedFileKeystore;
+use crate::external_signer::ExternalKeystore;
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 use crate::random_names::{random_name, random_names};
 use anyhow::{anyhow, bail, ensure, Context};
@@ -28,6 +30,8 @@ use sui_types::crypto::{
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileKeystore),
+    External(ExternalKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -149,6 +153,16 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(file) => {
+                writeln!(writer, "Keystore Type : Encrypted")?;
+                write!(writer, "Keystore Path : {:?}", file.path())?;
+                write!(f, "{}", writer)
+            }
+            Keystore::External(external) => {
+                writeln!(writer, "Keystore Type : External")?;
+                write!(writer, "Keystore Signer : {}", external.transport())?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -399,6 +413,10 @@ impl FileBasedKeystore {
         })
     }
 
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     pub fn set_path(&mut self, path: &Path) {
         self.path = Some(path.to_path_buf());
     }
@@ -449,8 +467,8 @@ impl FileBasedKeystore {
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct InMemKeystore {
-    aliases: BTreeMap<SuiAddress, Alias>,
-    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    pub(crate) aliases: BTreeMap<SuiAddress, Alias>,
+    pub(crate) keys: BTreeMap<SuiAddress, SuiKeyPair>,
 }
 
 impl AccountKeystore for InMemKeystore {
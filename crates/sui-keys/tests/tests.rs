@@ -3,9 +3,18 @@
 
 use std::fs;
 use std::str::FromStr;
+use std::time::Duration;
 
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::EncodeDecodeBase64;
+use sui_keys::encrypted_keystore::{
+    decrypt_with_passphrase, encrypt_with_passphrase, is_passphrase_encrypted,
+    EncryptedFileKeystore, KdfParams,
+};
+use sui_keys::external_signer::{
+    handle_external_signer_request, serve_external_signer, ExternalKeystore, ExternalSignerRequest,
+    ExternalSignerResponse, ExternalSignerTransport,
+};
 use sui_keys::key_derive::generate_new_key;
 use tempfile::TempDir;
 
@@ -266,3 +275,316 @@ fn get_alias_by_address_test() {
     let address = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
     assert!(keystore.get_alias_by_address(&address.0).is_err())
 }
+
+// Cheap scrypt parameters to keep the tests fast.
+const TEST_KDF_PARAMS: KdfParams = KdfParams {
+    log_n: 4,
+    r: 8,
+    p: 1,
+};
+
+#[test]
+fn encrypted_keystore_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(
+        EncryptedFileKeystore::new(&keystore_path, "passphrase", TEST_KDF_PARAMS).unwrap(),
+    );
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(
+            SignatureScheme::ED25519,
+            Some("my_alias".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(keystore.to_string().contains("Encrypted"));
+
+    // Neither the private key nor the alias is stored in plaintext.
+    let contents = fs::read_to_string(&keystore_path).unwrap();
+    let private_key = keystore.get_key(&address).unwrap().encode_base64();
+    assert!(!contents.contains(&private_key));
+    assert!(!contents.contains("my_alias"));
+    assert!(EncryptedFileKeystore::is_encrypted(&keystore_path));
+
+    let reopened = EncryptedFileKeystore::open(&keystore_path, "passphrase").unwrap();
+    assert_eq!(vec![address], reopened.addresses());
+    assert_eq!("my_alias", reopened.get_alias_by_address(&address).unwrap());
+    assert_eq!(
+        private_key,
+        reopened.get_key(&address).unwrap().encode_base64()
+    );
+    assert!(EncryptedFileKeystore::open(&keystore_path, "wrong passphrase").is_err());
+
+    // Creating a keystore over an existing file is refused.
+    assert!(EncryptedFileKeystore::new(&keystore_path, "passphrase", TEST_KDF_PARAMS).is_err());
+}
+
+#[test]
+fn encrypted_keystore_tampering_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore =
+        EncryptedFileKeystore::new(&keystore_path, "passphrase", TEST_KDF_PARAMS).unwrap();
+    keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+
+    // The KDF parameters are authenticated along with the ciphertext.
+    let mut file: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&keystore_path).unwrap()).unwrap();
+    file["kdf_params"]["r"] = 9.into();
+    fs::write(&keystore_path, file.to_string()).unwrap();
+    assert!(EncryptedFileKeystore::open(&keystore_path, "passphrase").is_err());
+}
+
+#[test]
+fn migrate_and_export_keystore_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut file_keystore = FileBasedKeystore::new(&keystore_path).unwrap();
+    file_keystore
+        .generate_and_add_new_key(
+            SignatureScheme::ED25519,
+            Some("first".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    file_keystore
+        .generate_and_add_new_key(
+            SignatureScheme::Secp256k1,
+            Some("second".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+
+    // Encrypt the keystore in place.
+    let encrypted = EncryptedFileKeystore::from_keystore(
+        &file_keystore,
+        &keystore_path,
+        "passphrase",
+        TEST_KDF_PARAMS,
+    )
+    .unwrap();
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+    // The plaintext keystore is replaced, not overwritten, and no temporary file is left behind.
+    assert!(fs::read_dir(temp_dir.path()).unwrap().all(|entry| !entry
+        .unwrap()
+        .file_name()
+        .to_string_lossy()
+        .ends_with(".tmp")));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&keystore_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    let encrypted_names = encrypted.alias_names();
+    assert_eq!(file_keystore.alias_names(), encrypted_names);
+    assert_eq!(file_keystore.addresses(), encrypted.addresses());
+
+    // Export it back to a plaintext keystore.
+    let export_path = temp_dir.path().join("exported.keystore");
+    encrypted.export(&export_path).unwrap();
+    let exported = FileBasedKeystore::new(&export_path).unwrap();
+    assert_eq!(file_keystore.addresses(), exported.addresses());
+    assert_eq!(file_keystore.alias_names(), exported.alias_names());
+    for address in file_keystore.addresses() {
+        assert_eq!(
+            file_keystore.get_key(&address).unwrap(),
+            exported.get_key(&address).unwrap()
+        );
+    }
+    assert!(encrypted.export(&export_path).is_err());
+}
+
+#[test]
+fn change_passphrase_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore =
+        EncryptedFileKeystore::new(&keystore_path, "old passphrase", TEST_KDF_PARAMS).unwrap();
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+
+    keystore.change_passphrase("new passphrase").unwrap();
+    assert!(EncryptedFileKeystore::open(&keystore_path, "old passphrase").is_err());
+    let reopened = EncryptedFileKeystore::open(&keystore_path, "new passphrase").unwrap();
+    assert_eq!(vec![address], reopened.addresses());
+    assert!(keystore.change_passphrase("").is_err());
+}
+
+#[test]
+fn unlock_session_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore =
+        EncryptedFileKeystore::new(&keystore_path, "passphrase", TEST_KDF_PARAMS).unwrap();
+    let (address, _, _) = keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+    assert!(EncryptedFileKeystore::session_expiry(&keystore_path).is_none());
+
+    // An active session unlocks the keystore with its secret, without the passphrase.
+    let secret = keystore
+        .start_session(std::time::Duration::from_secs(60))
+        .unwrap();
+    assert!(EncryptedFileKeystore::session_expiry(&keystore_path).is_some());
+    let unlocked = EncryptedFileKeystore::open_session(&keystore_path, &secret).unwrap();
+    assert_eq!(vec![address], unlocked.addresses());
+
+    // The secret is not stored with the session, and starting a new session invalidates it.
+    let session = std::fs::read_to_string(keystore_path.with_extension("session")).unwrap();
+    assert!(!session.contains(&secret));
+    let new_secret = keystore
+        .start_session(std::time::Duration::from_secs(60))
+        .unwrap();
+    assert!(EncryptedFileKeystore::open_session(&keystore_path, &secret).is_err());
+    assert!(EncryptedFileKeystore::open_session(&keystore_path, "not a secret").is_err());
+    EncryptedFileKeystore::open_session(&keystore_path, &new_secret).unwrap();
+
+    // Changing the passphrase ends the session.
+    keystore.change_passphrase("new passphrase").unwrap();
+    assert!(EncryptedFileKeystore::session_expiry(&keystore_path).is_none());
+
+    // An expired session is rejected.
+    let secret = keystore
+        .start_session(std::time::Duration::from_secs(0))
+        .unwrap();
+    assert!(EncryptedFileKeystore::session_expiry(&keystore_path).is_none());
+    assert!(EncryptedFileKeystore::open_session(&keystore_path, &secret).is_err());
+
+    keystore
+        .start_session(std::time::Duration::from_secs(60))
+        .unwrap();
+    EncryptedFileKeystore::lock(&keystore_path).unwrap();
+    assert!(EncryptedFileKeystore::session_expiry(&keystore_path).is_none());
+}
+
+/// Start a mock external signer serving the keys of `keystore` on a Unix socket in `dir`.
+#[cfg(unix)]
+fn start_mock_signer(dir: &std::path::Path, keystore: InMemKeystore) -> std::path::PathBuf {
+    use std::io::BufReader;
+    use std::os::unix::net::UnixListener;
+
+    let socket_path = dir.join("signer.sock");
+    let listener = UnixListener::bind(&socket_path).unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = stream.unwrap();
+            serve_external_signer(
+                &keystore,
+                BufReader::new(stream.try_clone().unwrap()),
+                stream,
+            )
+            .unwrap();
+        }
+    });
+    socket_path
+}
+
+#[cfg(unix)]
+#[test]
+fn external_signer_socket_test() {
+    use shared_crypto::intent::{Intent, PersonalMessage};
+
+    let temp_dir = TempDir::new().unwrap();
+    let mock_keystore = InMemKeystore::new_insecure_for_tests(2);
+    let expected_addresses = mock_keystore.addresses();
+    let expected_aliases = mock_keystore.alias_names().join(",");
+    let socket_path = start_mock_signer(temp_dir.path(), mock_keystore);
+
+    let transport =
+        ExternalSignerTransport::from_str(&format!("unix:{}", socket_path.display())).unwrap();
+    assert_eq!(ExternalSignerTransport::Socket(socket_path), transport);
+    let mut keystore = Keystore::from(ExternalKeystore::new(transport).unwrap());
+    assert_eq!(expected_addresses, keystore.addresses());
+    assert_eq!(expected_aliases, keystore.alias_names().join(","));
+    assert!(keystore.to_string().contains("External"));
+
+    // Signatures are produced by the signer and verify against the requested address.
+    let address = expected_addresses[0];
+    let msg = PersonalMessage {
+        message: b"hello".to_vec(),
+    };
+    let sig = keystore
+        .sign_secure(&address, &msg, Intent::personal_message())
+        .unwrap();
+    let expected = InMemKeystore::new_insecure_for_tests(2)
+        .sign_secure(&address, &msg, Intent::personal_message())
+        .unwrap();
+    assert_eq!(expected, sig);
+
+    // Keys never leave the signer.
+    assert!(keystore.get_key(&address).is_err());
+    let (_, keypair, _, _) = generate_new_key(SignatureScheme::ED25519, None, None).unwrap();
+    assert!(keystore.add_key(None, keypair).is_err());
+    let unknown = generate_new_key(SignatureScheme::ED25519, None, None)
+        .unwrap()
+        .0;
+    assert!(keystore.sign_hashed(&unknown, b"hello").is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn external_signer_command_test() {
+    // A signer that only ever lists a single key.
+    let keystore = InMemKeystore::new_insecure_for_tests(1);
+    let response = handle_external_signer_request(&keystore, ExternalSignerRequest::Keys);
+    assert!(matches!(response, ExternalSignerResponse::Keys(_)));
+    let transport = ExternalSignerTransport::Command {
+        program: "sh".to_string(),
+        args: vec![
+            "-c".to_string(),
+            format!(
+                "cat > /dev/null; echo '{}'",
+                serde_json::to_string(&response).unwrap()
+            ),
+        ],
+    };
+    let external = ExternalKeystore::new(transport).unwrap();
+    assert_eq!(keystore.addresses(), external.addresses());
+    // The canned response is not a signature.
+    assert!(external
+        .sign_hashed(&keystore.addresses()[0], b"hello")
+        .is_err());
+
+    // A failing signer is reported.
+    let transport = ExternalSignerTransport::from_str("false").unwrap();
+    assert!(ExternalKeystore::new(transport).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn external_signer_command_timeout_test() {
+    // A signer that never answers is killed once the timeout expires.
+    let transport = ExternalSignerTransport::Command {
+        program: "sh".to_string(),
+        args: vec!["-c".to_string(), "exec sleep 600".to_string()],
+    };
+    let start = std::time::Instant::now();
+    let err = ExternalKeystore::new_with_timeout(transport, Duration::from_millis(200))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("No response"), "{err}");
+    assert!(start.elapsed() < Duration::from_secs(60));
+}
+
+#[test]
+fn encrypt_with_passphrase_test() {
+    let plaintext = b"https://rpc.example.com/?api_key=secret";
+    let envelope = encrypt_with_passphrase(plaintext, "passphrase", TEST_KDF_PARAMS).unwrap();
+    assert!(is_passphrase_encrypted(&envelope));
+    assert!(!is_passphrase_encrypted(plaintext));
+    assert!(!String::from_utf8_lossy(&envelope).contains("secret"));
+
+    assert_eq!(
+        decrypt_with_passphrase(&envelope, "passphrase").unwrap(),
+        plaintext
+    );
+    assert!(decrypt_with_passphrase(&envelope, "wrong passphrase").is_err());
+    assert!(encrypt_with_passphrase(plaintext, "", TEST_KDF_PARAMS).is_err());
+}
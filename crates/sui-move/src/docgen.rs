@@ -0,0 +1,1186 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::{Parser, ValueEnum};
+use move_cli::base;
+use move_model::{
+    model::{
+        AbilitySet, FunctionEnv, FunctionVisibility, GlobalEnv, ModuleEnv, QualifiedId, StructEnv,
+        StructId, TypeParameter,
+    },
+    ty::Type,
+};
+use move_package::{BuildConfig, ModelConfig};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    path::PathBuf,
+};
+
+const DEFAULT_OUTPUT_DIR: &str = "docs";
+const INDEX_PAGE: &str = "index";
+const SEARCH_INDEX_JSON: &str = "search-index.json";
+const SEARCH_INDEX_JS: &str = "search-index.js";
+const SEARCH_JS: &str = "search.js";
+const STYLE_CSS: &str = "style.css";
+
+/// Generate a documentation site for the package and its dependencies, annotated with Sui
+/// concepts: entry functions, object ownership, events and displays.
+#[derive(Parser)]
+#[group(id = "sui-move-docgen")]
+pub struct Docgen {
+    /// Directory to write the site to. Defaults to `build/docs` in the package's directory
+    #[clap(long = "output-directory", value_name = "PATH")]
+    pub output_directory: Option<PathBuf>,
+    /// Format of the generated pages
+    #[clap(long, value_enum, default_value_t = SiteFormat::Html)]
+    pub format: SiteFormat,
+    /// Only document the modules of the package, not those of its dependencies
+    #[clap(long)]
+    pub exclude_dependencies: bool,
+    /// Also document private functions that are not entry functions
+    #[clap(long)]
+    pub include_private_fun: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SiteFormat {
+    Html,
+    Markdown,
+}
+
+impl Docgen {
+    pub fn execute(self, path: Option<PathBuf>, build_config: BuildConfig) -> anyhow::Result<()> {
+        let package_root = base::reroot_path(path)?;
+        let env = build_config.move_model_for_package(
+            &package_root,
+            ModelConfig {
+                all_files_as_targets: false,
+                target_filter: None,
+            },
+        )?;
+        anyhow::ensure!(
+            !env.has_errors(),
+            "Errors encountered while compiling the package, run `sui move build` for details"
+        );
+
+        let site = Site::new(&env, self.exclude_dependencies, self.include_private_fun);
+        let files = match self.format {
+            SiteFormat::Html => site.render::<Html>(),
+            SiteFormat::Markdown => site.render::<Markdown>(),
+        };
+
+        let output_dir = self
+            .output_directory
+            .unwrap_or_else(|| package_root.join("build").join(DEFAULT_OUTPUT_DIR));
+        for (file, content) in files {
+            let path = output_dir.join(file);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(&path, content)?;
+        }
+        println!(
+            "Documented {} module(s) in {}",
+            site.modules.len(),
+            output_dir.display()
+        );
+        Ok(())
+    }
+}
+
+/// A module, by address and name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ModuleRef {
+    address: String,
+    name: String,
+}
+
+/// A function, by module and name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct FunRef {
+    module: ModuleRef,
+    name: String,
+}
+
+/// A type appearing in a declaration, with the information needed to link struct types to their
+/// documentation.
+enum TypeDoc {
+    Primitive(String),
+    Struct {
+        module: ModuleRef,
+        name: String,
+        args: Vec<TypeDoc>,
+    },
+    Vector(Box<TypeDoc>),
+    Reference(bool, Box<TypeDoc>),
+    Param(String),
+    Tuple(Vec<TypeDoc>),
+}
+
+/// What the modules in the model do with instances of a struct.
+#[derive(Default)]
+struct StructUsage {
+    /// Shared with `sui::transfer::share_object` or `public_share_object`
+    shared: bool,
+    /// Transferred with `sui::transfer::transfer` or `public_transfer`
+    owned: bool,
+    /// Frozen with `sui::transfer::freeze_object` or `public_freeze_object`
+    frozen: bool,
+    /// Functions emitting the struct as an event
+    emitted_by: BTreeSet<FunRef>,
+    /// Whether a `sui::display::Display` is created for the struct
+    display: bool,
+}
+
+struct ModuleDoc {
+    module: ModuleRef,
+    /// Whether the module is part of the package, rather than one of its dependencies
+    is_target: bool,
+    doc: String,
+    structs: Vec<StructDoc>,
+    functions: Vec<FunctionDoc>,
+    constants: Vec<ConstantDoc>,
+}
+
+struct StructDoc {
+    name: String,
+    doc: String,
+    type_params: Vec<String>,
+    abilities: Vec<&'static str>,
+    fields: Vec<FieldDoc>,
+    usage: StructUsage,
+}
+
+struct FieldDoc {
+    name: String,
+    doc: String,
+    ty: TypeDoc,
+}
+
+struct FunctionDoc {
+    name: String,
+    doc: String,
+    visibility: String,
+    entry: bool,
+    type_params: Vec<String>,
+    params: Vec<(String, TypeDoc)>,
+    returns: Vec<TypeDoc>,
+    /// Events emitted by the function or the functions it calls
+    events: Vec<TypeDoc>,
+}
+
+struct ConstantDoc {
+    name: String,
+    doc: String,
+    ty: TypeDoc,
+}
+
+/// The documentation of all modules in a site.
+struct Site {
+    modules: Vec<ModuleDoc>,
+    /// Modules with a page in the site, which can be linked to
+    documented: BTreeSet<ModuleRef>,
+}
+
+impl ModuleRef {
+    fn new(module_env: &ModuleEnv) -> Self {
+        Self {
+            address: module_env.self_address().to_hex_literal(),
+            name: module_env
+                .symbol_pool()
+                .string(module_env.get_name().name())
+                .to_string(),
+        }
+    }
+
+    /// Path of the module's page, relative to the root of the site.
+    fn page(&self, extension: &str) -> String {
+        format!("{}/{}.{extension}", self.address, self.name)
+    }
+}
+
+impl fmt::Display for ModuleRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.address, self.name)
+    }
+}
+
+impl FunRef {
+    fn new(fun_env: &FunctionEnv) -> Self {
+        Self {
+            module: ModuleRef::new(&fun_env.module_env),
+            name: fun_env.get_simple_name_string().to_string(),
+        }
+    }
+}
+
+impl TypeDoc {
+    fn new(env: &GlobalEnv, ty: &Type, type_params: &[String]) -> Self {
+        match ty {
+            Type::Struct(mid, sid, args) => {
+                let struct_env = env.get_struct(mid.qualified(*sid));
+                TypeDoc::Struct {
+                    module: ModuleRef::new(&struct_env.module_env),
+                    name: env.symbol_pool().string(struct_env.get_name()).to_string(),
+                    args: args
+                        .iter()
+                        .map(|t| TypeDoc::new(env, t, type_params))
+                        .collect(),
+                }
+            }
+            Type::Vector(t) => TypeDoc::Vector(Box::new(TypeDoc::new(env, t, type_params))),
+            Type::Reference(mutable, t) => {
+                TypeDoc::Reference(*mutable, Box::new(TypeDoc::new(env, t, type_params)))
+            }
+            Type::TypeParameter(idx) => TypeDoc::Param(
+                type_params
+                    .get(*idx as usize)
+                    .cloned()
+                    .unwrap_or_else(|| format!("T{idx}")),
+            ),
+            Type::Tuple(tys) => TypeDoc::Tuple(
+                tys.iter()
+                    .map(|t| TypeDoc::new(env, t, type_params))
+                    .collect(),
+            ),
+            _ => TypeDoc::Primitive(ty.display(&env.get_type_display_ctx()).to_string()),
+        }
+    }
+
+    /// The outermost struct of the type, ignoring references, which the type links to.
+    fn linked_struct(&self) -> Option<(&ModuleRef, &str)> {
+        match self {
+            TypeDoc::Struct { module, name, .. } => Some((module, name)),
+            TypeDoc::Reference(_, t) => t.linked_struct(),
+            _ => None,
+        }
+    }
+
+    /// Renders the type as code, linking struct names with `link`.
+    fn code<F: Format>(&self, link: &dyn Fn(&ModuleRef, &str) -> Option<String>) -> String {
+        let list = |tys: &[TypeDoc]| {
+            tys.iter()
+                .map(|t| t.code::<F>(link))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            TypeDoc::Primitive(name) | TypeDoc::Param(name) => F::escape_code(name),
+            TypeDoc::Struct { module, name, args } => {
+                let name = match link(module, name) {
+                    Some(href) => F::code_link(&F::escape_code(name), &href),
+                    None => F::escape_code(name),
+                };
+                if args.is_empty() {
+                    name
+                } else {
+                    format!(
+                        "{name}{}{}{}",
+                        F::escape_code("<"),
+                        list(args),
+                        F::escape_code(">")
+                    )
+                }
+            }
+            TypeDoc::Vector(t) => format!(
+                "{}{}{}",
+                F::escape_code("vector<"),
+                t.code::<F>(link),
+                F::escape_code(">")
+            ),
+            TypeDoc::Reference(mutable, t) => {
+                let prefix = if *mutable { "&mut " } else { "&" };
+                format!("{}{}", F::escape_code(prefix), t.code::<F>(link))
+            }
+            TypeDoc::Tuple(tys) => format!("({})", list(tys)),
+        }
+    }
+}
+
+impl StructUsage {
+    /// How instances of the (object) struct are stored.
+    fn ownership(&self) -> Vec<&'static str> {
+        let mut kinds = vec![];
+        if self.shared {
+            kinds.push("shared");
+        }
+        if self.owned {
+            kinds.push("owned");
+        }
+        if self.frozen {
+            kinds.push("immutable");
+        }
+        kinds
+    }
+}
+
+impl Site {
+    fn new(env: &GlobalEnv, exclude_dependencies: bool, include_private_fun: bool) -> Self {
+        let mut usages = struct_usages(env);
+        let mut modules = vec![];
+        for module_env in env.get_modules() {
+            if module_env.is_script_module() || (exclude_dependencies && !module_env.is_target()) {
+                continue;
+            }
+            modules.push(ModuleDoc::new(
+                &module_env,
+                &mut usages,
+                include_private_fun,
+            ));
+        }
+        modules.sort_by(|m1, m2| m1.module.cmp(&m2.module));
+        let documented = modules.iter().map(|m| m.module.clone()).collect();
+        Self {
+            modules,
+            documented,
+        }
+    }
+
+    /// Renders all the files of the site, as paths relative to its root and their contents.
+    fn render<F: Format>(&self) -> Vec<(String, String)> {
+        let mut files = vec![(
+            format!("{INDEX_PAGE}.{}", F::EXTENSION),
+            self.index_page::<F>(),
+        )];
+        for module in &self.modules {
+            files.push((
+                module.module.page(F::EXTENSION),
+                self.module_page::<F>(module),
+            ));
+        }
+        let search_index = self.search_index::<F>();
+        files.push((SEARCH_INDEX_JSON.to_string(), search_index.to_string()));
+        files.extend(F::assets(&search_index));
+        files
+    }
+
+    /// Link from the page of module `from` (or the index, if `None`) to `anchor` in the page of
+    /// module `to`, if it has a page.
+    fn href<F: Format>(
+        &self,
+        from: Option<&ModuleRef>,
+        to: &ModuleRef,
+        anchor: Option<&str>,
+    ) -> Option<String> {
+        if !self.documented.contains(to) {
+            return None;
+        }
+        let anchor = anchor.map(|a| format!("#{a}")).unwrap_or_default();
+        Some(match from {
+            Some(from) if from == to => anchor,
+            Some(_) => format!("../{}{anchor}", to.page(F::EXTENSION)),
+            None => format!("{}{anchor}", to.page(F::EXTENSION)),
+        })
+    }
+
+    fn index_page<F: Format>(&self) -> String {
+        let mut out = String::new();
+        F::header(&mut out, 1, None, "Documentation");
+        let (package, dependencies): (Vec<_>, Vec<_>) =
+            self.modules.iter().partition(|m| m.is_target);
+
+        F::header(&mut out, 2, Some("package"), "Package");
+        F::list(&mut out, self.module_list::<F>(&package));
+
+        if !dependencies.is_empty() {
+            F::header(&mut out, 2, Some("dependencies"), "Dependencies");
+            let mut by_address: BTreeMap<&str, Vec<&ModuleDoc>> = BTreeMap::new();
+            for module in dependencies {
+                by_address
+                    .entry(module.module.address.as_str())
+                    .or_default()
+                    .push(module);
+            }
+            for (address, modules) in by_address {
+                F::header(&mut out, 3, None, &F::code(&F::escape_code(address)));
+                F::list(&mut out, self.module_list::<F>(&modules));
+            }
+        }
+        F::page("Documentation", "", &out)
+    }
+
+    fn module_list<F: Format>(&self, modules: &[&ModuleDoc]) -> Vec<String> {
+        modules
+            .iter()
+            .map(|m| {
+                let name = F::code(&F::escape_code(&m.module.to_string()));
+                let link = match self.href::<F>(None, &m.module, None) {
+                    Some(href) => F::link(&name, &href),
+                    None => name,
+                };
+                match summary(&m.doc) {
+                    "" => link,
+                    summary => format!("{link}: {}", F::text(summary)),
+                }
+            })
+            .collect()
+    }
+
+    fn module_page<F: Format>(&self, module: &ModuleDoc) -> String {
+        let from = Some(&module.module);
+        let type_link =
+            |m: &ModuleRef, name: &str| self.href::<F>(from, m, Some(&struct_anchor(name)));
+        let inline_type = |ty: &TypeDoc| {
+            let link = ty.linked_struct().and_then(|(m, name)| type_link(m, name));
+            F::inline_type(&ty.code::<F>(&type_link), link.as_deref())
+        };
+
+        let mut out = String::new();
+        F::header(
+            &mut out,
+            1,
+            None,
+            &format!(
+                "Module {}",
+                F::code(&F::escape_code(&module.module.to_string()))
+            ),
+        );
+        F::doc(&mut out, &module.doc);
+
+        if !module.structs.is_empty() {
+            F::header(&mut out, 2, Some("structs"), "Structs");
+        }
+        for s in &module.structs {
+            F::header(
+                &mut out,
+                3,
+                Some(&struct_anchor(&s.name)),
+                &format!("Struct {}", F::code(&F::escape_code(&s.name))),
+            );
+            F::paragraph(&mut out, &struct_annotations::<F>(s));
+            F::doc(&mut out, &s.doc);
+
+            let mut decl = format!("struct {}", s.name);
+            if !s.type_params.is_empty() {
+                decl.push_str(&format!("<{}>", s.type_params.join(", ")));
+            }
+            if !s.abilities.is_empty() {
+                decl.push_str(&format!(" has {}", s.abilities.join(", ")));
+            }
+            F::code_block(&mut out, &F::escape_code(&decl));
+
+            if !s.fields.is_empty() {
+                let rows = s
+                    .fields
+                    .iter()
+                    .map(|f| {
+                        vec![
+                            F::code(&F::escape_code(&f.name)),
+                            inline_type(&f.ty),
+                            F::text(&f.doc),
+                        ]
+                    })
+                    .collect();
+                F::table(&mut out, &["Field", "Type", "Description"], rows);
+            }
+            if !s.usage.emitted_by.is_empty() {
+                let emitters: Vec<_> = s
+                    .usage
+                    .emitted_by
+                    .iter()
+                    .map(|f| {
+                        let name = F::code(&F::escape_code(&format!("{}::{}", f.module, f.name)));
+                        match self.href::<F>(from, &f.module, Some(&fun_anchor(&f.name))) {
+                            Some(href) => F::link(&name, &href),
+                            None => name,
+                        }
+                    })
+                    .collect();
+                F::paragraph(&mut out, &format!("Emitted by: {}", emitters.join(", ")));
+            }
+        }
+
+        if !module.functions.is_empty() {
+            F::header(&mut out, 2, Some("functions"), "Functions");
+        }
+        for f in &module.functions {
+            F::header(
+                &mut out,
+                3,
+                Some(&fun_anchor(&f.name)),
+                &format!("Function {}", F::code(&F::escape_code(&f.name))),
+            );
+            let mut annotations = vec![F::badge(if f.visibility.is_empty() {
+                "private"
+            } else {
+                f.visibility.as_str()
+            })];
+            if f.entry {
+                annotations.push(F::badge("entry"));
+            }
+            F::paragraph(&mut out, &annotations.join(" "));
+            F::doc(&mut out, &f.doc);
+
+            let mut sig = F::escape_code(&format!(
+                "{}{}fun {}",
+                if f.visibility.is_empty() {
+                    String::new()
+                } else {
+                    format!("{} ", f.visibility)
+                },
+                if f.entry { "entry " } else { "" },
+                f.name
+            ));
+            if !f.type_params.is_empty() {
+                sig.push_str(&F::escape_code(&format!("<{}>", f.type_params.join(", "))));
+            }
+            let params: Vec<_> = f
+                .params
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", F::escape_code(name), ty.code::<F>(&type_link)))
+                .collect();
+            sig.push_str(&format!("({})", params.join(", ")));
+            match f.returns.as_slice() {
+                [] => (),
+                [ty] => sig.push_str(&format!(": {}", ty.code::<F>(&type_link))),
+                tys => {
+                    let tys: Vec<_> = tys.iter().map(|t| t.code::<F>(&type_link)).collect();
+                    sig.push_str(&format!(": ({})", tys.join(", ")));
+                }
+            }
+            F::code_block(&mut out, &sig);
+
+            if !f.events.is_empty() {
+                let events: Vec<_> = f.events.iter().map(inline_type).collect();
+                F::paragraph(&mut out, &format!("Emits: {}", events.join(", ")));
+            }
+        }
+
+        if !module.constants.is_empty() {
+            F::header(&mut out, 2, Some("constants"), "Constants");
+            let rows = module
+                .constants
+                .iter()
+                .map(|c| {
+                    vec![
+                        F::code(&F::escape_code(&c.name)),
+                        inline_type(&c.ty),
+                        F::text(&c.doc),
+                    ]
+                })
+                .collect();
+            F::table(&mut out, &["Name", "Type", "Description"], rows);
+        }
+
+        F::page(&format!("Module {}", module.module), "../", &out)
+    }
+
+    /// Entries for every module, struct, function and constant in the site, for searching.
+    fn search_index<F: Format>(&self) -> serde_json::Value {
+        let mut entries = vec![];
+        let mut entry =
+            |name: String, kind: &str, module: &ModuleRef, anchor: Option<String>, doc: &str| {
+                entries.push(json!({
+                    "name": name,
+                    "kind": kind,
+                    "href": self.href::<F>(None, module, anchor.as_deref()),
+                    "summary": summary(doc),
+                }))
+            };
+        for m in &self.modules {
+            entry(m.module.to_string(), "module", &m.module, None, &m.doc);
+            for s in &m.structs {
+                let name = format!("{}::{}", m.module, s.name);
+                entry(
+                    name,
+                    "struct",
+                    &m.module,
+                    Some(struct_anchor(&s.name)),
+                    &s.doc,
+                );
+            }
+            for f in &m.functions {
+                let name = format!("{}::{}", m.module, f.name);
+                entry(
+                    name,
+                    "function",
+                    &m.module,
+                    Some(fun_anchor(&f.name)),
+                    &f.doc,
+                );
+            }
+            for c in &m.constants {
+                let name = format!("{}::{}", m.module, c.name);
+                entry(
+                    name,
+                    "constant",
+                    &m.module,
+                    Some(const_anchor(&c.name)),
+                    &c.doc,
+                );
+            }
+        }
+        serde_json::Value::Array(entries)
+    }
+}
+
+impl ModuleDoc {
+    fn new(
+        module_env: &ModuleEnv,
+        usages: &mut BTreeMap<QualifiedId<StructId>, StructUsage>,
+        include_private_fun: bool,
+    ) -> Self {
+        let env = module_env.env;
+        let pool = env.symbol_pool();
+
+        let mut structs: Vec<_> = module_env.get_structs().collect();
+        structs.sort_by_key(|s| s.get_loc().span().start());
+        let structs = structs
+            .into_iter()
+            .map(|struct_env| {
+                let usage = usages
+                    .remove(&module_env.get_id().qualified(struct_env.get_id()))
+                    .unwrap_or_default();
+                StructDoc::new(&struct_env, usage)
+            })
+            .collect();
+
+        let mut functions: Vec<_> = module_env
+            .get_functions()
+            .filter(|f| {
+                include_private_fun || f.is_entry() || f.visibility() != FunctionVisibility::Private
+            })
+            .collect();
+        functions.sort_by_key(|f| f.get_loc().span().start());
+        let functions = functions.iter().map(FunctionDoc::new).collect();
+
+        let mut constants: Vec<_> = module_env.get_named_constants().collect();
+        constants.sort_by_key(|c| c.get_loc().span().start());
+        let constants = constants
+            .into_iter()
+            .map(|c| ConstantDoc {
+                name: pool.string(c.get_name()).to_string(),
+                doc: c.get_doc().to_string(),
+                ty: TypeDoc::new(env, &c.get_type(), &[]),
+            })
+            .collect();
+
+        Self {
+            module: ModuleRef::new(module_env),
+            is_target: module_env.is_target(),
+            doc: module_env.get_doc().to_string(),
+            structs,
+            functions,
+            constants,
+        }
+    }
+}
+
+impl StructDoc {
+    fn new(struct_env: &StructEnv, usage: StructUsage) -> Self {
+        let env = struct_env.module_env.env;
+        let pool = env.symbol_pool();
+        let named_params = struct_env.get_named_type_parameters();
+        let param_names: Vec<_> = named_params
+            .iter()
+            .map(|TypeParameter(name, _)| pool.string(*name).to_string())
+            .collect();
+        let type_params = named_params
+            .iter()
+            .enumerate()
+            .map(|(idx, tp)| {
+                let decl = type_param_decl(env, tp);
+                if struct_env.is_phantom_parameter(idx) {
+                    format!("phantom {decl}")
+                } else {
+                    decl
+                }
+            })
+            .collect();
+        let fields = struct_env
+            .get_fields()
+            .map(|field| FieldDoc {
+                name: pool.string(field.get_name()).to_string(),
+                doc: field.get_doc().to_string(),
+                ty: TypeDoc::new(env, &field.get_type(), &param_names),
+            })
+            .collect();
+        Self {
+            name: pool.string(struct_env.get_name()).to_string(),
+            doc: struct_env.get_doc().to_string(),
+            type_params,
+            abilities: ability_names(struct_env.get_abilities()),
+            fields,
+            usage,
+        }
+    }
+}
+
+impl FunctionDoc {
+    fn new(fun_env: &FunctionEnv) -> Self {
+        let env = fun_env.module_env.env;
+        let pool = env.symbol_pool();
+        let named_params = fun_env.get_named_type_parameters();
+        let param_names: Vec<_> = named_params
+            .iter()
+            .map(|TypeParameter(name, _)| pool.string(*name).to_string())
+            .collect();
+        let params = fun_env
+            .get_parameters()
+            .into_iter()
+            .map(|param| {
+                (
+                    pool.string(param.0).to_string(),
+                    TypeDoc::new(env, &param.1, &param_names),
+                )
+            })
+            .collect();
+        let returns = fun_env
+            .get_return_types()
+            .iter()
+            .map(|ty| TypeDoc::new(env, ty, &param_names))
+            .collect();
+        // type parameters of generic events are those of the emitting function, which may be a
+        // callee, so only concrete event types are listed
+        let events = fun_env
+            .get_events(true)
+            .iter()
+            .filter(|ty| matches!(ty, Type::Struct(..)) && !ty.is_open())
+            .map(|ty| TypeDoc::new(env, ty, &[]))
+            .collect();
+        Self {
+            name: fun_env.get_simple_name_string().to_string(),
+            doc: fun_env.get_doc().to_string(),
+            visibility: fun_env.visibility_str().trim_end().to_string(),
+            entry: fun_env.is_entry(),
+            type_params: named_params
+                .iter()
+                .map(|tp| type_param_decl(env, tp))
+                .collect(),
+            params,
+            returns,
+            events,
+        }
+    }
+}
+
+/// Finds what the functions of all modules in `env` do with each struct: which objects are shared,
+/// transferred or frozen, which structs are emitted as events, and which have a display.
+fn struct_usages(env: &GlobalEnv) -> BTreeMap<QualifiedId<StructId>, StructUsage> {
+    let mut usages = BTreeMap::new();
+    for module_env in env.get_modules() {
+        for fun_env in module_env.get_functions() {
+            for ty in fun_env.get_shared_objects(false) {
+                if let Some(u) = struct_usage(&mut usages, &ty) {
+                    u.shared = true;
+                }
+            }
+            for ty in fun_env.get_transferred_objects(false) {
+                if let Some(u) = struct_usage(&mut usages, &ty) {
+                    u.owned = true;
+                }
+            }
+            for ty in fun_env.get_frozen_objects(false) {
+                if let Some(u) = struct_usage(&mut usages, &ty) {
+                    u.frozen = true;
+                }
+            }
+            for ty in fun_env.get_events(false) {
+                if let Some(u) = struct_usage(&mut usages, &ty) {
+                    u.emitted_by.insert(FunRef::new(&fun_env));
+                }
+            }
+            for ty in fun_env.get_display_types(false) {
+                if let Some(u) = struct_usage(&mut usages, &ty) {
+                    u.display = true;
+                }
+            }
+        }
+    }
+    usages
+}
+
+fn struct_usage<'a>(
+    usages: &'a mut BTreeMap<QualifiedId<StructId>, StructUsage>,
+    ty: &Type,
+) -> Option<&'a mut StructUsage> {
+    match ty {
+        Type::Struct(mid, sid, _) => Some(usages.entry(mid.qualified(*sid)).or_default()),
+        _ => None,
+    }
+}
+
+fn struct_annotations<F: Format>(s: &StructDoc) -> String {
+    let mut annotations = vec![];
+    if s.abilities.is_empty() {
+        annotations.push("No abilities".to_string());
+    } else {
+        let abilities: Vec<_> = s.abilities.iter().map(|a| F::badge(a)).collect();
+        annotations.push(format!("Abilities: {}", abilities.join(" ")));
+    }
+    if s.abilities.contains(&"key") {
+        let ownership = s.usage.ownership();
+        if ownership.is_empty() {
+            annotations.push("Object".to_string());
+        } else {
+            let ownership: Vec<_> = ownership.into_iter().map(F::badge).collect();
+            annotations.push(format!("Object: {}", ownership.join(" ")));
+        }
+    }
+    if !s.usage.emitted_by.is_empty() {
+        annotations.push("Event".to_string());
+    }
+    if s.usage.display {
+        annotations.push("Has display".to_string());
+    }
+    annotations.join(" · ")
+}
+
+fn ability_names(abilities: AbilitySet) -> Vec<&'static str> {
+    let mut names = vec![];
+    if abilities.has_copy() {
+        names.push("copy");
+    }
+    if abilities.has_drop() {
+        names.push("drop");
+    }
+    if abilities.has_store() {
+        names.push("store");
+    }
+    if abilities.has_key() {
+        names.push("key");
+    }
+    names
+}
+
+fn type_param_decl(env: &GlobalEnv, TypeParameter(name, constraint): &TypeParameter) -> String {
+    let name = env.symbol_pool().string(*name);
+    let abilities = ability_names(constraint.0);
+    if abilities.is_empty() {
+        name.to_string()
+    } else {
+        format!("{name}: {}", abilities.join(" + "))
+    }
+}
+
+/// The first line of a doc comment.
+fn summary(doc: &str) -> &str {
+    doc.lines().next().unwrap_or_default().trim()
+}
+
+fn struct_anchor(name: &str) -> String {
+    format!("struct-{name}")
+}
+
+fn fun_anchor(name: &str) -> String {
+    format!("fun-{name}")
+}
+
+fn const_anchor(name: &str) -> String {
+    format!("const-{name}")
+}
+
+/// A format for the pages of the site. Functions returning `String`s produce inline content,
+/// those taking `out` append blocks to a page.
+trait Format {
+    const EXTENSION: &'static str;
+
+    /// Escapes text to be shown as code.
+    fn escape_code(code: &str) -> String;
+    /// A link inside code, whose text is already escaped.
+    fn code_link(code: &str, href: &str) -> String;
+    /// Inline code, already escaped.
+    fn code(code: &str) -> String;
+    /// A type inline, rendered as escaped code, optionally linking to the definition of its
+    /// outermost struct.
+    fn inline_type(code: &str, _href: Option<&str>) -> String {
+        Self::code(code)
+    }
+    fn link(inline: &str, href: &str) -> String;
+    /// Escapes plain text.
+    fn text(text: &str) -> String;
+    fn badge(text: &str) -> String;
+
+    fn header(out: &mut String, level: usize, anchor: Option<&str>, inline: &str);
+    fn paragraph(out: &mut String, inline: &str);
+    /// A block of code, already escaped.
+    fn code_block(out: &mut String, code: &str);
+    fn table(out: &mut String, headers: &[&str], rows: Vec<Vec<String>>);
+    fn list(out: &mut String, items: Vec<String>);
+    /// A doc comment.
+    fn doc(out: &mut String, doc: &str);
+
+    /// A complete page, given its title, the path to the root of the site and its body.
+    fn page(title: &str, root: &str, body: &str) -> String;
+    /// Files shared by all pages.
+    fn assets(search_index: &serde_json::Value) -> Vec<(String, String)>;
+}
+
+struct Markdown;
+
+struct Html;
+
+impl Format for Markdown {
+    const EXTENSION: &'static str = "md";
+
+    fn escape_code(code: &str) -> String {
+        code.to_string()
+    }
+
+    fn code_link(code: &str, _href: &str) -> String {
+        // links are not supported in markdown code blocks
+        code.to_string()
+    }
+
+    fn code(code: &str) -> String {
+        format!("`{code}`")
+    }
+
+    fn inline_type(code: &str, href: Option<&str>) -> String {
+        match href {
+            Some(href) => Self::link(&Self::code(code), href),
+            None => Self::code(code),
+        }
+    }
+
+    fn link(inline: &str, href: &str) -> String {
+        format!("[{inline}]({href})")
+    }
+
+    fn text(text: &str) -> String {
+        text.to_string()
+    }
+
+    fn badge(text: &str) -> String {
+        Self::code(text)
+    }
+
+    fn header(out: &mut String, level: usize, anchor: Option<&str>, inline: &str) {
+        if let Some(anchor) = anchor {
+            out.push_str(&format!("<a name=\"{anchor}\"></a>\n\n"));
+        }
+        out.push_str(&format!("{} {inline}\n\n", "#".repeat(level)));
+    }
+
+    fn paragraph(out: &mut String, inline: &str) {
+        out.push_str(&format!("{inline}\n\n"));
+    }
+
+    fn code_block(out: &mut String, code: &str) {
+        out.push_str(&format!("```move\n{code}\n```\n\n"));
+    }
+
+    fn table(out: &mut String, headers: &[&str], rows: Vec<Vec<String>>) {
+        let cell = |c: &str| c.replace('\n', " ").replace('|', "\\|");
+        out.push_str(&format!("| {} |\n", headers.join(" | ")));
+        out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+        for row in rows {
+            let row: Vec<_> = row.iter().map(|c| cell(c)).collect();
+            out.push_str(&format!("| {} |\n", row.join(" | ")));
+        }
+        out.push('\n');
+    }
+
+    fn list(out: &mut String, items: Vec<String>) {
+        for item in items {
+            out.push_str(&format!("- {item}\n"));
+        }
+        out.push('\n');
+    }
+
+    fn doc(out: &mut String, doc: &str) {
+        let doc = doc.trim();
+        if !doc.is_empty() {
+            out.push_str(&format!("{doc}\n\n"));
+        }
+    }
+
+    fn page(_title: &str, _root: &str, body: &str) -> String {
+        body.to_string()
+    }
+
+    fn assets(_search_index: &serde_json::Value) -> Vec<(String, String)> {
+        vec![]
+    }
+}
+
+impl Format for Html {
+    const EXTENSION: &'static str = "html";
+
+    fn escape_code(code: &str) -> String {
+        Self::text(code)
+    }
+
+    fn code_link(code: &str, href: &str) -> String {
+        Self::link(code, href)
+    }
+
+    fn code(code: &str) -> String {
+        format!("<code>{code}</code>")
+    }
+
+    fn link(inline: &str, href: &str) -> String {
+        format!("<a href=\"{}\">{inline}</a>", Self::text(href))
+    }
+
+    fn text(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn badge(text: &str) -> String {
+        format!("<span class=\"badge\">{}</span>", Self::text(text))
+    }
+
+    fn header(out: &mut String, level: usize, anchor: Option<&str>, inline: &str) {
+        match anchor {
+            Some(anchor) => {
+                out.push_str(&format!("<h{level} id=\"{anchor}\">{inline}</h{level}>\n"))
+            }
+            None => out.push_str(&format!("<h{level}>{inline}</h{level}>\n")),
+        }
+    }
+
+    fn paragraph(out: &mut String, inline: &str) {
+        out.push_str(&format!("<p>{inline}</p>\n"));
+    }
+
+    fn code_block(out: &mut String, code: &str) {
+        out.push_str(&format!("<pre><code>{code}</code></pre>\n"));
+    }
+
+    fn table(out: &mut String, headers: &[&str], rows: Vec<Vec<String>>) {
+        out.push_str("<table>\n<tr>");
+        for header in headers {
+            out.push_str(&format!("<th>{header}</th>"));
+        }
+        out.push_str("</tr>\n");
+        for row in rows {
+            out.push_str("<tr>");
+            for cell in row {
+                out.push_str(&format!("<td>{cell}</td>"));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+
+    fn list(out: &mut String, items: Vec<String>) {
+        out.push_str("<ul>\n");
+        for item in items {
+            out.push_str(&format!("<li>{item}</li>\n"));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    fn doc(out: &mut String, doc: &str) {
+        for paragraph in doc.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+            // render `code` spans, the most common markup in doc comments
+            let mut html = String::new();
+            for (i, part) in Self::text(paragraph).split('`').enumerate() {
+                if i % 2 == 1 {
+                    html.push_str(&Self::code(part));
+                } else {
+                    html.push_str(part);
+                }
+            }
+            Self::paragraph(out, &html);
+        }
+    }
+
+    fn page(title: &str, root: &str, body: &str) -> String {
+        format!(
+            "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<link rel=\"stylesheet\" href=\"{root}{STYLE_CSS}\">
+</head>
+<body data-root=\"{root}\">
+<nav>
+<a href=\"{root}{INDEX_PAGE}.html\">Index</a>
+<input id=\"search\" type=\"search\" placeholder=\"Search modules, structs, functions and constants\">
+<ul id=\"search-results\"></ul>
+</nav>
+<main>
+{body}</main>
+<script src=\"{root}{SEARCH_INDEX_JS}\"></script>
+<script src=\"{root}{SEARCH_JS}\"></script>
+</body>
+</html>
+",
+            title = Self::text(title),
+        )
+    }
+
+    fn assets(search_index: &serde_json::Value) -> Vec<(String, String)> {
+        vec![
+            // a script rather than JSON, so that pages can load it from the file system
+            (
+                SEARCH_INDEX_JS.to_string(),
+                format!("window.SEARCH_INDEX = {search_index};\n"),
+            ),
+            (SEARCH_JS.to_string(), SEARCH_SCRIPT.to_string()),
+            (STYLE_CSS.to_string(), STYLESHEET.to_string()),
+        ]
+    }
+}
+
+const SEARCH_SCRIPT: &str = r#"(function () {
+  const input = document.getElementById("search");
+  const results = document.getElementById("search-results");
+  const root = document.body.dataset.root;
+  input.addEventListener("input", function () {
+    const query = input.value.trim().toLowerCase();
+    results.replaceChildren();
+    if (!query) {
+      return;
+    }
+    for (const entry of window.SEARCH_INDEX) {
+      if (!entry.name.toLowerCase().includes(query)) {
+        continue;
+      }
+      const item = document.createElement("li");
+      const link = document.createElement("a");
+      link.href = root + entry.href;
+      link.textContent = entry.name;
+      item.append(link, " " + entry.kind);
+      if (entry.summary) {
+        item.append(": " + entry.summary);
+      }
+      results.append(item);
+      if (results.childElementCount >= 50) {
+        break;
+      }
+    }
+  });
+})();
+"#;
+
+const STYLESHEET: &str = r#"body {
+  font-family: sans-serif;
+  margin: 0 auto;
+  max-width: 60em;
+  padding: 0 1em;
+}
+nav {
+  border-bottom: 1px solid #ddd;
+  padding: 1em 0;
+}
+#search {
+  margin-left: 1em;
+  width: 30em;
+}
+pre {
+  background: #f6f8fa;
+  overflow-x: auto;
+  padding: 1em;
+}
+table {
+  border-collapse: collapse;
+}
+th, td {
+  border: 1px solid #ddd;
+  padding: 0.3em 0.6em;
+  text-align: left;
+}
+.badge {
+  background: #e7eefa;
+  border-radius: 0.3em;
+  font-size: 0.9em;
+  padding: 0.1em 0.4em;
+}
+"#;
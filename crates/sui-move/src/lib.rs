@@ -14,6 +14,8 @@ pub mod build;
 pub mod coverage;
 #[cfg(feature = "disassemble")]
 pub mod disassemble;
+#[cfg(feature = "docgen")]
+pub mod docgen;
 pub mod new;
 #[cfg(feature = "unit_test")]
 pub mod unit_test;
@@ -26,6 +28,8 @@ pub enum Command {
     Coverage(coverage::Coverage),
     #[cfg(feature = "disassemble")]
     Disassemble(disassemble::Disassemble),
+    #[cfg(feature = "docgen")]
+    Docgen(docgen::Docgen),
     New(new::New),
     #[cfg(feature = "unit_test")]
     Test(unit_test::Test),
@@ -53,6 +57,8 @@ pub fn execute_move_command(
         Command::Coverage(c) => c.execute(package_path, build_config),
         #[cfg(feature = "disassemble")]
         Command::Disassemble(c) => c.execute(package_path, build_config),
+        #[cfg(feature = "docgen")]
+        Command::Docgen(c) => c.execute(package_path, build_config),
         Command::New(c) => c.execute(package_path),
 
         #[cfg(feature = "unit_test")]
@@ -48,6 +48,11 @@ impl Test {
                 "The --coverage flag is currently supported only in debug builds. Please build the Sui CLI from source in debug mode."
             ));
         }
+        if !cfg!(debug_assertions) && self.test.profile_gas.is_some() {
+            return Err(anyhow::anyhow!(
+                "The --profile-gas flag is currently supported only in debug builds. Please build the Sui CLI from source in debug mode."
+            ));
+        }
         // find manifest file directory from a given path or (if missing) from current dir
         let rerooted_path = base::reroot_path(path)?;
         let unit_test_config = self.test.unit_test_config();
@@ -0,0 +1,202 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::future::Future;
+
+use simulacrum::ForkSource;
+use sui_json_rpc_types::{SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions};
+use sui_sdk::SuiClientBuilder;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::Object;
+use tokio::runtime::Handle;
+
+use crate::data_fetcher::{DataFetcher, RemoteFetcher};
+use crate::types::{ReplayEngineError, MAX_CONCURRENT_REQUESTS, RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD};
+
+/// A [`ForkSource`] which serves the state of a remote network, as of a fixed checkpoint, over
+/// JSON-RPC.
+///
+/// Full nodes only serve the latest version of an object, so to find the version that was live
+/// at the fork checkpoint we start from the latest version and walk backwards through the
+/// transactions that wrote it, until we reach one that was included at or before the fork
+/// checkpoint.
+pub struct RemoteForkSource {
+    fetcher: RemoteFetcher,
+    checkpoint: CheckpointSequenceNumber,
+    runtime: Handle,
+}
+
+impl RemoteForkSource {
+    /// Connect to the full node at `rpc_url` and fork from `checkpoint`, or from the latest
+    /// checkpoint if none is given.
+    ///
+    /// Must be called from within a multi-threaded tokio runtime, which is used to drive requests
+    /// made through the (synchronous) [`ForkSource`] interface.
+    pub async fn new(
+        rpc_url: &str,
+        checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> Result<Self, ReplayEngineError> {
+        let rpc_client = SuiClientBuilder::default()
+            .request_timeout(RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD)
+            .max_concurrent_requests(MAX_CONCURRENT_REQUESTS)
+            .build(rpc_url)
+            .await?;
+        let fetcher = RemoteFetcher::new(rpc_client);
+
+        let checkpoint = match checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => fetcher.get_latest_checkpoint_sequence_number().await?,
+        };
+
+        Ok(Self {
+            fetcher,
+            checkpoint,
+            runtime: Handle::current(),
+        })
+    }
+
+    /// The sequence number of the checkpoint that this source is forked from.
+    pub fn checkpoint(&self) -> CheckpointSequenceNumber {
+        self.checkpoint
+    }
+
+    /// The timestamp of the checkpoint that this source is forked from.
+    pub async fn checkpoint_timestamp_ms(&self) -> Result<u64, ReplayEngineError> {
+        Ok(self
+            .fetcher
+            .rpc_client
+            .read_api()
+            .get_checkpoint(self.checkpoint.into())
+            .await?
+            .timestamp_ms)
+    }
+
+    pub async fn chain_identifier(&self) -> Result<String, ReplayEngineError> {
+        self.fetcher.get_chain_id().await
+    }
+
+    async fn fetch_object(&self, id: ObjectID) -> Result<Option<Object>, ReplayEngineError> {
+        let Some(mut object) = not_found_as_none(self.fetcher.multi_get_latest(&[id]).await)?
+        else {
+            return Ok(None);
+        };
+
+        loop {
+            let Some(version) = self.version_before_fork(&object).await? else {
+                return Ok(None);
+            };
+
+            if version == object.version() {
+                return Ok(Some(object));
+            }
+
+            let Some(previous) =
+                not_found_as_none(self.fetcher.multi_get_versioned(&[(id, version)]).await)?
+            else {
+                return Ok(None);
+            };
+            object = previous;
+        }
+    }
+
+    async fn fetch_object_at_version(
+        &self,
+        id: ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>, ReplayEngineError> {
+        let Some(object) =
+            not_found_as_none(self.fetcher.multi_get_versioned(&[(id, version)]).await)?
+        else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .is_before_fork(object.previous_transaction)
+            .await?
+            .then_some(object))
+    }
+
+    /// Returns the version of `object` that was live at the fork checkpoint: either `object`'s
+    /// own version, if the transaction that wrote it was included at or before the fork, or the
+    /// version that transaction took as input. Returns `None` if the object was not live at the
+    /// fork checkpoint.
+    async fn version_before_fork(
+        &self,
+        object: &Object,
+    ) -> Result<Option<SequenceNumber>, ReplayEngineError> {
+        let digest = object.previous_transaction;
+        let response = self
+            .fetcher
+            .rpc_client
+            .read_api()
+            .get_transaction_with_options(
+                digest,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+            )
+            .await?;
+
+        if matches!(response.checkpoint, Some(c) if c <= self.checkpoint) {
+            return Ok(Some(object.version()));
+        }
+
+        let effects = response
+            .effects
+            .ok_or_else(|| ReplayEngineError::GeneralError {
+                err: format!("Effects for transaction {digest} were not returned"),
+            })?;
+
+        // If the object was not an input, it was created (or unwrapped) after the fork.
+        let id = object.id();
+        Ok(effects
+            .modified_at_versions()
+            .into_iter()
+            .find(|(object_id, _)| *object_id == id)
+            .map(|(_, version)| version))
+    }
+
+    async fn is_before_fork(&self, digest: TransactionDigest) -> Result<bool, ReplayEngineError> {
+        let response = self
+            .fetcher
+            .rpc_client
+            .read_api()
+            .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+            .await?;
+        Ok(matches!(response.checkpoint, Some(c) if c <= self.checkpoint))
+    }
+
+    fn block_on<T>(&self, future: impl Future<Output = T>) -> T {
+        tokio::task::block_in_place(|| self.runtime.block_on(future))
+    }
+}
+
+impl ForkSource for RemoteForkSource {
+    fn get_object(&self, id: &ObjectID) -> anyhow::Result<Option<Object>> {
+        Ok(self.block_on(self.fetch_object(*id))?)
+    }
+
+    fn get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> anyhow::Result<Option<Object>> {
+        Ok(self.block_on(self.fetch_object_at_version(*id, version))?)
+    }
+}
+
+/// Map the errors the fetcher uses to signal a missing object into `None`.
+fn not_found_as_none(
+    result: Result<Vec<Object>, ReplayEngineError>,
+) -> Result<Option<Object>, ReplayEngineError> {
+    match result {
+        Ok(mut objects) => Ok(objects.pop()),
+        Err(
+            ReplayEngineError::ObjectNotExist { .. }
+            | ReplayEngineError::ObjectDeleted { .. }
+            | ReplayEngineError::ObjectVersionNotFound { .. }
+            | ReplayEngineError::ObjectVersionTooHigh { .. },
+        ) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
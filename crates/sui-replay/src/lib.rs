@@ -23,9 +23,11 @@ use std::str::FromStr;
 use sui_config::node::ExpensiveSafetyCheckConfig;
 use sui_protocol_config::Chain;
 use sui_types::digests::TransactionDigest;
+use sui_types::transaction::TransactionData;
 use tracing::{error, info};
 pub mod config;
 mod data_fetcher;
+pub mod fork_source;
 pub mod fuzz;
 pub mod fuzz_mutations;
 mod replay;
@@ -75,6 +77,17 @@ pub enum ReplayToolCommand {
         executor_version_override: Option<i64>,
         #[arg(long, short, allow_hyphen_values = true)]
         protocol_version_override: Option<i64>,
+        /// Write a gas profile of the transaction, in the speedscope format
+        /// (https://www.speedscope.app), to the given directory or to the current one. Only
+        /// available in debug builds.
+        #[arg(
+            long,
+            value_name = "DIR",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "."
+        )]
+        profile_gas: Option<PathBuf>,
     },
 
     /// Replay transactions listed in a file
@@ -178,6 +191,7 @@ pub async fn execute_replay_command(
                 use_authority,
                 None,
                 None,
+                None,
             )
             .await?;
 
@@ -260,6 +274,7 @@ pub async fn execute_replay_command(
                             use_authority,
                             None,
                             None,
+                            None,
                         )
                         .await?;
 
@@ -349,7 +364,16 @@ pub async fn execute_replay_command(
             diag,
             executor_version_override,
             protocol_version_override,
+            profile_gas,
         } => {
+            if profile_gas.is_some() && !cfg!(debug_assertions) {
+                anyhow::bail!("Gas profiling is currently supported only in debug builds");
+            }
+            if profile_gas.is_some() && use_authority {
+                anyhow::bail!(
+                    "Gas profiling is not supported when executing through the authority"
+                );
+            }
             let tx_digest = TransactionDigest::from_str(&tx_digest)?;
             info!("Executing tx: {}", tx_digest);
             let sandbox_state = LocalExec::replay_with_network_config(
@@ -360,6 +384,7 @@ pub async fn execute_replay_command(
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                profile_gas,
             )
             .await?;
 
@@ -549,3 +574,26 @@ pub(crate) fn chain_from_chain_id(chain: &str) -> Chain {
         Chain::Unknown
     }
 }
+
+/// Execute `tx_data` locally on top of the latest state of the network at `rpc_url`, as a dry run
+/// of it would, and write a gas profile of the execution to `profile_dir`.
+pub async fn profile_dry_run(
+    rpc_url: &str,
+    tx_data: TransactionData,
+    profile_dir: PathBuf,
+) -> anyhow::Result<()> {
+    if !cfg!(debug_assertions) {
+        anyhow::bail!("Gas profiling is currently supported only in debug builds");
+    }
+    let mut lx = LocalExec::new_from_fn_url(rpc_url)
+        .await?
+        .init_for_execution()
+        .await?;
+    lx.enable_profiler = Some(profile_dir);
+    let sandbox_state = lx
+        .dry_run_transaction(tx_data, ExpensiveSafetyCheckConfig::default())
+        .await?;
+    // The profile is only meaningful if the local execution matches the dry run.
+    sandbox_state.check_effects()?;
+    Ok(())
+}
@@ -235,6 +235,11 @@ pub struct LocalExec {
     // -1 implies use latest version
     // None implies use the protocol version at the time of execution
     pub protocol_version_override: Option<i64>,
+    // If set, gas profiles of executed transactions are written to this directory
+    pub enable_profiler: Option<PathBuf>,
+    // Set when executing a transaction that is not on chain, whose dynamically loaded objects
+    // are not known ahead of execution. These are read at their latest versions
+    pub dry_run: bool,
     // Retry policies due to RPC errors
     pub num_retries_for_timeout: u32,
     pub sleep_period_for_timeout: std::time::Duration,
@@ -318,6 +323,7 @@ impl LocalExec {
         use_authority: bool,
         executor_version_override: Option<i64>,
         protocol_version_override: Option<i64>,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
         async fn inner_exec(
             rpc_url: String,
@@ -326,19 +332,21 @@ impl LocalExec {
             use_authority: bool,
             executor_version_override: Option<i64>,
             protocol_version_override: Option<i64>,
+            enable_profiler: Option<PathBuf>,
         ) -> Result<ExecutionSandboxState, ReplayEngineError> {
-            LocalExec::new_from_fn_url(&rpc_url)
+            let mut exec = LocalExec::new_from_fn_url(&rpc_url)
                 .await?
                 .init_for_execution()
-                .await?
-                .execute_transaction(
-                    &tx_digest,
-                    expensive_safety_check_config,
-                    use_authority,
-                    executor_version_override,
-                    protocol_version_override,
-                )
-                .await
+                .await?;
+            exec.enable_profiler = enable_profiler;
+            exec.execute_transaction(
+                &tx_digest,
+                expensive_safety_check_config,
+                use_authority,
+                executor_version_override,
+                protocol_version_override,
+            )
+            .await
         }
 
         if let Some(url) = rpc_url.clone() {
@@ -350,6 +358,7 @@ impl LocalExec {
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                enable_profiler.clone(),
             )
             .await
             {
@@ -376,6 +385,7 @@ impl LocalExec {
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                enable_profiler.clone(),
             )
             .await
             {
@@ -435,6 +445,8 @@ impl LocalExec {
             diag: Default::default(),
             executor_version_override: None,
             protocol_version_override: None,
+            enable_profiler: None,
+            dry_run: false,
         })
     }
 
@@ -477,6 +489,8 @@ impl LocalExec {
             diag: Default::default(),
             executor_version_override: None,
             protocol_version_override: None,
+            enable_profiler: None,
+            dry_run: false,
         })
     }
 
@@ -731,7 +745,12 @@ impl LocalExec {
         let ov = self.executor_version_override;
 
         // We could probably cache the executor per protocol config
-        let executor = get_executor(ov, protocol_config, expensive_safety_check_config);
+        let executor = get_executor(
+            ov,
+            protocol_config,
+            expensive_safety_check_config,
+            self.enable_profiler.clone(),
+        );
 
         // All prep done
         let expensive_checks = true;
@@ -991,6 +1010,93 @@ impl LocalExec {
                 .await
         }
     }
+
+    /// Execute `tx_data` on top of the latest state of the network, as a dry run of it would.
+    /// Must be called after `init_for_execution`
+    pub async fn dry_run_transaction(
+        &mut self,
+        tx_data: TransactionData,
+        expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    ) -> Result<ExecutionSandboxState, ReplayEngineError> {
+        assert!(self.is_remote_replay());
+        let client = self.fetcher.as_remote().rpc_client.clone();
+        // The objects the transaction reads and modifies are found by dry running it first
+        let SuiTransactionBlockEffects::V1(effects) = client
+            .read_api()
+            .dry_run_transaction_block(tx_data.clone())
+            .await?
+            .effects;
+        let system_state = client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await?;
+
+        let input_objects = tx_data
+            .input_objects()
+            .map_err(|e| ReplayEngineError::UserInputError { err: e })?;
+        let shared_object_refs: Vec<ObjectRef> = effects
+            .shared_objects()
+            .iter()
+            .map(|so_ref| {
+                if so_ref.digest == ObjectDigest::OBJECT_DIGEST_DELETED {
+                    Err(ReplayEngineError::DeletedSharedObjectNotSupported {
+                        id: so_ref.object_id,
+                    })
+                } else {
+                    Ok(so_ref.to_object_ref())
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        let gas_data = tx_data.gas_data().clone();
+
+        let epoch_id = effects.executed_epoch;
+        let chain = chain_from_chain_id(self.fetcher.get_chain_id().await?.as_str());
+        // The current epoch has not ended, so its start is read from the system state rather
+        // than from epoch change events
+        let epoch_start_timestamp = system_state.epoch_start_timestamp_ms;
+        let reference_gas_price = system_state.reference_gas_price;
+        self.fetcher
+            .as_remote()
+            .epoch_info_cache
+            .write()
+            .put(epoch_id, (epoch_start_timestamp, reference_gas_price));
+
+        let tx_info = OnChainTransactionInfo {
+            tx_digest: *effects.transaction_digest(),
+            sender_signed_data: SenderSignedData::new(
+                tx_data.clone(),
+                Intent::sui_transaction(),
+                vec![],
+            ),
+            sender: tx_data.sender(),
+            input_objects,
+            kind: tx_data.kind().clone(),
+            modified_at_versions: effects.modified_at_versions(),
+            shared_object_refs,
+            gas: gas_data.payment,
+            gas_budget: gas_data.budget,
+            gas_price: gas_data.price,
+            executed_epoch: epoch_id,
+            dependencies: effects.dependencies().to_vec(),
+            protocol_version: self.get_protocol_config(epoch_id, chain).await?.version,
+            effects: SuiTransactionBlockEffects::V1(effects),
+            epoch_start_timestamp,
+            reference_gas_price,
+            chain,
+        };
+
+        self.dry_run = true;
+        let result = self
+            .execution_engine_execute_with_tx_info_impl(
+                &tx_info,
+                None,
+                expensive_safety_check_config,
+            )
+            .await;
+        self.dry_run = false;
+        result
+    }
+
     fn system_package_ids(protocol_version: u64) -> Vec<ObjectID> {
         let mut ids = BuiltInFramework::all_package_ids();
 
@@ -1757,6 +1863,14 @@ impl ChildObjectResolver for LocalExec {
             child_version_upper_bound: SequenceNumber,
         ) -> SuiResult<Option<Object>> {
             let child_object = match self_.get_object(child)? {
+                // Objects loaded by a dry run are only known once it executes
+                None if self_.dry_run => match self_
+                    .get_or_download_object(child, false /* we expect a Move object */)
+                    .map_err(|e| SuiError::Unknown(e.to_string()))?
+                {
+                    None => return Ok(None),
+                    Some(o) => o,
+                },
                 None => return Ok(None),
                 Some(o) => o,
             };
@@ -2033,6 +2147,7 @@ pub fn get_executor(
     executor_version_override: Option<i64>,
     protocol_config: &ProtocolConfig,
     _expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    enable_profiler: Option<PathBuf>,
 ) -> Arc<dyn Executor + Send + Sync> {
     let protocol_config = executor_version_override
         .map(|q| {
@@ -2049,7 +2164,7 @@ pub fn get_executor(
         .unwrap_or(protocol_config.clone());
 
     let silent = true;
-    sui_execution::executor(&protocol_config, silent)
+    sui_execution::executor(&protocol_config, silent, enable_profiler)
         .expect("Creating an executor should not fail here")
 }
 
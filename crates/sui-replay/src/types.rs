@@ -190,6 +190,9 @@ pub enum ReplayEngineError {
 
     #[error("Unable to get chain id: {}", err)]
     UnableToGetChainId { err: String },
+
+    #[error("Dry running transactions that use deleted shared object {id} is not supported")]
+    DeletedSharedObjectNotSupported { id: ObjectID },
 }
 
 impl From<SuiObjectResponseError> for ReplayEngineError {
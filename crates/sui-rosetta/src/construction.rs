@@ -222,6 +222,38 @@ pub async fn metadata(
             let amount = amounts.iter().sum::<u64>();
             (Some(amount), vec![])
         }
+        InternalOperation::PayCoin {
+            sender,
+            amounts,
+            currency,
+            ..
+        } => {
+            let amount = amounts.iter().sum::<u64>();
+            let coin_type = currency.coin_type()?;
+            // Symbol and decimals are not part of the transaction, so check them here.
+            let known_currency = context.coin_metadata_cache.get_currency(&coin_type).await?;
+            if known_currency.as_ref() != Some(currency) {
+                return Err(Error::InvalidInput(format!(
+                    "Unknown currency {} of coin type [{coin_type}]",
+                    currency.symbol
+                )));
+            }
+            let coins = context
+                .client
+                .coin_read_api()
+                .select_coins(
+                    *sender,
+                    Some(coin_type.to_canonical_string(/* with_prefix */ true)),
+                    amount.into(),
+                    vec![],
+                )
+                .await?
+                .iter()
+                .map(|coin| coin.object_ref())
+                .collect();
+            // Only gas needs to be paid in SUI.
+            (Some(0), coins)
+        }
         InternalOperation::Stake { amount, .. } => (*amount, vec![]),
         InternalOperation::WithdrawStake { sender, stake_ids } => {
             let stake_ids = if stake_ids.is_empty() {
@@ -12,10 +12,11 @@ use tracing::info;
 
 use mysten_metrics::spawn_monitored_task;
 use sui_sdk::SuiClient;
+use sui_types::gas_coin::GAS;
 
 use crate::errors::Error;
 use crate::state::{CheckpointBlockProvider, OnlineServerContext};
-use crate::types::{Currency, SuiEnv};
+use crate::types::{Currency, CurrencyMetadata, SuiEnv};
 
 /// This lib implements the Rosetta online and offline server defined by the [Rosetta API Spec](https://www.rosetta-api.org/docs/Reference.html)
 mod account;
@@ -27,9 +28,14 @@ pub mod operations;
 mod state;
 pub mod types;
 
+pub use crate::state::CoinMetadataCache;
+
 pub static SUI: Lazy<Currency> = Lazy::new(|| Currency {
     symbol: "SUI".to_string(),
     decimals: 9,
+    metadata: CurrencyMetadata {
+        coin_type: GAS::type_tag().to_canonical_string(/* with_prefix */ true),
+    },
 });
 
 pub struct RosettaOnlineServer {
@@ -39,10 +45,14 @@ pub struct RosettaOnlineServer {
 
 impl RosettaOnlineServer {
     pub fn new(env: SuiEnv, client: SuiClient) -> Self {
-        let blocks = Arc::new(CheckpointBlockProvider::new(client.clone()));
+        let coin_metadata_cache = CoinMetadataCache::new(client.clone());
+        let blocks = Arc::new(CheckpointBlockProvider::new(
+            client.clone(),
+            coin_metadata_cache.clone(),
+        ));
         Self {
             env,
-            context: OnlineServerContext::new(client, blocks),
+            context: OnlineServerContext::new(client, blocks, coin_metadata_cache),
         }
     }
 
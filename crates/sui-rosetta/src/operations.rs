@@ -8,32 +8,36 @@ use std::vec;
 
 use anyhow::anyhow;
 use move_core_types::ident_str;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::resolver::ModuleResolver;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
 
+use sui_json_rpc_types::SuiArgument;
 use sui_json_rpc_types::SuiProgrammableMoveCall;
 use sui_json_rpc_types::SuiProgrammableTransactionBlock;
-use sui_json_rpc_types::{BalanceChange, SuiArgument};
 use sui_json_rpc_types::{SuiCallArg, SuiCommand};
 use sui_sdk::rpc_types::{
     SuiTransactionBlockData, SuiTransactionBlockDataAPI, SuiTransactionBlockEffectsAPI,
     SuiTransactionBlockKind, SuiTransactionBlockResponse,
 };
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::coin::{PAY_MODULE_NAME, PAY_SPLIT_AND_TRANSFER_FUNC_NAME};
 use sui_types::gas_coin::{GasCoin, GAS};
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::object::Owner;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::TransactionData;
-use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
+use sui_types::{
+    parse_sui_type_tag, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID,
+};
 
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
+    CurrencyMetadata, InternalOperation, OperationIdentifier, OperationStatus, OperationType,
 };
-use crate::Error;
+use crate::{CoinMetadataCache, Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
@@ -101,6 +105,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -108,11 +113,59 @@ impl Operations {
     }
 
     fn pay_sui_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops_to_internal()?;
+        if let Some(currency) = currency {
+            if currency.coin_type()? != GAS::type_tag() {
+                return Err(Error::InvalidInput(format!(
+                    "PaySui operations cannot be in {}, use PayCoin instead.",
+                    currency.symbol
+                )));
+            }
+        }
+        Ok(InternalOperation::PaySui {
+            sender,
+            recipients,
+            amounts,
+        })
+    }
+
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let (sender, recipients, amounts, currency) = self.pay_ops_to_internal()?;
+        let currency = currency.ok_or_else(|| Error::MissingInput("Currency".to_string()))?;
+        // The SUI coins paid from could also be selected to pay for gas.
+        if currency.coin_type()? == GAS::type_tag() {
+            return Err(Error::InvalidInput(
+                "PayCoin operations cannot be in SUI, use PaySui instead.".to_string(),
+            ));
+        }
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
+    /// The sender, recipients, amounts and currency of a payment.
+    #[allow(clippy::type_complexity)]
+    fn pay_ops_to_internal(
+        self,
+    ) -> Result<(SuiAddress, Vec<SuiAddress>, Vec<u64>, Option<Currency>), Error> {
         let mut recipients = vec![];
         let mut amounts = vec![];
         let mut sender = None;
+        let mut currency: Option<Currency> = None;
         for op in self {
             if let (Some(amount), Some(account)) = (op.amount.clone(), op.account.clone()) {
+                match &currency {
+                    Some(currency) if currency.coin_type()? != amount.currency.coin_type()? => {
+                        return Err(Error::MalformedOperationError(
+                            "Payment operations should all be in the same currency.".into(),
+                        ));
+                    }
+                    Some(_) => {}
+                    None => currency = Some(amount.currency.clone()),
+                }
                 if amount.value.is_negative() {
                     sender = Some(account.address)
                 } else {
@@ -128,11 +181,7 @@ impl Operations {
             }
         }
         let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
-        Ok(InternalOperation::PaySui {
-            sender,
-            recipients,
-            amounts,
-        })
+        Ok((sender, recipients, amounts, currency))
     }
 
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
@@ -278,6 +327,20 @@ impl Operations {
                 .collect::<Option<_>>()?;
             Some(amounts)
         }
+        fn merge_coins(
+            inputs: &[SuiCallArg],
+            coin: SuiArgument,
+            coins: &[SuiArgument],
+        ) -> Option<Vec<KnownValue>> {
+            // Only merging input coin objects, as in PayCoin transactions, is understood.
+            for coin in std::iter::once(&coin).chain(coins) {
+                let SuiArgument::Input(i) = *coin else {
+                    return None;
+                };
+                inputs.get(i as usize)?.object()?;
+            }
+            Some(vec![])
+        }
         fn transfer_object(
             aggregated_recipients: &mut HashMap<SuiAddress, u64>,
             inputs: &[SuiCallArg],
@@ -308,6 +371,40 @@ impl Operations {
             }
             Some(vec![])
         }
+        fn pay_coin_call(
+            aggregated_coin_recipients: &mut HashMap<SuiAddress, u64>,
+            coin_type: &mut Option<TypeTag>,
+            inputs: &[SuiCallArg],
+            call: &SuiProgrammableMoveCall,
+        ) -> Option<Vec<KnownValue>> {
+            let SuiProgrammableMoveCall {
+                type_arguments,
+                arguments,
+                ..
+            } = call;
+            let [type_argument] = &type_arguments[..] else {
+                return None;
+            };
+            let [SuiArgument::Input(coin), SuiArgument::Input(amount), SuiArgument::Input(recipient)] =
+                arguments[..]
+            else {
+                return None;
+            };
+            inputs.get(coin as usize)?.object()?;
+            let amount = pure_u64(inputs.get(amount as usize)?)?;
+            let recipient = inputs
+                .get(recipient as usize)?
+                .pure()?
+                .to_sui_address()
+                .ok()?;
+            // All payments of a transaction are in the same currency.
+            let call_coin_type = parse_sui_type_tag(type_argument).ok()?;
+            if *coin_type.get_or_insert_with(|| call_coin_type.clone()) != call_coin_type {
+                return None;
+            }
+            *aggregated_coin_recipients.entry(recipient).or_default() += amount;
+            Some(vec![])
+        }
         fn stake_call(
             inputs: &[SuiCallArg],
             known_results: &[Vec<KnownValue>],
@@ -363,6 +460,8 @@ impl Operations {
         let SuiProgrammableTransactionBlock { inputs, commands } = &pt;
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let mut aggregated_coin_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let mut coin_type = None;
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
@@ -371,6 +470,7 @@ impl Operations {
                 SuiCommand::SplitCoins(coin, amounts) => {
                     split_coins(inputs, &known_results, *coin, amounts)
                 }
+                SuiCommand::MergeCoins(coin, coins) => merge_coins(inputs, *coin, coins),
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
                     inputs,
@@ -393,6 +493,9 @@ impl Operations {
                         vec![]
                     })
                 }
+                SuiCommand::MoveCall(m) if Self::is_pay_coin_call(m) => {
+                    pay_coin_call(&mut aggregated_coin_recipients, &mut coin_type, inputs, m)
+                }
                 SuiCommand::MoveCall(m) if Self::is_unstake_call(m) => {
                     let stake_id = unstake_call(inputs, m)?;
                     stake_ids.push(stake_id);
@@ -408,16 +511,35 @@ impl Operations {
             }
         }
 
-        if !needs_generic && !aggregated_recipients.is_empty() {
-            let total_paid: u64 = aggregated_recipients.values().copied().sum();
-            operations.extend(
-                aggregated_recipients
-                    .into_iter()
-                    .map(|(recipient, amount)| {
-                        Operation::pay_sui(status, recipient, amount.into())
-                    }),
-            );
-            operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+        if !needs_generic
+            && !(aggregated_recipients.is_empty() && aggregated_coin_recipients.is_empty())
+        {
+            if !aggregated_recipients.is_empty() {
+                let total_paid: u64 = aggregated_recipients.values().copied().sum();
+                operations.extend(
+                    aggregated_recipients
+                        .into_iter()
+                        .map(|(recipient, amount)| {
+                            Operation::pay_sui(status, recipient, amount.into())
+                        }),
+                );
+                operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+            }
+            if let Some(coin_type) = coin_type {
+                let currency = unresolved_currency(&coin_type);
+                let total_paid: u64 = aggregated_coin_recipients.values().copied().sum();
+                operations.extend(aggregated_coin_recipients.into_iter().map(
+                    |(recipient, amount)| {
+                        Operation::pay_coin(status, recipient, amount.into(), currency.clone())
+                    },
+                ));
+                operations.push(Operation::pay_coin(
+                    status,
+                    sender,
+                    -(total_paid as i128),
+                    currency,
+                ));
+            }
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -449,6 +571,12 @@ impl Operations {
             && tx.function == ADD_STAKE_FUN_NAME.as_str()
     }
 
+    fn is_pay_coin_call(tx: &SuiProgrammableMoveCall) -> bool {
+        tx.package == SUI_FRAMEWORK_PACKAGE_ID
+            && tx.module == PAY_MODULE_NAME.as_str()
+            && tx.function == PAY_SPLIT_AND_TRANSFER_FUNC_NAME.as_str()
+    }
+
     fn is_unstake_call(tx: &SuiProgrammableMoveCall) -> bool {
         tx.package == SUI_SYSTEM_PACKAGE_ID
             && tx.module == SUI_SYSTEM_MODULE_NAME.as_str()
@@ -458,28 +586,31 @@ impl Operations {
     fn process_balance_change(
         gas_owner: SuiAddress,
         gas_used: i128,
-        balance_changes: &[BalanceChange],
+        balance_changes: Vec<(SuiAddress, TypeTag, i128)>,
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
-    ) -> impl Iterator<Item = Operation> {
-        let mut balances = balance_changes
-            .iter()
-            .fold(balances, |mut balances, balance_change| {
-                // Rosetta only care about address owner
-                if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
-                    }
-                }
+        balances: HashMap<(SuiAddress, TypeTag), i128>,
+        currencies: HashMap<TypeTag, Currency>,
+    ) -> Result<impl Iterator<Item = Operation>, Error> {
+        let mut balances = balance_changes.into_iter().fold(
+            balances,
+            |mut balances, (owner, coin_type, amount)| {
+                *balances.entry((owner, coin_type)).or_default() += amount;
                 balances
-            });
+            },
+        );
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
+        *balances.entry((gas_owner, GAS::type_tag())).or_default() -= gas_used;
 
         let balance_change = balances
             .into_iter()
             .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+            .map(|((addr, coin_type), amount)| {
+                let currency = currencies.get(&coin_type).cloned().ok_or_else(|| {
+                    Error::DataError(format!("Cannot find currency of coin type [{coin_type}]"))
+                })?;
+                Ok(Operation::balance_change(status, addr, amount, currency))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
 
         let gas = if gas_used != 0 {
             vec![Operation::gas(gas_owner, gas_used)]
@@ -487,7 +618,7 @@ impl Operations {
             // Gas can be 0 for system tx
             vec![]
         };
-        balance_change.chain(gas)
+        Ok(balance_change.into_iter().chain(gas))
     }
 }
 
@@ -503,9 +634,13 @@ impl TryFrom<SuiTransactionBlockData> for Operations {
     }
 }
 
-impl TryFrom<SuiTransactionBlockResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+impl Operations {
+    /// Operations of an executed transaction, including the balance changes of every currency
+    /// it touched. Currencies are looked up in `coin_metadata_cache`.
+    pub async fn try_from_response(
+        response: SuiTransactionBlockResponse,
+        coin_metadata_cache: &CoinMetadataCache,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
@@ -521,21 +656,42 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
 
         let status = Some(effect.into_status().into());
         let ops: Operations = tx.data.try_into()?;
-        let ops = ops.set_status(status).into_iter();
+        let mut ops: Vec<_> = ops.set_status(status).into_iter().collect();
+
+        // Operations only carry the coin type of their currency, the rest is looked up.
+        let mut currencies = HashMap::from([(GAS::type_tag(), SUI.clone())]);
+        for amount in ops.iter_mut().filter_map(|op| op.amount.as_mut()) {
+            let coin_type = amount.currency.coin_type()?;
+            let currency = match currencies.get(&coin_type) {
+                Some(currency) => currency.clone(),
+                None => {
+                    let currency = coin_metadata_cache
+                        .get_currency(&coin_type)
+                        .await?
+                        .ok_or_else(|| {
+                            Error::DataError(format!(
+                                "Cannot find metadata of coin type [{coin_type}]"
+                            ))
+                        })?;
+                    currencies.insert(coin_type, currency.clone());
+                    currency
+                }
+            };
+            amount.currency = currency;
+        }
 
         // We will need to subtract the operation amounts from the actual balance
         // change amount extracted from event to prevent double counting.
-        let mut accounted_balances =
-            ops.as_ref()
-                .iter()
-                .fold(HashMap::new(), |mut balances, op| {
-                    if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
-                        (&op.account, &op.amount, &op.status)
-                    {
-                        *balances.entry(acc.address).or_default() -= amount.value;
-                    }
-                    balances
-                });
+        let mut accounted_balances = HashMap::new();
+        for op in &ops {
+            if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
+                (&op.account, &op.amount, &op.status)
+            {
+                *accounted_balances
+                    .entry((acc.address, amount.currency.coin_type()?))
+                    .or_default() -= amount.value;
+            }
+        }
 
         let mut principal_amounts = 0;
         let mut reward_amounts = 0;
@@ -564,8 +720,12 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            *accounted_balances
+                .entry((sender, GAS::type_tag()))
+                .or_default() -= principal_amounts;
+            *accounted_balances
+                .entry((sender, GAS::type_tag()))
+                .or_default() -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -574,16 +734,34 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             vec![]
         };
 
+        let mut balance_changes = vec![];
+        for balance_change in response
+            .balance_changes
+            .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?
+        {
+            // Rosetta only care about address owner
+            let Owner::AddressOwner(owner) = balance_change.owner else {
+                continue;
+            };
+            // Coins without metadata have no currency to report their balances in.
+            if let Some(currency) = coin_metadata_cache
+                .get_currency(&balance_change.coin_type)
+                .await?
+            {
+                currencies.insert(balance_change.coin_type.clone(), currency);
+                balance_changes.push((owner, balance_change.coin_type, balance_change.amount));
+            }
+        }
+
         // Extract coin change operations from balance changes
         let coin_change_operations = Self::process_balance_change(
             gas_owner,
             gas_used,
-            &response
-                .balance_changes
-                .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
+            balance_changes,
             status,
             accounted_balances,
-        );
+            currencies,
+        )?;
 
         Ok(ops
             .into_iter()
@@ -593,6 +771,39 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
     }
 }
 
+/// A u64 passed to a Move call. Arguments are left as BCS bytes when the called module is
+/// not resolved, as in `/construction/parse`.
+fn pure_u64(arg: &SuiCallArg) -> Option<u64> {
+    match arg.pure()?.to_json_value() {
+        Value::String(value) => u64::from_str(&value).ok(),
+        Value::Array(bytes) => {
+            let bytes = bytes
+                .iter()
+                .map(|b| u8::try_from(b.as_u64()?).ok())
+                .collect::<Option<Vec<_>>>()?;
+            bcs::from_bytes(&bytes).ok()
+        }
+        _ => None,
+    }
+}
+
+/// The currency of `coin_type`, before its symbol and decimals are looked up in the
+/// `CoinMetadataCache`, which needs a fullnode. Offline, the symbol is the name of the coin's
+/// struct and decimals are reported as 0.
+fn unresolved_currency(coin_type: &TypeTag) -> Currency {
+    let symbol = match coin_type {
+        TypeTag::Struct(tag) => tag.name.to_string(),
+        _ => coin_type.to_string(),
+    };
+    Currency {
+        symbol,
+        decimals: 0,
+        metadata: CurrencyMetadata {
+            coin_type: coin_type.to_canonical_string(/* with_prefix */ true),
+        },
+    }
+}
+
 fn is_unstake_event(tag: &StructTag) -> bool {
     tag.address == SUI_SYSTEM_ADDRESS
         && tag.module.as_ident_str() == ident_str!("validator")
@@ -698,13 +909,35 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(
+        status: Option<OperationStatus>,
+        address: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(Amount::new_with_currency(amount, currency)),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(
+        status: Option<OperationStatus>,
+        addr: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
         Self {
             operation_identifier: Default::default(),
             type_: OperationType::SuiBalanceChange,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(Amount::new_with_currency(amount, currency)),
             coin_change: None,
             metadata: None,
         }
@@ -8,7 +8,9 @@ use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::transaction::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
 
 use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::types::{
+    ConstructionMetadata, Currency, CurrencyMetadata, InternalOperation, OperationType,
+};
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -50,6 +52,60 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_pay_coin_operation_data_parsing() -> Result<(), anyhow::Error> {
+    let object_ref = || {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    };
+    let gas = object_ref();
+    let sender = SuiAddress::random_for_testing_only();
+    let currency = Currency {
+        symbol: "TEST".to_string(),
+        decimals: 6,
+        metadata: CurrencyMetadata {
+            coin_type: "0x123::test::TEST".to_string(),
+        },
+    };
+    let coins = vec![object_ref(), object_ref()];
+    let gas_price = 10;
+    let metadata = || ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: coins.clone(),
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+    };
+
+    let data = InternalOperation::PayCoin {
+        sender,
+        recipients: vec![SuiAddress::random_for_testing_only()],
+        amounts: vec![10000],
+        currency: currency.clone(),
+    }
+    .try_into_data(metadata())?;
+
+    let ops: Operations = data.clone().try_into()?;
+    assert_eq!(Some(OperationType::PayCoin), ops.type_());
+    // Only the coin type can be read from the transaction, symbol and decimals are looked up
+    // online.
+    for op in ops.clone() {
+        let parsed = op.amount.unwrap().currency;
+        assert_eq!(parsed.symbol, "TEST");
+        assert_eq!(parsed.coin_type()?, currency.coin_type()?);
+    }
+
+    let parsed_data = ops.into_internal()?.try_into_data(metadata())?;
+    assert_eq!(data, parsed_data);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
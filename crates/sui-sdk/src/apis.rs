@@ -79,11 +79,9 @@ impl ReadApi {
         cursor: Option<ObjectID>,
         limit: Option<usize>,
     ) -> SuiRpcResult<ObjectsPage> {
-        Ok(self
-            .api
-            .http
-            .get_owned_objects(address, query, cursor, limit)
-            .await?)
+        self.api
+            .request(|http| http.get_owned_objects(address, query.clone(), cursor, limit))
+            .await
     }
 
     /// Return a paginated response with the dynamic fields owned by the given [ObjectID], or an error upon failure.
@@ -132,11 +130,9 @@ impl ReadApi {
         cursor: Option<ObjectID>,
         limit: Option<usize>,
     ) -> SuiRpcResult<DynamicFieldPage> {
-        Ok(self
-            .api
-            .http
-            .get_dynamic_fields(object_id, cursor, limit)
-            .await?)
+        self.api
+            .request(|http| http.get_dynamic_fields(object_id, cursor, limit))
+            .await
     }
 
     /// Return the dynamic field object information for a specified object.
@@ -145,11 +141,9 @@ impl ReadApi {
         parent_object_id: ObjectID,
         name: DynamicFieldName,
     ) -> SuiRpcResult<SuiObjectResponse> {
-        Ok(self
-            .api
-            .http
-            .get_dynamic_field_object(parent_object_id, name)
-            .await?)
+        self.api
+            .request(|http| http.get_dynamic_field_object(parent_object_id, name.clone()))
+            .await
     }
 
     /// Return a parsed past object for the provided [ObjectID] and version, or an error upon failure.
@@ -210,11 +204,9 @@ impl ReadApi {
         version: SequenceNumber,
         options: SuiObjectDataOptions,
     ) -> SuiRpcResult<SuiPastObjectResponse> {
-        Ok(self
-            .api
-            .http
-            .try_get_past_object(object_id, version, Some(options))
-            .await?)
+        self.api
+            .request(|http| http.try_get_past_object(object_id, version, Some(options.clone())))
+            .await
     }
 
     /// Return a list of [SuiPastObjectResponse] objects, or an error upon failure.
@@ -291,11 +283,11 @@ impl ReadApi {
         past_objects: Vec<SuiGetPastObjectRequest>,
         options: SuiObjectDataOptions,
     ) -> SuiRpcResult<Vec<SuiPastObjectResponse>> {
-        Ok(self
-            .api
-            .http
-            .try_multi_get_past_objects(past_objects, Some(options))
-            .await?)
+        self.api
+            .request(|http| {
+                http.try_multi_get_past_objects(past_objects.clone(), Some(options.clone()))
+            })
+            .await
     }
 
     /// Return a [SuiObjectResponse] based on the provided [ObjectID] and [SuiObjectDataOptions], or an error upon failure.
@@ -349,7 +341,9 @@ impl ReadApi {
         object_id: ObjectID,
         options: SuiObjectDataOptions,
     ) -> SuiRpcResult<SuiObjectResponse> {
-        Ok(self.api.http.get_object(object_id, Some(options)).await?)
+        self.api
+            .critical_request(|http| http.get_object(object_id, Some(options.clone())))
+            .await
     }
 
     /// Return a list of [SuiObjectResponse] from the given vector of [ObjectID]s and [SuiObjectDataOptions], or an error upon failure.
@@ -401,11 +395,11 @@ impl ReadApi {
         object_ids: Vec<ObjectID>,
         options: SuiObjectDataOptions,
     ) -> SuiRpcResult<Vec<SuiObjectResponse>> {
-        Ok(self
-            .api
-            .http
-            .multi_get_objects(object_ids, Some(options))
-            .await?)
+        self.api
+            .critical_request(|http| {
+                http.multi_get_objects(object_ids.clone(), Some(options.clone()))
+            })
+            .await
     }
 
     /// Return the total number of transaction blocks known to server, or an error upon failure.
@@ -426,7 +420,10 @@ impl ReadApi {
     /// }
     /// ```
     pub async fn get_total_transaction_blocks(&self) -> SuiRpcResult<u64> {
-        Ok(*self.api.http.get_total_transaction_blocks().await?)
+        Ok(*self
+            .api
+            .request(|http| http.get_total_transaction_blocks())
+            .await?)
     }
 
     /// Return a transaction and its effects in a [SuiTransactionBlockResponse] based on its
@@ -436,11 +433,9 @@ impl ReadApi {
         digest: TransactionDigest,
         options: SuiTransactionBlockResponseOptions,
     ) -> SuiRpcResult<SuiTransactionBlockResponse> {
-        Ok(self
-            .api
-            .http
-            .get_transaction_block(digest, Some(options))
-            .await?)
+        self.api
+            .critical_request(|http| http.get_transaction_block(digest, Some(options.clone())))
+            .await
     }
     /// Return a list of [SuiTransactionBlockResponse] based on the given vector of [TransactionDigest], or an error upon failure.
     ///
@@ -451,11 +446,11 @@ impl ReadApi {
         digests: Vec<TransactionDigest>,
         options: SuiTransactionBlockResponseOptions,
     ) -> SuiRpcResult<Vec<SuiTransactionBlockResponse>> {
-        Ok(self
-            .api
-            .http
-            .multi_get_transaction_blocks(digests, Some(options))
-            .await?)
+        self.api
+            .request(|http| {
+                http.multi_get_transaction_blocks(digests.clone(), Some(options.clone()))
+            })
+            .await
     }
 
     /// Return the [SuiCommittee] information for the provided `epoch`, or an error upon failure.
@@ -483,7 +478,9 @@ impl ReadApi {
         &self,
         epoch: Option<BigInt<u64>>,
     ) -> SuiRpcResult<SuiCommittee> {
-        Ok(self.api.http.get_committee_info(epoch).await?)
+        self.api
+            .request(|http| http.get_committee_info(epoch))
+            .await
     }
 
     /// Return a paginated response with all transaction blocks information, or an error upon failure.
@@ -494,16 +491,16 @@ impl ReadApi {
         limit: Option<usize>,
         descending_order: bool,
     ) -> SuiRpcResult<TransactionBlocksPage> {
-        Ok(self
-            .api
-            .http
-            .query_transaction_blocks(query, cursor, limit, Some(descending_order))
-            .await?)
+        self.api
+            .request(|http| {
+                http.query_transaction_blocks(query.clone(), cursor, limit, Some(descending_order))
+            })
+            .await
     }
 
     /// Return the first four bytes of the chain's genesis checkpoint digest, or an error upon failure.
     pub async fn get_chain_identifier(&self) -> SuiRpcResult<String> {
-        Ok(self.api.http.get_chain_identifier().await?)
+        self.api.request(|http| http.get_chain_identifier()).await
     }
 
     /// Return a checkpoint, or an error upon failure.
@@ -511,7 +508,7 @@ impl ReadApi {
     /// A Sui checkpoint is a sequence of transaction sets that a quorum of validators
     /// agree upon as having been executed within the Sui system.
     pub async fn get_checkpoint(&self, id: CheckpointId) -> SuiRpcResult<Checkpoint> {
-        Ok(self.api.http.get_checkpoint(id).await?)
+        self.api.request(|http| http.get_checkpoint(id)).await
     }
 
     /// Return a paginated list of checkpoints, or an error upon failure.
@@ -521,11 +518,9 @@ impl ReadApi {
         limit: Option<usize>,
         descending_order: bool,
     ) -> SuiRpcResult<CheckpointPage> {
-        Ok(self
-            .api
-            .http
-            .get_checkpoints(cursor, limit, descending_order)
-            .await?)
+        self.api
+            .request(|http| http.get_checkpoints(cursor, limit, descending_order))
+            .await
     }
 
     /// Return the sequence number of the latest checkpoint that has been executed, or an error upon failure.
@@ -534,8 +529,7 @@ impl ReadApi {
     ) -> SuiRpcResult<CheckpointSequenceNumber> {
         Ok(*self
             .api
-            .http
-            .get_latest_checkpoint_sequence_number()
+            .request(|http| http.get_latest_checkpoint_sequence_number())
             .await?)
     }
 
@@ -594,17 +588,18 @@ impl ReadApi {
         &self,
         package: ObjectID,
     ) -> SuiRpcResult<BTreeMap<String, SuiMoveNormalizedModule>> {
-        Ok(self
-            .api
-            .http
-            .get_normalized_move_modules_by_package(package)
-            .await?)
+        self.api
+            .request(|http| http.get_normalized_move_modules_by_package(package))
+            .await
     }
 
     // TODO(devx): we can probably cache this given an epoch
     /// Return the reference gas price, or an error upon failure.
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
-        Ok(*self.api.http.get_reference_gas_price().await?)
+        Ok(*self
+            .api
+            .request(|http| http.get_reference_gas_price())
+            .await?)
     }
 
     /// Dry run a transaction block given the provided transaction data. Returns an error upon failure.
@@ -616,11 +611,10 @@ impl ReadApi {
         &self,
         tx: TransactionData,
     ) -> SuiRpcResult<DryRunTransactionBlockResponse> {
-        Ok(self
-            .api
-            .http
-            .dry_run_transaction_block(Base64::from_bytes(&bcs::to_bytes(&tx)?))
-            .await?)
+        let tx_bytes = Base64::from_bytes(&bcs::to_bytes(&tx)?);
+        self.api
+            .request(|http| http.dry_run_transaction_block(tx_bytes.clone()))
+            .await
     }
 
     /// Return the inspection of the transaction block, or an error upon failure.
@@ -652,16 +646,17 @@ impl ReadApi {
         gas_price: Option<BigInt<u64>>,
         epoch: Option<BigInt<u64>>,
     ) -> SuiRpcResult<DevInspectResults> {
-        Ok(self
-            .api
-            .http
-            .dev_inspect_transaction_block(
-                sender_address,
-                Base64::from_bytes(&bcs::to_bytes(&tx)?),
-                gas_price,
-                epoch,
-            )
-            .await?)
+        let tx_bytes = Base64::from_bytes(&bcs::to_bytes(&tx)?);
+        self.api
+            .request(|http| {
+                http.dev_inspect_transaction_block(
+                    sender_address,
+                    tx_bytes.clone(),
+                    gas_price,
+                    epoch,
+                )
+            })
+            .await
     }
 
     /// Return the loaded child objects response for the the provided digest, or an error upon failure.
@@ -672,7 +667,9 @@ impl ReadApi {
         &self,
         digest: TransactionDigest,
     ) -> SuiRpcResult<SuiLoadedChildObjectsResponse> {
-        Ok(self.api.http.get_loaded_child_objects(digest).await?)
+        self.api
+            .request(|http| http.get_loaded_child_objects(digest))
+            .await
     }
 
     /// Return the protocol config, or an error upon failure.
@@ -680,7 +677,9 @@ impl ReadApi {
         &self,
         version: Option<BigInt<u64>>,
     ) -> SuiRpcResult<ProtocolConfigResponse> {
-        Ok(self.api.http.get_protocol_config(version).await?)
+        self.api
+            .request(|http| http.get_protocol_config(version))
+            .await
     }
 }
 
@@ -725,11 +724,9 @@ impl CoinReadApi {
         cursor: Option<ObjectID>,
         limit: Option<usize>,
     ) -> SuiRpcResult<CoinPage> {
-        Ok(self
-            .api
-            .http
-            .get_coins(owner, coin_type, cursor, limit)
-            .await?)
+        self.api
+            .request(|http| http.get_coins(owner, coin_type.clone(), cursor, limit))
+            .await
     }
     /// Return a paginated response with all the coins for the given address, or an error upon failure.
     ///
@@ -759,7 +756,9 @@ impl CoinReadApi {
         cursor: Option<ObjectID>,
         limit: Option<usize>,
     ) -> SuiRpcResult<CoinPage> {
-        Ok(self.api.http.get_all_coins(owner, cursor, limit).await?)
+        self.api
+            .request(|http| http.get_all_coins(owner, cursor, limit))
+            .await
     }
 
     /// Return the coins for the given address as a stream.
@@ -899,7 +898,9 @@ impl CoinReadApi {
         owner: SuiAddress,
         coin_type: Option<String>,
     ) -> SuiRpcResult<Balance> {
-        Ok(self.api.http.get_balance(owner, coin_type).await?)
+        self.api
+            .critical_request(|http| http.get_balance(owner, coin_type.clone()))
+            .await
     }
 
     /// Return a list of balances for each coin type owned by the given address,
@@ -926,7 +927,7 @@ impl CoinReadApi {
     /// }
     /// ```
     pub async fn get_all_balances(&self, owner: SuiAddress) -> SuiRpcResult<Vec<Balance>> {
-        Ok(self.api.http.get_all_balances(owner).await?)
+        self.api.request(|http| http.get_all_balances(owner)).await
     }
 
     /// Return the coin metadata (name, symbol, description, decimals, etc.) for a given coin type,
@@ -950,7 +951,9 @@ impl CoinReadApi {
         &self,
         coin_type: String,
     ) -> SuiRpcResult<Option<SuiCoinMetadata>> {
-        Ok(self.api.http.get_coin_metadata(coin_type).await?)
+        self.api
+            .request(|http| http.get_coin_metadata(coin_type.clone()))
+            .await
     }
 
     /// Return the total supply for a given coin type, or an error upon failure.
@@ -971,7 +974,9 @@ impl CoinReadApi {
     /// }
     /// ```
     pub async fn get_total_supply(&self, coin_type: String) -> SuiRpcResult<Supply> {
-        Ok(self.api.http.get_total_supply(coin_type).await?)
+        self.api
+            .request(|http| http.get_total_supply(coin_type.clone()))
+            .await
     }
 }
 
@@ -1032,7 +1037,7 @@ impl EventApi {
 
     /// Return a list of events for the given transaction digest, or an error upon failure.
     pub async fn get_events(&self, digest: TransactionDigest) -> SuiRpcResult<Vec<SuiEvent>> {
-        Ok(self.api.http.get_events(digest).await?)
+        self.api.request(|http| http.get_events(digest)).await
     }
 
     /// Return a paginated response with events for the given event filter, or an error upon failure.
@@ -1046,11 +1051,9 @@ impl EventApi {
         limit: Option<usize>,
         descending_order: bool,
     ) -> SuiRpcResult<EventPage> {
-        Ok(self
-            .api
-            .http
-            .query_events(query, cursor, limit, Some(descending_order))
-            .await?)
+        self.api
+            .request(|http| http.query_events(query.clone(), cursor, limit, Some(descending_order)))
+            .await
     }
 
     /// Return a stream of events for the given event filter.
@@ -1113,15 +1116,18 @@ impl QuorumDriverApi {
         let mut retry_count = 0;
         let start = Instant::now();
         while retry_count < WAIT_FOR_LOCAL_EXECUTION_RETRY_COUNT {
+            // Retried on the other endpoints if the node cannot be reached, the transaction keeps
+            // its digest so it is only executed once.
             let response: SuiTransactionBlockResponse = self
                 .api
-                .http
-                .execute_transaction_block(
-                    tx_bytes.clone(),
-                    signatures.clone(),
-                    Some(options.clone()),
-                    Some(request_type.clone()),
-                )
+                .request(|http| {
+                    http.execute_transaction_block(
+                        tx_bytes.clone(),
+                        signatures.clone(),
+                        Some(options.clone()),
+                        Some(request_type.clone()),
+                    )
+                })
                 .await?;
 
             match request_type {
@@ -1159,7 +1165,7 @@ impl GovernanceApi {
 
     /// Return a list of [DelegatedStake] objects for the given address, or an error upon failure.
     pub async fn get_stakes(&self, owner: SuiAddress) -> SuiRpcResult<Vec<DelegatedStake>> {
-        Ok(self.api.http.get_stakes(owner).await?)
+        self.api.request(|http| http.get_stakes(owner)).await
     }
 
     /// Return the [SuiCommittee] information for the given `epoch`, or an error upon failure.
@@ -1185,7 +1191,9 @@ impl GovernanceApi {
         &self,
         epoch: Option<BigInt<u64>>,
     ) -> SuiRpcResult<SuiCommittee> {
-        Ok(self.api.http.get_committee_info(epoch).await?)
+        self.api
+            .request(|http| http.get_committee_info(epoch))
+            .await
     }
 
     /// Return the latest SUI system state object on-chain, or an error upon failure.
@@ -1194,11 +1202,16 @@ impl GovernanceApi {
     /// the protocol version, the reference gas price, the total stake, active validators,
     /// and much more. See the [SuiSystemStateSummary] for all the available fields.
     pub async fn get_latest_sui_system_state(&self) -> SuiRpcResult<SuiSystemStateSummary> {
-        Ok(self.api.http.get_latest_sui_system_state().await?)
+        self.api
+            .request(|http| http.get_latest_sui_system_state())
+            .await
     }
 
     /// Return the reference gas price for the network, or an error upon failure.
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
-        Ok(*self.api.http.get_reference_gas_price().await?)
+        Ok(*self
+            .api
+            .request(|http| http.get_reference_gas_price())
+            .await?)
     }
 }
@@ -31,4 +31,6 @@ pub enum Error {
     },
     #[error("Insufficient fund for address [{address}], requested amount: {amount}")]
     InsufficientFund { address: SuiAddress, amount: u128 },
+    #[error("Quorum read failed, endpoints {first} and {other} returned different results")]
+    QuorumMismatch { first: String, other: String },
 }
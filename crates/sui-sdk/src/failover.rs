@@ -0,0 +1,395 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retries and failover across the RPC endpoints of a [SuiClient](crate::SuiClient).
+//!
+//! Requests go to the first healthy endpoint, in the order they were given to the
+//! [SuiClientBuilder](crate::SuiClientBuilder). Transport failures and timeouts are retried on
+//! the next endpoint, with a backoff once every endpoint has been tried. Errors returned by the
+//! node itself, like invalid params, are not retried. An endpoint failing `failure_threshold`
+//! times in a row is skipped for `cooldown`, unless no healthy endpoint is left.
+//!
+//! All requests of the SDK can be retried: reads are idempotent, and executing a transaction
+//! again, on the same or another endpoint, has the same digest and is only executed once.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use jsonrpsee::http_client::HttpClient;
+use jsonrpsee::types::error::CallError;
+use serde::Serialize;
+use sui_json_rpc_api::TRANSIENT_ERROR_CODE;
+use tracing::warn;
+
+use crate::error::{Error, SuiRpcResult};
+use crate::RpcClient;
+
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Number of times a failed request is retried, on the same or another endpoint.
+    pub max_retries: u32,
+    /// Backoff before retrying once every endpoint has failed, doubled each round.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Number of consecutive failures after which an endpoint is considered unhealthy.
+    pub failure_threshold: u32,
+    /// How long an unhealthy endpoint is skipped.
+    pub cooldown: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Fail on the first error, as a client with a single endpoint used to.
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    fn backoff(&self, round: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(round))
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointHealth {
+    pub url: String,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+}
+
+pub(crate) struct Endpoint {
+    pub(crate) url: String,
+    pub(crate) http: HttpClient,
+    health: Mutex<Health>,
+}
+
+#[derive(Default)]
+struct Health {
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+impl Endpoint {
+    pub(crate) fn new(url: String, http: HttpClient) -> Self {
+        Self {
+            url,
+            http,
+            health: Default::default(),
+        }
+    }
+
+    pub(crate) fn health(&self) -> EndpointHealth {
+        let health = self.health.lock().unwrap();
+        EndpointHealth {
+            url: self.url.clone(),
+            healthy: Self::is_healthy(&health),
+            consecutive_failures: health.consecutive_failures,
+        }
+    }
+
+    fn is_healthy(health: &Health) -> bool {
+        health
+            .unhealthy_until
+            .map_or(true, |until| until <= Instant::now())
+    }
+
+    fn record_success(&self) {
+        *self.health.lock().unwrap() = Health::default();
+    }
+
+    fn record_failure(&self, policy: &RetryPolicy) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= policy.failure_threshold {
+            health.unhealthy_until = Some(Instant::now() + policy.cooldown);
+        }
+    }
+
+    pub(crate) fn mark_unhealthy(&self, policy: &RetryPolicy) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures = health.consecutive_failures.max(policy.failure_threshold);
+        health.unhealthy_until = Some(Instant::now() + policy.cooldown);
+    }
+}
+
+/// Whether the request may succeed if sent again: the node could not be reached or did not
+/// answer in time, or it answered with an error it marks as transient.
+pub(crate) fn is_retryable(err: &jsonrpsee::core::Error) -> bool {
+    match err {
+        jsonrpsee::core::Error::Call(CallError::Custom(error)) => {
+            error.code() == TRANSIENT_ERROR_CODE
+        }
+        jsonrpsee::core::Error::Transport(_)
+        | jsonrpsee::core::Error::RequestTimeout
+        | jsonrpsee::core::Error::RestartNeeded(_) => true,
+        _ => false,
+    }
+}
+
+impl RpcClient {
+    /// The endpoints in the order they are tried: the healthy ones first, each group in
+    /// configuration order.
+    fn endpoints_by_health(&self) -> Vec<&Endpoint> {
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = self
+            .endpoints
+            .iter()
+            .partition(|endpoint| Endpoint::is_healthy(&endpoint.health.lock().unwrap()));
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    /// Send `request` to the first healthy endpoint, retrying on the next ones according to the
+    /// retry policy.
+    pub(crate) async fn request<'a, T, F, Fut>(&'a self, request: F) -> SuiRpcResult<T>
+    where
+        F: Fn(&'a HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, jsonrpsee::core::Error>>,
+    {
+        // The order is taken once, so that an endpoint marked unhealthy by a failed attempt does
+        // not move and get tried again before the others.
+        let endpoints = self.endpoints_by_health();
+        let mut attempt = 0;
+        loop {
+            let endpoint = endpoints[attempt as usize % endpoints.len()];
+            match request(&endpoint.http).await {
+                Ok(result) => {
+                    endpoint.record_success();
+                    return Ok(result);
+                }
+                Err(e) if is_retryable(&e) => {
+                    endpoint.record_failure(&self.retry_policy);
+                    if attempt >= self.retry_policy.max_retries {
+                        return Err(e.into());
+                    }
+                    warn!("Request to {} failed, retrying: {e}", endpoint.url);
+                    attempt += 1;
+                    // Fail over right away, back off once all the endpoints have been tried.
+                    let tried = endpoints.len() as u32;
+                    if attempt % tried == 0 {
+                        tokio::time::sleep(self.retry_policy.backoff(attempt / tried - 1)).await;
+                    }
+                }
+                Err(e) => {
+                    // The node is up, it rejected the request.
+                    endpoint.record_success();
+                    return Err(e.into());
+                }
+            }
+        }
+    }
+
+    /// Send `request` to `quorum` endpoints at once, and return the result only if all of them
+    /// return the same one.
+    pub(crate) async fn quorum_request<'a, T, F, Fut>(
+        &'a self,
+        quorum: usize,
+        request: F,
+    ) -> SuiRpcResult<T>
+    where
+        F: Fn(&'a HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, jsonrpsee::core::Error>>,
+        T: Serialize,
+    {
+        let endpoints = self.endpoints_by_health();
+        if quorum == 0 || quorum > endpoints.len() {
+            return Err(Error::DataError(format!(
+                "Cannot read from {quorum} endpoints, the client has {}",
+                endpoints.len()
+            )));
+        }
+        let request = &request;
+        let responses = join_all(
+            endpoints[..quorum]
+                .iter()
+                .copied()
+                .map(|endpoint| async move { (endpoint, request(&endpoint.http).await) }),
+        )
+        .await;
+
+        let mut agreed: Option<(&Endpoint, serde_json::Value, T)> = None;
+        for (endpoint, response) in responses {
+            let result = match response {
+                Ok(result) => {
+                    endpoint.record_success();
+                    result
+                }
+                Err(e) => {
+                    if is_retryable(&e) {
+                        endpoint.record_failure(&self.retry_policy);
+                    }
+                    return Err(e.into());
+                }
+            };
+            let value = serde_json::to_value(&result).map_err(|e| {
+                Error::DataError(format!("Cannot compare responses of {}: {e}", endpoint.url))
+            })?;
+            match &agreed {
+                None => agreed = Some((endpoint, value, result)),
+                Some((first, first_value, _)) if first_value != &value => {
+                    return Err(Error::QuorumMismatch {
+                        first: first.url.clone(),
+                        other: endpoint.url.clone(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(agreed
+            .map(|(_, _, result)| result)
+            .expect("quorum is not 0"))
+    }
+
+    /// Use a quorum read if the client is configured with a read quorum, a normal request
+    /// otherwise.
+    pub(crate) async fn critical_request<'a, T, F, Fut>(&'a self, request: F) -> SuiRpcResult<T>
+    where
+        F: Fn(&'a HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, jsonrpsee::core::Error>>,
+        T: Serialize,
+    {
+        match self.read_quorum {
+            Some(quorum) => self.quorum_request(quorum, request).await,
+            None => self.request(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+
+    use super::{Endpoint, RetryPolicy};
+    use crate::error::Error;
+    use crate::{RpcClient, ServerInfo};
+
+    const FAILING: usize = 0;
+    const HEALTHY: usize = 1;
+
+    fn client(retry_policy: RetryPolicy) -> RpcClient {
+        // Nothing is sent to these URLs, the requests of the tests never use the HTTP clients.
+        let endpoints = ["http://127.0.0.1:9001", "http://127.0.0.1:9002"]
+            .into_iter()
+            .map(|url| {
+                Endpoint::new(
+                    url.to_string(),
+                    HttpClientBuilder::default().build(url).unwrap(),
+                )
+            })
+            .collect();
+        RpcClient {
+            endpoints,
+            ws: None,
+            info: ServerInfo {
+                rpc_methods: vec![],
+                subscriptions: vec![],
+                version: String::new(),
+            },
+            retry_policy,
+            read_quorum: None,
+        }
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_millis(50),
+            failure_threshold: 1,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+
+    fn index_of(client: &RpcClient, http: &HttpClient) -> usize {
+        client
+            .endpoints
+            .iter()
+            .position(|endpoint| std::ptr::eq(&endpoint.http, http))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_fails_over_to_healthy_endpoint() {
+        let client = client(policy());
+        let calls = Mutex::new(vec![]);
+        let request = |http| {
+            let index = index_of(&client, http);
+            calls.lock().unwrap().push(index);
+            async move {
+                match index {
+                    FAILING => Err(jsonrpsee::core::Error::RequestTimeout),
+                    _ => Ok(index),
+                }
+            }
+        };
+
+        assert_eq!(client.request(request).await.unwrap(), HEALTHY);
+        assert_eq!(*calls.lock().unwrap(), vec![FAILING, HEALTHY]);
+        assert!(!client.endpoints[FAILING].health().healthy);
+        assert!(client.endpoints[HEALTHY].health().healthy);
+
+        // The unhealthy endpoint is skipped by the next requests.
+        calls.lock().unwrap().clear();
+        assert_eq!(client.request(request).await.unwrap(), HEALTHY);
+        assert_eq!(*calls.lock().unwrap(), vec![HEALTHY]);
+    }
+
+    #[tokio::test]
+    async fn test_request_backs_off_after_trying_every_endpoint() {
+        let client = client(policy());
+        let calls = Mutex::new(vec![]);
+        let start = Instant::now();
+        let result = client
+            .request(|http| {
+                calls.lock().unwrap().push(index_of(&client, http));
+                async { Err::<(), _>(jsonrpsee::core::Error::RequestTimeout) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        // Each endpoint is tried in turn, even once they are all unhealthy, with a backoff
+        // between the rounds.
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![FAILING, HEALTHY, FAILING, HEALTHY]
+        );
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_quorum_request_detects_mismatch() {
+        let client = client(policy());
+        let agreed = client
+            .quorum_request(2, |_| async { Ok::<_, jsonrpsee::core::Error>(1) })
+            .await;
+        assert_eq!(agreed.unwrap(), 1);
+
+        let mismatch = client
+            .quorum_request(2, |http| {
+                let index = index_of(&client, http);
+                async move { Ok::<_, jsonrpsee::core::Error>(index) }
+            })
+            .await;
+        assert!(matches!(mismatch, Err(Error::QuorumMismatch { .. })));
+    }
+}
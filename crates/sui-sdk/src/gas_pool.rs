@@ -0,0 +1,552 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A pool of gas coins owned by one address, for backends sending many concurrent transactions
+//! from that address.
+//!
+//! Selecting gas coins per transaction makes concurrent transactions race for the same coin,
+//! which fails with object version conflicts or, worse, locks the coin for the rest of the
+//! epoch. The [GasPool] splits a large coin of the owner into `num_coins` managed coins and
+//! leases each of them to at most one transaction at a time. The managed coins are recorded in
+//! a journal file so a restarted process can take them back, and coins that have been used
+//! down to dust are merged back into the owner's largest coin and replaced by fresh ones.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::{
+    SuiObjectDataOptions, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::crypto::SuiKeyPair;
+use sui_types::gas_coin::GasCoin;
+use sui_types::object::{Object, Owner};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::transaction::{ProgrammableTransaction, Transaction, TransactionData};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::SuiClient;
+
+/// Maximum number of coins created by one split transaction.
+const MAX_SPLITS_PER_TRANSACTION: usize = 256;
+/// Maximum number of coins merged by one transaction, bounded by the gas payment limit.
+const MAX_MERGES_PER_TRANSACTION: usize = 255;
+
+#[derive(Clone, Debug)]
+pub struct GasPoolConfig {
+    /// Number of gas coins the pool keeps.
+    pub num_coins: usize,
+    /// Balance of each new gas coin, in MIST.
+    pub coin_balance: u64,
+    /// Coins whose balance falls below this are merged back and replaced.
+    pub min_coin_balance: u64,
+    /// How long a coin can stay leased before [GasPool::reclaim_expired_leases] takes it back.
+    /// It must be longer than it takes to execute a transaction, or the coin could be leased to
+    /// a second transaction while the first one is still in flight.
+    pub lease_duration: Duration,
+    /// How long [GasPool::lease] waits for a coin to be released when all of them are leased.
+    pub acquire_timeout: Duration,
+    /// Gas budget of the split and merge transactions made by the pool.
+    pub maintenance_gas_budget: u64,
+    /// File recording the coins of the pool, so they are recovered after a restart.
+    pub journal_path: Option<PathBuf>,
+}
+
+impl Default for GasPoolConfig {
+    fn default() -> Self {
+        Self {
+            num_coins: 32,
+            coin_balance: 1_000_000_000,
+            min_coin_balance: 100_000_000,
+            lease_duration: Duration::from_secs(60),
+            acquire_timeout: Duration::from_secs(30),
+            maintenance_gas_budget: 100_000_000,
+            journal_path: None,
+        }
+    }
+}
+
+/// A gas coin leased to one transaction. It must be given back with [GasPool::release] once the
+/// transaction has been executed, or it is only reclaimed when the lease expires.
+#[derive(Debug)]
+pub struct GasLease {
+    pub coin: ObjectRef,
+    pub balance: u64,
+    // Lease count of the coin when it was leased, so that releasing a lease that has already
+    // been reclaimed does not free the coin while it is leased to another transaction.
+    generation: u64,
+}
+
+#[derive(Debug)]
+struct PooledCoin {
+    object_ref: ObjectRef,
+    balance: u64,
+    leased_until: Option<Instant>,
+    generation: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GasPoolJournal {
+    owner: SuiAddress,
+    coins: Vec<ObjectID>,
+    leased: Vec<ObjectID>,
+}
+
+pub struct GasPool {
+    client: SuiClient,
+    keypair: SuiKeyPair,
+    owner: SuiAddress,
+    config: GasPoolConfig,
+    coins: Mutex<BTreeMap<ObjectID, PooledCoin>>,
+    released: Notify,
+    // Serializes the split and merge transactions, which use the coins outside of the pool.
+    maintenance: tokio::sync::Mutex<()>,
+}
+
+impl GasPool {
+    /// Create the pool of the address of `keypair`, taking back the coins recorded in the
+    /// journal if there is one, and splitting new coins until the pool has `num_coins`.
+    pub async fn new(
+        client: SuiClient,
+        keypair: SuiKeyPair,
+        config: GasPoolConfig,
+    ) -> Result<Self, anyhow::Error> {
+        ensure!(config.num_coins > 0, "The gas pool needs at least one coin");
+        let owner = SuiAddress::from(&keypair.public());
+        let pool = Self {
+            client,
+            keypair,
+            owner,
+            config,
+            coins: Default::default(),
+            released: Notify::new(),
+            maintenance: Default::default(),
+        };
+        pool.recover().await?;
+        pool.replenish().await?;
+        Ok(pool)
+    }
+
+    pub fn owner(&self) -> SuiAddress {
+        self.owner
+    }
+
+    /// Number of coins in the pool, leased or not.
+    pub fn size(&self) -> usize {
+        self.coins.lock().unwrap().len()
+    }
+
+    /// Number of coins that can be leased right now.
+    pub fn available(&self) -> usize {
+        self.coins
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|coin| coin.leased_until.is_none())
+            .count()
+    }
+
+    /// Lease a coin to pay for one transaction, waiting up to `acquire_timeout` for one to be
+    /// released if all of them are leased.
+    pub async fn lease(&self) -> Result<GasLease, anyhow::Error> {
+        let deadline = tokio::time::Instant::now() + self.config.acquire_timeout;
+        loop {
+            // Created before looking at the coins so a release in between is not missed.
+            let released = self.released.notified();
+            if let Some(lease) = self.try_lease()? {
+                return Ok(lease);
+            }
+            tokio::time::timeout_at(deadline, released)
+                .await
+                .map_err(|_| {
+                    anyhow!(
+                        "No gas coin of {} available after {:?}",
+                        self.owner,
+                        self.config.acquire_timeout
+                    )
+                })?;
+        }
+    }
+
+    /// Lease a coin if one is available, without waiting.
+    pub fn try_lease(&self) -> Result<Option<GasLease>, anyhow::Error> {
+        let mut coins = self.coins.lock().unwrap();
+        let Some(coin) = coins
+            .values_mut()
+            .filter(|coin| coin.leased_until.is_none())
+            .max_by_key(|coin| coin.balance)
+        else {
+            return Ok(None);
+        };
+        coin.leased_until = Some(Instant::now() + self.config.lease_duration);
+        coin.generation += 1;
+        let lease = GasLease {
+            coin: coin.object_ref,
+            balance: coin.balance,
+            generation: coin.generation,
+        };
+        self.write_journal(&coins)?;
+        Ok(Some(lease))
+    }
+
+    /// Give back a leased coin, once the transaction it paid for has been executed or has
+    /// definitely failed. A transaction whose outcome is unknown, e.g. because its execution
+    /// timed out, may still use the coin, so its lease must be left to expire instead. The coin
+    /// is read again from the network, it leaves the pool if it has been deleted or transferred
+    /// by the transaction. Releasing a lease that has already been reclaimed does nothing.
+    pub async fn release(&self, lease: GasLease) -> Result<(), anyhow::Error> {
+        let id = lease.coin.0;
+        let refreshed = self.fetch_coin(id).await;
+        let mut coins = self.coins.lock().unwrap();
+        // The lease may have been reclaimed, and the coin leased again, since it was taken.
+        let Some(coin) = coins
+            .get_mut(&id)
+            .filter(|coin| coin.leased_until.is_some() && coin.generation == lease.generation)
+        else {
+            warn!("Ignoring release of reclaimed lease of gas coin {id}");
+            return Ok(());
+        };
+        match refreshed {
+            Ok(Some((object_ref, balance))) => {
+                coin.object_ref = object_ref;
+                coin.balance = balance;
+                coin.leased_until = None;
+            }
+            Ok(None) => {
+                info!("Gas coin {id} is no longer owned by {}", self.owner);
+                coins.remove(&id);
+            }
+            Err(e) => {
+                // The lease stays until it expires and reclaim_expired_leases retries.
+                warn!("Cannot refresh gas coin {id}: {e}");
+                return Err(e);
+            }
+        }
+        self.write_journal(&coins)?;
+        drop(coins);
+        self.released.notify_waiters();
+        Ok(())
+    }
+
+    /// Execute a transaction sent by the owner of the pool and paid by a leased coin.
+    pub async fn execute(
+        &self,
+        pt: ProgrammableTransaction,
+        gas_budget: u64,
+    ) -> Result<SuiTransactionBlockResponse, anyhow::Error> {
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+        let lease = self.lease().await?;
+        let response = self
+            .sign_and_execute(vec![lease.coin], pt, gas_budget, gas_price)
+            .await
+            .map_err(|e| {
+                // The transaction may still be executed after a timeout or a failed submission,
+                // so the coin stays leased until reclaim_expired_leases takes it back.
+                warn!(
+                    "Outcome of transaction paid by gas coin {} is unknown, keeping its lease: {e}",
+                    lease.coin.0
+                );
+                e
+            })?;
+        // The transaction has been executed, so its response is returned even if the coin cannot
+        // be refreshed: its lease is then left to reclaim_expired_leases.
+        let id = lease.coin.0;
+        if let Err(e) = self.release(lease).await {
+            warn!("Cannot release gas coin {id} after executing a transaction it paid for: {e}");
+        }
+        Ok(response)
+    }
+
+    /// Take back the coins whose lease has expired, in case their holder never released them.
+    pub async fn reclaim_expired_leases(&self) -> Result<(), anyhow::Error> {
+        let now = Instant::now();
+        let expired = self
+            .coins
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|coin| matches!(coin.leased_until, Some(until) if until <= now))
+            .map(|coin| GasLease {
+                coin: coin.object_ref,
+                balance: coin.balance,
+                generation: coin.generation,
+            })
+            .collect::<Vec<_>>();
+        for lease in expired {
+            warn!("Lease of gas coin {} expired", lease.coin.0);
+            self.release(lease).await?;
+        }
+        Ok(())
+    }
+
+    /// Merge the coins used down to less than `min_coin_balance` back into the largest coin of
+    /// the owner that is not in the pool.
+    pub async fn merge_dust(&self) -> Result<(), anyhow::Error> {
+        let _guard = self.maintenance.lock().await;
+        let dust = {
+            let mut coins = self.coins.lock().unwrap();
+            let ids = coins
+                .iter()
+                .filter(|(_, coin)| {
+                    coin.leased_until.is_none() && coin.balance < self.config.min_coin_balance
+                })
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            let dust = ids
+                .iter()
+                .filter_map(|id| coins.remove(id))
+                .map(|coin| coin.object_ref)
+                .collect::<Vec<_>>();
+            self.write_journal(&coins)?;
+            dust
+        };
+        if dust.is_empty() {
+            return Ok(());
+        }
+        info!("Merging {} gas coins of {}", dust.len(), self.owner);
+
+        let mut primary = self.reserve_coin().await?.map(|(object_ref, _)| object_ref);
+        for chunk in dust.chunks(MAX_MERGES_PER_TRANSACTION) {
+            // Gas smashing merges all the gas coins into the first one, the transaction itself
+            // only sends that coin back to the owner.
+            let gas = primary.into_iter().chain(chunk.iter().copied()).collect();
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.pay_all_sui(self.owner);
+            let effects = self.execute_maintenance(gas, builder.finish()).await?;
+            primary = Some(effects.gas_object().reference.to_object_ref());
+        }
+        Ok(())
+    }
+
+    /// Split new coins out of the largest coin of the owner that is not in the pool, until the
+    /// pool has `num_coins`.
+    pub async fn replenish(&self) -> Result<(), anyhow::Error> {
+        let _guard = self.maintenance.lock().await;
+        let missing = self.config.num_coins.saturating_sub(self.size());
+        if missing == 0 {
+            return Ok(());
+        }
+        let Some((mut reserve, reserve_balance)) = self.reserve_coin().await? else {
+            bail!("{} has no coin to split gas coins from", self.owner);
+        };
+        let needed = missing as u64 * self.config.coin_balance + self.config.maintenance_gas_budget;
+        ensure!(
+            reserve_balance >= needed,
+            "The largest coin of {} has {reserve_balance} MIST, {needed} are needed to split {missing} gas coins",
+            self.owner
+        );
+        info!("Splitting {missing} gas coins for {}", self.owner);
+
+        let mut remaining = missing;
+        while remaining > 0 {
+            let count = remaining.min(MAX_SPLITS_PER_TRANSACTION);
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.pay_sui(
+                vec![self.owner; count],
+                vec![self.config.coin_balance; count],
+            )?;
+            let effects = self
+                .execute_maintenance(vec![reserve], builder.finish())
+                .await?;
+            reserve = effects.gas_object().reference.to_object_ref();
+
+            let mut coins = self.coins.lock().unwrap();
+            for created in effects.created() {
+                let object_ref = created.reference.to_object_ref();
+                coins.insert(
+                    object_ref.0,
+                    PooledCoin {
+                        object_ref,
+                        balance: self.config.coin_balance,
+                        leased_until: None,
+                        generation: 0,
+                    },
+                );
+            }
+            self.write_journal(&coins)?;
+            remaining -= count;
+        }
+        self.released.notify_waiters();
+        Ok(())
+    }
+
+    /// Run `reclaim_expired_leases`, `merge_dust` and `replenish` every `interval` until the
+    /// returned task is aborted.
+    pub fn spawn_maintenance(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = pool.reclaim_expired_leases().await {
+                    warn!("Cannot reclaim expired gas coin leases: {e}");
+                }
+                if let Err(e) = pool.merge_dust().await {
+                    warn!("Cannot merge gas coins: {e}");
+                }
+                if let Err(e) = pool.replenish().await {
+                    warn!("Cannot replenish gas pool: {e}");
+                }
+            }
+        })
+    }
+
+    /// Take back the coins recorded in the journal. Leases do not survive a restart: whatever
+    /// the transactions of the previous process did, the coins are read again from the network.
+    async fn recover(&self) -> Result<(), anyhow::Error> {
+        let Some(path) = &self.config.journal_path else {
+            return Ok(());
+        };
+        if !path.exists() {
+            return Ok(());
+        }
+        let journal: GasPoolJournal = serde_json::from_slice(
+            &fs::read(path)
+                .with_context(|| format!("Cannot read gas pool journal {}", path.display()))?,
+        )
+        .with_context(|| format!("Invalid gas pool journal {}", path.display()))?;
+        ensure!(
+            journal.owner == self.owner,
+            "Gas pool journal {} belongs to {}, not {}",
+            path.display(),
+            journal.owner,
+            self.owner
+        );
+        if !journal.leased.is_empty() {
+            info!(
+                "Recovering {} gas coins leased before restart",
+                journal.leased.len()
+            );
+        }
+
+        let mut recovered = BTreeMap::new();
+        for id in journal.coins {
+            if let Some((object_ref, balance)) = self.fetch_coin(id).await? {
+                recovered.insert(
+                    id,
+                    PooledCoin {
+                        object_ref,
+                        balance,
+                        leased_until: None,
+                        generation: 0,
+                    },
+                );
+            }
+        }
+        let mut coins = self.coins.lock().unwrap();
+        *coins = recovered;
+        self.write_journal(&coins)
+    }
+
+    /// The largest coin of the owner that is not managed by the pool.
+    async fn reserve_coin(&self) -> Result<Option<(ObjectRef, u64)>, anyhow::Error> {
+        let coins = self
+            .client
+            .coin_read_api()
+            .get_coins_stream(self.owner, None)
+            .collect::<Vec<_>>()
+            .await;
+        let pool = self.coins.lock().unwrap();
+        Ok(coins
+            .into_iter()
+            .filter(|coin| !pool.contains_key(&coin.coin_object_id))
+            .max_by_key(|coin| coin.balance)
+            .map(|coin| (coin.object_ref(), coin.balance)))
+    }
+
+    async fn fetch_coin(&self, id: ObjectID) -> Result<Option<(ObjectRef, u64)>, anyhow::Error> {
+        #[cfg(msim)]
+        {
+            let mut fail = false;
+            sui_macros::fail_point_if!("gas-pool-fetch-coin-error", || fail = true);
+            if fail {
+                bail!("Injected failure to fetch gas coin {id}");
+            }
+        }
+        let response = self
+            .client
+            .read_api()
+            .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
+            .await?;
+        let Some(data) = response.data else {
+            return Ok(None);
+        };
+        if data.owner != Some(Owner::AddressOwner(self.owner)) {
+            return Ok(None);
+        }
+        let object_ref = data.object_ref();
+        let object: Object = data.try_into()?;
+        Ok(GasCoin::try_from(&object)
+            .ok()
+            .map(|coin| (object_ref, coin.value())))
+    }
+
+    async fn execute_maintenance(
+        &self,
+        gas: Vec<ObjectRef>,
+        pt: ProgrammableTransaction,
+    ) -> Result<SuiTransactionBlockEffects, anyhow::Error> {
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+        let response = self
+            .sign_and_execute(gas, pt, self.config.maintenance_gas_budget, gas_price)
+            .await?;
+        let effects = response
+            .effects
+            .ok_or_else(|| anyhow!("Effects from SuiTransactionBlockResult should not be empty"))?;
+        ensure!(
+            effects.status().is_ok(),
+            "Gas pool transaction {} failed: {:?}",
+            effects.transaction_digest(),
+            effects.status()
+        );
+        Ok(effects)
+    }
+
+    async fn sign_and_execute(
+        &self,
+        gas: Vec<ObjectRef>,
+        pt: ProgrammableTransaction,
+        gas_budget: u64,
+        gas_price: u64,
+    ) -> Result<SuiTransactionBlockResponse, anyhow::Error> {
+        let tx_data = TransactionData::new_programmable(self.owner, gas, pt, gas_budget, gas_price);
+        let tx = Transaction::from_data_and_signer(tx_data, vec![&self.keypair]);
+        Ok(self
+            .client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                tx,
+                SuiTransactionBlockResponseOptions::full_content(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?)
+    }
+
+    fn write_journal(&self, coins: &BTreeMap<ObjectID, PooledCoin>) -> Result<(), anyhow::Error> {
+        let Some(path) = &self.config.journal_path else {
+            return Ok(());
+        };
+        let journal = GasPoolJournal {
+            owner: self.owner,
+            coins: coins.keys().copied().collect(),
+            leased: coins
+                .iter()
+                .filter(|(_, coin)| coin.leased_until.is_some())
+                .map(|(id, _)| *id)
+                .collect(),
+        };
+        fs::write(path, serde_json::to_vec_pretty(&journal)?)
+            .with_context(|| format!("Cannot write gas pool journal {}", path.display()))
+    }
+}
@@ -72,6 +72,7 @@
 
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -80,7 +81,9 @@ use jsonrpsee::core::client::ClientT;
 use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
 use jsonrpsee::rpc_params;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use serde::Serialize;
 use serde_json::Value;
+use tracing::warn;
 
 use move_core_types::language_storage::StructTag;
 pub use sui_json as json;
@@ -98,11 +101,16 @@ use sui_types::base_types::{ObjectID, ObjectInfo, SuiAddress};
 
 use crate::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriverApi, ReadApi};
 use crate::error::{Error, SuiRpcResult};
+use crate::failover::{Endpoint, EndpointHealth, RetryPolicy};
 
 pub mod apis;
 pub mod error;
+pub mod failover;
+pub mod gas_pool;
 pub mod json_rpc_error;
+pub mod move_bindings;
 pub mod sui_client_config;
+pub mod transaction_preview;
 pub mod wallet_context;
 
 pub const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
@@ -120,6 +128,9 @@ pub const SUI_TESTNET_URL: &str = "https://fullnode.testnet.sui.io:443";
 /// value of your choice to prevent the inactive WS subscription being
 /// disconnected due to proxy timeout.
 ///
+/// Requests that fail because the node cannot be reached are retried following the
+/// [RetryPolicy], on the `fallback_urls` if there are any. See the [failover] module.
+///
 /// # Examples
 ///
 /// ```rust,no_run
@@ -139,6 +150,9 @@ pub struct SuiClientBuilder {
     max_concurrent_requests: usize,
     ws_url: Option<String>,
     ws_ping_interval: Option<Duration>,
+    fallback_urls: Vec<String>,
+    retry_policy: RetryPolicy,
+    read_quorum: Option<usize>,
 }
 
 impl Default for SuiClientBuilder {
@@ -148,6 +162,9 @@ impl Default for SuiClientBuilder {
             max_concurrent_requests: 256,
             ws_url: None,
             ws_ping_interval: None,
+            fallback_urls: vec![],
+            retry_policy: RetryPolicy::default(),
+            read_quorum: None,
         }
     }
 }
@@ -177,6 +194,28 @@ impl SuiClientBuilder {
         self
     }
 
+    /// Set the HTTP URLs of other nodes to fail over to when the one passed to `build` fails
+    pub fn fallback_urls(mut self, urls: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.fallback_urls = urls
+            .into_iter()
+            .map(|url| url.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// Set how failed requests are retried and when an endpoint is considered unhealthy
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Read objects, transactions and balances from `quorum` endpoints at once, and fail unless
+    /// they all return the same result
+    pub fn read_quorum(mut self, quorum: usize) -> Self {
+        self.read_quorum = Some(quorum);
+        self
+    }
+
     /// Returns a [SuiClient] object connected to the Sui network running at the URI provided.
     ///
     /// # Examples
@@ -224,16 +263,41 @@ impl SuiClientBuilder {
             None
         };
 
-        let http = HttpClientBuilder::default()
-            .max_request_body_size(2 << 30)
-            .max_concurrent_requests(self.max_concurrent_requests)
-            .set_headers(headers.clone())
-            .request_timeout(self.request_timeout)
-            .build(http)?;
-
-        let info = Self::get_server_info(&http, &ws).await?;
-
-        let rpc = RpcClient { http, ws, info };
+        let endpoints = std::iter::once(http.as_ref().to_string())
+            .chain(self.fallback_urls)
+            .map(|url| {
+                let http = HttpClientBuilder::default()
+                    .max_request_body_size(2 << 30)
+                    .max_concurrent_requests(self.max_concurrent_requests)
+                    .set_headers(headers.clone())
+                    .request_timeout(self.request_timeout)
+                    .build(&url)?;
+                Ok(Endpoint::new(url, http))
+            })
+            .collect::<SuiRpcResult<Vec<_>>>()?;
+
+        // Any reachable endpoint can describe the server, the unreachable ones are skipped until
+        // their cooldown ends.
+        let mut info = Err(Error::DataError("No RPC endpoint".into()));
+        for endpoint in &endpoints {
+            info = Self::get_server_info(&endpoint.http, &ws).await;
+            match &info {
+                Ok(_) => break,
+                Err(e) => {
+                    warn!("Cannot reach RPC endpoint {}: {e}", endpoint.url);
+                    endpoint.mark_unhealthy(&self.retry_policy);
+                }
+            }
+        }
+        let info = info?;
+
+        let rpc = RpcClient {
+            endpoints,
+            ws,
+            info,
+            retry_policy: self.retry_policy,
+            read_quorum: self.read_quorum,
+        };
         let api = Arc::new(rpc);
         let read_api = Arc::new(ReadApi::new(api.clone()));
         let quorum_driver_api = QuorumDriverApi::new(api.clone());
@@ -417,18 +481,22 @@ pub struct SuiClient {
 }
 
 pub(crate) struct RpcClient {
-    http: HttpClient,
+    // The first endpoint is the URL passed to `SuiClientBuilder::build`, then the fallbacks.
+    endpoints: Vec<Endpoint>,
     ws: Option<WsClient>,
     info: ServerInfo,
+    retry_policy: RetryPolicy,
+    read_quorum: Option<usize>,
 }
 
 impl Debug for RpcClient {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "RPC client. Http: {:?}, Websocket: {:?}",
-            self.http, self.ws
-        )
+        let urls = self
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.url.as_str())
+            .collect::<Vec<_>>();
+        write!(f, "RPC client. Http: {:?}, Websocket: {:?}", urls, self.ws)
     }
 }
 
@@ -501,9 +569,49 @@ impl SuiClient {
         &self.transaction_builder
     }
 
-    /// Returns a reference to the underlying http client.
+    /// Returns a reference to the underlying http client of the URL the client was built with.
     pub fn http(&self) -> &HttpClient {
-        &self.api.http
+        &self.api.endpoints[0].http
+    }
+
+    /// Returns the health of each RPC endpoint, the URL the client was built with first.
+    pub fn endpoint_health(&self) -> Vec<EndpointHealth> {
+        self.api
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.health())
+            .collect()
+    }
+
+    /// Sends `request` to `quorum` endpoints at once, and returns the result only if they all
+    /// return the same one. Use it for reads that must not come from a single, possibly lagging
+    /// or misbehaving, node.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use sui_json_rpc_api::ReadApiClient;
+    /// use sui_sdk::SuiClientBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), anyhow::Error> {
+    ///     let sui = SuiClientBuilder::default()
+    ///         .fallback_urls(["http://127.0.0.1:9001"])
+    ///         .build("http://127.0.0.1:9000")
+    ///         .await?;
+    ///     let checkpoint = sui
+    ///         .quorum_read(2, |http| http.get_latest_checkpoint_sequence_number())
+    ///         .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn quorum_read<'a, T, F, Fut>(&'a self, quorum: usize, request: F) -> SuiRpcResult<T>
+    where
+        F: Fn(&'a HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, jsonrpsee::core::Error>>,
+        T: Serialize,
+    {
+        self.api.quorum_request(quorum, request).await
     }
 
     /// Returns a reference to the underlying WebSocket client, if any.
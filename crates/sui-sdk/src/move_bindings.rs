@@ -0,0 +1,544 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generate typed Rust bindings for a Move package.
+//!
+//! The bindings are generated from the normalized modules of the package, as returned by
+//! `sui_getNormalizedMoveModulesByPackage` or normalized from the compiled modules. For each
+//! module they contain:
+//! * a Rust struct for every Move struct, with serde support so that the BCS contents of an
+//!   object can be deserialized into it, and a `struct_tag` function returning its type;
+//! * a function for every public or entry function, appending a call to it to a
+//!   [ProgrammableTransactionBuilder](sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder).
+//!   Arguments that can be passed as pure values take the corresponding Rust type, objects and
+//!   results of previous commands are passed as an `Argument`.
+//!
+//! The generated code depends on the `sui-sdk`, `move-core-types`, `serde` and `anyhow` crates
+//! and is meant to be added to a crate as a module of its own.
+//!
+//! ```rust,no_run
+//! use std::str::FromStr;
+//! use sui_sdk::move_bindings::MoveBindings;
+//! use sui_sdk::types::base_types::ObjectID;
+//! use sui_sdk::SuiClientBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), anyhow::Error> {
+//!     let sui = SuiClientBuilder::default().build_testnet().await?;
+//!     let package = ObjectID::from_str("0x2")?;
+//!     let bindings = MoveBindings::from_package(&sui, package).await?;
+//!     std::fs::write("src/sui_framework.rs", bindings.generate())?;
+//!     Ok(())
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use sui_json_rpc_types::{
+    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct,
+    SuiMoveNormalizedType, SuiMoveVisibility,
+};
+use sui_types::base_types::ObjectID;
+use sui_types::{MOVE_STDLIB_PACKAGE_ID, SUI_FRAMEWORK_PACKAGE_ID};
+
+use crate::error::SuiRpcResult;
+use crate::transaction_preview::{format_type, is_tx_context};
+use crate::SuiClient;
+
+const TYPES: &str = "sui_sdk::types";
+
+pub struct MoveBindings {
+    package_id: ObjectID,
+    modules: BTreeMap<String, SuiMoveNormalizedModule>,
+}
+
+impl MoveBindings {
+    /// Bindings for the given modules, calling the package published at `package_id`.
+    pub fn new(package_id: ObjectID, modules: BTreeMap<String, SuiMoveNormalizedModule>) -> Self {
+        Self {
+            package_id,
+            modules,
+        }
+    }
+
+    /// Bindings for the modules of an on-chain package.
+    pub async fn from_package(client: &SuiClient, package_id: ObjectID) -> SuiRpcResult<Self> {
+        let modules = client
+            .read_api()
+            .get_normalized_move_modules_by_package(package_id)
+            .await?;
+        Ok(Self::new(package_id, modules))
+    }
+
+    pub fn package_id(&self) -> ObjectID {
+        self.package_id
+    }
+
+    /// The Rust source of the bindings.
+    pub fn generate(&self) -> String {
+        let bindable = self.bindable_structs();
+        let mut out = String::new();
+        writeln!(
+            out,
+            "// Generated by `sui client bindgen` for package {}, do not edit.",
+            self.package_id
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "/// The package the functions are called on.").unwrap();
+        writeln!(
+            out,
+            "pub const PACKAGE_ID: {TYPES}::base_types::ObjectID = \
+             {TYPES}::base_types::ObjectID::new({});",
+            bytes_literal(self.package_id.as_ref())
+        )
+        .unwrap();
+        if let Some(address) = self.original_package_id() {
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "/// The package the types are defined in, its first version."
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "pub const ORIGINAL_PACKAGE_ID: {TYPES}::base_types::ObjectID = \
+                 {TYPES}::base_types::ObjectID::new({});",
+                bytes_literal(address.as_ref())
+            )
+            .unwrap();
+        }
+        for (name, module) in &self.modules {
+            writeln!(out).unwrap();
+            self.generate_module(&mut out, name, module, &bindable);
+        }
+        out
+    }
+
+    fn original_package_id(&self) -> Option<ObjectID> {
+        self.modules
+            .values()
+            .next()
+            .and_then(|module| ObjectID::from_hex_literal(&module.address).ok())
+    }
+
+    fn generate_module(
+        &self,
+        out: &mut String,
+        name: &str,
+        module: &SuiMoveNormalizedModule,
+        bindable: &BTreeSet<(String, String)>,
+    ) {
+        writeln!(out, "pub mod {} {{", rust_ident(name)).unwrap();
+        writeln!(out, "    pub const MODULE_NAME: &str = \"{name}\";").unwrap();
+        for (struct_name, struct_) in &module.structs {
+            writeln!(out).unwrap();
+            if bindable.contains(&(name.to_string(), struct_name.clone())) {
+                self.generate_struct(out, struct_name, struct_);
+            } else {
+                writeln!(
+                    out,
+                    "    // `{struct_name}` has no binding, one of its fields has a type \
+                     defined in another package."
+                )
+                .unwrap();
+            }
+        }
+        for (function_name, function) in &module.exposed_functions {
+            if matches!(function.visibility, SuiMoveVisibility::Public) || function.is_entry {
+                writeln!(out).unwrap();
+                self.generate_function(out, function_name, function);
+            }
+        }
+        writeln!(out, "}}").unwrap();
+    }
+
+    fn generate_struct(&self, out: &mut String, name: &str, struct_: &SuiMoveNormalizedStruct) {
+        let generics = rust_generics(struct_);
+        writeln!(
+            out,
+            "    #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]"
+        )
+        .unwrap();
+        writeln!(out, "    pub struct {name}{generics} {{").unwrap();
+        for field in &struct_.fields {
+            let type_ = self
+                .field_type(&field.type_)
+                .expect("fields of bindable structs have a Rust type");
+            writeln!(out, "        pub {}: {type_},", rust_ident(&field.name)).unwrap();
+        }
+        writeln!(out, "    }}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "    impl{generics} {name}{generics} {{").unwrap();
+        writeln!(
+            out,
+            "        pub const STRUCT_NAME: &'static str = \"{name}\";"
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        let type_params = if struct_.type_parameters.is_empty() {
+            ""
+        } else {
+            "type_params: Vec<sui_sdk::types::TypeTag>"
+        };
+        writeln!(
+            out,
+            "        pub fn struct_tag({type_params}) -> move_core_types::language_storage::StructTag {{"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "            move_core_types::language_storage::StructTag {{"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "                address: super::ORIGINAL_PACKAGE_ID.into(),"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "                module: {TYPES}::Identifier::new(MODULE_NAME).unwrap(),"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "                name: {TYPES}::Identifier::new(Self::STRUCT_NAME).unwrap(),"
+        )
+        .unwrap();
+        if struct_.type_parameters.is_empty() {
+            writeln!(out, "                type_params: vec![],").unwrap();
+        } else {
+            writeln!(out, "                type_params,").unwrap();
+        }
+        writeln!(out, "            }}").unwrap();
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+
+    fn generate_function(
+        &self,
+        out: &mut String,
+        name: &str,
+        function: &SuiMoveNormalizedFunction,
+    ) {
+        let parameters = function
+            .parameters
+            .iter()
+            .filter(|param| !is_tx_context(param))
+            .collect::<Vec<_>>();
+
+        let signature = parameters
+            .iter()
+            .map(|param| format_type(param, &[]))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let visibility = match (&function.visibility, function.is_entry) {
+            (SuiMoveVisibility::Public, true) => "public entry ",
+            (SuiMoveVisibility::Public, false) => "public ",
+            (_, true) => "entry ",
+            (_, false) => "",
+        };
+        writeln!(out, "    /// `{visibility}fun {name}({signature})`").unwrap();
+        writeln!(
+            out,
+            "    #[allow(clippy::too_many_arguments, clippy::vec_init_then_push)]"
+        )
+        .unwrap();
+        write!(
+            out,
+            "    pub fn {}(\n        builder: &mut {TYPES}::programmable_transaction_builder::ProgrammableTransactionBuilder,\n",
+            rust_ident(name)
+        )
+        .unwrap();
+        if !function.type_parameters.is_empty() {
+            writeln!(
+                out,
+                "        type_args: [{TYPES}::TypeTag; {}],",
+                function.type_parameters.len()
+            )
+            .unwrap();
+        }
+        for (i, param) in parameters.iter().enumerate() {
+            let type_ =
+                pure_type(param).unwrap_or_else(|| format!("{TYPES}::transaction::Argument"));
+            writeln!(out, "        arg{i}: {type_},").unwrap();
+        }
+        writeln!(
+            out,
+            "    ) -> anyhow::Result<{TYPES}::transaction::Argument> {{"
+        )
+        .unwrap();
+        writeln!(out, "        let mut arguments = vec![];").unwrap();
+        for (i, param) in parameters.iter().enumerate() {
+            if pure_type(param).is_some() {
+                writeln!(out, "        arguments.push(builder.pure(arg{i})?);").unwrap();
+            } else {
+                writeln!(out, "        arguments.push(arg{i});").unwrap();
+            }
+        }
+        let type_args = if function.type_parameters.is_empty() {
+            "vec![]"
+        } else {
+            "type_args.to_vec()"
+        };
+        writeln!(out, "        Ok(builder.programmable_move_call(").unwrap();
+        writeln!(out, "            super::PACKAGE_ID,").unwrap();
+        writeln!(
+            out,
+            "            {TYPES}::Identifier::new(MODULE_NAME).unwrap(),"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "            {TYPES}::Identifier::new(\"{name}\").unwrap(),"
+        )
+        .unwrap();
+        writeln!(out, "            {type_args},").unwrap();
+        writeln!(out, "            arguments,").unwrap();
+        writeln!(out, "        ))").unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+
+    /// The structs whose fields all have a Rust type: primitives, types of the Move standard
+    /// library and the Sui framework that have a Rust counterpart, and other bindable structs of
+    /// this package.
+    fn bindable_structs(&self) -> BTreeSet<(String, String)> {
+        let mut bindable: BTreeSet<_> = self
+            .modules
+            .iter()
+            .flat_map(|(module_name, module)| {
+                module
+                    .structs
+                    .keys()
+                    .map(move |name| (module_name.clone(), name.clone()))
+            })
+            .collect();
+        // Remove the structs depending on unbindable ones until none is left to remove.
+        loop {
+            let unbindable: Vec<_> = bindable
+                .iter()
+                .filter(|(module, name)| {
+                    self.modules[module].structs[name]
+                        .fields
+                        .iter()
+                        .any(|field| !self.has_rust_type(&field.type_, &bindable))
+                })
+                .cloned()
+                .collect();
+            if unbindable.is_empty() {
+                return bindable;
+            }
+            for key in unbindable {
+                bindable.remove(&key);
+            }
+        }
+    }
+
+    fn has_rust_type(
+        &self,
+        type_: &SuiMoveNormalizedType,
+        bindable: &BTreeSet<(String, String)>,
+    ) -> bool {
+        match type_ {
+            SuiMoveNormalizedType::Vector(t) => self.has_rust_type(t, bindable),
+            SuiMoveNormalizedType::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => {
+                if self.is_own_address(address) {
+                    bindable.contains(&(module.clone(), name.clone()))
+                        && type_arguments
+                            .iter()
+                            .all(|t| self.has_rust_type(t, bindable))
+                } else {
+                    match framework_type(address, module, name) {
+                        Some(FrameworkType::Option) => type_arguments
+                            .first()
+                            .is_some_and(|t| self.has_rust_type(t, bindable)),
+                        Some(FrameworkType::Rust(_)) => true,
+                        None => false,
+                    }
+                }
+            }
+            _ => self.field_type(type_).is_some(),
+        }
+    }
+
+    fn is_own_address(&self, address: &str) -> bool {
+        self.modules
+            .values()
+            .any(|module| same_address(&module.address, address))
+    }
+
+    /// The Rust type of a struct field, or `None` if it has none.
+    fn field_type(&self, type_: &SuiMoveNormalizedType) -> Option<String> {
+        Some(match type_ {
+            SuiMoveNormalizedType::Bool => "bool".to_string(),
+            SuiMoveNormalizedType::U8 => "u8".to_string(),
+            SuiMoveNormalizedType::U16 => "u16".to_string(),
+            SuiMoveNormalizedType::U32 => "u32".to_string(),
+            SuiMoveNormalizedType::U64 => "u64".to_string(),
+            SuiMoveNormalizedType::U128 => "u128".to_string(),
+            SuiMoveNormalizedType::U256 => "move_core_types::u256::U256".to_string(),
+            SuiMoveNormalizedType::Address => format!("{TYPES}::base_types::SuiAddress"),
+            SuiMoveNormalizedType::Vector(t) => format!("Vec<{}>", self.field_type(t)?),
+            SuiMoveNormalizedType::TypeParameter(index) => format!("T{index}"),
+            SuiMoveNormalizedType::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } if self.is_own_address(address) => {
+                let struct_ = self.modules.get(module)?.structs.get(name)?;
+                // Phantom type parameters are not generic parameters of the Rust struct.
+                let args = type_arguments
+                    .iter()
+                    .zip(&struct_.type_parameters)
+                    .filter(|(_, param)| !param.is_phantom)
+                    .map(|(t, _)| self.field_type(t))
+                    .collect::<Option<Vec<_>>>()?;
+                let path = format!("super::{}::{name}", rust_ident(module));
+                if args.is_empty() {
+                    path
+                } else {
+                    format!("{path}<{}>", args.join(", "))
+                }
+            }
+            SuiMoveNormalizedType::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => match framework_type(address, module, name)? {
+                FrameworkType::Option => {
+                    format!("Option<{}>", self.field_type(type_arguments.first()?)?)
+                }
+                FrameworkType::Rust(type_) => type_,
+            },
+            SuiMoveNormalizedType::Signer
+            | SuiMoveNormalizedType::Reference(_)
+            | SuiMoveNormalizedType::MutableReference(_) => return None,
+        })
+    }
+}
+
+enum FrameworkType {
+    Option,
+    Rust(String),
+}
+
+/// The Rust counterpart of a struct of the Move standard library or the Sui framework.
+fn framework_type(address: &str, module: &str, name: &str) -> Option<FrameworkType> {
+    let address = ObjectID::from_hex_literal(address).ok()?;
+    let type_ = if address == MOVE_STDLIB_PACKAGE_ID {
+        match (module, name) {
+            ("string", "String") | ("ascii", "String") => "String".to_string(),
+            ("option", "Option") => return Some(FrameworkType::Option),
+            _ => return None,
+        }
+    } else if address == SUI_FRAMEWORK_PACKAGE_ID {
+        match (module, name) {
+            ("object", "UID") => format!("{TYPES}::id::UID"),
+            ("object", "ID") => format!("{TYPES}::id::ID"),
+            ("balance", "Balance") => format!("{TYPES}::balance::Balance"),
+            ("balance", "Supply") => format!("{TYPES}::balance::Supply"),
+            ("coin", "Coin") => format!("{TYPES}::coin::Coin"),
+            ("url", "Url") => "String".to_string(),
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    Some(FrameworkType::Rust(type_))
+}
+
+/// The Rust type of a parameter that can be passed as a pure value, or `None` if it is passed as
+/// an `Argument`.
+fn pure_type(type_: &SuiMoveNormalizedType) -> Option<String> {
+    Some(match type_ {
+        SuiMoveNormalizedType::Bool => "bool".to_string(),
+        SuiMoveNormalizedType::U8 => "u8".to_string(),
+        SuiMoveNormalizedType::U16 => "u16".to_string(),
+        SuiMoveNormalizedType::U32 => "u32".to_string(),
+        SuiMoveNormalizedType::U64 => "u64".to_string(),
+        SuiMoveNormalizedType::U128 => "u128".to_string(),
+        SuiMoveNormalizedType::U256 => "move_core_types::u256::U256".to_string(),
+        SuiMoveNormalizedType::Address => format!("{TYPES}::base_types::SuiAddress"),
+        SuiMoveNormalizedType::Vector(t) => format!("Vec<{}>", pure_type(t)?),
+        SuiMoveNormalizedType::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => {
+            let address = ObjectID::from_hex_literal(address).ok()?;
+            match (module.as_str(), name.as_str()) {
+                ("string", "String") | ("ascii", "String") if address == MOVE_STDLIB_PACKAGE_ID => {
+                    "String".to_string()
+                }
+                ("option", "Option") if address == MOVE_STDLIB_PACKAGE_ID => {
+                    format!("Option<{}>", pure_type(type_arguments.first()?)?)
+                }
+                ("object", "ID") if address == SUI_FRAMEWORK_PACKAGE_ID => {
+                    format!("{TYPES}::base_types::ObjectID")
+                }
+                _ => return None,
+            }
+        }
+        SuiMoveNormalizedType::Signer
+        | SuiMoveNormalizedType::TypeParameter(_)
+        | SuiMoveNormalizedType::Reference(_)
+        | SuiMoveNormalizedType::MutableReference(_) => return None,
+    })
+}
+
+fn rust_generics(struct_: &SuiMoveNormalizedStruct) -> String {
+    let params = struct_
+        .type_parameters
+        .iter()
+        .enumerate()
+        .filter(|(_, param)| !param.is_phantom)
+        .map(|(index, _)| format!("T{index}"))
+        .collect::<Vec<_>>();
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn same_address(a: &str, b: &str) -> bool {
+    match (ObjectID::from_hex_literal(a), ObjectID::from_hex_literal(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn bytes_literal(bytes: &[u8]) -> String {
+    let bytes = bytes
+        .iter()
+        .map(|b| format!("{b:#04x}"))
+        .collect::<Vec<_>>();
+    format!("[{}]", bytes.join(", "))
+}
+
+/// Escape Move identifiers that are Rust keywords.
+fn rust_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let",
+        "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+        "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+        "virtual", "where", "while", "yield",
+    ];
+    match name {
+        // These cannot be raw identifiers.
+        "self" | "Self" | "super" | "crate" => format!("{name}_"),
+        _ if KEYWORDS.contains(&name) => format!("r#{name}"),
+        _ => name.to_string(),
+    }
+}
@@ -1,14 +1,18 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter, Write};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use crate::{SuiClient, SuiClientBuilder, SUI_DEVNET_URL, SUI_LOCAL_NETWORK_URL, SUI_TESTNET_URL};
 use sui_config::Config;
+use sui_keys::encrypted_keystore::{
+    decrypt_with_passphrase, encrypt_with_passphrase, is_passphrase_encrypted, KdfParams,
+};
 use sui_keys::keystore::{AccountKeystore, Keystore};
 use sui_types::base_types::*;
 
@@ -48,6 +52,13 @@ impl SuiClientConfig {
         })
     }
 
+    pub fn get_env_mut(&mut self, alias: &str) -> Result<&mut SuiEnv, anyhow::Error> {
+        self.envs
+            .iter_mut()
+            .find(|env| env.alias == alias)
+            .ok_or_else(|| anyhow!("Environment configuration not found for env [{alias}]"))
+    }
+
     pub fn add_env(&mut self, env: SuiEnv) {
         if !self
             .envs
@@ -57,13 +68,133 @@ impl SuiClientConfig {
             self.envs.push(env)
         }
     }
+
+    /// The default gas budget of the active environment, if it has one.
+    pub fn default_gas_budget(&self) -> Option<u64> {
+        self.get_active_env()
+            .ok()
+            .and_then(|env| env.default_gas_budget)
+    }
+
+    /// Bundle the environments with the given aliases, or all of them if `aliases` is empty.
+    pub fn export_envs(&self, aliases: &[String]) -> Result<EnvBundle, anyhow::Error> {
+        let envs = if aliases.is_empty() {
+            self.envs.clone()
+        } else {
+            aliases
+                .iter()
+                .map(|alias| {
+                    self.get_env(&Some(alias.clone())).cloned().ok_or_else(|| {
+                        anyhow!("Environment configuration not found for env [{alias}]")
+                    })
+                })
+                .collect::<Result<_, _>>()?
+        };
+        Ok(EnvBundle { envs })
+    }
+
+    /// Add the environments of `bundle`. Environments whose alias is already used are replaced
+    /// if `overwrite` is set, and skipped otherwise.
+    pub fn import_envs(&mut self, bundle: EnvBundle, overwrite: bool) -> EnvImport {
+        let mut import = EnvImport::default();
+        for env in bundle.envs {
+            match self.envs.iter_mut().find(|other| other.alias == env.alias) {
+                None => {
+                    import.added.push(env.alias.clone());
+                    self.envs.push(env);
+                }
+                Some(existing) if overwrite => {
+                    import.replaced.push(env.alias.clone());
+                    *existing = env;
+                }
+                Some(_) => import.skipped.push(env.alias),
+            }
+        }
+        if self.active_env.is_none() {
+            self.active_env = self.envs.first().map(|env| env.alias.clone());
+        }
+        import
+    }
 }
 
+/// Environments exported from a client config, to be shared with other users and imported
+/// into their own config.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvBundle {
+    pub envs: Vec<SuiEnv>,
+}
+
+impl EnvBundle {
+    /// Serialize the bundle, encrypted with the passphrase if one is given. The RPC URLs of
+    /// private nodes often embed an API key, encrypt bundles holding them before sharing.
+    pub fn to_bytes(
+        &self,
+        passphrase: Option<(&str, KdfParams)>,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+        match passphrase {
+            Some((passphrase, kdf_params)) => {
+                encrypt_with_passphrase(&bytes, passphrase, kdf_params)
+            }
+            None => Ok(bytes),
+        }
+    }
+
+    /// Deserialize a bundle, calling `passphrase` to get the passphrase if it is encrypted.
+    pub fn from_bytes(
+        bytes: &[u8],
+        passphrase: impl FnOnce() -> Result<String, anyhow::Error>,
+    ) -> Result<Self, anyhow::Error> {
+        let bytes = if Self::is_encrypted(bytes) {
+            decrypt_with_passphrase(bytes, &passphrase()?)?
+        } else {
+            bytes.to_vec()
+        };
+        let bundle: Self = serde_json::from_slice(&bytes)
+            .map_err(|e| anyhow!("Cannot deserialize the environments: {e}"))?;
+        for (i, env) in bundle.envs.iter().enumerate() {
+            if bundle.envs[..i]
+                .iter()
+                .any(|other| other.alias == env.alias)
+            {
+                bail!("Environment [{}] is defined twice", env.alias);
+            }
+        }
+        Ok(bundle)
+    }
+
+    pub fn is_encrypted(bytes: &[u8]) -> bool {
+        is_passphrase_encrypted(bytes)
+    }
+}
+
+/// The aliases of the environments added, replaced and skipped by an import.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EnvImport {
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SuiEnv {
     pub alias: String,
     pub rpc: String,
     pub ws: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphql: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faucet: Option<String>,
+    /// Base URL of a block explorer for the network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer: Option<String>,
+    /// Gas budget of the transactions sent by the CLI when none is specified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_gas_budget: Option<u64>,
+    /// Names of addresses commonly used on the network, which the CLI accepts in place of the
+    /// addresses. Unlike key aliases, these do not need a key in the keystore.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub address_aliases: BTreeMap<String, SuiAddress>,
 }
 
 impl SuiEnv {
@@ -91,6 +222,7 @@ impl SuiEnv {
             alias: "devnet".to_string(),
             rpc: SUI_DEVNET_URL.into(),
             ws: None,
+            ..Default::default()
         }
     }
     pub fn testnet() -> Self {
@@ -98,6 +230,7 @@ impl SuiEnv {
             alias: "testnet".to_string(),
             rpc: SUI_TESTNET_URL.into(),
             ws: None,
+            ..Default::default()
         }
     }
 
@@ -106,6 +239,7 @@ impl SuiEnv {
             alias: "local".to_string(),
             rpc: SUI_LOCAL_NETWORK_URL.into(),
             ws: None,
+            ..Default::default()
         }
     }
 }
@@ -119,6 +253,26 @@ impl Display for SuiEnv {
             writeln!(writer)?;
             write!(writer, "Websocket URL: {ws}")?;
         }
+        if let Some(graphql) = &self.graphql {
+            writeln!(writer)?;
+            write!(writer, "GraphQL URL: {graphql}")?;
+        }
+        if let Some(faucet) = &self.faucet {
+            writeln!(writer)?;
+            write!(writer, "Faucet URL: {faucet}")?;
+        }
+        if let Some(explorer) = &self.explorer {
+            writeln!(writer)?;
+            write!(writer, "Explorer URL: {explorer}")?;
+        }
+        if let Some(gas_budget) = self.default_gas_budget {
+            writeln!(writer)?;
+            write!(writer, "Default gas budget: {gas_budget}")?;
+        }
+        for (name, address) in &self.address_aliases {
+            writeln!(writer)?;
+            write!(writer, "Address alias: {name} = {address}")?;
+        }
         write!(f, "{}", writer)
     }
 }
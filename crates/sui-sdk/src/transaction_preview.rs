@@ -0,0 +1,262 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human readable preview of a transaction, built by dry running its `TransactionData` before it
+//! is signed. The preview is serializable so wallets can render it in their own UI.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use move_core_types::language_storage::TypeTag;
+use serde::Serialize;
+use sui_json_rpc_types::{
+    BalanceChange, ObjectChange, SuiExecutionStatus, SuiMoveNormalizedModule,
+    SuiMoveNormalizedType, SuiTransactionBlockEffectsAPI,
+};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::crypto::default_hash;
+use sui_types::digests::TransactionDigest;
+use sui_types::transaction::{Command, TransactionData, TransactionDataAPI, TransactionKind};
+use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
+
+use crate::error::{Error, SuiRpcResult};
+use crate::SuiClient;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionPreview {
+    pub digest: TransactionDigest,
+    pub sender: SuiAddress,
+    /// Status of the dry run, a failure here means the transaction would fail if executed.
+    pub status: SuiExecutionStatus,
+    pub move_calls: Vec<MoveCallPreview>,
+    pub balance_changes: Vec<BalanceChange>,
+    pub object_changes: Vec<ObjectChange>,
+    pub gas: GasPreview,
+}
+
+/// A Move function called by the transaction, with its parameter types resolved against the
+/// type arguments of the call.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveCallPreview {
+    pub package: ObjectID,
+    pub module: String,
+    pub function: String,
+    pub type_arguments: Vec<String>,
+    pub parameters: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GasPreview {
+    pub budget: u64,
+    pub price: u64,
+    /// Gas used by the dry run, computation plus storage cost, which the budget must cover.
+    pub estimate: u64,
+    pub computation_cost: u64,
+    pub storage_cost: u64,
+    pub storage_rebate: u64,
+    /// Gas used minus the storage rebate, a negative number means a refund.
+    pub net_gas_usage: i64,
+}
+
+impl GasPreview {
+    pub fn budget_too_low(&self) -> bool {
+        self.budget < self.estimate
+    }
+}
+
+impl TransactionPreview {
+    /// Dry run `tx_data` and summarize what it would do if executed.
+    pub async fn new(client: &SuiClient, tx_data: &TransactionData) -> SuiRpcResult<Self> {
+        let response = client
+            .read_api()
+            .dry_run_transaction_block(tx_data.clone())
+            .await?;
+        let move_calls = resolve_move_calls(client, tx_data).await?;
+        let gas_summary = response.effects.gas_cost_summary();
+
+        Ok(Self {
+            digest: TransactionDigest::new(default_hash(tx_data)),
+            sender: tx_data.sender(),
+            status: response.effects.status().clone(),
+            move_calls,
+            balance_changes: response.balance_changes,
+            object_changes: response.object_changes,
+            gas: GasPreview {
+                budget: tx_data.gas_budget(),
+                price: tx_data.gas_price(),
+                estimate: gas_summary.gas_used(),
+                computation_cost: gas_summary.computation_cost,
+                storage_cost: gas_summary.storage_cost,
+                storage_rebate: gas_summary.storage_rebate,
+                net_gas_usage: gas_summary.net_gas_usage(),
+            },
+        })
+    }
+}
+
+async fn resolve_move_calls(
+    client: &SuiClient,
+    tx_data: &TransactionData,
+) -> SuiRpcResult<Vec<MoveCallPreview>> {
+    let TransactionKind::ProgrammableTransaction(pt) = tx_data.kind() else {
+        return Ok(vec![]);
+    };
+    let mut packages: BTreeMap<ObjectID, BTreeMap<String, SuiMoveNormalizedModule>> =
+        BTreeMap::new();
+    let mut move_calls = vec![];
+    for command in &pt.commands {
+        let Command::MoveCall(call) = command else {
+            continue;
+        };
+        if !packages.contains_key(&call.package) {
+            let modules = client
+                .read_api()
+                .get_normalized_move_modules_by_package(call.package)
+                .await?;
+            packages.insert(call.package, modules);
+        }
+        let function = packages[&call.package]
+            .get(call.module.as_str())
+            .and_then(|module| module.exposed_functions.get(call.function.as_str()))
+            .ok_or_else(|| {
+                Error::DataError(format!(
+                    "Cannot find function {}::{}::{}",
+                    call.package, call.module, call.function
+                ))
+            })?;
+        let parameters = function
+            .parameters
+            .iter()
+            .filter(|param| !is_tx_context(param))
+            .map(|param| format_type(param, &call.type_arguments))
+            .collect();
+        move_calls.push(MoveCallPreview {
+            package: call.package,
+            module: call.module.to_string(),
+            function: call.function.to_string(),
+            type_arguments: call.type_arguments.iter().map(|t| t.to_string()).collect(),
+            parameters,
+        });
+    }
+    Ok(move_calls)
+}
+
+/// The `TxContext` parameter is passed in by the runtime, not by the transaction.
+pub(crate) fn is_tx_context(ty: &SuiMoveNormalizedType) -> bool {
+    match ty {
+        SuiMoveNormalizedType::Reference(inner)
+        | SuiMoveNormalizedType::MutableReference(inner) => {
+            matches!(
+                inner.as_ref(),
+                SuiMoveNormalizedType::Struct { address, module, name, .. }
+                    if ObjectID::from_hex_literal(address).ok() == Some(SUI_FRAMEWORK_PACKAGE_ID)
+                        && module == "tx_context"
+                        && name == "TxContext"
+            )
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn format_type(ty: &SuiMoveNormalizedType, type_arguments: &[TypeTag]) -> String {
+    match ty {
+        SuiMoveNormalizedType::Bool => "bool".to_string(),
+        SuiMoveNormalizedType::U8 => "u8".to_string(),
+        SuiMoveNormalizedType::U16 => "u16".to_string(),
+        SuiMoveNormalizedType::U32 => "u32".to_string(),
+        SuiMoveNormalizedType::U64 => "u64".to_string(),
+        SuiMoveNormalizedType::U128 => "u128".to_string(),
+        SuiMoveNormalizedType::U256 => "u256".to_string(),
+        SuiMoveNormalizedType::Address => "address".to_string(),
+        SuiMoveNormalizedType::Signer => "signer".to_string(),
+        SuiMoveNormalizedType::Struct {
+            address,
+            module,
+            name,
+            type_arguments: struct_type_arguments,
+        } => {
+            let address = ObjectID::from_hex_literal(address)
+                .map(|id| id.to_hex_literal())
+                .unwrap_or_else(|_| address.clone());
+            if struct_type_arguments.is_empty() {
+                format!("{address}::{module}::{name}")
+            } else {
+                let args = struct_type_arguments
+                    .iter()
+                    .map(|t| format_type(t, type_arguments))
+                    .collect::<Vec<_>>();
+                format!("{address}::{module}::{name}<{}>", args.join(", "))
+            }
+        }
+        SuiMoveNormalizedType::Vector(t) => format!("vector<{}>", format_type(t, type_arguments)),
+        SuiMoveNormalizedType::TypeParameter(index) => type_arguments
+            .get(*index as usize)
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| format!("T{index}")),
+        SuiMoveNormalizedType::Reference(t) => format!("&{}", format_type(t, type_arguments)),
+        SuiMoveNormalizedType::MutableReference(t) => {
+            format!("&mut {}", format_type(t, type_arguments))
+        }
+    }
+}
+
+impl Display for TransactionPreview {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Transaction Preview")?;
+        writeln!(f, "Digest: {}", self.digest)?;
+        writeln!(f, "Sender: {}", self.sender)?;
+        match &self.status {
+            SuiExecutionStatus::Success => writeln!(f, "Dry Run Status: Success")?,
+            SuiExecutionStatus::Failure { error } => {
+                writeln!(f, "Dry Run Status: Failure, {error}")?
+            }
+        }
+        if !self.move_calls.is_empty() {
+            writeln!(f, "Move Calls:")?;
+            for call in &self.move_calls {
+                let type_arguments = if call.type_arguments.is_empty() {
+                    String::new()
+                } else {
+                    format!("<{}>", call.type_arguments.join(", "))
+                };
+                writeln!(
+                    f,
+                    "  {}::{}::{}{type_arguments}({})",
+                    call.package,
+                    call.module,
+                    call.function,
+                    call.parameters.join(", ")
+                )?;
+            }
+        }
+        if !self.balance_changes.is_empty() {
+            writeln!(f, "Balance Changes:")?;
+            for change in &self.balance_changes {
+                writeln!(f, "{change}")?;
+            }
+        }
+        if !self.object_changes.is_empty() {
+            writeln!(f, "Object Changes:")?;
+            for change in &self.object_changes {
+                writeln!(f, "{change}")?;
+            }
+        }
+        write!(
+            f,
+            "Gas: budget {} MIST at price {}, estimated {} MIST (computation {}, storage {}, rebate {})",
+            self.gas.budget,
+            self.gas.price,
+            self.gas.estimate,
+            self.gas.computation_cost,
+            self.gas.storage_cost,
+            self.gas.storage_rebate
+        )?;
+        if self.gas.budget_too_low() {
+            write!(f, "\nWarning: the gas budget is lower than the estimate")?;
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::sui_client_config::SuiClientConfig;
+use crate::transaction_preview::TransactionPreview;
 use crate::SuiClient;
 use anyhow::anyhow;
 use colored::Colorize;
@@ -26,6 +27,7 @@ pub struct WalletContext {
     request_timeout: Option<std::time::Duration>,
     client: Arc<RwLock<Option<SuiClient>>>,
     max_concurrent_requests: Option<u64>,
+    preview_transactions: bool,
 }
 
 impl WalletContext {
@@ -47,10 +49,34 @@ impl WalletContext {
             request_timeout,
             client: Default::default(),
             max_concurrent_requests,
+            preview_transactions: false,
         };
         Ok(context)
     }
 
+    /// Show a `TransactionPreview` of each transaction before it is signed by the CLI.
+    pub fn with_transaction_preview(mut self, preview_transactions: bool) -> Self {
+        self.preview_transactions = preview_transactions;
+        self
+    }
+
+    pub fn preview_transactions(&self) -> bool {
+        self.preview_transactions
+    }
+
+    /// The gas budget to use for a transaction: `gas_budget` if it is set, or the default gas
+    /// budget of the active environment.
+    pub fn gas_budget(&self, gas_budget: Option<u64>) -> Result<u64, anyhow::Error> {
+        gas_budget
+            .or_else(|| self.config.default_gas_budget())
+            .ok_or_else(|| {
+                anyhow!(
+                    "No gas budget specified, and the active environment has no default gas \
+                     budget. Set one with `sui client update-env --gas-budget <GAS_BUDGET>`."
+                )
+            })
+    }
+
     pub fn get_addresses(&self) -> Vec<SuiAddress> {
         self.config.keystore.addresses()
     }
@@ -304,6 +330,15 @@ impl WalletContext {
         response
     }
 
+    /// Dry run `tx_data` and summarize its effects, see [TransactionPreview].
+    pub async fn preview_transaction(
+        &self,
+        tx_data: &TransactionData,
+    ) -> Result<TransactionPreview, anyhow::Error> {
+        let client = self.get_client().await?;
+        Ok(TransactionPreview::new(&client, tx_data).await?)
+    }
+
     /// Execute a transaction and wait for it to be locally executed on the fullnode.
     /// The transaction execution is not guaranteed to succeed and may fail. This is usually only
     /// needed in non-test environment or the caller is explicitly testing some failure behavior.
@@ -1,8 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use std::str::FromStr;
 use tempfile::TempDir;
 
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_keys::encrypted_keystore::KdfParams;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
+use sui_sdk::sui_client_config::{EnvBundle, SuiClientConfig, SuiEnv};
+use sui_types::base_types::SuiAddress;
 use sui_types::crypto::Ed25519SuiSignature;
 use sui_types::crypto::{SignatureScheme, SuiSignatureInner};
 #[test]
@@ -32,3 +36,52 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn env_bundle_test() -> Result<(), anyhow::Error> {
+    let alice = SuiAddress::from_str("0x1")?;
+    let mut config = SuiClientConfig::new(Keystore::from(InMemKeystore::new_insecure_for_tests(0)));
+    config.add_env(SuiEnv::devnet());
+    config.add_env(SuiEnv {
+        alias: "private".to_string(),
+        rpc: "https://rpc.example.com/?api_key=secret".to_string(),
+        default_gas_budget: Some(10_000_000),
+        address_aliases: [("alice".to_string(), alice)].into(),
+        ..Default::default()
+    });
+    config.active_env = Some("private".to_string());
+    assert_eq!(config.default_gas_budget(), Some(10_000_000));
+
+    let bundle = config.export_envs(&["private".to_string()])?;
+    assert_eq!(bundle.envs.len(), 1);
+    assert!(config.export_envs(&["unknown".to_string()]).is_err());
+
+    // Cheap scrypt parameters to keep the test fast.
+    let kdf_params = KdfParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+    let encrypted = bundle.to_bytes(Some(("passphrase", kdf_params)))?;
+    assert!(EnvBundle::is_encrypted(&encrypted));
+    assert!(!String::from_utf8_lossy(&encrypted).contains("secret"));
+    assert!(EnvBundle::from_bytes(&encrypted, || Ok("wrong passphrase".to_string())).is_err());
+    let bundle = EnvBundle::from_bytes(&encrypted, || Ok("passphrase".to_string()))?;
+    assert_eq!(bundle.envs[0].address_aliases["alice"], alice);
+
+    let mut other = SuiClientConfig::new(Keystore::from(InMemKeystore::new_insecure_for_tests(0)));
+    other.add_env(SuiEnv {
+        alias: "private".to_string(),
+        rpc: "http://127.0.0.1:9000".to_string(),
+        ..Default::default()
+    });
+    let import = other.import_envs(config.export_envs(&[])?, false);
+    assert_eq!(import.added, vec!["devnet"]);
+    assert_eq!(import.skipped, vec!["private"]);
+    assert_eq!(other.envs[0].rpc, "http://127.0.0.1:9000");
+
+    let import = other.import_envs(bundle, true);
+    assert_eq!(import.replaced, vec!["private"]);
+    assert_eq!(other.envs[0].default_gas_budget, Some(10_000_000));
+    Ok(())
+}
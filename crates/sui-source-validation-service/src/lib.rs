@@ -1,20 +1,23 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use axum::body::Bytes;
 use axum::middleware::{self, Next};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fmt;
-use std::net::TcpListener;
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr, TcpListener};
 use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use std::{ffi::OsString, fs, path::Path, process::Command};
 use tokio::sync::oneshot::Sender;
 
-use anyhow::{anyhow, bail};
-use axum::extract::{Query, State};
+use anyhow::{anyhow, bail, Context};
+use axum::extract::connect_info::IntoMakeServiceWithConnectInfo;
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Query, State};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{get, IntoMakeService};
+use axum::routing::{get, post};
 use axum::Extension;
 use axum::{Json, Router, Server};
 use hyper::http::{HeaderName, HeaderValue, Method};
@@ -30,16 +33,38 @@ use tower::ServiceBuilder;
 use tracing::{debug, error, info};
 use url::Url;
 
+use move_compiler::editions::Flavor;
 use move_core_types::account_address::AccountAddress;
+use move_package::source_package::manifest_parser::parse_move_manifest_from_file;
+use move_package::source_package::parsed_manifest::{Dependency, DependencyKind, SourceManifest};
 use move_package::BuildConfig as MoveBuildConfig;
 use move_symbol_pool::Symbol;
 use sui_move::build::resolve_lock_file_path;
-use sui_move_build::{BuildConfig, SuiPackageHooks};
+use sui_move_build::{BuildConfig, CompiledPackage, PublishedAtError, SuiPackageHooks};
 use sui_sdk::rpc_types::{SuiTransactionBlockEffects, TransactionFilter};
 use sui_sdk::types::base_types::ObjectID;
 use sui_sdk::SuiClientBuilder;
 use sui_source_validation::{BytecodeSourceVerifier, SourceMode};
 
+use crate::store::{
+    source_urls, BuildFlags, VerificationRecord, VerificationStatus, VerificationStore,
+};
+
+pub mod store;
+
+const GIT_REVISION: &str = {
+    if let Some(revision) = option_env!("GIT_REVISION") {
+        revision
+    } else {
+        git_version::git_version!(
+            args = ["--always", "--dirty", "--exclude", "*"],
+            fallback = "DIRTY"
+        )
+    }
+};
+
+pub const VERSION: &str = const_str::concat!(env!("CARGO_PKG_VERSION"), "-", GIT_REVISION);
+
 pub const HOST_PORT_ENV: &str = "HOST_PORT";
 pub const SUI_SOURCE_VALIDATION_VERSION_HEADER: &str = "x-sui-source-validation-version";
 pub const SUI_SOURCE_VALIDATION_VERSION: &str = "0.1";
@@ -59,6 +84,28 @@ pub const WS_PING_INTERVAL: Duration = Duration::from_millis(20_000);
 pub const METRICS_ROUTE: &str = "/metrics";
 pub const METRICS_HOST_PORT: &str = "0.0.0.0:9184";
 
+/// Maximum size of a package tarball submitted for verification.
+pub const MAX_TARBALL_SIZE: usize = 16 * 1024 * 1024;
+/// Maximum size of the contents of a package tarball, once decompressed.
+pub const MAX_UNPACKED_SIZE: u64 = 64 * 1024 * 1024;
+/// Maximum number of entries (files and directories) in a package tarball.
+pub const MAX_TARBALL_ENTRIES: usize = 1024;
+/// How long building a submitted package may take before its verification fails.
+pub const BUILD_TIMEOUT: Duration = Duration::from_secs(120);
+/// How many verifications a client may request per `VERIFY_RATE_LIMIT_WINDOW`.
+pub const VERIFY_RATE_LIMIT: usize = 10;
+pub const VERIFY_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// The repository of the framework packages, the only dependencies submitted packages may have:
+/// anything else would be fetched or read from the service's filesystem while building them.
+pub const SUI_REPOSITORY_URL: &str = "https://github.com/MystenLabs/sui.git";
+pub const FRAMEWORK_PACKAGES: &[(&str, &str)] = &[
+    ("MoveStdlib", "crates/sui-framework/packages/move-stdlib"),
+    ("Sui", "crates/sui-framework/packages/sui-framework"),
+    ("SuiSystem", "crates/sui-framework/packages/sui-system"),
+    ("DeepBook", "crates/sui-framework/packages/deepbook"),
+];
+
 pub fn host_port() -> String {
     match option_env!("HOST_PORT") {
         Some(v) => v.to_string(),
@@ -107,7 +154,7 @@ pub struct SourceInfo {
     pub source: Option<String>,
 }
 
-#[derive(Eq, PartialEq, Clone, Default, Deserialize, Debug, Ord, PartialOrd)]
+#[derive(Eq, PartialEq, Clone, Default, Serialize, Deserialize, Debug, Ord, PartialOrd)]
 #[serde(rename_all = "lowercase")]
 pub enum Network {
     #[default]
@@ -143,15 +190,19 @@ pub type AddressLookup = BTreeMap<AccountAddress, SourceLookup>;
 /// Top-level lookup that maps network to sources for corresponding on-chain networks.
 pub type NetworkLookup = BTreeMap<Network, AddressLookup>;
 
-pub async fn verify_package(
-    network: &Network,
-    package_path: impl AsRef<Path>,
-) -> anyhow::Result<(Network, AddressLookup)> {
+fn network_url(network: &Network) -> &'static str {
+    match network {
+        Network::Mainnet => MAINNET_URL,
+        Network::Testnet => TESTNET_URL,
+        Network::Devnet => DEVNET_URL,
+        Network::Localnet => LOCALNET_URL,
+    }
+}
+
+fn build_package(package_path: &Path) -> anyhow::Result<CompiledPackage> {
     move_package::package_hooks::register_package_hooks(Box::new(SuiPackageHooks));
-    let mut config = resolve_lock_file_path(
-        MoveBuildConfig::default(),
-        Some(package_path.as_ref().to_path_buf()),
-    )?;
+    let mut config =
+        resolve_lock_file_path(MoveBuildConfig::default(), Some(package_path.to_path_buf()))?;
     config.no_lint = true;
     config.silence_warnings = true;
     let build_config = BuildConfig {
@@ -159,15 +210,17 @@ pub async fn verify_package(
         run_bytecode_verifier: false, /* no need to run verifier if code is on-chain */
         print_diags_to_stderr: false,
     };
-    let compiled_package = build_config.build(package_path.as_ref().to_path_buf())?;
+    Ok(build_config.build(package_path.to_path_buf())?)
+}
 
-    let network_url = match network {
-        Network::Mainnet => MAINNET_URL,
-        Network::Testnet => TESTNET_URL,
-        Network::Devnet => DEVNET_URL,
-        Network::Localnet => LOCALNET_URL,
-    };
-    let client = SuiClientBuilder::default().build(network_url).await?;
+pub async fn verify_package(
+    network: &Network,
+    package_path: impl AsRef<Path>,
+) -> anyhow::Result<(Network, AddressLookup)> {
+    let compiled_package = build_package(package_path.as_ref())?;
+    let client = SuiClientBuilder::default()
+        .build(network_url(network))
+        .await?;
     BytecodeSourceVerifier::new(client.read_api())
         .verify_package(
             &compiled_package,
@@ -199,6 +252,169 @@ pub async fn verify_package(
     Ok((network.clone(), address_map))
 }
 
+/// Unpacks a gzipped tarball of a package's sources into `dest`, and returns the path of the
+/// package: either `dest` itself or, if the package was archived with its directory, its only
+/// subdirectory. Only regular files and directories are unpacked, and tarballs with more than
+/// `MAX_TARBALL_ENTRIES` entries or `MAX_UNPACKED_SIZE` bytes of contents are rejected.
+pub fn unpack_package(tarball: &[u8], dest: &Path) -> anyhow::Result<PathBuf> {
+    // The decompressed stream is bounded too, as tar headers and padding are not file contents.
+    let decoder = flate2::read::GzDecoder::new(tarball).take(2 * MAX_UNPACKED_SIZE);
+    let mut archive = tar::Archive::new(decoder);
+    let mut unpacked_size = 0u64;
+    for (i, entry) in archive
+        .entries()
+        .context("Malformed package tarball")?
+        .enumerate()
+    {
+        if i >= MAX_TARBALL_ENTRIES {
+            bail!("Package tarball has more than {MAX_TARBALL_ENTRIES} entries");
+        }
+        let mut entry = entry.context("Malformed package tarball")?;
+        let entry_type = entry.header().entry_type();
+        // Archives created by `git archive` start with a global header, which is harmless.
+        if entry_type == tar::EntryType::XGlobalHeader {
+            continue;
+        }
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            bail!(
+                "Package tarball contains {}, which is neither a file nor a directory",
+                entry.path()?.display()
+            );
+        }
+        unpacked_size += entry.size();
+        if unpacked_size > MAX_UNPACKED_SIZE {
+            bail!("Package tarball contents exceed {MAX_UNPACKED_SIZE} bytes");
+        }
+        // Entries that would be unpacked outside of `dest` are skipped.
+        entry.unpack_in(dest).context("Malformed package tarball")?;
+    }
+    if dest.join("Move.toml").exists() {
+        return Ok(dest.to_path_buf());
+    }
+    let entries = fs::read_dir(dest)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    match entries.as_slice() {
+        [dir] if dir.join("Move.toml").exists() => Ok(dir.clone()),
+        _ => bail!("No Move.toml found at the root of the tarball or of its top-level directory"),
+    }
+}
+
+/// Checks that the only dependencies of a submitted package are framework packages from the Sui
+/// repository.
+pub fn check_dependencies(manifest: &SourceManifest) -> anyhow::Result<()> {
+    let is_sui_repository = |url: &str| {
+        let url = url.trim_end_matches('/');
+        let url = url.strip_suffix(".git").unwrap_or(url);
+        url.eq_ignore_ascii_case(SUI_REPOSITORY_URL.trim_end_matches(".git"))
+    };
+    for (name, dep) in manifest
+        .dependencies
+        .iter()
+        .chain(manifest.dev_dependencies.iter())
+    {
+        let Some((_, subdir)) = FRAMEWORK_PACKAGES.iter().find(|(n, _)| *n == name.as_str()) else {
+            bail!("Dependency {name} is not a framework package");
+        };
+        match dep {
+            Dependency::Internal(dep) => match &dep.kind {
+                DependencyKind::Git(git)
+                    if is_sui_repository(git.git_url.as_str())
+                        && git.subdir == Path::new(subdir) => {}
+                _ => {
+                    bail!("Dependency {name} must be fetched from {subdir} in {SUI_REPOSITORY_URL}")
+                }
+            },
+            Dependency::External(_) => {
+                bail!("Dependency {name} must be fetched from {subdir} in {SUI_REPOSITORY_URL}")
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Verifies the package at `package_path` against the bytecode published at `address` on
+/// `network`. Errors are only returned for malformed packages: a package that fails to build or
+/// does not match the on-chain bytecode results in a failed `VerificationRecord`. The sources of
+/// the package's modules are returned for verified packages. Packages may only depend on framework
+/// packages, see `check_dependencies`.
+pub async fn verify_submission(
+    network: &Network,
+    address: AccountAddress,
+    package_path: &Path,
+) -> anyhow::Result<(VerificationRecord, SourceLookup)> {
+    let manifest = parse_move_manifest_from_file(package_path)?;
+    check_dependencies(&manifest)?;
+    let mut record = VerificationRecord {
+        network: network.clone(),
+        address: address.to_hex_literal(),
+        package_name: manifest.package.name.to_string(),
+        status: VerificationStatus::Failed,
+        error: None,
+        modules: vec![],
+        compiler_version: VERSION.to_string(),
+        build_flags: BuildFlags {
+            edition: manifest.package.edition.unwrap_or_default().to_string(),
+            flavor: manifest.package.flavor.unwrap_or(Flavor::Sui).to_string(),
+            dev_mode: false,
+            test_mode: false,
+        },
+        timestamp_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64),
+    };
+
+    match verify_package_at(network, address, package_path).await {
+        Ok(sources) => {
+            record.status = VerificationStatus::Verified;
+            record.modules = sources.keys().map(|m| m.to_string()).collect();
+            Ok((record, sources))
+        }
+        Err(e) => {
+            info!("verification of {address} on {network} failed: {e:#}");
+            record.error = Some(format!("{e:#}"));
+            Ok((record, SourceLookup::new()))
+        }
+    }
+}
+
+async fn verify_package_at(
+    network: &Network,
+    address: AccountAddress,
+    package_path: &Path,
+) -> anyhow::Result<SourceLookup> {
+    // Building is blocking and, for submitted packages, of unbounded duration.
+    let path = package_path.to_path_buf();
+    let build = tokio::task::spawn_blocking(move || build_package(&path));
+    let compiled_package = match tokio::time::timeout(BUILD_TIMEOUT, build).await {
+        Ok(result) => result??,
+        Err(_) => bail!("Building the package took longer than {BUILD_TIMEOUT:?}"),
+    };
+    // Packages whose manifest records where they are published are verified there, others are
+    // verified as if their address was substituted with the submitted one.
+    let source_mode = match &compiled_package.published_at {
+        Ok(id) if **id == address => SourceMode::Verify,
+        Ok(id) => bail!("Package is published at {id} according to its manifest, not {address}"),
+        Err(PublishedAtError::NotPresent) => SourceMode::VerifyAt(address),
+        Err(PublishedAtError::Invalid(id)) => bail!("Invalid published-at address '{id}'"),
+    };
+
+    let client = SuiClientBuilder::default()
+        .build(network_url(network))
+        .await?;
+    BytecodeSourceVerifier::new(client.read_api())
+        .verify_package(&compiled_package, /* verify_deps */ false, source_mode)
+        .await?;
+
+    let mut sources = SourceLookup::new();
+    for v in &compiled_package.package.root_compiled_units {
+        let path = v.source_path.to_path_buf();
+        let source = Some(fs::read_to_string(path.as_path())?);
+        sources.insert(v.unit.name, SourceInfo { path, source });
+    }
+    Ok(sources)
+}
+
 pub fn parse_config(config_path: impl AsRef<Path>) -> anyhow::Result<Config> {
     let contents = fs::read_to_string(config_path)?;
     Ok(toml::from_str(&contents)?)
@@ -456,26 +672,79 @@ pub async fn watch_for_upgrades(
 pub struct AppState {
     pub sources: NetworkLookup,
     pub metrics: Option<SourceServiceMetrics>,
+    /// Where the results of submitted verifications are persisted, if anywhere.
+    pub store: Option<VerificationStore>,
+}
+
+/// Limits how many requests each client may make within a sliding window.
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    requests: Mutex<HashMap<IpAddr, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records a request from `ip`, unless it has already made `limit` requests within the
+    /// window, in which case returns false.
+    pub fn check(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut requests = self.requests.lock().unwrap();
+        // Forget requests that left the window, and clients without any left.
+        requests.retain(|_, times| {
+            while times
+                .front()
+                .is_some_and(|t| now.duration_since(*t) >= self.window)
+            {
+                times.pop_front();
+            }
+            !times.is_empty()
+        });
+        let times = requests.entry(ip).or_default();
+        if times.len() >= self.limit {
+            return false;
+        }
+        times.push_back(now);
+        true
+    }
 }
 
 pub fn serve(
     app_state: Arc<RwLock<AppState>>,
-) -> anyhow::Result<Server<AddrIncoming, IntoMakeService<Router>>> {
+) -> anyhow::Result<Server<AddrIncoming, IntoMakeServiceWithConnectInfo<Router, SocketAddr>>> {
+    let verify_limiter = Arc::new(RateLimiter::new(
+        VERIFY_RATE_LIMIT,
+        VERIFY_RATE_LIMIT_WINDOW,
+    ));
     let app = Router::new()
         .route("/api", get(api_route))
         .route("/api/list", get(list_route))
+        .route("/api/package", get(package_route))
+        .route(
+            "/api/verify",
+            post(verify_route)
+                .layer(DefaultBodyLimit::max(MAX_TARBALL_SIZE))
+                .layer(Extension(verify_limiter)),
+        )
         .layer(
             ServiceBuilder::new()
                 .layer(
                     tower_http::cors::CorsLayer::new()
-                        .allow_methods([Method::GET])
+                        .allow_methods([Method::GET, Method::POST])
                         .allow_origin(tower_http::cors::Any),
                 )
                 .layer(middleware::from_fn(check_version_header)),
         )
         .with_state(app_state);
     let listener = TcpListener::bind(host_port())?;
-    Ok(Server::from_tcp(listener)?.serve(app.into_make_service()))
+    Ok(Server::from_tcp(listener)?.serve(app.into_make_service_with_connect_info::<SocketAddr>()))
 }
 
 #[derive(Deserialize)]
@@ -496,6 +765,21 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
+#[derive(Deserialize)]
+pub struct PackageRequest {
+    #[serde(default)]
+    network: Network,
+    address: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PackageResponse {
+    #[serde(flatten)]
+    pub record: VerificationRecord,
+    /// Where the source of each module is served, for verified packages.
+    pub sources: BTreeMap<String, String>,
+}
+
 async fn api_route(
     State(app_state): State<Arc<RwLock<AppState>>>,
     Query(Request {
@@ -548,6 +832,141 @@ async fn api_route(
     }
 }
 
+/// Verifies a package whose sources are submitted as a gzipped tarball against the bytecode
+/// published at `address`, persists the result, and serves the sources of the package if it is
+/// verified. Each client may request `VERIFY_RATE_LIMIT` verifications per
+/// `VERIFY_RATE_LIMIT_WINDOW`.
+async fn verify_route(
+    State(app_state): State<Arc<RwLock<AppState>>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Extension(limiter): Extension<Arc<RateLimiter>>,
+    Query(PackageRequest { network, address }): Query<PackageRequest>,
+    tarball: Bytes,
+) -> impl IntoResponse {
+    debug!("verify request network={network}&address={address} from {client}");
+    if !limiter.check(client.ip()) {
+        let error = format!(
+            "Too many verification requests, at most {VERIFY_RATE_LIMIT} are allowed every {}s",
+            VERIFY_RATE_LIMIT_WINDOW.as_secs()
+        );
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(ErrorResponse { error }).into_response(),
+        );
+    }
+    let Ok(address) = AccountAddress::from_hex_literal(&address) else {
+        let error = format!("Invalid hex address {address}");
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error }).into_response(),
+        );
+    };
+
+    let submission = async {
+        let tmp_dir = tempfile::tempdir()?;
+        let package_path = unpack_package(&tarball, tmp_dir.path())?;
+        verify_submission(&network, address, &package_path).await
+    };
+    let (record, sources) = match submission.await {
+        Ok(result) => result,
+        Err(e) => {
+            let error = format!("Invalid package submission: {e:#}");
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse { error }).into_response(),
+            );
+        }
+    };
+
+    let mut app_state = app_state.write().unwrap();
+    if let Some(metrics) = &app_state.metrics {
+        metrics.total_requests_received.inc();
+    }
+    if let Some(store) = &app_state.store {
+        if let Err(e) = store.save(&record, &sources) {
+            error!("Failed to persist verification of {address} on {network}: {e:#}");
+            let error = "Failed to persist verification result".to_string();
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error }).into_response(),
+            );
+        }
+    }
+    if record.status == VerificationStatus::Verified {
+        // Sources are served from the store, as the submitted package is deleted.
+        let sources = sources
+            .into_iter()
+            .map(|(module, SourceInfo { path, source })| {
+                let path = app_state.store.as_ref().map_or(path, |store| {
+                    store.source_path(&network, address, module.as_str())
+                });
+                (module, SourceInfo { path, source })
+            })
+            .collect();
+        app_state
+            .sources
+            .entry(network)
+            .or_default()
+            .insert(address, sources);
+    }
+    (StatusCode::OK, Json(record).into_response())
+}
+
+/// Returns the latest verification result for the package at `address`, and where the sources of
+/// its modules are served if it is verified.
+async fn package_route(
+    State(app_state): State<Arc<RwLock<AppState>>>,
+    Query(PackageRequest { network, address }): Query<PackageRequest>,
+) -> impl IntoResponse {
+    debug!("package request network={network}&address={address}");
+    let Ok(address) = AccountAddress::from_hex_literal(&address) else {
+        let error = format!("Invalid hex address {address}");
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error }).into_response(),
+        );
+    };
+
+    let app_state = app_state.read().unwrap();
+    if let Some(metrics) = &app_state.metrics {
+        metrics.total_requests_received.inc();
+    }
+    let record = app_state
+        .store
+        .as_ref()
+        .map(|store| store.get(&network, address))
+        .transpose();
+    match record {
+        Ok(Some(Some(record))) => {
+            let sources = match record.status {
+                VerificationStatus::Verified => source_urls(&record),
+                VerificationStatus::Failed => BTreeMap::new(),
+            };
+            (
+                StatusCode::OK,
+                Json(PackageResponse { record, sources }).into_response(),
+            )
+        }
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: format!("No verification result found for {address} on network {network}"),
+            })
+            .into_response(),
+        ),
+        Err(e) => {
+            error!("Failed to read verification of {address} on {network}: {e:#}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Failed to read verification result".to_string(),
+                })
+                .into_response(),
+            )
+        }
+    }
+}
+
 async fn check_version_header<B>(
     headers: HeaderMap,
     req: hyper::Request<B>,
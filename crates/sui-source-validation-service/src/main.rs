@@ -10,29 +10,20 @@ use clap::Parser;
 use telemetry_subscribers::TelemetryConfig;
 
 use sui_source_validation_service::{
-    host_port, initialize, parse_config, serve, start_prometheus_server, watch_for_upgrades,
-    AppState, DirectorySource, Network, PackageSource, RepositorySource, SourceServiceMetrics,
-    METRICS_HOST_PORT,
+    host_port, initialize, parse_config, serve, start_prometheus_server, store::VerificationStore,
+    watch_for_upgrades, AppState, DirectorySource, Network, PackageSource, RepositorySource,
+    SourceServiceMetrics, METRICS_HOST_PORT, VERSION,
 };
 
 #[derive(Parser, Debug)]
 struct Args {
     config_path: PathBuf,
+    /// Directory to persist the results of verifications submitted through the API to. Results
+    /// are only kept in memory if unset.
+    #[clap(long)]
+    store_dir: Option<PathBuf>,
 }
 
-const GIT_REVISION: &str = {
-    if let Some(revision) = option_env!("GIT_REVISION") {
-        revision
-    } else {
-        git_version::git_version!(
-            args = ["--always", "--dirty", "--exclude", "*"],
-            fallback = "DIRTY"
-        )
-    }
-};
-
-pub const VERSION: &str = const_str::concat!(env!("CARGO_PKG_VERSION"), "-", GIT_REVISION);
-
 #[tokio::main]
 pub async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
@@ -40,9 +31,24 @@ pub async fn main() -> anyhow::Result<()> {
     let package_config = parse_config(args.config_path)?;
     let tmp_dir = tempfile::tempdir()?;
     let start = tokio::time::Instant::now();
-    let sources = initialize(&package_config, tmp_dir.path()).await?;
+    let mut sources = initialize(&package_config, tmp_dir.path()).await?;
     info!("verification complete in {:?}", start.elapsed());
 
+    let store = match args.store_dir {
+        Some(store_dir) => {
+            let store = VerificationStore::open(store_dir)?;
+            // Sources verified from the config take precedence over submitted ones.
+            for (network, submitted) in store.load_sources()? {
+                let lookup = sources.entry(network).or_default();
+                for (address, source_lookup) in submitted {
+                    lookup.entry(address).or_insert(source_lookup);
+                }
+            }
+            Some(store)
+        }
+        None => None,
+    };
+
     let metrics_listener = std::net::TcpListener::bind(METRICS_HOST_PORT)?;
     let registry_service = start_prometheus_server(metrics_listener);
     let prometheus_registry = registry_service.default_registry();
@@ -51,6 +57,7 @@ pub async fn main() -> anyhow::Result<()> {
     let app_state = Arc::new(RwLock::new(AppState {
         sources,
         metrics: Some(metrics),
+        store,
     }));
     let mut threads = vec![];
     let networks_to_watch = vec![
@@ -91,7 +98,7 @@ pub async fn main() -> anyhow::Result<()> {
     let app_state_copy = app_state.clone();
     let server = tokio::spawn(async { serve(app_state_copy)?.await.map_err(anyhow::Error::from) });
     threads.push(server);
-    info!("serving on {}", host_port());
+    info!("serving version {VERSION} on {}", host_port());
     for t in threads {
         t.await.unwrap()?;
     }
@@ -0,0 +1,192 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use move_core_types::account_address::AccountAddress;
+use move_symbol_pool::Symbol;
+
+use crate::{AddressLookup, Network, NetworkLookup, SourceInfo, SourceLookup};
+
+const RECORD_FILE: &str = "record.json";
+const SOURCES_DIR: &str = "sources";
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationStatus {
+    Verified,
+    Failed,
+}
+
+/// Flags affecting the bytecode a package compiles to, recorded with each result so that it can
+/// be reproduced.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct BuildFlags {
+    pub edition: String,
+    pub flavor: String,
+    pub dev_mode: bool,
+    pub test_mode: bool,
+}
+
+/// The result of verifying a submitted package against the bytecode published at `address`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct VerificationRecord {
+    pub network: Network,
+    /// Address of the package on chain, as a hex literal.
+    pub address: String,
+    pub package_name: String,
+    pub status: VerificationStatus,
+    /// Why verification failed, for failed submissions.
+    pub error: Option<String>,
+    /// Names of the package's modules, whose sources are served for verified packages.
+    pub modules: Vec<String>,
+    /// Version of the service (and so of the compiler) the package was verified with.
+    pub compiler_version: String,
+    pub build_flags: BuildFlags,
+    /// When the package was verified, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+}
+
+/// Persists verification results on disk, under `<root>/<network>/<address>/`: the result is in
+/// `record.json`, and the sources of verified packages in `sources/`.
+pub struct VerificationStore {
+    root: PathBuf,
+}
+
+impl VerificationStore {
+    pub fn open(root: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)
+            .with_context(|| format!("Cannot create store at {}", root.display()))?;
+        Ok(Self { root })
+    }
+
+    fn package_dir(&self, network: &Network, address: AccountAddress) -> PathBuf {
+        self.root
+            .join(network.to_string())
+            .join(address.to_hex_literal())
+    }
+
+    /// Saves the result of a verification and, if it succeeded, the sources of the package's
+    /// modules. A failed verification never replaces a successful one, since anyone can submit
+    /// sources for any address.
+    pub fn save(&self, record: &VerificationRecord, sources: &SourceLookup) -> anyhow::Result<()> {
+        let address = AccountAddress::from_hex_literal(&record.address)?;
+        if record.status == VerificationStatus::Failed {
+            if let Some(existing) = self.get(&record.network, address)? {
+                if existing.status == VerificationStatus::Verified {
+                    return Ok(());
+                }
+            }
+        }
+
+        let dir = self.package_dir(&record.network, address);
+        let sources_dir = dir.join(SOURCES_DIR);
+        if sources_dir.exists() {
+            fs::remove_dir_all(&sources_dir)?;
+        }
+        fs::create_dir_all(&sources_dir)?;
+        if record.status == VerificationStatus::Verified {
+            for (module, info) in sources {
+                if let Some(source) = &info.source {
+                    fs::write(sources_dir.join(format!("{module}.move")), source)?;
+                }
+            }
+        }
+
+        // Write the record last, and atomically, so that a record is never read without its
+        // sources.
+        let tmp = dir.join(format!("{RECORD_FILE}.tmp"));
+        fs::write(&tmp, serde_json::to_vec_pretty(record)?)?;
+        fs::rename(&tmp, dir.join(RECORD_FILE))?;
+        Ok(())
+    }
+
+    /// The latest result saved for the package at `address` on `network`, if any.
+    pub fn get(
+        &self,
+        network: &Network,
+        address: AccountAddress,
+    ) -> anyhow::Result<Option<VerificationRecord>> {
+        let path = self.package_dir(network, address).join(RECORD_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let record = serde_json::from_slice(&fs::read(&path)?)
+            .with_context(|| format!("Corrupt verification record at {}", path.display()))?;
+        Ok(Some(record))
+    }
+
+    /// All results saved in the store.
+    pub fn list(&self) -> anyhow::Result<Vec<VerificationRecord>> {
+        let mut records = vec![];
+        for network_dir in fs::read_dir(&self.root)? {
+            let network_dir = network_dir?.path();
+            if !network_dir.is_dir() {
+                continue;
+            }
+            for package_dir in fs::read_dir(&network_dir)? {
+                let path = package_dir?.path().join(RECORD_FILE);
+                if path.exists() {
+                    records.push(serde_json::from_slice(&fs::read(&path)?).with_context(|| {
+                        format!("Corrupt verification record at {}", path.display())
+                    })?);
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    /// Loads the sources of all verified packages, to be served alongside those verified from the
+    /// service's config.
+    pub fn load_sources(&self) -> anyhow::Result<NetworkLookup> {
+        let mut lookup = NetworkLookup::new();
+        for record in self.list()? {
+            if record.status != VerificationStatus::Verified {
+                continue;
+            }
+            let address = AccountAddress::from_hex_literal(&record.address)?;
+            let sources_dir = self.package_dir(&record.network, address).join(SOURCES_DIR);
+            let mut source_lookup = SourceLookup::new();
+            for module in &record.modules {
+                let path = sources_dir.join(format!("{module}.move"));
+                let source = Some(fs::read_to_string(&path)?);
+                source_lookup.insert(Symbol::from(module.as_str()), SourceInfo { path, source });
+            }
+            lookup
+                .entry(record.network)
+                .or_insert_with(AddressLookup::new)
+                .insert(address, source_lookup);
+        }
+        Ok(lookup)
+    }
+
+    /// Path of the stored source of `module` in the package at `address`.
+    pub fn source_path(&self, network: &Network, address: AccountAddress, module: &str) -> PathBuf {
+        self.package_dir(network, address)
+            .join(SOURCES_DIR)
+            .join(format!("{module}.move"))
+    }
+}
+
+/// Modules of a verified package by name, mapped to where their source is served.
+pub fn source_urls(record: &VerificationRecord) -> BTreeMap<String, String> {
+    record
+        .modules
+        .iter()
+        .map(|module| {
+            (
+                module.clone(),
+                format!(
+                    "/api?network={}&address={}&module={module}",
+                    record.network, record.address
+                ),
+            )
+        })
+        .collect()
+}
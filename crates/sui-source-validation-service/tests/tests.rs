@@ -5,9 +5,11 @@ use expect_test::expect;
 use reqwest::Client;
 use std::fs;
 use std::io::Read;
+use std::net::IpAddr;
 use std::os::unix::fs::FileExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use sui::client_commands::{SuiClientCommandResult, SuiClientCommands};
 use sui_json_rpc_types::{SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI};
 use sui_move_build::{BuildConfig, SuiPackageHooks};
@@ -21,12 +23,18 @@ use sui_sdk::wallet_context::WalletContext;
 use tokio::sync::oneshot;
 
 use move_core_types::account_address::AccountAddress;
+use move_package::source_package::manifest_parser::parse_move_manifest_from_file;
 use move_symbol_pool::Symbol;
+use sui_source_validation_service::store::{
+    BuildFlags, VerificationRecord, VerificationStatus, VerificationStore,
+};
 use sui_source_validation_service::{
-    host_port, initialize, serve, start_prometheus_server, verify_packages, watch_for_upgrades,
-    AddressLookup, AppState, CloneCommand, Config, DirectorySource, ErrorResponse, Network,
-    NetworkLookup, Package, PackageSource, RepositorySource, SourceInfo, SourceLookup,
-    SourceResponse, SourceServiceMetrics, METRICS_HOST_PORT, SUI_SOURCE_VALIDATION_VERSION_HEADER,
+    check_dependencies, host_port, initialize, serve, start_prometheus_server, unpack_package,
+    verify_packages, verify_submission, watch_for_upgrades, AddressLookup, AppState, CloneCommand,
+    Config, DirectorySource, ErrorResponse, Network, NetworkLookup, Package, PackageResponse,
+    PackageSource, RateLimiter, RepositorySource, SourceInfo, SourceLookup, SourceResponse,
+    SourceServiceMetrics, MAX_TARBALL_ENTRIES, MAX_TARBALL_SIZE, MAX_UNPACKED_SIZE,
+    METRICS_HOST_PORT, SUI_SOURCE_VALIDATION_VERSION_HEADER,
 };
 use test_cluster::TestClusterBuilder;
 
@@ -93,6 +101,7 @@ async fn test_end_to_end() -> anyhow::Result<()> {
     let app_state = Arc::new(RwLock::new(AppState {
         sources,
         metrics: None,
+        store: None,
     }));
     let app_state_ref = app_state.clone();
     let (tx, rx) = oneshot::channel();
@@ -122,6 +131,64 @@ async fn test_end_to_end() -> anyhow::Result<()> {
     let app_state_ref = app_state_ref.read().unwrap();
     assert!(app_state_ref.sources.is_empty());
 
+    ///////////////////////////
+    // Test verify_submission
+    //////////////////////////
+    let submission_dir = tempfile::tempdir()?;
+    let tarball = package_tarball(&package_path)?;
+    let submitted_path = unpack_package(&tarball, submission_dir.path())?;
+    assert!(submitted_path.join("Move.toml").exists());
+
+    // The fixture is not published according to its manifest, so it is verified at the submitted
+    // address.
+    let (record, sources) =
+        verify_submission(&Network::Localnet, package_id.into(), &submitted_path).await?;
+    assert_eq!(
+        record.status,
+        VerificationStatus::Verified,
+        "{:?}",
+        record.error
+    );
+    assert_eq!(record.package_name, "custom");
+    assert_eq!(record.modules, vec!["foo".to_string()]);
+    assert_eq!(record.build_flags.flavor, "sui");
+
+    let store_dir = tempfile::tempdir()?;
+    VerificationStore::open(store_dir.path())?.save(&record, &sources)?;
+    // Results outlive the store they were saved with.
+    let store = VerificationStore::open(store_dir.path())?;
+    assert_eq!(
+        store.get(&Network::Localnet, package_id.into())?,
+        Some(record.clone())
+    );
+    let lookup = store.load_sources()?;
+    let served = &lookup[&Network::Localnet][&AccountAddress::from(package_id)];
+    assert!(served[&Symbol::from("foo")]
+        .source
+        .as_ref()
+        .unwrap()
+        .contains("module custom::foo"));
+
+    // The upgrade cap is not a package: verification fails, without replacing the result of
+    // the successful verification.
+    let (failed, _) = verify_submission(
+        &Network::Localnet,
+        cap.reference.object_id.into(),
+        &submitted_path,
+    )
+    .await?;
+    assert_eq!(failed.status, VerificationStatus::Failed);
+    assert!(failed.error.is_some());
+    let failed = VerificationRecord {
+        address: record.address.clone(),
+        ..failed
+    };
+    store.save(&failed, &SourceLookup::new())?;
+    assert_eq!(
+        store.get(&Network::Localnet, package_id.into())?,
+        Some(record)
+    );
+
     ///////////////////////////
     // Test verify_packages
     //////////////////////////
@@ -174,7 +241,7 @@ async fn run_publish(
         package_path: package_path.clone(),
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -204,7 +271,7 @@ async fn run_upgrade(
         upgrade_capability: cap.reference.object_id,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -221,6 +288,17 @@ async fn run_upgrade(
     Ok(())
 }
 
+/// Archive the package at `package_path` in a gzipped tarball, under a top-level directory, as it
+/// is submitted for verification.
+fn package_tarball(package_path: &Path) -> anyhow::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        vec![],
+        flate2::Compression::default(),
+    ));
+    builder.append_dir_all("custom", package_path)?;
+    Ok(builder.into_inner()?.finish()?)
+}
+
 /// Copy the package and set `published-at` in the Move toml file. The need for
 /// this will be subsumed by automated address management.
 fn copy_with_published_at_manifest(
@@ -291,9 +369,40 @@ async fn test_api_route() -> anyhow::Result<()> {
     address_lookup.insert(account_address, source_lookup);
     let mut sources = NetworkLookup::new();
     sources.insert(Network::Localnet, address_lookup);
+
+    // set up a sample verification result to serve
+    let store_dir = tempfile::tempdir()?;
+    let store = VerificationStore::open(store_dir.path())?;
+    let record = VerificationRecord {
+        network: Network::Localnet,
+        address: "0x3".into(),
+        package_name: "custom".into(),
+        status: VerificationStatus::Verified,
+        error: None,
+        modules: vec!["foo".into()],
+        compiler_version: "1.0.0-test".into(),
+        build_flags: BuildFlags {
+            edition: "legacy".into(),
+            flavor: "sui".into(),
+            dev_mode: false,
+            test_mode: false,
+        },
+        timestamp_ms: 0,
+    };
+    let mut source_lookup = SourceLookup::new();
+    source_lookup.insert(
+        Symbol::from("foo"),
+        SourceInfo {
+            path: "foo.move".into(),
+            source: Some("module custom::foo {...}".to_owned()),
+        },
+    );
+    store.save(&record, &source_lookup)?;
+
     let app_state = Arc::new(RwLock::new(AppState {
         sources,
         metrics: None,
+        store: Some(store),
     }));
     tokio::spawn(serve(app_state).expect("Cannot start service."));
 
@@ -331,6 +440,51 @@ async fn test_api_route() -> anyhow::Result<()> {
         expect!["Unsupported version 'bogus' specified in header x-sui-source-validation-version"];
     expected.assert_eq(&json.error);
 
+    // check that the saved verification result is served, with where to find its sources
+    let json = client
+        .get(format!(
+            "http://{}/api/package?address=0x3&network=localnet",
+            host_port()
+        ))
+        .send()
+        .await
+        .expect("Request failed.")
+        .json::<PackageResponse>()
+        .await?;
+    assert_eq!(json.record, record);
+    let expected = expect![[r#"
+        {
+            "foo": "/api?network=localnet&address=0x3&module=foo",
+        }"#]];
+    expected.assert_eq(&format!("{:#?}", json.sources));
+
+    // check that unknown packages are not found
+    let response = client
+        .get(format!(
+            "http://{}/api/package?address=0x4&network=localnet",
+            host_port()
+        ))
+        .send()
+        .await
+        .expect("Request failed.");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // check that malformed submissions are rejected
+    let json = client
+        .post(format!(
+            "http://{}/api/verify?address=0x3&network=localnet",
+            host_port()
+        ))
+        .body("not a tarball")
+        .send()
+        .await
+        .expect("Request failed.")
+        .json::<ErrorResponse>()
+        .await?;
+    assert!(json
+        .error
+        .starts_with("Invalid package submission: Malformed package tarball"));
+
     Ok(())
 }
 
@@ -480,3 +634,116 @@ fn test_clone_command() -> anyhow::Result<()> {
     expect.assert_eq(&format!("{:#?}", command));
     Ok(())
 }
+
+/// Archive `entries`, each a path and its contents, in a gzipped tarball.
+fn tarball_of(entries: impl IntoIterator<Item = (String, Box<dyn Read>, u64)>) -> Vec<u8> {
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        vec![],
+        flate2::Compression::default(),
+    ));
+    for (path, data, size) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(size);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, path, data).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+#[test]
+fn test_unpack_package_limits() -> anyhow::Result<()> {
+    let manifest = "[package]\nname = \"a\"\nversion = \"0.0.1\"\n";
+    let file = |path: &str, contents: &'static str| -> (String, Box<dyn Read>, u64) {
+        (
+            path.into(),
+            Box::new(contents.as_bytes()),
+            contents.len() as u64,
+        )
+    };
+
+    let tarball = tarball_of([file("a/Move.toml", manifest), file("a/sources/a.move", "")]);
+    let dir = tempfile::tempdir()?;
+    assert_eq!(unpack_package(&tarball, dir.path())?, dir.path().join("a"));
+
+    // Contents that decompress past the limit are rejected, however small the tarball.
+    let size = MAX_UNPACKED_SIZE + 1;
+    let tarball = tarball_of([
+        file("Move.toml", manifest),
+        ("big".into(), Box::new(std::io::repeat(0).take(size)), size),
+    ]);
+    assert!(tarball.len() < MAX_TARBALL_SIZE);
+    let dir = tempfile::tempdir()?;
+    let err = unpack_package(&tarball, dir.path()).unwrap_err();
+    assert!(err.to_string().contains("contents exceed"), "{err:#}");
+
+    let tarball = tarball_of(
+        std::iter::once(file("Move.toml", manifest))
+            .chain((0..MAX_TARBALL_ENTRIES).map(|i| file(&format!("sources/{i}.move"), ""))),
+    );
+    let dir = tempfile::tempdir()?;
+    let err = unpack_package(&tarball, dir.path()).unwrap_err();
+    assert!(err.to_string().contains("more than"), "{err:#}");
+
+    // Links could point anywhere on the service's filesystem.
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        vec![],
+        flate2::Compression::default(),
+    ));
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Symlink);
+    header.set_size(0);
+    builder.append_link(&mut header, "Move.toml", "/etc/passwd")?;
+    let tarball = builder.into_inner()?.finish()?;
+    let dir = tempfile::tempdir()?;
+    let err = unpack_package(&tarball, dir.path()).unwrap_err();
+    assert!(err.to_string().contains("neither a file"), "{err:#}");
+    assert!(!dir.path().join("Move.toml").exists());
+    Ok(())
+}
+
+#[test]
+fn test_check_dependencies() -> anyhow::Result<()> {
+    let check = |dependencies: &str| -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Move.toml"),
+            format!(
+                "[package]\nname = \"a\"\nversion = \"0.0.1\"\n\n[dependencies]\n{dependencies}"
+            ),
+        )?;
+        check_dependencies(&parse_move_manifest_from_file(dir.path())?)
+    };
+
+    check("")?;
+    check(
+        r#"Sui = { git = "https://github.com/MystenLabs/sui.git", subdir = "crates/sui-framework/packages/sui-framework", rev = "framework/mainnet" }
+MoveStdlib = { git = "https://github.com/mystenlabs/sui", subdir = "crates/sui-framework/packages/move-stdlib", rev = "framework/mainnet" }
+"#,
+    )?;
+
+    for dependencies in [
+        // Local dependencies are read from the service's filesystem.
+        r#"Sui = { local = "/sui/crates/sui-framework/packages/sui-framework" }"#,
+        r#"Sui = { git = "https://github.com/user/sui.git", subdir = "crates/sui-framework/packages/sui-framework", rev = "main" }"#,
+        r#"Sui = { git = "https://github.com/MystenLabs/sui.git", subdir = "crates/sui-framework/packages/deepbook", rev = "main" }"#,
+        r#"Other = { git = "https://github.com/MystenLabs/sui.git", subdir = "crates/sui-framework/packages/sui-framework", rev = "main" }"#,
+    ] {
+        assert!(check(dependencies).is_err(), "{dependencies} is allowed");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_rate_limiter() {
+    let limiter = RateLimiter::new(2, Duration::from_millis(100));
+    let a = IpAddr::from([10, 0, 0, 1]);
+    let b = IpAddr::from([10, 0, 0, 2]);
+    assert!(limiter.check(a));
+    assert!(limiter.check(a));
+    assert!(!limiter.check(a));
+    // Clients are limited separately.
+    assert!(limiter.check(b));
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(limiter.check(a));
+}
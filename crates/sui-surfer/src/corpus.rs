@@ -0,0 +1,194 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use sui_types::base_types::ObjectID;
+use tracing::{info, warn};
+
+const CORPUS_DIR: &str = "corpus";
+const FAILURES_DIR: &str = "failures";
+
+/// A call to an entry function, recorded so that it can be replayed and mutated.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SurfCall {
+    pub package: ObjectID,
+    pub module: String,
+    pub function: String,
+    /// BCS bytes of each pure argument, by parameter position. Object arguments are `None`, and
+    /// are chosen afresh from the inventory of whichever task executes the call.
+    pub pure_args: Vec<Option<Vec<u8>>>,
+}
+
+/// A sequence of calls that covered new bytecode offsets when it was executed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CorpusEntry {
+    pub calls: Vec<SurfCall>,
+    /// How many offsets the sequence covered for the first time.
+    pub new_offsets: usize,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The VM or the adapter hit an invariant violation.
+    InvariantViolation,
+    /// Move code aborted without an explicit `abort`, e.g. on arithmetic overflow or an
+    /// out-of-bounds vector access.
+    UnexpectedAbort,
+}
+
+impl FailureKind {
+    /// Classify the error of a failed transaction, as formatted in its effects' status, returning
+    /// `None` for failures that are expected while surfing, like explicit aborts and running out
+    /// of gas.
+    pub fn classify(error: &str) -> Option<Self> {
+        if error.starts_with("InvariantViolation") || error.starts_with("VMInvariantViolation") {
+            Some(FailureKind::InvariantViolation)
+        } else if error.starts_with("MovePrimitiveRuntimeError") {
+            Some(FailureKind::UnexpectedAbort)
+        } else {
+            None
+        }
+    }
+}
+
+/// A minimized sequence of calls, the last of which fails in an interesting way.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Failure {
+    pub kind: FailureKind,
+    /// The error that the last call failed with.
+    pub error: String,
+    pub calls: Vec<SurfCall>,
+}
+
+/// Interesting call sequences found while surfing, shared between surfer tasks, and optionally
+/// persisted to a directory so that later runs can pick up where earlier ones left off.
+pub struct Corpus {
+    dir: Option<PathBuf>,
+    entries: Vec<CorpusEntry>,
+    failures: Vec<Failure>,
+    num_saved_entries: usize,
+    num_saved_failures: usize,
+}
+
+impl Corpus {
+    /// A corpus persisted to `dir`, starting with the entries already saved there, or an
+    /// in-memory corpus if `dir` is `None`.
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let mut corpus = Self {
+            dir,
+            entries: vec![],
+            failures: vec![],
+            num_saved_entries: 0,
+            num_saved_failures: 0,
+        };
+        let Some(dir) = corpus.dir.clone() else {
+            return corpus;
+        };
+
+        for subdir in [CORPUS_DIR, FAILURES_DIR] {
+            if let Err(e) = std::fs::create_dir_all(dir.join(subdir)) {
+                warn!("Failed to create corpus directory {:?}: {:?}", dir, e);
+                corpus.dir = None;
+                return corpus;
+            }
+        }
+        corpus.entries = load_all(&dir.join(CORPUS_DIR));
+        corpus.num_saved_entries = corpus.entries.len();
+        corpus.failures = load_all(&dir.join(FAILURES_DIR));
+        corpus.num_saved_failures = corpus.failures.len();
+        info!(
+            "Loaded {} corpus entries and {} failures from {:?}",
+            corpus.entries.len(),
+            corpus.failures.len(),
+            dir
+        );
+        corpus
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+
+    /// Choose an entry to mutate, favouring those that covered the most new offsets.
+    pub fn choose(&self, rng: &mut StdRng) -> Option<&CorpusEntry> {
+        self.entries
+            .choose_weighted(rng, |entry| entry.new_offsets.max(1))
+            .ok()
+    }
+
+    pub fn add(&mut self, entry: CorpusEntry) {
+        if let Some(dir) = &self.dir {
+            let path = dir
+                .join(CORPUS_DIR)
+                .join(format!("{:06}.json", self.num_saved_entries));
+            save(&path, &entry);
+            self.num_saved_entries += 1;
+        }
+        self.entries.push(entry);
+    }
+
+    /// Whether a failure with this error has already been recorded.
+    pub fn has_failure(&self, error: &str) -> bool {
+        self.failures.iter().any(|failure| failure.error == error)
+    }
+
+    pub fn record_failure(&mut self, failure: Failure) {
+        if let Some(dir) = &self.dir {
+            let path = dir
+                .join(FAILURES_DIR)
+                .join(format!("{:06}.json", self.num_saved_failures));
+            save(&path, &failure);
+            info!("Saved {:?} reproducer to {:?}", failure.kind, path);
+            self.num_saved_failures += 1;
+        }
+        self.failures.push(failure);
+    }
+}
+
+fn save<T: Serialize>(path: &Path, value: &T) {
+    let result = serde_json::to_string_pretty(value)
+        .map_err(std::io::Error::from)
+        .and_then(|json| std::fs::write(path, json));
+    if let Err(e) = result {
+        warn!("Failed to save {:?}: {:?}", path, e);
+    }
+}
+
+/// Every JSON file in `dir` that deserializes to a `T`, in file name order.
+fn load_all<T: for<'de> Deserialize<'de>>(dir: &Path) -> Vec<T> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let value = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(serde_json::from_str(&json)?));
+            match value {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    warn!("Skipping unreadable corpus file {:?}: {:?}", path, e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
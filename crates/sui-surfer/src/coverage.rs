@@ -0,0 +1,265 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_coverage::coverage_map::{output_map_to_file, CoverageMap};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use sui_types::base_types::ObjectID;
+use tracing::{debug, warn};
+
+/// Environment variable that the Move VM reads the path of its execution trace from. Tracing is
+/// only compiled into the VM in debug builds, or when its `debugging` feature is enabled.
+pub const MOVE_VM_TRACE_ENV_VAR: &str = "MOVE_VM_TRACE";
+
+/// All coverage is recorded under this execution ID, rather than the process and thread IDs
+/// that the VM writes, so that offsets are considered covered regardless of which validator
+/// executed them.
+const EXEC_ID: &str = "surfer";
+
+/// Tracks which bytecode offsets of the surfed packages have been executed, by tailing the Move
+/// VM's execution trace.
+///
+/// Every validator in the test cluster appends to the same trace, and surfer tasks run
+/// concurrently, so coverage can't be attributed precisely to a single transaction: offsets are
+/// attributed to whichever task refreshes the tracker first after they show up in the trace.
+pub struct CoverageTracker {
+    trace: Option<PathBuf>,
+    /// How far into the trace has been processed.
+    position: u64,
+    /// A trailing line that the VM hadn't finished writing the last time the trace was read.
+    partial_line: String,
+    /// Addresses of the packages whose coverage is tracked.
+    packages: HashSet<AccountAddress>,
+    coverage: CoverageMap,
+    num_covered_offsets: usize,
+}
+
+impl CoverageTracker {
+    /// A tracker for the trace at `trace`, or one that never observes any coverage if `None`.
+    pub fn new(trace: Option<PathBuf>) -> Self {
+        // Coverage from before the tracker was created (e.g. genesis) is not interesting.
+        let position = trace
+            .as_ref()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map_or(0, |metadata| metadata.len());
+        Self {
+            trace,
+            position,
+            partial_line: String::new(),
+            packages: HashSet::new(),
+            coverage: CoverageMap {
+                exec_maps: Default::default(),
+            },
+            num_covered_offsets: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    /// Start tracking coverage of the modules in `package`.
+    pub fn watch_package(&mut self, package: ObjectID) {
+        self.packages.insert(package.into());
+    }
+
+    pub fn num_covered_offsets(&self) -> usize {
+        self.num_covered_offsets
+    }
+
+    /// Process everything appended to the trace since the last refresh, and return how many
+    /// offsets in the tracked packages were covered for the first time.
+    pub fn refresh(&mut self) -> usize {
+        let Some(trace) = &self.trace else {
+            return 0;
+        };
+
+        let mut new_trace = String::new();
+        let read = File::open(trace).and_then(|mut file| {
+            file.seek(SeekFrom::Start(self.position))?;
+            file.read_to_string(&mut new_trace)
+        });
+        match read {
+            Ok(bytes) => self.position += bytes as u64,
+            Err(e) => {
+                warn!("Failed to read Move VM trace {:?}: {:?}", trace, e);
+                return 0;
+            }
+        }
+
+        let mut lines = std::mem::take(&mut self.partial_line);
+        lines.push_str(&new_trace);
+        let complete = match lines.rfind('\n') {
+            Some(end) => {
+                self.partial_line = lines.split_off(end + 1);
+                lines
+            }
+            None => {
+                self.partial_line = lines;
+                return 0;
+            }
+        };
+
+        let newly_covered = complete
+            .lines()
+            .filter(|line| self.record_trace_line(line))
+            .count();
+        self.num_covered_offsets += newly_covered;
+        debug!(
+            "{} offsets covered for the first time, {} in total",
+            newly_covered, self.num_covered_offsets
+        );
+        newly_covered
+    }
+
+    /// Record a line of the trace, formatted as `<exec id>,<address>::<module>::<function>,<pc>,
+    /// <instruction>`, returning whether it covered an offset of a tracked package for the first
+    /// time.
+    fn record_trace_line(&mut self, line: &str) -> bool {
+        let mut fields = line.splitn(4, ',');
+        let (Some(_), Some(context), Some(pc)) = (fields.next(), fields.next(), fields.next())
+        else {
+            return false;
+        };
+        let mut segments = context.split("::");
+        let (Some(address), Some(module), Some(function), None) = (
+            segments.next(),
+            segments.next(),
+            segments.next(),
+            segments.next(),
+        ) else {
+            return false;
+        };
+        let (Ok(address), Ok(module), Ok(function), Ok(pc)) = (
+            AccountAddress::from_hex_literal(address),
+            Identifier::new(module),
+            Identifier::new(function),
+            pc.parse::<u64>(),
+        ) else {
+            return false;
+        };
+        if !self.packages.contains(&address) {
+            return false;
+        }
+
+        let already_covered = self
+            .coverage
+            .exec_maps
+            .get(EXEC_ID)
+            .and_then(|exec| exec.module_maps.get(&(address, module.clone())))
+            .and_then(|module| module.get_function_coverage(&function))
+            .is_some_and(|function| function.contains_key(&pc));
+        self.coverage.insert(EXEC_ID, address, module, function, pc);
+        !already_covered
+    }
+
+    /// Write the coverage map in the format that `move coverage` reads.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        output_map_to_file(path, &self.coverage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const PACKAGE: &str = "0x42";
+
+    fn tracker(trace: &Path) -> CoverageTracker {
+        let mut tracker = CoverageTracker::new(Some(trace.to_owned()));
+        tracker.watch_package(ObjectID::from_hex_literal(PACKAGE).unwrap());
+        tracker
+    }
+
+    fn line(address: &str, function: &str, pc: u64) -> String {
+        format!("1-1,{address}::m::{function},{pc},Ret\n")
+    }
+
+    fn append(trace: &Path, contents: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(trace)
+            .unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_record_trace_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tracker = tracker(&dir.path().join("trace"));
+
+        assert!(tracker.record_trace_line(line(PACKAGE, "f", 0).trim_end()));
+        // Offsets are only covered for the first time once, regardless of who executed them.
+        assert!(!tracker.record_trace_line(line(PACKAGE, "f", 0).trim_end()));
+        assert!(!tracker.record_trace_line("2-7,0x42::m::f,0,Ret"));
+        assert!(tracker.record_trace_line(line(PACKAGE, "f", 1).trim_end()));
+        assert!(tracker.record_trace_line(line(PACKAGE, "g", 0).trim_end()));
+        assert!(tracker.record_trace_line(line("0x0042", "g", 2).trim_end()));
+
+        // Other packages are not tracked.
+        assert!(!tracker.record_trace_line(line("0x2", "f", 0).trim_end()));
+
+        // Lines that aren't instructions are skipped.
+        for line in [
+            "",
+            "1-1,0x42::m::f",
+            "1-1,0x42::m,0,Ret",
+            "1-1,0x42::m::f::g,0,Ret",
+            "1-1,not_an_address::m::f,0,Ret",
+            "1-1,0x42::m::f,pc,Ret",
+            "1-1,0x42::m::not-an-identifier,0,Ret",
+        ] {
+            assert!(!tracker.record_trace_line(line), "{line:?}");
+        }
+    }
+
+    #[test]
+    fn test_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace = dir.path().join("trace");
+
+        // Coverage from before the tracker was created is ignored.
+        append(&trace, &line(PACKAGE, "f", 0));
+        let mut tracker = tracker(&trace);
+        assert!(tracker.is_enabled());
+        assert_eq!(tracker.refresh(), 0);
+
+        append(&trace, &line(PACKAGE, "f", 1));
+        append(&trace, &line("0x2", "f", 1));
+        append(&trace, &line(PACKAGE, "f", 1));
+        append(&trace, &line(PACKAGE, "f", 2));
+        assert_eq!(tracker.refresh(), 2);
+        assert_eq!(tracker.refresh(), 0);
+
+        // A line that is still being written is held back until it is complete.
+        let partial = line(PACKAGE, "f", 0);
+        let (start, end) = partial.split_at(10);
+        append(&trace, &format!("{}{start}", line(PACKAGE, "g", 0)));
+        assert_eq!(tracker.refresh(), 1);
+        append(&trace, end);
+        assert_eq!(tracker.refresh(), 1);
+
+        assert_eq!(tracker.num_covered_offsets(), 4);
+    }
+
+    #[test]
+    fn test_refresh_without_trace() {
+        let mut tracker = CoverageTracker::new(None);
+        assert!(!tracker.is_enabled());
+        assert_eq!(tracker.refresh(), 0);
+
+        // The trace may not have been created yet.
+        let dir = tempfile::tempdir().unwrap();
+        let trace = dir.path().join("trace");
+        let mut tracker = self::tracker(&trace);
+        assert_eq!(tracker.refresh(), 0);
+        append(&trace, &line(PACKAGE, "f", 0));
+        assert_eq!(tracker.refresh(), 1);
+    }
+}
@@ -0,0 +1,638 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use move_binary_format::normalized::Type;
+use rand::{seq::SliceRandom, Rng};
+use sui_json_rpc_types::SuiExecutionStatus;
+use sui_types::transaction::CallArg;
+use tokio::sync::watch;
+use tracing::{debug, info};
+
+use crate::{
+    corpus::{CorpusEntry, Failure, FailureKind, SurfCall},
+    default_surf_strategy::{DefaultSurfStrategy, InputObjectPassKind},
+    surf_strategy::SurfStrategy,
+    surfer_state::{EntryFunction, SurferState},
+};
+
+/// Number of call sequences to execute before returning control to the surfer task.
+const SEQUENCES_PER_ROUND: usize = 4;
+
+/// Maximum number of calls in a sequence.
+const MAX_SEQUENCE_LEN: usize = 8;
+
+/// Probability of mutating a sequence from the corpus, rather than generating a new one.
+const MUTATION_PROBABILITY: f64 = 0.8;
+
+/// Maximum number of mutations applied to a sequence from the corpus in one go.
+const MAX_MUTATIONS: usize = 4;
+
+/// Maximum number of times a failing sequence is replayed while minimizing it.
+const MAX_MINIMIZATION_RUNS: usize = 32;
+
+/// Largest amount added to or subtracted from an integer argument by a single mutation.
+const MAX_ARITHMETIC_DELTA: u8 = 16;
+
+/// A strategy that keeps a corpus of call sequences that covered bytecode offsets for the first
+/// time, and spends most of its time mutating them, on the basis that inputs near ones that found
+/// new code are likely to find more of it.
+///
+/// Sequences whose last call hits an invariant violation or aborts unexpectedly are minimized by
+/// dropping calls and zeroing arguments for as long as the failure still reproduces, and saved
+/// alongside the corpus.
+#[derive(Default)]
+pub struct CoverageGuidedSurfStrategy {}
+
+/// What happened when a sequence of calls was executed.
+struct SequenceOutcome {
+    /// The calls that were executed, which may be a prefix of the sequence, if arguments couldn't
+    /// be found for a call, or a call failed in an interesting way.
+    executed: Vec<SurfCall>,
+    /// How many offsets were covered for the first time while executing the sequence.
+    new_offsets: usize,
+    /// How the last executed call failed, if it failed in an interesting way.
+    failure: Option<(FailureKind, String)>,
+}
+
+#[async_trait]
+impl SurfStrategy for CoverageGuidedSurfStrategy {
+    async fn surf_for_a_while(
+        &mut self,
+        state: &mut SurferState,
+        entry_functions: Vec<EntryFunction>,
+        exit: &watch::Receiver<()>,
+    ) {
+        if entry_functions.is_empty() {
+            return;
+        }
+
+        for _ in 0..SEQUENCES_PER_ROUND {
+            let sequence = Self::next_sequence(state, &entry_functions).await;
+            let outcome = Self::execute_sequence(state, &entry_functions, &sequence).await;
+
+            if outcome.new_offsets > 0 {
+                debug!(
+                    "Sequence of {} calls covered {} new offsets",
+                    outcome.executed.len(),
+                    outcome.new_offsets
+                );
+                state.corpus.lock().await.add(CorpusEntry {
+                    calls: outcome.executed.clone(),
+                    new_offsets: outcome.new_offsets,
+                });
+            }
+
+            if let Some((kind, error)) = outcome.failure {
+                state.stats.num_interesting_failures += 1;
+                if !state.corpus.lock().await.has_failure(&error) {
+                    info!("Found {:?}: {}. Minimizing..", kind, error);
+                    let calls =
+                        Self::minimize(state, &entry_functions, outcome.executed, &error).await;
+                    state
+                        .corpus
+                        .lock()
+                        .await
+                        .record_failure(Failure { kind, error, calls });
+                }
+            }
+
+            if exit.has_changed().unwrap() {
+                return;
+            }
+        }
+    }
+
+    fn uses_coverage() -> bool {
+        true
+    }
+}
+
+impl CoverageGuidedSurfStrategy {
+    /// Either a mutation of a sequence from the corpus, or a new random sequence.
+    async fn next_sequence(
+        state: &mut SurferState,
+        entry_functions: &[EntryFunction],
+    ) -> Vec<SurfCall> {
+        let base = if state.rng.gen_bool(MUTATION_PROBABILITY) {
+            let corpus = state.corpus.lock().await;
+            corpus
+                .choose(&mut state.rng)
+                .map(|entry| entry.calls.clone())
+        } else {
+            None
+        };
+
+        match base {
+            Some(mut calls) => {
+                for _ in 0..state.rng.gen_range(1..=MAX_MUTATIONS) {
+                    Self::mutate_sequence(state, entry_functions, &mut calls).await;
+                }
+                calls
+            }
+            None => {
+                let len = state.rng.gen_range(1..=MAX_SEQUENCE_LEN);
+                (0..len)
+                    .filter_map(|_| Self::random_call(state, entry_functions))
+                    .collect()
+            }
+        }
+    }
+
+    async fn mutate_sequence(
+        state: &mut SurferState,
+        entry_functions: &[EntryFunction],
+        calls: &mut Vec<SurfCall>,
+    ) {
+        match state.rng.gen_range(0..4) {
+            // Mutate a pure argument of one of the calls.
+            0 => {
+                let mutable: Vec<_> = calls
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(c, call)| {
+                        call.pure_args
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, arg)| arg.is_some())
+                            .map(move |(a, _)| (c, a))
+                    })
+                    .collect();
+                let Some(&(c, a)) = mutable.choose(&mut state.rng) else {
+                    return;
+                };
+                let Some(ty) = resolve(entry_functions, &calls[c])
+                    .and_then(|entry| entry.parameters.get(a))
+                    .cloned()
+                else {
+                    return;
+                };
+                if let Some(bytes) = calls[c].pure_args[a].as_mut() {
+                    mutate_pure(state, &ty, bytes);
+                }
+            }
+            // Insert a new call.
+            1 => {
+                if calls.len() >= MAX_SEQUENCE_LEN {
+                    return;
+                }
+                if let Some(call) = Self::random_call(state, entry_functions) {
+                    let position = state.rng.gen_range(0..=calls.len());
+                    calls.insert(position, call);
+                }
+            }
+            // Remove a call.
+            2 => {
+                if calls.len() > 1 {
+                    let position = state.rng.gen_range(0..calls.len());
+                    calls.remove(position);
+                }
+            }
+            // Splice on calls from another sequence in the corpus.
+            _ => {
+                let corpus = state.corpus.lock().await;
+                if let Some(other) = corpus.choose(&mut state.rng) {
+                    let room = MAX_SEQUENCE_LEN.saturating_sub(calls.len());
+                    calls.extend(other.calls.iter().take(room).cloned());
+                }
+            }
+        }
+    }
+
+    /// A call to a random entry function, with random pure arguments, or `None` if the function
+    /// has parameters of a type that the surfer can't produce.
+    fn random_call(state: &mut SurferState, entry_functions: &[EntryFunction]) -> Option<SurfCall> {
+        let entry = entry_functions.choose(&mut state.rng)?;
+        let mut pure_args = vec![];
+        for param in &entry.parameters {
+            let arg = match param {
+                Type::Struct { .. } | Type::Reference(_) | Type::MutableReference(_) => None,
+                ty => Some(random_pure(state, ty)?),
+            };
+            pure_args.push(arg);
+        }
+
+        Some(SurfCall {
+            package: entry.package,
+            module: entry.module.clone(),
+            function: entry.function.clone(),
+            pure_args,
+        })
+    }
+
+    /// Execute `calls` in order, stopping early if a call can't be made, or fails in an
+    /// interesting way.
+    async fn execute_sequence(
+        state: &mut SurferState,
+        entry_functions: &[EntryFunction],
+        calls: &[SurfCall],
+    ) -> SequenceOutcome {
+        let mut outcome = SequenceOutcome {
+            executed: vec![],
+            new_offsets: 0,
+            failure: None,
+        };
+
+        for call in calls {
+            let Some(entry) = resolve(entry_functions, call) else {
+                debug!(
+                    "Move function {:?}::{:?} no longer exists",
+                    call.module, call.function
+                );
+                break;
+            };
+            let Some(args) = Self::call_args(state, entry, &call.pure_args).await else {
+                debug!(
+                    "Failed to choose arguments for Move function {:?}::{:?}",
+                    entry.module, entry.function
+                );
+                break;
+            };
+
+            let status = state
+                .execute_move_transaction(
+                    entry.package,
+                    entry.module.clone(),
+                    entry.function.clone(),
+                    args,
+                )
+                .await;
+            outcome.executed.push(SurfCall {
+                package: entry.package,
+                ..call.clone()
+            });
+            outcome.new_offsets += state.coverage.lock().await.refresh();
+
+            if let SuiExecutionStatus::Failure { error } = status {
+                if let Some(kind) = FailureKind::classify(&error) {
+                    outcome.failure = Some((kind, error));
+                    break;
+                }
+            }
+        }
+
+        outcome
+    }
+
+    /// Arguments for a call to `entry`, using `pure_args` for its pure parameters, and objects
+    /// from the inventory for the rest.
+    async fn call_args(
+        state: &mut SurferState,
+        entry: &EntryFunction,
+        pure_args: &[Option<Vec<u8>>],
+    ) -> Option<Vec<CallArg>> {
+        if entry.parameters.len() != pure_args.len() {
+            return None;
+        }
+
+        let mut args = vec![];
+        let mut chosen_owned_objects = vec![];
+        for (param, pure) in entry.parameters.iter().zip(pure_args) {
+            let arg = match (pure, param.clone()) {
+                (Some(bytes), _) => Some(CallArg::Pure(bytes.clone())),
+                (None, ty @ Type::Struct { .. }) => {
+                    DefaultSurfStrategy::choose_object_call_arg(
+                        state,
+                        InputObjectPassKind::Value,
+                        ty,
+                        &mut chosen_owned_objects,
+                    )
+                    .await
+                }
+                (None, Type::Reference(ty)) => {
+                    DefaultSurfStrategy::choose_object_call_arg(
+                        state,
+                        InputObjectPassKind::ByRef,
+                        *ty,
+                        &mut chosen_owned_objects,
+                    )
+                    .await
+                }
+                (None, Type::MutableReference(ty)) => {
+                    DefaultSurfStrategy::choose_object_call_arg(
+                        state,
+                        InputObjectPassKind::MutRef,
+                        *ty,
+                        &mut chosen_owned_objects,
+                    )
+                    .await
+                }
+                (None, _) => None,
+            };
+
+            match arg {
+                Some(arg) => args.push(arg),
+                None => {
+                    for (struct_tag, obj_ref) in chosen_owned_objects {
+                        state
+                            .owned_objects
+                            .get_mut(&struct_tag)
+                            .unwrap()
+                            .insert(obj_ref);
+                    }
+                    return None;
+                }
+            }
+        }
+
+        Some(args)
+    }
+
+    /// Shrink `calls`, whose last call failed with `error`, by dropping earlier calls and then
+    /// zeroing pure arguments, keeping each change only if replaying the sequence still fails the
+    /// same way.
+    async fn minimize(
+        state: &mut SurferState,
+        entry_functions: &[EntryFunction],
+        mut calls: Vec<SurfCall>,
+        error: &str,
+    ) -> Vec<SurfCall> {
+        let mut runs = 0;
+
+        let mut i = 0;
+        while i + 1 < calls.len() && runs < MAX_MINIMIZATION_RUNS {
+            let mut candidate = calls.clone();
+            candidate.remove(i);
+            runs += 1;
+            if Self::reproduces(state, entry_functions, &candidate, error).await {
+                calls = candidate;
+            } else {
+                i += 1;
+            }
+        }
+
+        'calls: for c in 0..calls.len() {
+            for a in 0..calls[c].pure_args.len() {
+                if runs >= MAX_MINIMIZATION_RUNS {
+                    break 'calls;
+                }
+                let Some(bytes) = &calls[c].pure_args[a] else {
+                    continue;
+                };
+                if bytes.iter().all(|b| *b == 0) {
+                    continue;
+                }
+
+                let mut candidate = calls.clone();
+                candidate[c].pure_args[a] = Some(vec![0; bytes.len()]);
+                runs += 1;
+                if Self::reproduces(state, entry_functions, &candidate, error).await {
+                    calls = candidate;
+                }
+            }
+        }
+
+        info!(
+            "Minimized failing sequence to {} calls in {} runs",
+            calls.len(),
+            runs
+        );
+        calls
+    }
+
+    /// Whether executing all of `calls` ends with the last one failing with `error`.
+    async fn reproduces(
+        state: &mut SurferState,
+        entry_functions: &[EntryFunction],
+        calls: &[SurfCall],
+        error: &str,
+    ) -> bool {
+        let outcome = Self::execute_sequence(state, entry_functions, calls).await;
+        outcome.executed.len() == calls.len()
+            && outcome.failure.is_some_and(|(_, failure)| failure == error)
+    }
+}
+
+/// The entry function that `call` calls. Calls loaded from a corpus saved by an earlier run may
+/// refer to a package ID that doesn't exist in this run, in which case they are matched to a
+/// function with the same module and name in any package.
+fn resolve<'a>(entry_functions: &'a [EntryFunction], call: &SurfCall) -> Option<&'a EntryFunction> {
+    let same_function =
+        |entry: &&EntryFunction| entry.module == call.module && entry.function == call.function;
+    entry_functions
+        .iter()
+        .filter(same_function)
+        .find(|entry| entry.package == call.package)
+        .or_else(|| entry_functions.iter().find(same_function))
+}
+
+/// Width, in bytes, of the BCS encoding of an unsigned integer type.
+fn int_width(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::U8 => Some(1),
+        Type::U16 => Some(2),
+        Type::U32 => Some(4),
+        Type::U64 => Some(8),
+        Type::U128 => Some(16),
+        Type::U256 => Some(32),
+        _ => None,
+    }
+}
+
+/// BCS bytes of a random value of `ty`, or `None` if it isn't a type of pure argument that the
+/// surfer can produce.
+fn random_pure(state: &mut SurferState, ty: &Type) -> Option<Vec<u8>> {
+    match ty {
+        Type::Bool => Some(bcs::to_bytes(&state.rng.gen::<bool>()).unwrap()),
+        Type::Address => Some(
+            bcs::to_bytes(
+                state
+                    .cluster
+                    .get_addresses()
+                    .choose(&mut state.rng)
+                    .unwrap(),
+            )
+            .unwrap(),
+        ),
+        ty => {
+            let width = int_width(ty)?;
+            let mut bytes = vec![0; width];
+            // Boundary values are far more likely to reach interesting code than uniformly random
+            // ones, so bias towards them.
+            if state.rng.gen_bool(0.25) {
+                set_interesting_int(&mut state.rng, &mut bytes);
+            } else {
+                state.rng.fill(&mut bytes[..]);
+            }
+            Some(bytes)
+        }
+    }
+}
+
+/// Mutate the BCS bytes of a value of `ty` in place.
+fn mutate_pure(state: &mut SurferState, ty: &Type, bytes: &mut Vec<u8>) {
+    match ty {
+        Type::Bool => {
+            if let Some(b) = bytes.first_mut() {
+                *b = (*b == 0) as u8;
+            }
+        }
+        Type::Address => {
+            if let Some(address) = random_pure(state, ty) {
+                *bytes = address;
+            }
+        }
+        _ => mutate_int(&mut state.rng, bytes),
+    }
+}
+
+/// Mutate a little-endian unsigned integer in place.
+fn mutate_int(rng: &mut impl Rng, bytes: &mut [u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    match rng.gen_range(0..4) {
+        0 => set_interesting_int(rng, bytes),
+        1 => {
+            let delta = rng.gen_range(1..=MAX_ARITHMETIC_DELTA);
+            if rng.gen() {
+                wrapping_add_le(bytes, delta)
+            } else {
+                wrapping_sub_le(bytes, delta)
+            }
+        }
+        2 => {
+            let bit = rng.gen_range(0..bytes.len() * 8);
+            bytes[bit / 8] ^= 1 << (bit % 8);
+        }
+        _ => rng.fill(bytes),
+    }
+}
+
+/// Set a little-endian unsigned integer to one of zero, one, the maximum, one less than the
+/// maximum, or the midpoint of its range.
+fn set_interesting_int(rng: &mut impl Rng, bytes: &mut [u8]) {
+    bytes.fill(0);
+    match rng.gen_range(0..5) {
+        0 => (),
+        1 => bytes[0] = 1,
+        2 => bytes.fill(u8::MAX),
+        3 => {
+            bytes.fill(u8::MAX);
+            bytes[0] = u8::MAX - 1;
+        }
+        _ => *bytes.last_mut().unwrap() = 0x80,
+    }
+}
+
+fn wrapping_add_le(bytes: &mut [u8], delta: u8) {
+    let mut carry = delta;
+    for b in bytes {
+        let (sum, overflow) = b.overflowing_add(carry);
+        *b = sum;
+        if !overflow {
+            return;
+        }
+        carry = 1;
+    }
+}
+
+fn wrapping_sub_le(bytes: &mut [u8], delta: u8) {
+    let mut borrow = delta;
+    for b in bytes {
+        let (difference, overflow) = b.overflowing_sub(borrow);
+        *b = difference;
+        if !overflow {
+            return;
+        }
+        borrow = 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_int_width() {
+        for (ty, width) in [
+            (Type::U8, 1),
+            (Type::U16, 2),
+            (Type::U32, 4),
+            (Type::U64, 8),
+            (Type::U128, 16),
+            (Type::U256, 32),
+        ] {
+            assert_eq!(int_width(&ty), Some(width));
+        }
+        assert_eq!(int_width(&Type::Bool), None);
+        assert_eq!(int_width(&Type::Address), None);
+    }
+
+    #[test]
+    fn test_wrapping_arithmetic_le() {
+        for (value, delta) in [
+            (0u64, 1u8),
+            (0xff, 1),
+            (0x00ff_ffff, 16),
+            (u64::MAX, 1),
+            (u64::MAX - 3, 16),
+            (0x1234_5678_9abc_def0, 7),
+        ] {
+            let mut bytes = value.to_le_bytes();
+            wrapping_add_le(&mut bytes, delta);
+            assert_eq!(u64::from_le_bytes(bytes), value.wrapping_add(delta as u64));
+
+            let mut bytes = value.to_le_bytes();
+            wrapping_sub_le(&mut bytes, delta);
+            assert_eq!(u64::from_le_bytes(bytes), value.wrapping_sub(delta as u64));
+        }
+
+        let mut byte = [u8::MAX];
+        wrapping_add_le(&mut byte, 2);
+        assert_eq!(byte, [1]);
+        wrapping_sub_le(&mut byte, 2);
+        assert_eq!(byte, [u8::MAX]);
+    }
+
+    #[test]
+    fn test_set_interesting_int() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let interesting = [0, 1, u32::MAX, u32::MAX - 1, 1 << 31];
+        let mut seen = HashSet::new();
+        for _ in 0..200 {
+            let mut bytes = rng.gen::<u32>().to_le_bytes();
+            set_interesting_int(&mut rng, &mut bytes);
+            let value = u32::from_le_bytes(bytes);
+            assert!(interesting.contains(&value), "{value} is not interesting");
+            seen.insert(value);
+        }
+        assert_eq!(seen.len(), interesting.len());
+    }
+
+    #[test]
+    fn test_mutate_int() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut changed = 0;
+        for _ in 0..200 {
+            let original = rng.gen::<u128>().to_le_bytes();
+            let mut bytes = original;
+            mutate_int(&mut rng, &mut bytes);
+            changed += (bytes != original) as usize;
+        }
+        // Only setting an interesting value or filling with random bytes can leave the value
+        // unchanged, and both are unlikely to for random 128-bit values.
+        assert!(
+            changed > 190,
+            "only {changed} of 200 mutations changed the value"
+        );
+
+        // Empty values are left alone.
+        let mut empty: [u8; 0] = [];
+        mutate_int(&mut rng, &mut empty);
+
+        // The same seed produces the same mutations.
+        let mutate = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut bytes = [0u8; 8];
+            for _ in 0..16 {
+                mutate_int(&mut rng, &mut bytes);
+            }
+            bytes
+        };
+        assert_eq!(mutate(7), mutate(7));
+    }
+}
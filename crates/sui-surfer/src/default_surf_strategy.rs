@@ -17,7 +17,7 @@ use crate::{
     surfer_state::{EntryFunction, SurferState},
 };
 
-enum InputObjectPassKind {
+pub(crate) enum InputObjectPassKind {
     Value,
     ByRef,
     MutRef,
@@ -141,7 +141,7 @@ impl DefaultSurfStrategy {
         }
     }
 
-    async fn choose_object_call_arg(
+    pub(crate) async fn choose_object_call_arg(
         state: &mut SurferState,
         kind: InputObjectPassKind,
         arg_type: Type,
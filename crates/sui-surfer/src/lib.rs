@@ -5,18 +5,23 @@ use futures::future::join_all;
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::{Rng, SeedableRng};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use sui_swarm_config::genesis_config::{AccountConfig, DEFAULT_GAS_AMOUNT};
 use surf_strategy::SurfStrategy;
 use test_cluster::TestClusterBuilder;
-use tokio::sync::watch;
-use tracing::info;
+use tokio::sync::{watch, Mutex};
+use tracing::{info, warn};
 
+use crate::corpus::Corpus;
+use crate::coverage::{CoverageTracker, MOVE_VM_TRACE_ENV_VAR};
 use crate::surfer_state::SurfStatistics;
 use crate::surfer_task::SurferTask;
 
+pub mod corpus;
+pub mod coverage;
+pub mod coverage_guided_surf_strategy;
 pub mod default_surf_strategy;
 mod surf_strategy;
 mod surfer_state;
@@ -28,10 +33,21 @@ const EPOCH_DURATION_MS: u64 = 15000;
 const ACCOUNT_NUM: usize = 20;
 const GAS_OBJECT_COUNT: usize = 3;
 
+/// File in the corpus directory that the coverage map is written to at the end of a run.
+const COVERAGE_MAP_FILE: &str = "coverage_map.mvcov";
+
+/// Surf the packages at `package_paths` for `run_duration`. If `corpus_dir` is provided, the
+/// interesting call sequences that strategies find are saved there, and loaded again by later
+/// runs.
 pub async fn run<S: SurfStrategy + Default>(
     run_duration: Duration,
     package_paths: Vec<PathBuf>,
+    corpus_dir: Option<PathBuf>,
 ) -> SurfStatistics {
+    // The VM reads where to trace to when it first executes an instruction, so this needs to
+    // happen before the cluster runs genesis.
+    let trace = S::uses_coverage().then(|| enable_move_vm_trace(corpus_dir.as_deref()));
+
     let cluster = Arc::new(
         TestClusterBuilder::new()
             .with_num_validators(VALIDATOR_COUNT)
@@ -56,8 +72,17 @@ pub async fn run<S: SurfStrategy + Default>(
     let mut rng = StdRng::seed_from_u64(seed);
     let (exit_sender, exit_rcv) = watch::channel(());
 
-    let mut tasks =
-        SurferTask::create_surfer_tasks::<S>(cluster.clone(), rng.gen::<u64>(), exit_rcv).await;
+    let coverage = Arc::new(Mutex::new(CoverageTracker::new(trace)));
+    let corpus = Arc::new(Mutex::new(Corpus::new(corpus_dir.clone())));
+
+    let mut tasks = SurferTask::create_surfer_tasks::<S>(
+        cluster.clone(),
+        rng.gen::<u64>(),
+        exit_rcv,
+        coverage.clone(),
+        corpus.clone(),
+    )
+    .await;
     info!("Created {} surfer tasks", tasks.len());
 
     for path in package_paths {
@@ -76,8 +101,46 @@ pub async fn run<S: SurfStrategy + Default>(
     tokio::time::sleep(run_duration).await;
     exit_sender.send(()).unwrap();
     let all_stats: Result<Vec<_>, _> = join_all(handles).await.into_iter().collect();
-    SurfStatistics::aggregate(all_stats.unwrap())
+    let mut stats = SurfStatistics::aggregate(all_stats.unwrap());
+
+    let mut coverage = coverage.lock().await;
+    coverage.refresh();
+    stats.num_covered_offsets = coverage.num_covered_offsets();
+    stats.num_corpus_entries = corpus.lock().await.len();
+    if coverage.is_enabled() && stats.num_covered_offsets == 0 {
+        warn!(
+            "No coverage was observed. The Move VM only traces execution in debug builds, or with \
+             its `debugging` feature enabled"
+        );
+    }
+    if let Some(dir) = corpus_dir.filter(|_| coverage.is_enabled()) {
+        let path = dir.join(COVERAGE_MAP_FILE);
+        match coverage.save(&path) {
+            Ok(()) => info!("Saved coverage map to {:?}", path),
+            Err(e) => warn!("Failed to save coverage map to {:?}: {:?}", path, e),
+        }
+    }
+    stats
 
     // TODO: Right now it will panic here complaining about dropping a tokio runtime
     // inside of another tokio runtime. Reason unclear.
 }
+
+/// Point the Move VM's execution trace at a file in `corpus_dir` (or the temporary directory),
+/// unless it has already been pointed somewhere, and return its path.
+fn enable_move_vm_trace(corpus_dir: Option<&Path>) -> PathBuf {
+    if let Some(path) = std::env::var_os(MOVE_VM_TRACE_ENV_VAR) {
+        return PathBuf::from(path);
+    }
+
+    let path = match corpus_dir {
+        Some(dir) => dir.join("move_vm.trace"),
+        None => std::env::temp_dir().join(format!("sui-surfer-{}.trace", std::process::id())),
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    info!("Tracing Move VM execution to {:?}", path);
+    std::env::set_var(MOVE_VM_TRACE_ENV_VAR, &path);
+    path
+}
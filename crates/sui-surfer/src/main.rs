@@ -1,12 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::{path::PathBuf, time::Duration};
 use tracing::info;
 
+use sui_surfer::coverage_guided_surf_strategy::CoverageGuidedSurfStrategy;
 use sui_surfer::default_surf_strategy::DefaultSurfStrategy;
 
+#[derive(Clone, Copy, ValueEnum)]
+enum Strategy {
+    /// Call every entry function with random arguments, in a random order.
+    Default,
+    /// Mutate call sequences that reached new bytecode, and minimize sequences that fail in
+    /// interesting ways.
+    CoverageGuided,
+}
+
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
 struct Args {
@@ -15,6 +25,21 @@ struct Args {
 
     #[clap(long, help = "List of package paths to surf")]
     packages: Vec<PathBuf>,
+
+    #[clap(
+        long,
+        value_enum,
+        default_value = "default",
+        help = "Strategy to surf with"
+    )]
+    strategy: Strategy,
+
+    #[clap(
+        long,
+        help = "Directory to save interesting call sequences, failure reproducers and coverage to, \
+                and to load the corpus of earlier runs from"
+    )]
+    corpus_dir: Option<PathBuf>,
 }
 
 const DEFAULT_RUN_DURATION: u64 = 30;
@@ -32,11 +57,21 @@ async fn main() {
         .with_env()
         .init();
 
-    let results = sui_surfer::run::<DefaultSurfStrategy>(
-        Duration::from_secs(args.run_duration.unwrap_or(DEFAULT_RUN_DURATION)),
-        args.packages,
-    )
-    .await;
+    let run_duration = Duration::from_secs(args.run_duration.unwrap_or(DEFAULT_RUN_DURATION));
+    let results = match args.strategy {
+        Strategy::Default => {
+            sui_surfer::run::<DefaultSurfStrategy>(run_duration, args.packages, args.corpus_dir)
+                .await
+        }
+        Strategy::CoverageGuided => {
+            sui_surfer::run::<CoverageGuidedSurfStrategy>(
+                run_duration,
+                args.packages,
+                args.corpus_dir,
+            )
+            .await
+        }
+    };
     results.print_stats();
     info!("Finished surfing");
 }
@@ -18,4 +18,14 @@ pub trait SurfStrategy: Send + Sync + 'static {
         entry_functions: Vec<EntryFunction>,
         exit: &watch::Receiver<()>,
     );
+
+    /// Whether the strategy makes use of the coverage that `SurferState::coverage` tracks.
+    /// Tracking coverage requires tracing every instruction the Move VM executes, so it is only
+    /// enabled for strategies that ask for it.
+    fn uses_coverage() -> bool
+    where
+        Self: Sized,
+    {
+        false
+    }
 }
@@ -10,7 +10,9 @@ use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use sui_json_rpc_types::{SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI};
+use sui_json_rpc_types::{
+    SuiExecutionStatus, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+};
 use sui_move_build::BuildConfig;
 use sui_protocol_config::ProtocolConfig;
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress};
@@ -19,9 +21,12 @@ use sui_types::storage::WriteKind;
 use sui_types::transaction::{CallArg, ObjectArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_PUBLISH};
 use sui_types::{Identifier, SUI_FRAMEWORK_ADDRESS};
 use test_cluster::TestCluster;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, error, info};
 
+use crate::corpus::Corpus;
+use crate::coverage::CoverageTracker;
+
 #[derive(Debug, Clone)]
 pub struct EntryFunction {
     pub package: ObjectID,
@@ -37,6 +42,12 @@ pub struct SurfStatistics {
     pub num_owned_obj_transactions: u64,
     pub num_shared_obj_transactions: u64,
     pub unique_move_functions_called: HashSet<(ObjectID, String, String)>,
+    /// Transactions that hit an invariant violation or aborted unexpectedly.
+    pub num_interesting_failures: u64,
+    /// Bytecode offsets of the surfed packages that were executed, if coverage was tracked.
+    pub num_covered_offsets: usize,
+    /// Call sequences in the corpus at the end of the run.
+    pub num_corpus_entries: usize,
 }
 
 impl SurfStatistics {
@@ -72,6 +83,7 @@ impl SurfStatistics {
             result
                 .unique_move_functions_called
                 .extend(stat.unique_move_functions_called);
+            result.num_interesting_failures += stat.num_interesting_failures;
         }
         result
     }
@@ -91,6 +103,10 @@ impl SurfStatistics {
             "Unique move functions called: {}",
             self.unique_move_functions_called.len()
         );
+        info!(
+            "Interesting failures: {}, covered offsets: {}, corpus entries: {}",
+            self.num_interesting_failures, self.num_covered_offsets, self.num_corpus_entries
+        );
     }
 }
 
@@ -112,6 +128,8 @@ pub struct SurferState {
     pub immutable_objects: ImmObjects,
     pub shared_objects: SharedObjects,
     pub entry_functions: Arc<RwLock<Vec<EntryFunction>>>,
+    pub coverage: Arc<Mutex<CoverageTracker>>,
+    pub corpus: Arc<Mutex<Corpus>>,
 
     pub stats: SurfStatistics,
 }
@@ -126,6 +144,8 @@ impl SurferState {
         immutable_objects: ImmObjects,
         shared_objects: SharedObjects,
         entry_functions: Arc<RwLock<Vec<EntryFunction>>>,
+        coverage: Arc<Mutex<CoverageTracker>>,
+        corpus: Arc<Mutex<Corpus>>,
     ) -> Self {
         Self {
             cluster,
@@ -136,17 +156,20 @@ impl SurferState {
             immutable_objects,
             shared_objects,
             entry_functions,
+            coverage,
+            corpus,
             stats: Default::default(),
         }
     }
 
+    /// Call `package::module::function` with `args`, and return the status of the transaction.
     pub async fn execute_move_transaction(
         &mut self,
         package: ObjectID,
         module: String,
         function: String,
         args: Vec<CallArg>,
-    ) {
+    ) -> SuiExecutionStatus {
         let rgp = self.cluster.get_reference_gas_price().await;
         let use_shared_object = args
             .iter()
@@ -198,6 +221,7 @@ impl SurferState {
             function,
         );
         self.process_tx_effects(&effects).await;
+        effects.status().clone()
     }
 
     async fn process_tx_effects(&mut self, effects: &SuiTransactionBlockEffects) {
@@ -260,6 +284,7 @@ impl SurferState {
 
     async fn discover_entry_functions(&self, package: Object) {
         let package_id = package.id();
+        self.coverage.lock().await.watch_package(package_id);
         let move_package = package.into_inner().data.try_into_package().unwrap();
         let config = ProtocolConfig::get_for_max_version_UNSAFE();
         let entry_functions: Vec<_> = move_package
@@ -10,9 +10,11 @@ use sui_types::{
     object::Owner,
 };
 use test_cluster::TestCluster;
-use tokio::sync::{watch, RwLock};
+use tokio::sync::{watch, Mutex, RwLock};
 
 use crate::{
+    corpus::Corpus,
+    coverage::CoverageTracker,
     surf_strategy::SurfStrategy,
     surfer_state::{ImmObjects, OwnedObjects, SharedObjects, SurfStatistics, SurferState},
 };
@@ -28,6 +30,8 @@ impl SurferTask {
         cluster: Arc<TestCluster>,
         seed: u64,
         exit_rcv: watch::Receiver<()>,
+        coverage: Arc<Mutex<CoverageTracker>>,
+        corpus: Arc<Mutex<Corpus>>,
     ) -> Vec<SurferTask> {
         let mut rng = StdRng::seed_from_u64(seed);
         let immutable_objects: ImmObjects = Arc::new(RwLock::new(HashMap::new()));
@@ -107,6 +111,8 @@ impl SurferTask {
                     immutable_objects.clone(),
                     shared_objects.clone(),
                     entry_functions.clone(),
+                    coverage.clone(),
+                    corpus.clone(),
                 );
                 SurferTask {
                     state,
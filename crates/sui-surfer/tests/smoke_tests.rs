@@ -4,6 +4,7 @@
 use std::path::PathBuf;
 use std::time::Duration;
 use sui_macros::sim_test;
+use sui_surfer::coverage_guided_surf_strategy::CoverageGuidedSurfStrategy;
 use sui_surfer::default_surf_strategy::DefaultSurfStrategy;
 
 #[sim_test]
@@ -12,7 +13,32 @@ async fn smoke_test() {
 
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.extend(["tests", "move_building_blocks"]);
-    let results = sui_surfer::run::<DefaultSurfStrategy>(Duration::from_secs(30), vec![path]).await;
+    let results =
+        sui_surfer::run::<DefaultSurfStrategy>(Duration::from_secs(30), vec![path], None).await;
     assert!(results.num_successful_transactions > 0);
     assert!(!results.unique_move_functions_called.is_empty());
 }
+
+#[sim_test]
+async fn coverage_guided_smoke_test() {
+    // This test makes sure that the sui surfer runs with the coverage guided strategy, and that
+    // it builds up a corpus from the coverage it observes.
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.extend(["tests", "move_building_blocks"]);
+    let corpus_dir = tempfile::tempdir().unwrap();
+    let results = sui_surfer::run::<CoverageGuidedSurfStrategy>(
+        Duration::from_secs(30),
+        vec![path],
+        Some(corpus_dir.path().to_owned()),
+    )
+    .await;
+    assert!(results.num_successful_transactions > 0);
+    assert!(!results.unique_move_functions_called.is_empty());
+    // Coverage is derived from the Move VM's execution trace, which is only compiled into debug
+    // builds unless the VM's `debugging` feature is enabled.
+    if cfg!(debug_assertions) {
+        assert!(results.num_covered_offsets > 0);
+        assert!(results.num_corpus_entries > 0);
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Run a local network whose state is forked from a remote network.
+//!
+//! Instead of starting validators, the forked network is a single [`Simulacrum`] that pulls
+//! objects from the remote network's full node the first time they are read, so transactions can
+//! be executed locally against real (e.g. mainnet or testnet) state.
+
+use anyhow::Result;
+use rand::rngs::OsRng;
+use simulacrum::Simulacrum;
+use simulacrum_server::{start_faucet, start_rpc_server};
+use std::{net::SocketAddr, sync::Arc};
+use sui_replay::fork_source::RemoteForkSource;
+use tokio::sync::RwLock;
+
+/// Fork the network served by `fork_url` at `fork_checkpoint` (or its latest checkpoint), and
+/// serve the forked network's JSON-RPC API and faucet on the given ports.
+pub async fn start_forked_network(
+    fork_url: &str,
+    fork_checkpoint: Option<u64>,
+    fullnode_rpc_port: u16,
+    faucet_port: u16,
+) -> Result<()> {
+    let source = RemoteForkSource::new(fork_url, fork_checkpoint).await?;
+    let timestamp_ms = source.checkpoint_timestamp_ms().await?;
+
+    println!(
+        "Forking {} (chain {}) at checkpoint {}",
+        fork_url,
+        source.chain_identifier().await?,
+        source.checkpoint(),
+    );
+
+    let simulacrum = Arc::new(RwLock::new(Simulacrum::new_forked(
+        OsRng,
+        timestamp_ms,
+        source,
+    )));
+
+    let rpc_address = SocketAddr::from(([0, 0, 0, 0], fullnode_rpc_port));
+    let _rpc_handle = start_rpc_server(simulacrum.clone(), rpc_address).await?;
+    println!("Fullnode RPC URL: http://{}", rpc_address);
+
+    let faucet_address = SocketAddr::from(([0, 0, 0, 0], faucet_port));
+    println!("Faucet URL: http://{}", faucet_address);
+    start_faucet(simulacrum, faucet_address).await
+}
@@ -24,6 +24,8 @@ use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
 use uuid::Uuid;
 
+mod fork;
+
 /// Start a Sui validator and fullnode for easy testing.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -91,6 +93,16 @@ struct Args {
     /// If we should use the new version of the indexer
     #[clap(long)]
     pub use_indexer_v2: bool,
+
+    /// RPC URL of a full node to fork the network's state from, e.g. a mainnet or testnet full
+    /// node. Instead of starting a fresh network, objects are fetched from this node the first
+    /// time they are read, and transactions are executed locally against them.
+    #[clap(long)]
+    fork_url: Option<String>,
+
+    /// The checkpoint to fork from (defaults to the latest checkpoint of the forked network)
+    #[clap(long, requires = "fork_url")]
+    fork_checkpoint: Option<u64>,
 }
 
 #[tokio::main]
@@ -116,8 +128,20 @@ async fn main() -> Result<()> {
         with_indexer,
         use_indexer_experimental_methods,
         use_indexer_v2,
+        fork_url,
+        fork_checkpoint,
     } = args;
 
+    if let Some(fork_url) = fork_url {
+        return fork::start_forked_network(
+            &fork_url,
+            fork_checkpoint,
+            fullnode_rpc_port,
+            faucet_port,
+        )
+        .await;
+    }
+
     // We don't pass epoch duration if we have a genesis config.
     let epoch_duration_ms = if config_dir.is_some() {
         None
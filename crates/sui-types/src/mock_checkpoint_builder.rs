@@ -21,7 +21,7 @@ pub trait ValidatorKeypairProvider {
 
 /// A utility to build consecutive checkpoints by adding transactions to the checkpoint builder.
 /// It's mostly used by simulations, tests and benchmarks.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct MockCheckpointBuilder {
     previous_checkpoint: VerifiedCheckpoint,
     transactions: Vec<VerifiedExecutionData>,
@@ -4,10 +4,11 @@
 use std::{
     fmt::{Debug, Display, Formatter, Write},
     path::PathBuf,
+    str::FromStr,
     sync::Arc,
 };
 
-use anyhow::{anyhow, ensure};
+use anyhow::{anyhow, ensure, Context};
 use bip32::DerivationPath;
 use clap::*;
 use colored::Colorize;
@@ -16,7 +17,9 @@ use fastcrypto::{
     traits::ToFromBytes,
 };
 
+use inquire::Password;
 use json_to_table::json_to_table;
+use move_binary_format::normalized::Module as NormalizedModule;
 use move_core_types::language_storage::TypeTag;
 use move_package::BuildConfig as MoveBuildConfig;
 use prometheus::Registry;
@@ -30,18 +33,21 @@ use shared_crypto::intent::Intent;
 use sui_execution::verifier::VerifierOverrides;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiParsedData, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    DryRunTransactionBlockResponse, DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiParsedData, SuiRawData, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
+use sui_keys::encrypted_keystore::KdfParams;
 use sui_keys::keystore::AccountKeystore;
 use sui_move_build::{
     build_from_resolution_graph, check_invalid_dependencies, check_unpublished_dependencies,
     gather_published_ids, BuildConfig, CompiledPackage, PackageDependencies, PublishedAtError,
 };
 use sui_replay::ReplayToolCommand;
-use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv};
+use sui_sdk::move_bindings::MoveBindings;
+use sui_sdk::sui_client_config::{EnvBundle, EnvImport, SuiClientConfig, SuiEnv};
+use sui_sdk::transaction_preview::TransactionPreview;
 use sui_sdk::wallet_context::WalletContext;
 use sui_sdk::SuiClient;
 use sui_types::{
@@ -68,7 +74,11 @@ use tabled::{
 };
 use tracing::info;
 
-use crate::key_identity::{get_identity_address, KeyIdentity};
+use crate::{
+    client_ptb::{parse_ptb, transaction_data as ptb_transaction_data, PtbBuilder},
+    key_identity::{get_identity_address, KeyIdentity},
+    multisig_request::MultiSigSigningRequest,
+};
 
 macro_rules! serialize_or_execute {
     ($tx_data:expr, $serialize_unsigned:expr, $serialize_signed:expr, $context:expr, $result_variant:ident) => {{
@@ -79,6 +89,15 @@ macro_rules! serialize_or_execute {
         if $serialize_unsigned {
             SuiClientCommandResult::SerializedUnsignedTransaction($tx_data)
         } else {
+            if $context.preview_transactions() {
+                match $context.preview_transaction(&$tx_data).await {
+                    Ok(preview) => eprintln!("{preview}\n"),
+                    Err(e) => eprintln!(
+                        "{}",
+                        format!("Cannot preview transaction: {e}").yellow()
+                    ),
+                }
+            }
             let signature = $context.config.keystore.sign_secure(
                 &$tx_data.sender(),
                 &$tx_data,
@@ -125,6 +144,30 @@ pub enum SuiClientCommands {
         #[clap(long, short = 's')]
         sort_by_alias: bool,
     },
+    /// Generate typed Rust bindings for a Move package: a Rust struct for each Move struct, and a
+    /// function adding a call to each public or entry function to a programmable transaction.
+    #[clap(name = "bindgen")]
+    Bindgen {
+        /// Path to the Move package to build, when generating the bindings of a local package
+        #[clap(name = "package_path", default_value = ".")]
+        package_path: PathBuf,
+
+        /// Package build options
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+
+        /// Generate the bindings of this on-chain package instead of building a local one
+        #[clap(long)]
+        package: Option<ObjectID>,
+
+        /// ID the local package is published at, if it is not recorded in its Move.toml
+        #[clap(long, conflicts_with = "package")]
+        published_at: Option<ObjectID>,
+
+        /// File to write the bindings to, instead of printing them
+        #[clap(long, short)]
+        output: Option<PathBuf>,
+    },
     /// Call Move function
     #[clap(name = "call")]
     Call {
@@ -154,9 +197,10 @@ pub enum SuiClientCommands {
         /// If not provided, a gas object with at least gas_budget value will be selected
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for this call.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -201,6 +245,22 @@ pub enum SuiClientCommands {
         signatures: Vec<String>,
     },
 
+    /// Combine the partial signatures collected in a MultiSig signing request (see
+    /// `sui keytool multi-sig-create-request`) and execute its transaction.
+    ExecuteMultiSigRequest {
+        /// Path to the signing request file.
+        #[clap(long)]
+        request: PathBuf,
+    },
+
+    /// Dry run a transaction and print a summary of what it would do if executed: balance
+    /// changes, objects changes, Move functions called and the gas it would use.
+    PreviewTx {
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        #[clap(long)]
+        tx_bytes: String,
+    },
+
     /// Obtain all gas objects owned by the address.
     /// An address' alias can be used instead of the address.
     #[clap(name = "gas")]
@@ -223,9 +283,10 @@ pub enum SuiClientCommands {
         /// If not provided, a gas object with at least gas_budget value will be selected.
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for this call.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -260,6 +321,8 @@ pub enum SuiClientCommands {
         rpc: String,
         #[clap(long, value_hint = ValueHint::Url)]
         ws: Option<String>,
+        #[clap(flatten)]
+        settings: EnvSettings,
     },
 
     /// Get object info
@@ -303,9 +366,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for this transaction
+        /// Gas budget for this transaction.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -329,9 +393,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         recipient: KeyIdentity,
 
-        /// Gas budget for this transaction
+        /// Gas budget for this transaction.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -361,9 +426,76 @@ pub enum SuiClientCommands {
         #[clap(long, num_args(1..))]
         amounts: Vec<u64>,
 
-        /// Gas budget for this transaction
+        /// Gas budget for this transaction.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
+        /// (TransactionData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_unsigned_transaction: bool,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the signed transaction data
+        /// (SenderSignedData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_signed_transaction: bool,
+    },
+
+    /// Build a programmable transaction block from a sequence of commands, and execute it. Each
+    /// command can name its result, for later commands to use, e.g.:
+    /// `sui client ptb "coins = split_coins(gas, [1000, 2000])" "transfer_objects([coins.0, coins.1], @0xa11ce)"`
+    #[clap(name = "ptb")]
+    Ptb {
+        /// Commands of the transaction block, in order: Move calls
+        /// (`package::module::function<T, ..>(args)`), `split_coins`, `merge_coins`,
+        /// `transfer_objects`, `make_move_vec`, `publish` and `upgrade`
+        #[clap(
+            value_name = "COMMAND",
+            required_unless_present = "file",
+            conflicts_with = "file"
+        )]
+        commands: Vec<String>,
+
+        /// Read the commands of the transaction block from a file, one per line
+        #[clap(long)]
+        file: Option<PathBuf>,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, the gas coin of the sender with the highest balance is used
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this transaction. If not provided, the gas budget of the active
+        /// environment is used if it has one, or it is estimated by dry-running the transaction
+        #[clap(long)]
+        gas_budget: Option<u64>,
+
+        /// Dry run the transaction instead of executing it, showing its effects and gas budget
+        #[clap(
+            long,
+            conflicts_with_all = ["serialize_unsigned_transaction", "serialize_signed_transaction"]
+        )]
+        dry_run: bool,
+
+        /// With `--dry-run`, also execute the transaction locally against the latest state of the
+        /// network, and write a gas profile of it, in the speedscope format
+        /// (https://www.speedscope.app), to the given directory or to the current one. Currently
+        /// supported only in debug builds.
+        #[clap(
+            long,
+            value_name = "DIR",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = ".",
+            requires = "dry_run"
+        )]
+        profile_gas: Option<PathBuf>,
+
+        /// Publish and upgrade packages without checking whether compiling dependencies from
+        /// source results in bytecode matching the dependencies found on-chain.
+        #[clap(long)]
+        skip_dependency_verification: bool,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -392,9 +524,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for running module initializers
+        /// Gas budget for running module initializers.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Publish the package without checking whether compiling dependencies from source results
         /// in bytecode matching the dependencies found on-chain.
@@ -432,9 +565,10 @@ pub enum SuiClientCommands {
         /// If not provided, a gas object with at least gas_budget value will be selected
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for this call.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -460,6 +594,55 @@ pub enum SuiClientCommands {
         env: Option<String>,
     },
 
+    /// Change the URLs, default gas budget or address aliases of an environment.
+    #[clap(name = "update-env")]
+    UpdateEnv {
+        /// Alias of the environment to update. Defaults to the active environment
+        #[clap(long)]
+        alias: Option<String>,
+        #[clap(long, value_hint = ValueHint::Url)]
+        rpc: Option<String>,
+        #[clap(long, value_hint = ValueHint::Url)]
+        ws: Option<String>,
+        #[clap(flatten)]
+        settings: EnvSettings,
+        /// Name an address for this environment, as `<NAME>=<ADDRESS>`. The name can then be used
+        /// in place of the address in commands, like the alias of a key
+        #[clap(long, value_parser = parse_address_alias)]
+        add_address_alias: Vec<(String, SuiAddress)>,
+        /// Remove an address alias of this environment
+        #[clap(long)]
+        remove_address_alias: Vec<String>,
+    },
+
+    /// Write environments to a file, to share them with other users. They import them with
+    /// `sui client import-envs`.
+    #[clap(name = "export-envs")]
+    ExportEnvs {
+        /// File to write the environments to
+        #[clap(long, short)]
+        output: PathBuf,
+        /// Aliases of the environments to export. Defaults to all of them
+        #[clap(long, num_args(1..))]
+        envs: Vec<String>,
+        /// Encrypt the file with a passphrase, read from the SUI_ENV_BUNDLE_PASSPHRASE environment
+        /// variable or prompted for. Use it when the URLs embed API keys
+        #[clap(long)]
+        encrypt: bool,
+    },
+
+    /// Add the environments of a file written by `sui client export-envs`.
+    #[clap(name = "import-envs")]
+    ImportEnvs {
+        /// File to read the environments from
+        #[clap(name = "path")]
+        path: PathBuf,
+        /// Replace the environments that have the same alias as an imported one, instead of
+        /// skipping the imported one
+        #[clap(long)]
+        overwrite: bool,
+    },
+
     /// Get the effects of executing the given transaction block
     #[clap(name = "tx-block")]
     TransactionBlock {
@@ -484,9 +667,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for this transfer
+        /// Gas budget for this transfer.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -512,9 +696,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         sui_coin_object_id: ObjectID,
 
-        /// Gas budget for this transfer
+        /// Gas budget for this transfer.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// The amount to transfer, if not specified, the entire coin object will be transferred.
         #[clap(long)]
@@ -551,9 +736,10 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for running module initializers
+        /// Gas budget for running module initializers.
+        /// Defaults to the gas budget of the active environment
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Publish the package without checking whether compiling dependencies from source results
         /// in bytecode matching the dependencies found on-chain.
@@ -622,6 +808,19 @@ pub enum SuiClientCommands {
         /// Log extra gas-related information
         #[arg(long, short)]
         gas_info: bool,
+
+        /// Write a gas profile of the transaction, in the speedscope format
+        /// (https://www.speedscope.app), to the given directory or to the current one. The profile
+        /// attributes gas to each Move function and native called. Currently supported only in
+        /// debug builds.
+        #[arg(
+            long,
+            value_name = "DIR",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "."
+        )]
+        profile_gas: Option<PathBuf>,
     },
 
     /// Replay transactions listed in a file.
@@ -653,6 +852,73 @@ pub enum SuiClientCommands {
     },
 }
 
+/// Optional settings of an environment.
+#[derive(Args, Debug, Default)]
+pub struct EnvSettings {
+    /// URL of the GraphQL service of the network
+    #[clap(long, value_hint = ValueHint::Url)]
+    pub graphql: Option<String>,
+    /// URL of the faucet of the network
+    #[clap(long, value_hint = ValueHint::Url)]
+    pub faucet: Option<String>,
+    /// Base URL of a block explorer for the network
+    #[clap(long, value_hint = ValueHint::Url)]
+    pub explorer: Option<String>,
+    /// Gas budget of the transactions sent to the network when none is specified
+    #[clap(long)]
+    pub gas_budget: Option<u64>,
+}
+
+impl EnvSettings {
+    fn apply(self, env: &mut SuiEnv) {
+        if let Some(graphql) = self.graphql {
+            env.graphql = Some(graphql);
+        }
+        if let Some(faucet) = self.faucet {
+            env.faucet = Some(faucet);
+        }
+        if let Some(explorer) = self.explorer {
+            env.explorer = Some(explorer);
+        }
+        if let Some(gas_budget) = self.gas_budget {
+            env.default_gas_budget = Some(gas_budget);
+        }
+    }
+}
+
+/// Environment variable holding the passphrase of encrypted environment files.
+pub const SUI_ENV_BUNDLE_PASSPHRASE_ENV: &str = "SUI_ENV_BUNDLE_PASSPHRASE";
+
+fn parse_address_alias(s: &str) -> Result<(String, SuiAddress), anyhow::Error> {
+    let (name, address) = s
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Expected <NAME>=<ADDRESS>, got {s}"))?;
+    ensure!(
+        !name.is_empty(),
+        "The name of an address alias cannot be empty"
+    );
+    Ok((name.to_string(), SuiAddress::from_str(address)?))
+}
+
+/// Read the passphrase of an environment file from the environment, or prompt for it.
+fn read_env_bundle_passphrase(confirm: bool) -> Result<String, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(SUI_ENV_BUNDLE_PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    let prompt = Password::new("Passphrase of the environments file:");
+    let prompt = if confirm {
+        prompt.with_custom_confirmation_message("Confirm passphrase:")
+    } else {
+        prompt.without_confirmation()
+    };
+    prompt.prompt().map_err(|e| {
+        anyhow!(
+            "Cannot read the passphrase: {e}. \
+            Set {SUI_ENV_BUNDLE_PASSPHRASE_ENV} when running non-interactively."
+        )
+    })
+}
+
 impl SuiClientCommands {
     pub async fn execute(
         self,
@@ -662,6 +928,7 @@ impl SuiClientCommands {
             SuiClientCommands::ReplayTransaction {
                 tx_digest,
                 gas_info: _,
+                profile_gas,
             } => {
                 let cmd = ReplayToolCommand::ReplayTransaction {
                     tx_digest,
@@ -669,6 +936,7 @@ impl SuiClientCommands {
                     diag: false,
                     executor_version_override: None,
                     protocol_version_override: None,
+                    profile_gas,
                 };
 
                 let rpc = context.config.get_active_env()?.rpc.clone();
@@ -725,6 +993,51 @@ impl SuiClientCommands {
                 };
                 SuiClientCommandResult::Addresses(output)
             }
+            SuiClientCommands::Bindgen {
+                package_path,
+                build_config,
+                package,
+                published_at,
+                output,
+            } => {
+                let bindings = match package {
+                    Some(package_id) => {
+                        let client = context.get_client().await?;
+                        MoveBindings::from_package(&client, package_id).await?
+                    }
+                    None => {
+                        let compiled_package = compile_package_simple(build_config, package_path)?;
+                        // The functions of an unpublished package call its own address, 0x0.
+                        let package_id = published_at.unwrap_or_else(|| {
+                            compiled_package
+                                .published_at
+                                .as_ref()
+                                .copied()
+                                .unwrap_or(ObjectID::ZERO)
+                        });
+                        let modules = compiled_package
+                            .get_modules()
+                            .map(|module| {
+                                (
+                                    module.self_id().name().to_string(),
+                                    NormalizedModule::new(module).into(),
+                                )
+                            })
+                            .collect();
+                        MoveBindings::new(package_id, modules)
+                    }
+                };
+                let source = bindings.generate();
+                if let Some(output) = &output {
+                    std::fs::write(output, &source)
+                        .with_context(|| format!("Cannot write to {}", output.display()))?;
+                }
+                SuiClientCommandResult::Bindgen {
+                    package_id: bindings.package_id(),
+                    output,
+                    source,
+                }
+            }
             SuiClientCommands::DynamicFieldQuery { id, cursor, limit } => {
                 let client = context.get_client().await?;
                 let df_read = client
@@ -745,6 +1058,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
 
@@ -822,6 +1136,56 @@ impl SuiClientCommands {
                     Upgrade
                 )
             }
+            SuiClientCommands::Ptb {
+                commands,
+                file,
+                gas,
+                gas_budget,
+                dry_run,
+                profile_gas,
+                skip_dependency_verification,
+                serialize_unsigned_transaction,
+                serialize_signed_transaction,
+            } => {
+                let source = match file {
+                    Some(file) => std::fs::read_to_string(&file)
+                        .with_context(|| format!("Failed to read {}", file.display()))?,
+                    None => commands.join("\n"),
+                };
+                let statements = parse_ptb(&source)?;
+
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+                let client = context.get_client().await?;
+                let pt = PtbBuilder::new(&client, sender, skip_dependency_verification)
+                    .build(statements)
+                    .await?;
+                let gas_budget = gas_budget.or(context.config.default_gas_budget());
+                let tx_data = ptb_transaction_data(context, sender, pt, gas, gas_budget).await?;
+                if dry_run {
+                    let gas_budget = tx_data.gas_budget();
+                    let response = client
+                        .read_api()
+                        .dry_run_transaction_block(tx_data.clone())
+                        .await?;
+                    if let Some(profile_dir) = profile_gas {
+                        let rpc = context.config.get_active_env()?.rpc.clone();
+                        sui_replay::profile_dry_run(&rpc, tx_data, profile_dir).await?;
+                    }
+                    SuiClientCommandResult::PtbDryRun {
+                        gas_budget,
+                        response,
+                    }
+                } else {
+                    serialize_or_execute!(
+                        tx_data,
+                        serialize_unsigned_transaction,
+                        serialize_signed_transaction,
+                        context,
+                        Ptb
+                    )
+                }
+            }
             SuiClientCommands::Publish {
                 package_path,
                 gas,
@@ -832,6 +1196,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 if build_config.test_mode {
                     return Err(SuiError::ModulePublishFailure {
                         error:
@@ -959,6 +1324,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 let tx_data = construct_move_call_transaction(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
                 )
@@ -980,6 +1346,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 let from = context.get_object_owner(&object_id).await?;
                 let to = get_identity_address(Some(to), context)?;
                 let client = context.get_client().await?;
@@ -1004,6 +1371,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 let from = context.get_object_owner(&object_id).await?;
                 let to = get_identity_address(Some(to), context)?;
                 let client = context.get_client().await?;
@@ -1029,6 +1397,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 ensure!(
                     !input_coins.is_empty(),
                     "Pay transaction requires a non-empty list of input coins"
@@ -1073,6 +1442,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 ensure!(
                     !input_coins.is_empty(),
                     "PaySui transaction requires a non-empty list of input coins"
@@ -1116,6 +1486,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 ensure!(
                     !input_coins.is_empty(),
                     "PayAllSui transaction requires a non-empty list of input coins"
@@ -1219,6 +1590,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 let signer = context.get_object_owner(&coin_id).await?;
                 let client = context.get_client().await?;
                 let data = match (amounts, count) {
@@ -1257,6 +1629,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget)?;
                 let client = context.get_client().await?;
                 let signer = context.get_object_owner(&primary_coin).await?;
                 let data = client
@@ -1327,13 +1700,42 @@ impl SuiClientCommands {
                 let response = context.execute_transaction_may_fail(transaction).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
             }
-            SuiClientCommands::NewEnv { alias, rpc, ws } => {
+            SuiClientCommands::ExecuteMultiSigRequest { request } => {
+                let request = MultiSigSigningRequest::read(&request)?;
+                let transaction = Transaction::from_generic_sig_data(
+                    request.transaction_data()?,
+                    vec![request.combine()?],
+                );
+                let response = context.execute_transaction_may_fail(transaction).await?;
+                SuiClientCommandResult::ExecuteSignedTx(response)
+            }
+            SuiClientCommands::PreviewTx { tx_bytes } => {
+                let data = bcs::from_bytes(
+                    &Base64::try_from(tx_bytes)
+                        .map_err(|e| anyhow!(e))?
+                        .to_vec()
+                        .map_err(|e| anyhow!(e))?,
+                )?;
+                SuiClientCommandResult::PreviewTx(context.preview_transaction(&data).await?)
+            }
+            SuiClientCommands::NewEnv {
+                alias,
+                rpc,
+                ws,
+                settings,
+            } => {
                 if context.config.envs.iter().any(|env| env.alias == alias) {
                     return Err(anyhow!(
                         "Environment config with name [{alias}] already exists."
                     ));
                 }
-                let env = SuiEnv { alias, rpc, ws };
+                let mut env = SuiEnv {
+                    alias,
+                    rpc,
+                    ws,
+                    ..Default::default()
+                };
+                settings.apply(&mut env);
 
                 // Check urls are valid and server is reachable
                 env.create_rpc_client(None, None).await?;
@@ -1341,6 +1743,71 @@ impl SuiClientCommands {
                 context.config.save()?;
                 SuiClientCommandResult::NewEnv(env)
             }
+            SuiClientCommands::UpdateEnv {
+                alias,
+                rpc,
+                ws,
+                settings,
+                add_address_alias,
+                remove_address_alias,
+            } => {
+                let alias = match alias {
+                    Some(alias) => alias,
+                    None => context.config.get_active_env()?.alias.clone(),
+                };
+                let env = context.config.get_env_mut(&alias)?;
+                let reconnect = rpc.is_some() || ws.is_some();
+                if let Some(rpc) = rpc {
+                    env.rpc = rpc;
+                }
+                if let Some(ws) = ws {
+                    env.ws = Some(ws);
+                }
+                settings.apply(env);
+                for name in remove_address_alias {
+                    ensure!(
+                        env.address_aliases.remove(&name).is_some(),
+                        "No address alias [{name}] in env [{alias}]"
+                    );
+                }
+                env.address_aliases.extend(add_address_alias);
+                let env = env.clone();
+
+                if reconnect {
+                    // Check urls are valid and server is reachable
+                    env.create_rpc_client(None, None).await?;
+                }
+                context.config.save()?;
+                SuiClientCommandResult::UpdateEnv(env)
+            }
+            SuiClientCommands::ExportEnvs {
+                output,
+                envs,
+                encrypt,
+            } => {
+                let bundle = context.config.export_envs(&envs)?;
+                let bytes = if encrypt {
+                    let passphrase = read_env_bundle_passphrase(true)?;
+                    bundle.to_bytes(Some((passphrase.as_str(), KdfParams::default())))?
+                } else {
+                    bundle.to_bytes(None)?
+                };
+                std::fs::write(&output, bytes)
+                    .with_context(|| format!("Cannot write to {}", output.display()))?;
+                SuiClientCommandResult::ExportEnvs {
+                    path: output,
+                    envs: bundle.envs.into_iter().map(|env| env.alias).collect(),
+                    encrypted: encrypt,
+                }
+            }
+            SuiClientCommands::ImportEnvs { path, overwrite } => {
+                let bytes = std::fs::read(&path)
+                    .with_context(|| format!("Cannot read {}", path.display()))?;
+                let bundle = EnvBundle::from_bytes(&bytes, || read_env_bundle_passphrase(false))?;
+                let import = context.config.import_envs(bundle, overwrite);
+                context.config.save()?;
+                SuiClientCommandResult::ImportEnvs(import)
+            }
             SuiClientCommands::ActiveEnv => {
                 SuiClientCommandResult::ActiveEnv(context.config.active_env.clone())
             }
@@ -1417,7 +1884,7 @@ fn compile_package_simple(
     )?)
 }
 
-async fn compile_package(
+pub(crate) async fn compile_package(
     client: &SuiClient,
     build_config: MoveBuildConfig,
     package_path: PathBuf,
@@ -1689,12 +2156,39 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::PayAllSui(response) => {
                 write!(writer, "{}", response)?;
             }
+            SuiClientCommandResult::PreviewTx(preview) => {
+                write!(writer, "{}", preview)?;
+            }
+            SuiClientCommandResult::Ptb(response) => {
+                write!(writer, "{}", response)?;
+            }
+            SuiClientCommandResult::PtbDryRun {
+                gas_budget,
+                response,
+            } => {
+                writeln!(writer, "Gas budget: {gas_budget}")?;
+                writeln!(writer, "{}", response.input)?;
+                writeln!(writer, "{}", response.effects)?;
+                write!(writer, "{}", response.events)?;
+            }
             SuiClientCommandResult::SyncClientState => {
                 writeln!(writer, "Client state sync complete.")?;
             }
             SuiClientCommandResult::ChainIdentifier(ci) => {
                 writeln!(writer, "{}", ci)?;
             }
+            SuiClientCommandResult::Bindgen {
+                package_id,
+                output,
+                source,
+            } => match output {
+                Some(output) => writeln!(
+                    writer,
+                    "Bindings for package {package_id} written to {}",
+                    output.display()
+                )?,
+                None => write!(writer, "{source}")?,
+            },
             SuiClientCommandResult::SplitCoin(response) => {
                 write!(writer, "{}", response)?;
             }
@@ -1719,6 +2213,37 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::NewEnv(env) => {
                 writeln!(writer, "Added new Sui env [{}] to config.", env.alias)?;
             }
+            SuiClientCommandResult::UpdateEnv(env) => {
+                writeln!(writer, "Updated Sui env [{}].", env.alias)?;
+            }
+            SuiClientCommandResult::ExportEnvs {
+                path,
+                envs,
+                encrypted,
+            } => {
+                writeln!(
+                    writer,
+                    "Exported {} env(s) to {}{}: {}",
+                    envs.len(),
+                    path.display(),
+                    if *encrypted { ", encrypted" } else { "" },
+                    envs.join(", ")
+                )?;
+            }
+            SuiClientCommandResult::ImportEnvs(import) => {
+                for (envs, what) in [
+                    (&import.added, "Added"),
+                    (&import.replaced, "Replaced"),
+                    (
+                        &import.skipped,
+                        "Skipped, the alias is already used by another env,",
+                    ),
+                ] {
+                    if !envs.is_empty() {
+                        writeln!(writer, "{what} {}", envs.join(", "))?;
+                    }
+                }
+            }
             SuiClientCommandResult::Envs(envs, active) => {
                 let mut builder = TableBuilder::default();
                 builder.set_header(["alias", "url", "active"]);
@@ -1880,8 +2405,8 @@ impl SuiClientCommandResult {
         use SuiClientCommandResult::*;
         match self {
             Upgrade(b) | Publish(b) | TransactionBlock(b) | Call(b) | Transfer(b)
-            | TransferSui(b) | Pay(b) | PaySui(b) | PayAllSui(b) | SplitCoin(b) | MergeCoin(b)
-            | ExecuteSignedTx(b) => Some(b),
+            | TransferSui(b) | Pay(b) | PaySui(b) | PayAllSui(b) | Ptb(b) | SplitCoin(b)
+            | MergeCoin(b) | ExecuteSignedTx(b) => Some(b),
             _ => None,
         }
     }
@@ -2018,12 +2543,23 @@ pub enum SuiClientCommandResult {
     ActiveAddress(Option<SuiAddress>),
     ActiveEnv(Option<String>),
     Addresses(AddressesOutput),
+    Bindgen {
+        package_id: ObjectID,
+        output: Option<PathBuf>,
+        source: String,
+    },
     Call(SuiTransactionBlockResponse),
     ChainIdentifier(String),
     DynamicFieldQuery(DynamicFieldPage),
     Envs(Vec<SuiEnv>, Option<String>),
+    ExportEnvs {
+        path: PathBuf,
+        envs: Vec<String>,
+        encrypted: bool,
+    },
     ExecuteSignedTx(SuiTransactionBlockResponse),
     Gas(Vec<GasCoin>),
+    ImportEnvs(EnvImport),
     MergeCoin(SuiTransactionBlockResponse),
     NewAddress(NewAddressOutput),
     NewEnv(SuiEnv),
@@ -2032,6 +2568,12 @@ pub enum SuiClientCommandResult {
     Pay(SuiTransactionBlockResponse),
     PayAllSui(SuiTransactionBlockResponse),
     PaySui(SuiTransactionBlockResponse),
+    PreviewTx(TransactionPreview),
+    Ptb(SuiTransactionBlockResponse),
+    PtbDryRun {
+        gas_budget: u64,
+        response: DryRunTransactionBlockResponse,
+    },
     Publish(SuiTransactionBlockResponse),
     RawObject(SuiObjectResponse),
     SerializedSignedTransaction(SenderSignedData),
@@ -2043,6 +2585,7 @@ pub enum SuiClientCommandResult {
     Transfer(SuiTransactionBlockResponse),
     TransferSui(SuiTransactionBlockResponse),
     Upgrade(SuiTransactionBlockResponse),
+    UpdateEnv(SuiEnv),
     VerifyBytecodeMeter {
         max_module_ticks: u128,
         max_function_ticks: u128,
@@ -0,0 +1,1046 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Programmable transaction blocks described on the command line, for `sui client ptb`. A block is
+//! a sequence of statements, one per line (or command line argument), each running a command and
+//! optionally naming its result for later commands to use:
+//!
+//! ```text
+//! coins = split_coins(gas, [1000, 2000])
+//! transfer_objects([coins.0, coins.1], @0xa11ce)
+//! 0x2::pay::split_and_transfer<0x2::sui::SUI>(@0xc011, 500, sender)
+//! ```
+//!
+//! Commands are `split_coins(coin, [amounts])`, `merge_coins(coin, [coins])`,
+//! `transfer_objects([objects], recipient)`, `make_move_vec<T>([elements])`, `publish("path")`,
+//! `upgrade("path", @upgrade_cap)`, and calls to Move functions written
+//! `package::module::function<T, ..>(arguments)`.
+//!
+//! Arguments are `gas` (the gas coin), `sender` (the address of the sender), names of results (or
+//! `name.i` for the i-th result of a command returning several), object IDs and addresses written
+//! `@0x..`, and pure values: numbers (with an optional type suffix, as in `10u8`), `true` and
+//! `false`, strings, vectors `[..]`, and options `none` and `some(..)`. The types of pure values,
+//! and whether `@0x..` is an object or an address, are inferred from the command or the signature
+//! of the Move function called.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use move_core_types::{
+    account_address::AccountAddress, ident_str, identifier::Identifier, language_storage::TypeTag,
+    runtime_value::MoveValue, u256::U256,
+};
+use move_package::BuildConfig as MoveBuildConfig;
+use sui_json_rpc_types::{
+    SuiExecutionStatus, SuiMoveNormalizedModule, SuiMoveNormalizedType, SuiObjectDataOptions,
+    SuiTransactionBlockEffectsAPI,
+};
+use sui_move_build::{CompiledPackage, PackageDependencies, PublishedAtError};
+use sui_protocol_config::ProtocolConfig;
+use sui_sdk::{wallet_context::WalletContext, SuiClient};
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    gas::GasCostSummary,
+    move_package::{UpgradeCap, PACKAGE_MODULE_NAME},
+    object::Owner,
+    parse_sui_type_tag,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{
+        Argument, Command, InputObjectKind, ObjectArg, ProgrammableTransaction, TransactionData,
+        TransactionKind,
+    },
+    MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS, SUI_FRAMEWORK_PACKAGE_ID,
+};
+
+use crate::client_commands::compile_package;
+
+#[cfg(test)]
+#[path = "unit_tests/client_ptb_tests.rs"]
+mod client_ptb_tests;
+
+/// Overhead added to the gas used by a transaction in a dry run when estimating its gas budget, in
+/// multiples of the reference gas price.
+const GAS_SAFE_OVERHEAD: u64 = 1000;
+
+/// A statement of a programmable transaction block.
+#[derive(Debug, PartialEq)]
+pub struct Statement {
+    /// Name given to the result of the command
+    pub name: Option<String>,
+    pub command: PtbCommand,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PtbCommand {
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_args: Vec<TypeTag>,
+        args: Vec<PtbValue>,
+    },
+    SplitCoins(PtbValue, Vec<PtbValue>),
+    MergeCoins(PtbValue, Vec<PtbValue>),
+    TransferObjects(Vec<PtbValue>, PtbValue),
+    MakeMoveVec(Option<TypeTag>, Vec<PtbValue>),
+    Publish(PathBuf),
+    Upgrade(PathBuf, PtbValue),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PtbValue {
+    Gas,
+    Sender,
+    /// The result of an earlier command, or one of its results if it returned several
+    Result(String, Option<u16>),
+    Address(SuiAddress),
+    /// A number, and the type given by its suffix, if any
+    Number(String, Option<ArgType>),
+    Bool(bool),
+    String(String),
+    Vector(Vec<PtbValue>),
+    OptionNone,
+    OptionSome(Box<PtbValue>),
+}
+
+/// The type of an argument, as far as it determines how the argument is passed to a command.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    /// `0x1::string::String` or `0x1::ascii::String`
+    String,
+    /// `0x2::object::ID`
+    Id,
+    Vector(Box<ArgType>),
+    Option(Box<ArgType>),
+    /// `0x2::transfer::Receiving<T>`
+    Receiving,
+    /// Any other struct, passed as an object or the result of an earlier command
+    Object {
+        mutable: bool,
+    },
+    /// A type that cannot be inferred, e.g., of the elements of `make_move_vec` without a type
+    /// argument
+    Unknown,
+}
+
+/// Parses the statements of a programmable transaction block, one per non-empty line. Lines
+/// starting with `#` are comments.
+pub fn parse_ptb(source: &str) -> anyhow::Result<Vec<Statement>> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            Parser {
+                input: line,
+                pos: 0,
+            }
+            .statement()
+            .with_context(|| format!("Invalid command `{line}`"))
+        })
+        .collect()
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn statement(mut self) -> anyhow::Result<Statement> {
+        let mut token = self.token()?;
+        let mut name = None;
+        if self.eat("=") {
+            ensure!(is_identifier(token), "`{token}` is not a valid name");
+            name = Some(token.to_string());
+            token = self.token()?;
+        }
+        let command = self.command(token)?;
+        self.skip_whitespace();
+        ensure!(
+            self.rest().is_empty(),
+            "Unexpected `{}` after the command",
+            self.rest()
+        );
+        Ok(Statement { name, command })
+    }
+
+    fn command(&mut self, token: &str) -> anyhow::Result<PtbCommand> {
+        if self.eat("::") {
+            let package = ObjectID::from_hex_literal(token)
+                .map_err(|e| anyhow!("Invalid package ID `{token}`: {e}"))?;
+            let module = self.token()?.to_string();
+            self.expect("::")?;
+            let function = self.token()?.to_string();
+            let type_args = self.type_args()?;
+            self.expect("(")?;
+            let args = self.values(")")?;
+            return Ok(PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_args,
+                args,
+            });
+        }
+
+        let mut type_args = self.type_args()?;
+        ensure!(
+            type_args.is_empty() || token == "make_move_vec",
+            "`{token}` does not take type arguments"
+        );
+        self.expect("(")?;
+        let command = match token {
+            "split_coins" => {
+                let coin = self.value()?;
+                self.expect(",")?;
+                PtbCommand::SplitCoins(coin, self.vector()?)
+            }
+            "merge_coins" => {
+                let coin = self.value()?;
+                self.expect(",")?;
+                PtbCommand::MergeCoins(coin, self.vector()?)
+            }
+            "transfer_objects" => {
+                let objects = self.vector()?;
+                self.expect(",")?;
+                PtbCommand::TransferObjects(objects, self.value()?)
+            }
+            "make_move_vec" => {
+                ensure!(
+                    type_args.len() <= 1,
+                    "`make_move_vec` takes at most one type argument"
+                );
+                PtbCommand::MakeMoveVec(type_args.pop(), self.vector()?)
+            }
+            "publish" => PtbCommand::Publish(self.string()?.into()),
+            "upgrade" => {
+                let path = self.string()?.into();
+                self.expect(",")?;
+                PtbCommand::Upgrade(path, self.value()?)
+            }
+            _ => bail!(
+                "Unknown command `{token}`, expected a Move function or one of `split_coins`, \
+                 `merge_coins`, `transfer_objects`, `make_move_vec`, `publish` and `upgrade`"
+            ),
+        };
+        self.expect(")")?;
+        Ok(command)
+    }
+
+    fn value(&mut self) -> anyhow::Result<PtbValue> {
+        if self.eat("@") {
+            let token = self.token()?;
+            let address = ObjectID::from_hex_literal(token)
+                .map_err(|e| anyhow!("Invalid address `{token}`: {e}"))?;
+            return Ok(PtbValue::Address(address.into()));
+        }
+        if self.eat("[") {
+            return Ok(PtbValue::Vector(self.values("]")?));
+        }
+        if self.rest().starts_with('"') {
+            return Ok(PtbValue::String(self.string()?));
+        }
+        let token = self.token()?;
+        Ok(match token {
+            "gas" => PtbValue::Gas,
+            "sender" => PtbValue::Sender,
+            "true" => PtbValue::Bool(true),
+            "false" => PtbValue::Bool(false),
+            "none" => PtbValue::OptionNone,
+            "some" => {
+                self.expect("(")?;
+                let value = self.value()?;
+                self.expect(")")?;
+                PtbValue::OptionSome(Box::new(value))
+            }
+            _ if token.starts_with(|c: char| c.is_ascii_digit()) => number(token)?,
+            _ => {
+                ensure!(is_identifier(token), "`{token}` is not a valid name");
+                let index = if self.eat(".") {
+                    let index = self.token()?;
+                    Some(
+                        index
+                            .parse()
+                            .map_err(|_| anyhow!("Invalid result index `{index}`"))?,
+                    )
+                } else {
+                    None
+                };
+                PtbValue::Result(token.to_string(), index)
+            }
+        })
+    }
+
+    /// Values separated by commas, up to `close`, which is consumed.
+    fn values(&mut self, close: &str) -> anyhow::Result<Vec<PtbValue>> {
+        let mut values = vec![];
+        while !self.eat(close) {
+            values.push(self.value()?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(values)
+    }
+
+    fn vector(&mut self) -> anyhow::Result<Vec<PtbValue>> {
+        self.expect("[")?;
+        self.values("]")
+    }
+
+    fn string(&mut self) -> anyhow::Result<String> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(string);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => string.push('\n'),
+                    Some((_, c @ ('"' | '\\'))) => string.push(c),
+                    _ => bail!("Invalid escape sequence in string"),
+                },
+                c => string.push(c),
+            }
+        }
+        bail!("Unterminated string")
+    }
+
+    /// Type arguments between angle brackets, if any.
+    fn type_args(&mut self) -> anyhow::Result<Vec<TypeTag>> {
+        if !self.eat("<") {
+            return Ok(vec![]);
+        }
+        let mut type_args = vec![];
+        let mut depth = 0;
+        let mut start = self.pos;
+        for (i, c) in self.rest().char_indices() {
+            match c {
+                '<' => depth += 1,
+                '>' if depth > 0 => depth -= 1,
+                ',' | '>' if depth == 0 => {
+                    let type_arg = self.input[start..self.pos + i].trim();
+                    type_args.push(
+                        parse_sui_type_tag(type_arg)
+                            .map_err(|e| anyhow!("Invalid type `{type_arg}`: {e}"))?,
+                    );
+                    start = self.pos + i + 1;
+                    if c == '>' {
+                        self.pos = start;
+                        return Ok(type_args);
+                    }
+                }
+                _ => (),
+            }
+        }
+        bail!("Unterminated type arguments")
+    }
+
+    /// A name, number or address.
+    fn token(&mut self) -> anyhow::Result<&'a str> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            match rest.chars().next() {
+                Some(c) => bail!("Unexpected `{c}`"),
+                None => bail!("Unexpected end of the command"),
+            }
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> anyhow::Result<()> {
+        if self.eat(s) {
+            return Ok(());
+        }
+        match self.rest().chars().next() {
+            Some(c) => bail!("Expected `{s}`, found `{c}`"),
+            None => bail!("Expected `{s}`, found the end of the command"),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.input.len() - self.rest().trim_start().len();
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+}
+
+fn is_identifier(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+}
+
+/// A number, with an optional type suffix and `_` separating digits.
+fn number(token: &str) -> anyhow::Result<PtbValue> {
+    let digits = token
+        .find(|c: char| !(c.is_ascii_digit() || c == '_'))
+        .unwrap_or(token.len());
+    let ty = match &token[digits..] {
+        "" => None,
+        "u8" => Some(ArgType::U8),
+        "u16" => Some(ArgType::U16),
+        "u32" => Some(ArgType::U32),
+        "u64" => Some(ArgType::U64),
+        "u128" => Some(ArgType::U128),
+        "u256" => Some(ArgType::U256),
+        _ => bail!("Invalid number `{token}`"),
+    };
+    Ok(PtbValue::Number(token[..digits].replace('_', ""), ty))
+}
+
+/// Builds a programmable transaction from the statements of a block, fetching the objects and the
+/// signatures of the Move functions they use.
+pub struct PtbBuilder<'a> {
+    client: &'a SuiClient,
+    sender: SuiAddress,
+    skip_dependency_verification: bool,
+    builder: ProgrammableTransactionBuilder,
+    /// Results of the commands named so far
+    results: BTreeMap<String, Argument>,
+    /// Normalized modules of the packages called so far
+    packages: BTreeMap<ObjectID, BTreeMap<String, SuiMoveNormalizedModule>>,
+}
+
+impl<'a> PtbBuilder<'a> {
+    pub fn new(
+        client: &'a SuiClient,
+        sender: SuiAddress,
+        skip_dependency_verification: bool,
+    ) -> Self {
+        Self {
+            client,
+            sender,
+            skip_dependency_verification,
+            builder: ProgrammableTransactionBuilder::new(),
+            results: BTreeMap::new(),
+            packages: BTreeMap::new(),
+        }
+    }
+
+    pub async fn build(
+        mut self,
+        statements: Vec<Statement>,
+    ) -> anyhow::Result<ProgrammableTransaction> {
+        for (i, Statement { name, command }) in statements.into_iter().enumerate() {
+            let result = self
+                .command(command)
+                .await
+                .with_context(|| format!("Failed to build command {}", i + 1))?;
+            if let Some(name) = name {
+                self.results.insert(name, result);
+            }
+        }
+        Ok(self.builder.finish())
+    }
+
+    async fn command(&mut self, command: PtbCommand) -> anyhow::Result<Argument> {
+        let object = ArgType::Object { mutable: true };
+        Ok(match command {
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_args,
+                args,
+            } => {
+                self.move_call(package, module, function, type_args, args)
+                    .await?
+            }
+            PtbCommand::SplitCoins(coin, amounts) => {
+                let coin = self.argument(&coin, &object).await?;
+                let amounts = self.arguments(&amounts, &ArgType::U64).await?;
+                self.builder.command(Command::SplitCoins(coin, amounts))
+            }
+            PtbCommand::MergeCoins(coin, coins) => {
+                let coin = self.argument(&coin, &object).await?;
+                let coins = self.arguments(&coins, &object).await?;
+                self.builder.command(Command::MergeCoins(coin, coins))
+            }
+            PtbCommand::TransferObjects(objects, recipient) => {
+                let objects = self.arguments(&objects, &object).await?;
+                let recipient = self.argument(&recipient, &ArgType::Address).await?;
+                self.builder
+                    .command(Command::TransferObjects(objects, recipient))
+            }
+            PtbCommand::MakeMoveVec(type_arg, elements) => {
+                ensure!(
+                    type_arg.is_some() || !elements.is_empty(),
+                    "The type of the elements of an empty vector must be given"
+                );
+                let ty = type_arg
+                    .as_ref()
+                    .map_or(ArgType::Unknown, ArgType::from_type_tag);
+                let elements = self.arguments(&elements, &ty).await?;
+                self.builder
+                    .command(Command::MakeMoveVec(type_arg, elements))
+            }
+            PtbCommand::Publish(path) => {
+                let (dependencies, modules, _, _) = self.compile(path).await?;
+                self.builder
+                    .publish_upgradeable(modules, dependencies.published.into_values().collect())
+            }
+            PtbCommand::Upgrade(path, cap) => self.upgrade(path, cap).await?,
+        })
+    }
+
+    async fn move_call(
+        &mut self,
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_args: Vec<TypeTag>,
+        args: Vec<PtbValue>,
+    ) -> anyhow::Result<Argument> {
+        if !self.packages.contains_key(&package) {
+            let modules = self
+                .client
+                .read_api()
+                .get_normalized_move_modules_by_package(package)
+                .await?;
+            self.packages.insert(package, modules);
+        }
+        let params = {
+            let signature = self.packages[&package]
+                .get(&module)
+                .and_then(|m| m.exposed_functions.get(&function))
+                .ok_or_else(|| anyhow!("Function {package}::{module}::{function} not found"))?;
+            ensure!(
+                signature.type_parameters.len() == type_args.len(),
+                "{package}::{module}::{function} takes {} type arguments, found {}",
+                signature.type_parameters.len(),
+                type_args.len()
+            );
+            signature
+                .parameters
+                .iter()
+                .filter(|ty| !is_tx_context(ty))
+                .map(|ty| ArgType::from_normalized(ty, &type_args))
+                .collect::<anyhow::Result<Vec<_>>>()?
+        };
+        ensure!(
+            params.len() == args.len(),
+            "{package}::{module}::{function} takes {} arguments, found {}",
+            params.len(),
+            args.len()
+        );
+
+        let mut arguments = vec![];
+        for (arg, ty) in args.iter().zip(&params) {
+            arguments.push(self.argument(arg, ty).await?);
+        }
+        Ok(self.builder.programmable_move_call(
+            package,
+            Identifier::new(module)?,
+            Identifier::new(function)?,
+            type_args,
+            arguments,
+        ))
+    }
+
+    async fn upgrade(&mut self, path: PathBuf, cap: PtbValue) -> anyhow::Result<Argument> {
+        let PtbValue::Address(cap_id) = cap else {
+            bail!("The upgrade capability must be given by its ID, as in `@0x..`");
+        };
+        let cap_id = ObjectID::from(cap_id);
+        let (dependencies, modules, package, package_id) = self.compile(path).await?;
+        let package_id = package_id.map_err(|e| match e {
+            PublishedAtError::NotPresent => {
+                anyhow!("No 'published-at' field in manifest for package to be upgraded.")
+            }
+            PublishedAtError::Invalid(v) => anyhow!(
+                "Invalid 'published-at' field in manifest of package to be upgraded. \
+                 Expected an on-chain address, but found: {v:?}"
+            ),
+        })?;
+
+        let upgrade_cap: UpgradeCap = self
+            .client
+            .read_api()
+            .get_object_with_options(cap_id, SuiObjectDataOptions::new().with_bcs())
+            .await?
+            .into_object()
+            .map_err(|e| anyhow!("Could not find upgrade capability at {cap_id}: {e}"))?
+            .bcs
+            .ok_or_else(|| anyhow!("Fetch upgrade capability object but no data was returned"))?
+            .try_as_move()
+            .ok_or_else(|| anyhow!("Upgrade capability is not a Move Object"))?
+            .deserialize()?;
+        // As with `sui client upgrade`, the existing policy of the package is kept
+        let cap = self
+            .object(cap_id, &ArgType::Object { mutable: true })
+            .await?;
+        let policy = self.builder.pure(upgrade_cap.policy)?;
+        let digest = self
+            .builder
+            .pure(package.get_package_digest(false).to_vec())?;
+        let ticket = self.builder.programmable_move_call(
+            SUI_FRAMEWORK_PACKAGE_ID,
+            PACKAGE_MODULE_NAME.to_owned(),
+            ident_str!("authorize_upgrade").to_owned(),
+            vec![],
+            vec![cap, policy, digest],
+        );
+        let receipt = self.builder.upgrade(
+            package_id,
+            ticket,
+            dependencies.published.into_values().collect(),
+            modules,
+        );
+        Ok(self.builder.programmable_move_call(
+            SUI_FRAMEWORK_PACKAGE_ID,
+            PACKAGE_MODULE_NAME.to_owned(),
+            ident_str!("commit_upgrade").to_owned(),
+            vec![],
+            vec![cap, receipt],
+        ))
+    }
+
+    async fn compile(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<(
+        PackageDependencies,
+        Vec<Vec<u8>>,
+        CompiledPackage,
+        Result<ObjectID, PublishedAtError>,
+    )> {
+        compile_package(
+            self.client,
+            MoveBuildConfig::default(),
+            path,
+            false,
+            self.skip_dependency_verification,
+        )
+        .await
+    }
+
+    async fn arguments(
+        &mut self,
+        values: &[PtbValue],
+        ty: &ArgType,
+    ) -> anyhow::Result<Vec<Argument>> {
+        let mut arguments = vec![];
+        for value in values {
+            arguments.push(self.argument(value, ty).await?);
+        }
+        Ok(arguments)
+    }
+
+    async fn argument(&mut self, value: &PtbValue, ty: &ArgType) -> anyhow::Result<Argument> {
+        let PtbValue::Vector(elements) = value else {
+            return self.element(value, ty).await;
+        };
+        if ty.is_pure() && elements.iter().all(PtbValue::is_literal) {
+            return self.element(value, ty).await;
+        }
+        // Vectors of objects or results are made by a separate command
+        let element_ty = match ty {
+            ArgType::Vector(element_ty) => element_ty.as_ref().clone(),
+            _ => ArgType::Unknown,
+        };
+        let mut arguments = vec![];
+        for element in elements {
+            arguments.push(self.element(element, &element_ty).await?);
+        }
+        Ok(self.builder.command(Command::MakeMoveVec(None, arguments)))
+    }
+
+    /// An argument that is not a vector of objects or results.
+    async fn element(&mut self, value: &PtbValue, ty: &ArgType) -> anyhow::Result<Argument> {
+        match value {
+            PtbValue::Gas => Ok(Argument::GasCoin),
+            PtbValue::Result(name, index) => {
+                let result = *self
+                    .results
+                    .get(name)
+                    .ok_or_else(|| anyhow!("Unknown name `{name}`"))?;
+                match (result, index) {
+                    (_, None) => Ok(result),
+                    (Argument::Result(i), Some(j)) => Ok(Argument::NestedResult(i, *j)),
+                    (_, Some(_)) => bail!("`{name}` is not the result of a command"),
+                }
+            }
+            PtbValue::Address(address) if !ty.is_pure() => {
+                self.object(ObjectID::from(*address), ty).await
+            }
+            _ => {
+                let bytes = bcs::to_bytes(&pure_value(value, ty, self.sender)?)?;
+                Ok(self.builder.pure_bytes(bytes, false))
+            }
+        }
+    }
+
+    async fn object(&mut self, id: ObjectID, ty: &ArgType) -> anyhow::Result<Argument> {
+        let object = self
+            .client
+            .read_api()
+            .get_object_with_options(id, SuiObjectDataOptions::new().with_owner())
+            .await?
+            .into_object()
+            .map_err(|e| anyhow!("Could not find object {id}: {e}"))?;
+        let owner = object
+            .owner
+            .ok_or_else(|| anyhow!("Owner of object {id} was not returned"))?;
+        let object_arg = match (ty, owner) {
+            (ArgType::Receiving, _) => ObjectArg::Receiving(object.object_ref()),
+            (
+                _,
+                Owner::Shared {
+                    initial_shared_version,
+                },
+            ) => ObjectArg::SharedObject {
+                id,
+                initial_shared_version,
+                mutable: !matches!(ty, ArgType::Object { mutable: false }),
+            },
+            _ => ObjectArg::ImmOrOwnedObject(object.object_ref()),
+        };
+        self.builder.obj(object_arg)
+    }
+}
+
+/// Transaction data for a programmable transaction, paying for gas with `gas`, or with the
+/// richest gas coin of the sender that the transaction does not use. The gas budget is estimated
+/// by a dry run of the transaction if it is not given.
+pub async fn transaction_data(
+    context: &WalletContext,
+    sender: SuiAddress,
+    pt: ProgrammableTransaction,
+    gas: Option<ObjectID>,
+    gas_budget: Option<u64>,
+) -> anyhow::Result<TransactionData> {
+    let input_objects: BTreeSet<_> = pt
+        .input_objects()?
+        .iter()
+        .filter_map(|object| match object {
+            InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
+            _ => None,
+        })
+        .collect();
+    let coins = context.gas_objects(sender).await?;
+    let (balance, coin) = match gas {
+        Some(gas) => coins
+            .into_iter()
+            .find(|(_, coin)| coin.object_id == gas)
+            .ok_or_else(|| anyhow!("Gas object {gas} is not a gas coin owned by {sender}"))?,
+        None => coins
+            .into_iter()
+            .filter(|(_, coin)| !input_objects.contains(&coin.object_id))
+            .max_by_key(|(balance, _)| *balance)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Cannot find a gas coin owned by {sender} that the transaction does not use"
+                )
+            })?,
+    };
+    ensure!(
+        !input_objects.contains(&coin.object_id),
+        "Gas object {} is also an input of the transaction, use `gas` to refer to it",
+        coin.object_id
+    );
+
+    let client = context.get_client().await?;
+    let gas_price = client.read_api().get_reference_gas_price().await?;
+    let kind = TransactionKind::programmable(pt);
+    let gas_budget = match gas_budget {
+        Some(gas_budget) => gas_budget,
+        None => {
+            let max_budget = balance.min(ProtocolConfig::get_for_max_version_UNSAFE().max_tx_gas());
+            let tx_data = TransactionData::new(
+                kind.clone(),
+                sender,
+                coin.object_ref(),
+                max_budget,
+                gas_price,
+            );
+            let effects = client
+                .read_api()
+                .dry_run_transaction_block(tx_data)
+                .await?
+                .effects;
+            if let SuiExecutionStatus::Failure { error } = effects.status() {
+                bail!(
+                    "Cannot estimate the gas budget, the transaction fails in a dry run: {error}"
+                );
+            }
+            estimate_gas_budget(effects.gas_cost_summary(), gas_price)
+        }
+    };
+    Ok(TransactionData::new(
+        kind,
+        sender,
+        coin.object_ref(),
+        gas_budget,
+        gas_price,
+    ))
+}
+
+/// A gas budget covering the gas used by a transaction in a dry run, with some overhead.
+fn estimate_gas_budget(gas_used: &GasCostSummary, gas_price: u64) -> u64 {
+    let computation = gas_used.computation_cost + GAS_SAFE_OVERHEAD * gas_price;
+    (computation + gas_used.storage_cost)
+        .saturating_sub(gas_used.storage_rebate)
+        .max(computation)
+}
+
+/// A pure value of type `ty`. Numbers may give their type with a suffix, which must agree with
+/// `ty` when it is known.
+fn pure_value(value: &PtbValue, ty: &ArgType, sender: SuiAddress) -> anyhow::Result<MoveValue> {
+    Ok(match (value, ty) {
+        (PtbValue::Number(n, Some(suffix)), ty) if ty == suffix || *ty == ArgType::Unknown => {
+            number_value(n, suffix)?
+        }
+        (PtbValue::Number(n, None), ty) => number_value(n, ty)?,
+        (PtbValue::Bool(b), ArgType::Bool | ArgType::Unknown) => MoveValue::Bool(*b),
+        (PtbValue::Address(a), ArgType::Address | ArgType::Id | ArgType::Unknown) => {
+            MoveValue::Address((*a).into())
+        }
+        (PtbValue::Sender, ArgType::Address | ArgType::Id | ArgType::Unknown) => {
+            MoveValue::Address(sender.into())
+        }
+        // Strings are encoded as their UTF-8 bytes
+        (PtbValue::String(s), ArgType::String | ArgType::Unknown) => bytes_value(s),
+        (PtbValue::String(s), ArgType::Vector(element)) if **element == ArgType::U8 => {
+            bytes_value(s)
+        }
+        (PtbValue::Vector(elements), ArgType::Vector(element)) => MoveValue::Vector(
+            elements
+                .iter()
+                .map(|e| pure_value(e, element, sender))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        // Options are encoded as vectors of at most one element
+        (PtbValue::OptionNone, ArgType::Option(_)) => MoveValue::Vector(vec![]),
+        (PtbValue::OptionSome(v), ArgType::Option(inner)) => {
+            MoveValue::Vector(vec![pure_value(v, inner, sender)?])
+        }
+        (PtbValue::Gas | PtbValue::Result(..), _) => {
+            bail!("`{value}` cannot be part of a pure value")
+        }
+        (_, ArgType::Unknown) => bail!("Cannot infer the type of `{value}`"),
+        (_, ty) => bail!("Expected a value of type {ty}, found `{value}`"),
+    })
+}
+
+fn number_value(n: &str, ty: &ArgType) -> anyhow::Result<MoveValue> {
+    let invalid = || format!("`{n}` is not a valid {ty}");
+    Ok(match ty {
+        ArgType::U8 => MoveValue::U8(n.parse().with_context(invalid)?),
+        ArgType::U16 => MoveValue::U16(n.parse().with_context(invalid)?),
+        ArgType::U32 => MoveValue::U32(n.parse().with_context(invalid)?),
+        ArgType::U64 => MoveValue::U64(n.parse().with_context(invalid)?),
+        ArgType::U128 => MoveValue::U128(n.parse().with_context(invalid)?),
+        ArgType::U256 => MoveValue::U256(U256::from_str(n).map_err(|_| anyhow!(invalid()))?),
+        ArgType::Unknown => {
+            bail!("Cannot infer the type of `{n}`, give it with a suffix, as in `{n}u64`")
+        }
+        ty => bail!("Expected a value of type {ty}, found `{n}`"),
+    })
+}
+
+fn bytes_value(s: &str) -> MoveValue {
+    MoveValue::Vector(s.bytes().map(MoveValue::U8).collect())
+}
+
+fn is_tx_context(ty: &SuiMoveNormalizedType) -> bool {
+    let (SuiMoveNormalizedType::Reference(ty) | SuiMoveNormalizedType::MutableReference(ty)) = ty
+    else {
+        return false;
+    };
+    matches!(
+        ty.as_ref(),
+        SuiMoveNormalizedType::Struct { address, module, name, .. }
+            if module == "tx_context"
+                && name == "TxContext"
+                && AccountAddress::from_hex_literal(address).ok() == Some(SUI_FRAMEWORK_ADDRESS)
+    )
+}
+
+impl ArgType {
+    /// Whether values of the type are passed as pure inputs.
+    fn is_pure(&self) -> bool {
+        match self {
+            ArgType::Bool
+            | ArgType::U8
+            | ArgType::U16
+            | ArgType::U32
+            | ArgType::U64
+            | ArgType::U128
+            | ArgType::U256
+            | ArgType::Address
+            | ArgType::String
+            | ArgType::Id => true,
+            ArgType::Vector(ty) | ArgType::Option(ty) => ty.is_pure(),
+            ArgType::Receiving | ArgType::Object { .. } | ArgType::Unknown => false,
+        }
+    }
+
+    fn from_type_tag(tag: &TypeTag) -> Self {
+        match tag {
+            TypeTag::Bool => ArgType::Bool,
+            TypeTag::U8 => ArgType::U8,
+            TypeTag::U16 => ArgType::U16,
+            TypeTag::U32 => ArgType::U32,
+            TypeTag::U64 => ArgType::U64,
+            TypeTag::U128 => ArgType::U128,
+            TypeTag::U256 => ArgType::U256,
+            TypeTag::Address => ArgType::Address,
+            TypeTag::Signer => ArgType::Unknown,
+            TypeTag::Vector(ty) => ArgType::Vector(Box::new(Self::from_type_tag(ty))),
+            TypeTag::Struct(s) => Self::from_struct(
+                s.address,
+                s.module.as_str(),
+                s.name.as_str(),
+                s.type_params.first().map(Self::from_type_tag),
+            ),
+        }
+    }
+
+    fn from_normalized(ty: &SuiMoveNormalizedType, type_args: &[TypeTag]) -> anyhow::Result<Self> {
+        use SuiMoveNormalizedType as T;
+        Ok(match ty {
+            T::Bool => ArgType::Bool,
+            T::U8 => ArgType::U8,
+            T::U16 => ArgType::U16,
+            T::U32 => ArgType::U32,
+            T::U64 => ArgType::U64,
+            T::U128 => ArgType::U128,
+            T::U256 => ArgType::U256,
+            T::Address => ArgType::Address,
+            T::Signer => ArgType::Unknown,
+            T::Vector(ty) => ArgType::Vector(Box::new(Self::from_normalized(ty, type_args)?)),
+            T::TypeParameter(i) => Self::from_type_tag(
+                type_args
+                    .get(*i as usize)
+                    .ok_or_else(|| anyhow!("Missing type argument {i}"))?,
+            ),
+            T::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => Self::from_struct(
+                AccountAddress::from_hex_literal(address)?,
+                module,
+                name,
+                type_arguments
+                    .first()
+                    .map(|ty| Self::from_normalized(ty, type_args))
+                    .transpose()?,
+            ),
+            T::Reference(ty) => match Self::from_normalized(ty, type_args)? {
+                ArgType::Object { .. } => ArgType::Object { mutable: false },
+                ty => ty,
+            },
+            T::MutableReference(ty) => Self::from_normalized(ty, type_args)?,
+        })
+    }
+
+    fn from_struct(
+        address: AccountAddress,
+        module: &str,
+        name: &str,
+        type_arg: Option<ArgType>,
+    ) -> Self {
+        match (module, name) {
+            ("string" | "ascii", "String") if address == MOVE_STDLIB_ADDRESS => ArgType::String,
+            ("option", "Option") if address == MOVE_STDLIB_ADDRESS => {
+                ArgType::Option(Box::new(type_arg.unwrap_or(ArgType::Unknown)))
+            }
+            ("object", "ID") if address == SUI_FRAMEWORK_ADDRESS => ArgType::Id,
+            ("transfer", "Receiving") if address == SUI_FRAMEWORK_ADDRESS => ArgType::Receiving,
+            // objects passed by value may be mutated
+            _ => ArgType::Object { mutable: true },
+        }
+    }
+}
+
+impl PtbValue {
+    /// Whether the value is made of literals only (and so may be a pure value).
+    fn is_literal(&self) -> bool {
+        match self {
+            PtbValue::Gas | PtbValue::Result(..) => false,
+            PtbValue::Vector(elements) => elements.iter().all(PtbValue::is_literal),
+            PtbValue::OptionSome(value) => value.is_literal(),
+            _ => true,
+        }
+    }
+}
+
+impl Display for ArgType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgType::Bool => write!(f, "bool"),
+            ArgType::U8 => write!(f, "u8"),
+            ArgType::U16 => write!(f, "u16"),
+            ArgType::U32 => write!(f, "u32"),
+            ArgType::U64 => write!(f, "u64"),
+            ArgType::U128 => write!(f, "u128"),
+            ArgType::U256 => write!(f, "u256"),
+            ArgType::Address => write!(f, "address"),
+            ArgType::String => write!(f, "string"),
+            ArgType::Id => write!(f, "0x2::object::ID"),
+            ArgType::Vector(ty) => write!(f, "vector<{ty}>"),
+            ArgType::Option(ty) => write!(f, "0x1::option::Option<{ty}>"),
+            ArgType::Receiving => write!(f, "0x2::transfer::Receiving"),
+            ArgType::Object { .. } => write!(f, "object"),
+            ArgType::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl Display for PtbValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PtbValue::Gas => write!(f, "gas"),
+            PtbValue::Sender => write!(f, "sender"),
+            PtbValue::Result(name, None) => write!(f, "{name}"),
+            PtbValue::Result(name, Some(i)) => write!(f, "{name}.{i}"),
+            PtbValue::Address(address) => write!(f, "@{address}"),
+            PtbValue::Number(n, None) => write!(f, "{n}"),
+            PtbValue::Number(n, Some(ty)) => write!(f, "{n}{ty}"),
+            PtbValue::Bool(b) => write!(f, "{b}"),
+            PtbValue::String(s) => write!(f, "{s:?}"),
+            PtbValue::Vector(elements) => {
+                let elements: Vec<_> = elements.iter().map(|e| e.to_string()).collect();
+                write!(f, "[{}]", elements.join(", "))
+            }
+            PtbValue::OptionNone => write!(f, "none"),
+            PtbValue::OptionSome(value) => write!(f, "some({value})"),
+        }
+    }
+}
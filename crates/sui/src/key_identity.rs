@@ -41,14 +41,27 @@ impl Display for KeyIdentity {
 
 /// Get the SuiAddress corresponding to this key identity.
 /// If no string is provided, then the curernt active address is returned.
+/// Aliases are looked up in the keystore first, then in the address aliases of the active
+/// environment.
 pub fn get_identity_address(
     input: Option<KeyIdentity>,
     ctx: &mut WalletContext,
 ) -> Result<SuiAddress, Error> {
-    if let Some(addr) = input {
-        get_identity_address_from_keystore(addr, &ctx.config.keystore)
-    } else {
-        Ok(ctx.active_address()?)
+    match input {
+        Some(KeyIdentity::Alias(alias)) => {
+            match ctx.config.keystore.get_address_by_alias(alias.clone()) {
+                Ok(address) => Ok(*address),
+                // Fall back to the address aliases of the active environment.
+                Err(e) => ctx
+                    .config
+                    .get_active_env()
+                    .ok()
+                    .and_then(|env| env.address_aliases.get(&alias).copied())
+                    .ok_or(e),
+            }
+        }
+        Some(addr) => get_identity_address_from_keystore(addr, &ctx.config.keystore),
+        None => Ok(ctx.active_address()?),
     }
 }
 
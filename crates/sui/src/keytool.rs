@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::key_identity::{get_identity_address_from_keystore, KeyIdentity};
+use crate::multisig_request::{MultiSigRequestStatus, MultiSigSigningRequest};
 use crate::zklogin_commands_util::{perform_zk_login_test_tx, read_cli_line};
 use anyhow::anyhow;
 use bip32::DerivationPath;
@@ -30,6 +31,9 @@ use shared_crypto::intent::{Intent, IntentMessage, IntentScope, PersonalMessage}
 use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
+use sui_keys::encrypted_keystore::{read_new_passphrase, EncryptedFileKeystore, KdfParams};
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
@@ -69,6 +73,9 @@ pub enum KeyToolCommand {
         /// The alias must start with a letter and can contain only letters, digits, dots, hyphens (-), or underscores (_).
         new_alias: Option<String>,
     },
+    /// Re-encrypt the encrypted keystore under a new passphrase. The new passphrase is read from
+    /// SUI_KEYSTORE_NEW_PASSPHRASE if set, and prompted for otherwise. Any unlock session is ended.
+    ChangePassphrase,
     /// Convert private key from wallet format (hex of 32 byte private key) to sui.keystore format
     /// (base64 of 33 byte flag || private key) or vice versa.
     Convert { value: String },
@@ -85,6 +92,13 @@ pub enum KeyToolCommand {
         #[clap(long)]
         tx_bytes: Option<String>,
     },
+    /// Encrypt the plaintext sui.keystore in place with a passphrase-derived key, and point the
+    /// client config at the encrypted keystore. The passphrase is read from
+    /// SUI_KEYSTORE_NEW_PASSPHRASE if set, and prompted for otherwise.
+    EncryptKeystore,
+    /// Write the keys of the encrypted keystore in plaintext to a new file keystore at the given
+    /// path, e.g. to move them to another machine. Handle the exported file with care.
+    ExportKeystore { path: PathBuf },
     /// Generate a new keypair with key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// with optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or
     /// m/54'/784'/0'/0/0 for secp256k1 or m/74'/784'/0'/0/0 for secp256r1. Word
@@ -126,6 +140,8 @@ pub enum KeyToolCommand {
     /// (Base64 encoded `privkey`). This prints out the account keypair as Base64 encoded `flag || privkey`,
     /// the network keypair, worker keypair, protocol keypair as Base64 encoded `privkey`.
     LoadKeypair { file: PathBuf },
+    /// End the unlock session of the encrypted keystore started by `unlock`.
+    Lock,
     /// To MultiSig Sui Address. Pass in a list of all public keys `flag || pk` in Base64.
     /// See `keytool list` for example public keys.
     MultiSigAddress {
@@ -165,6 +181,52 @@ pub enum KeyToolCommand {
         #[clap(long)]
         threshold: ThresholdUnit,
     },
+    /// Combine the partial signatures collected in a MultiSig signing request into a MultiSig
+    /// signature, once their weight reaches the threshold. The result can be used with
+    /// `sui client execute-signed-tx`, or use `sui client execute-multi-sig-request` to combine
+    /// and execute in one step.
+    MultiSigCombineRequest {
+        /// Path to the signing request file.
+        #[clap(long)]
+        request: PathBuf,
+    },
+    /// Create a signing request file for a transaction sent from a MultiSig address, to be
+    /// passed around to its members to collect their signatures offline. Pass in the
+    /// Base64 encoded BCS bytes of the TransactionData, and the public keys, weights and
+    /// threshold that define the MultiSig address.
+    MultiSigCreateRequest {
+        #[clap(long)]
+        tx_bytes: String,
+        #[clap(long, num_args(1..))]
+        pks: Vec<PublicKey>,
+        #[clap(long, num_args(1..))]
+        weights: Vec<WeightUnit>,
+        #[clap(long)]
+        threshold: ThresholdUnit,
+        /// Path of the signing request file to create.
+        #[clap(long)]
+        output: PathBuf,
+    },
+    /// Show a summary of the transaction in a MultiSig signing request, which members have
+    /// signed it, and their total weight against the threshold.
+    MultiSigRequestStatus {
+        /// Path to the signing request file.
+        #[clap(long)]
+        request: PathBuf,
+    },
+    /// Sign the transaction of a MultiSig signing request with the key of the given member
+    /// address (or its alias) in sui keystore, and add the partial signature to the request
+    /// file. A summary of the transaction is printed, and signing must be confirmed.
+    MultiSigSignRequest {
+        /// Path to the signing request file.
+        #[clap(long)]
+        request: PathBuf,
+        #[clap(long)]
+        address: KeyIdentity,
+        /// Sign without asking for confirmation. Implied by `--json`.
+        #[clap(short = 'y', long)]
+        yes: bool,
+    },
 
     /// Read the content at the provided file path. The accepted format can be
     /// [enum SuiKeyPair] (Base64 encoded of 33-byte `flag || privkey`) or `type AuthorityKeyPair`
@@ -198,6 +260,14 @@ pub enum KeyToolCommand {
         #[clap(long)]
         base64pk: String,
     },
+    /// Unlock the encrypted keystore for the given duration, so that CLI commands run with
+    /// `SUI_KEYSTORE_SESSION` set to the printed session secret can sign without asking for the
+    /// passphrase again until it elapses or `lock` is called.
+    Unlock {
+        /// How long the keystore stays unlocked, in seconds.
+        #[clap(long, default_value_t = 900)]
+        timeout_secs: u64,
+    },
     /// This takes [enum SuiKeyPair] of Base64 encoded of 33-byte `flag || privkey`). It
     /// outputs the keypair into a file at the current directory where the address is the filename,
     /// and prints out its Sui address, Base64 encoded public key, the key scheme, and the key scheme flag.
@@ -308,6 +378,18 @@ pub struct Key {
     peer_id: Option<String>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreStatus {
+    keystore_path: PathBuf,
+    encrypted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unlocked_for_secs: Option<u64>,
+    /// Secret of the unlock session, to export as `SUI_KEYSTORE_SESSION`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_secret: Option<String>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct KeypairData {
@@ -417,11 +499,13 @@ pub enum CommandOutput {
     Error(String),
     Generate(Key),
     Import(Key),
+    Keystore(KeystoreStatus),
     List(Vec<Key>),
     LoadKeypair(KeypairData),
     MultiSigAddress(MultiSigAddress),
     MultiSigCombinePartialSig(MultiSigCombinePartialSig),
     MultiSigCombinePartialSigLegacy(MultiSigCombinePartialSigLegacyOutput),
+    MultiSigRequest(MultiSigRequestStatus),
     PrivateKeyBase64(PrivateKeyBase64),
     Show(Key),
     Sign(SignData),
@@ -444,6 +528,20 @@ impl KeyToolCommand {
                     new_alias,
                 })
             }
+            KeyToolCommand::ChangePassphrase => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!(
+                        "The keystore is not encrypted, use `sui keytool encrypt-keystore` first"
+                    ));
+                };
+                encrypted.change_passphrase(&read_new_passphrase()?)?;
+                CommandOutput::Keystore(KeystoreStatus {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    encrypted: true,
+                    unlocked_for_secs: None,
+                    session_secret: None,
+                })
+            }
             KeyToolCommand::Convert { value } => {
                 let result = convert_private_key_to_base64(value)?;
                 CommandOutput::Convert(result)
@@ -509,6 +607,47 @@ impl KeyToolCommand {
                 CommandOutput::DecodeTxBytes(tx_data)
             }
 
+            KeyToolCommand::EncryptKeystore => {
+                let Keystore::File(file) = keystore else {
+                    return Err(anyhow!("Only a file keystore can be encrypted"));
+                };
+                let path = file
+                    .path()
+                    .ok_or_else(|| anyhow!("The keystore has no file path"))?
+                    .to_path_buf();
+                let encrypted = EncryptedFileKeystore::from_keystore(
+                    &*file,
+                    &path,
+                    &read_new_passphrase()?,
+                    KdfParams::default(),
+                )?;
+                // The aliases are now stored encrypted alongside the keys.
+                let mut aliases_path = path.clone();
+                aliases_path.set_extension("aliases");
+                if aliases_path.exists() {
+                    fs::remove_file(aliases_path)?;
+                }
+                use_encrypted_keystore_in_client_config(&path)?;
+                *keystore = Keystore::Encrypted(encrypted);
+                CommandOutput::Keystore(KeystoreStatus {
+                    keystore_path: path,
+                    encrypted: true,
+                    unlocked_for_secs: None,
+                    session_secret: None,
+                })
+            }
+            KeyToolCommand::ExportKeystore { path } => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!("The keystore is not encrypted"));
+                };
+                encrypted.export(&path)?;
+                CommandOutput::Keystore(KeystoreStatus {
+                    keystore_path: path,
+                    encrypted: false,
+                    unlocked_for_secs: None,
+                    session_secret: None,
+                })
+            }
             KeyToolCommand::Generate {
                 key_scheme,
                 derivation_path,
@@ -634,6 +773,18 @@ impl KeyToolCommand {
                 CommandOutput::LoadKeypair(output)
             }
 
+            KeyToolCommand::Lock => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!("The keystore is not encrypted"));
+                };
+                EncryptedFileKeystore::lock(encrypted.path())?;
+                CommandOutput::Keystore(KeystoreStatus {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    encrypted: true,
+                    unlocked_for_secs: None,
+                    session_secret: None,
+                })
+            }
             KeyToolCommand::MultiSigAddress {
                 threshold,
                 pks,
@@ -697,6 +848,67 @@ impl KeyToolCommand {
                 )
             }
 
+            KeyToolCommand::MultiSigCombineRequest { request } => {
+                let request = MultiSigSigningRequest::read(&request)?;
+                let generic_sig = request.combine()?;
+                let multisig_serialized = generic_sig.encode_base64();
+                CommandOutput::MultiSigCombinePartialSig(MultiSigCombinePartialSig {
+                    multisig_address: request.multisig_address,
+                    multisig_parsed: generic_sig,
+                    multisig_serialized,
+                })
+            }
+
+            KeyToolCommand::MultiSigCreateRequest {
+                tx_bytes,
+                pks,
+                weights,
+                threshold,
+                output,
+            } => {
+                let tx_bytes = Base64::decode(&tx_bytes)
+                    .map_err(|e| anyhow!("Invalid base64 tx bytes: {:?}", e))?;
+                let tx_data: TransactionData = bcs::from_bytes(&tx_bytes)?;
+                let request = MultiSigSigningRequest::new(&tx_data, pks, weights, threshold)?;
+                request.write(&output)?;
+                CommandOutput::MultiSigRequest(request.status()?)
+            }
+
+            KeyToolCommand::MultiSigRequestStatus { request } => {
+                CommandOutput::MultiSigRequest(MultiSigSigningRequest::read(&request)?.status()?)
+            }
+
+            KeyToolCommand::MultiSigSignRequest {
+                request,
+                address,
+                yes,
+            } => {
+                let path = request;
+                let mut request = MultiSigSigningRequest::read(&path)?;
+                let address = get_identity_address_from_keystore(address, keystore)?;
+                if !yes {
+                    let mut table = json_to_table(&json!(request.summary()?));
+                    table.with(tabled::settings::Style::rounded().horizontals([]));
+                    table.array_orientation(Orientation::Column);
+                    println!("{table}");
+                    print!("Sign this transaction with {address}? [y/N] ");
+                    let _ = std::io::Write::flush(&mut std::io::stdout());
+                    let mut answer = String::new();
+                    std::io::stdin().read_line(&mut answer)?;
+                    if !answer.trim().eq_ignore_ascii_case("y") {
+                        return Err(anyhow!("Signing request {} aborted", path.display()));
+                    }
+                }
+                let signature = keystore.sign_secure(
+                    &address,
+                    &request.transaction_data()?,
+                    Intent::sui_transaction(),
+                )?;
+                request.add_signature(signature)?;
+                request.write(&path)?;
+                CommandOutput::MultiSigRequest(request.status()?)
+            }
+
             KeyToolCommand::Show { file } => {
                 let res = read_keypair_from_file(&file);
                 match res {
@@ -816,6 +1028,19 @@ impl KeyToolCommand {
                 })
             }
 
+            KeyToolCommand::Unlock { timeout_secs } => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!("The keystore is not encrypted"));
+                };
+                let secret = encrypted.start_session(Duration::from_secs(timeout_secs))?;
+                CommandOutput::Keystore(KeystoreStatus {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    encrypted: true,
+                    unlocked_for_secs: Some(timeout_secs),
+                    session_secret: Some(secret),
+                })
+            }
+
             KeyToolCommand::Unpack { keypair } => {
                 let keypair: SuiKeyPair = keypair.parse()
                     .expect("Expected a Base64 private key, but could not decode the input string to a SuiKeyPair");
@@ -1205,6 +1430,39 @@ fn convert_private_key_to_base64(value: String) -> Result<ConvertOutput, anyhow:
     }
 }
 
+/// Point the client config at the encrypted keystore at `path`, if the config currently uses
+/// the plaintext keystore at that path. The config is edited as plain YAML because it can no
+/// longer be loaded with the `File` keystore once the keystore is encrypted.
+fn use_encrypted_keystore_in_client_config(path: &Path) -> Result<(), anyhow::Error> {
+    let config_path = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
+    if !config_path.exists() {
+        return Ok(());
+    }
+    let mut config: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(&config_path)?)?;
+    let Some(keystore) = config.get_mut("keystore") else {
+        return Ok(());
+    };
+    let uses_path = keystore
+        .get("File")
+        .and_then(|file| file.as_str())
+        .is_some_and(|file| {
+            Path::new(file) == path
+                || fs::canonicalize(file)
+                    .ok()
+                    .is_some_and(|file| Some(file) == fs::canonicalize(path).ok())
+        });
+    if uses_path {
+        let mut encrypted = serde_yaml::Mapping::new();
+        encrypted.insert(
+            "Encrypted".into(),
+            path.to_string_lossy().into_owned().into(),
+        );
+        *keystore = serde_yaml::Value::Mapping(encrypted);
+        fs::write(&config_path, serde_yaml::to_string(&config)?)?;
+    }
+    Ok(())
+}
+
 fn anemo_styling(pk: &PublicKey) -> Option<String> {
     if let PublicKey::Ed25519(public_key) = pk {
         Some(anemo::PeerId(public_key.0).to_string())
@@ -3,12 +3,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod client_commands;
+pub mod client_ptb;
 pub mod console;
 pub mod fire_drill;
 pub mod genesis_ceremony;
 pub mod genesis_inspector;
 pub mod key_identity;
 pub mod keytool;
+pub mod multisig_request;
+pub mod on_chain_deps;
 pub mod shell;
 pub mod sui_commands;
 pub mod validator_commands;
@@ -0,0 +1,263 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! File based workflow to collect the partial signatures of a MultiSig transaction.
+//!
+//! A signing request is a JSON file holding the transaction to sign, the members and threshold
+//! of the MultiSig, and the partial signatures collected so far. It is created once, passed
+//! around to the members who each add their signature with their own keystore, and then
+//! combined into a MultiSig signature once the threshold is reached.
+
+use anyhow::{anyhow, bail, ensure, Context};
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::fs;
+use std::path::Path;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{default_hash, EncodeDecodeBase64, PublicKey, Signature};
+use sui_types::digests::TransactionDigest;
+use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
+use sui_types::signature::{AuthenticatorTrait, GenericSignature, VerifyParams};
+use sui_types::transaction::{TransactionData, TransactionDataAPI, TransactionExpiration};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigSigningRequest {
+    pub multisig_address: SuiAddress,
+    pub threshold: ThresholdUnit,
+    pub members: Vec<MultiSigMember>,
+    /// Base64 encoded BCS bytes of the `TransactionData` to sign.
+    pub tx_bytes: String,
+    pub signatures: Vec<PartialSignature>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigMember {
+    /// Base64 encoded `flag || pk`.
+    pub public_key: String,
+    pub weight: WeightUnit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialSignature {
+    pub signer: SuiAddress,
+    /// Base64 encoded `flag || sig || pk`.
+    pub signature: String,
+}
+
+/// Human readable description of the transaction of a signing request.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSummary {
+    pub digest: TransactionDigest,
+    pub sender: SuiAddress,
+    pub gas_owner: SuiAddress,
+    pub gas_budget: u64,
+    pub gas_price: u64,
+    pub gas_payment: Vec<String>,
+    pub expiration: String,
+    pub kind: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigMemberStatus {
+    pub address: SuiAddress,
+    pub public_base64_key: String,
+    pub weight: WeightUnit,
+    pub signed: bool,
+}
+
+/// Which members have signed a request, and whether their weight reaches the threshold.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigRequestStatus {
+    pub multisig_address: SuiAddress,
+    pub transaction: TransactionSummary,
+    pub members: Vec<MultiSigMemberStatus>,
+    pub signed_weight: u16,
+    pub threshold: ThresholdUnit,
+    pub ready: bool,
+}
+
+impl MultiSigSigningRequest {
+    /// Create a request to sign `tx_data` by the MultiSig defined by `pks`, `weights` and
+    /// `threshold`, which must be the sender of the transaction.
+    pub fn new(
+        tx_data: &TransactionData,
+        pks: Vec<PublicKey>,
+        weights: Vec<WeightUnit>,
+        threshold: ThresholdUnit,
+    ) -> Result<Self, anyhow::Error> {
+        let multisig_pk = MultiSigPublicKey::new(pks.clone(), weights.clone(), threshold)?;
+        let multisig_address = SuiAddress::from(&multisig_pk);
+        ensure!(
+            tx_data.sender() == multisig_address,
+            "The transaction sender {} is not the MultiSig address {multisig_address}",
+            tx_data.sender()
+        );
+        Ok(Self {
+            multisig_address,
+            threshold,
+            members: pks
+                .iter()
+                .zip(weights)
+                .map(|(pk, weight)| MultiSigMember {
+                    public_key: pk.encode_base64(),
+                    weight,
+                })
+                .collect(),
+            tx_bytes: Base64::encode(bcs::to_bytes(tx_data)?),
+            signatures: vec![],
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let request = fs::read_to_string(path)
+            .with_context(|| format!("Cannot read signing request: {}", path.display()))?;
+        serde_json::from_str(&request)
+            .with_context(|| format!("Invalid signing request: {}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Cannot write signing request: {}", path.display()))
+    }
+
+    pub fn transaction_data(&self) -> Result<TransactionData, anyhow::Error> {
+        let tx_bytes = Base64::decode(&self.tx_bytes)
+            .map_err(|e| anyhow!("Invalid base64 tx bytes: {:?}", e))?;
+        Ok(bcs::from_bytes(&tx_bytes)?)
+    }
+
+    pub fn multisig_pk(&self) -> Result<MultiSigPublicKey, anyhow::Error> {
+        let pks = self
+            .members
+            .iter()
+            .map(|member| {
+                PublicKey::decode_base64(&member.public_key)
+                    .map_err(|e| anyhow!("Invalid public key {}: {e}", member.public_key))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let weights = self.members.iter().map(|member| member.weight).collect();
+        let multisig_pk = MultiSigPublicKey::new(pks, weights, self.threshold)?;
+        ensure!(
+            SuiAddress::from(&multisig_pk) == self.multisig_address,
+            "The members and threshold do not match the MultiSig address {}",
+            self.multisig_address
+        );
+        Ok(multisig_pk)
+    }
+
+    /// Add the partial signature of a member, replacing any previous one from the same member.
+    /// The signature is verified against the transaction before it is added.
+    pub fn add_signature(&mut self, signature: Signature) -> Result<SuiAddress, anyhow::Error> {
+        let multisig_pk = self.multisig_pk()?;
+        let generic_sig = GenericSignature::Signature(signature);
+        let pk = generic_sig.to_public_key()?;
+        ensure!(
+            multisig_pk.get_index(&pk).is_some(),
+            "{} is not a member of MultiSig {}",
+            pk.encode_base64(),
+            self.multisig_address
+        );
+        let signer = SuiAddress::from(&pk);
+        generic_sig
+            .verify_authenticator(
+                &IntentMessage::new(Intent::sui_transaction(), self.transaction_data()?),
+                signer,
+                None,
+                &VerifyParams::default(),
+            )
+            .map_err(|e| anyhow!("Invalid signature from {signer}: {e}"))?;
+
+        self.signatures.retain(|sig| sig.signer != signer);
+        self.signatures.push(PartialSignature {
+            signer,
+            signature: generic_sig.encode_base64(),
+        });
+        Ok(signer)
+    }
+
+    pub fn status(&self) -> Result<MultiSigRequestStatus, anyhow::Error> {
+        let multisig_pk = self.multisig_pk()?;
+        let members = multisig_pk
+            .pubkeys()
+            .iter()
+            .map(|(pk, weight)| {
+                let address = SuiAddress::from(pk);
+                MultiSigMemberStatus {
+                    address,
+                    public_base64_key: pk.encode_base64(),
+                    weight: *weight,
+                    signed: self.signatures.iter().any(|sig| sig.signer == address),
+                }
+            })
+            .collect::<Vec<_>>();
+        let signed_weight = members
+            .iter()
+            .filter(|member| member.signed)
+            .map(|member| member.weight as u16)
+            .sum::<u16>();
+        Ok(MultiSigRequestStatus {
+            multisig_address: self.multisig_address,
+            transaction: self.summary()?,
+            members,
+            signed_weight,
+            threshold: self.threshold,
+            ready: signed_weight >= self.threshold,
+        })
+    }
+
+    /// Combine the partial signatures into a MultiSig signature, once their weight reaches the
+    /// threshold.
+    pub fn combine(&self) -> Result<GenericSignature, anyhow::Error> {
+        let status = self.status()?;
+        if !status.ready {
+            bail!(
+                "Not enough signatures: signed weight {} is below the threshold {}",
+                status.signed_weight,
+                status.threshold
+            );
+        }
+        // The signatures must be in the same order as the public keys of the MultiSig.
+        let sigs = status
+            .members
+            .iter()
+            .filter_map(|member| {
+                self.signatures
+                    .iter()
+                    .find(|sig| sig.signer == member.address)
+            })
+            .map(|sig| {
+                GenericSignature::decode_base64(&sig.signature)
+                    .map_err(|e| anyhow!("Invalid signature from {}: {e}", sig.signer))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(MultiSig::combine(sigs, self.multisig_pk()?)?.into())
+    }
+
+    pub fn summary(&self) -> Result<TransactionSummary, anyhow::Error> {
+        let tx_data = self.transaction_data()?;
+        Ok(TransactionSummary {
+            digest: TransactionDigest::new(default_hash(&tx_data)),
+            sender: tx_data.sender(),
+            gas_owner: tx_data.gas_owner(),
+            gas_budget: tx_data.gas_budget(),
+            gas_price: tx_data.gas_price(),
+            gas_payment: tx_data
+                .gas()
+                .iter()
+                .map(|(id, version, _)| format!("{id} (version {})", version.value()))
+                .collect(),
+            expiration: match tx_data.expiration() {
+                TransactionExpiration::None => "None".to_string(),
+                TransactionExpiration::Epoch(epoch) => format!("Epoch {epoch}"),
+            },
+            kind: tx_data.kind().to_string(),
+        })
+    }
+}
@@ -0,0 +1,349 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for dependencies on published packages, declared in a package manifest as
+//! `Dep = { on-chain = "<package id>", network = "<network>" }`. The package's bytecode is fetched
+//! from a fullnode and written out as a source package containing interface stubs for its modules
+//! (struct definitions and the signatures of its public functions), which is all that is needed
+//! to compile against it. Packages are immutable once published, so the fetched package is cached
+//! and builds depending on it work offline from then on.
+
+use anyhow::{anyhow, bail, Context};
+use move_binary_format::{access::ModuleAccess, CompiledModule};
+use move_compiler::interface_generator::write_module_to_string;
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
+use move_package::{
+    package_hooks::PackageHooks,
+    source_package::parsed_manifest::{CustomDepInfo, OnChainInfo, SourceManifest},
+};
+use move_symbol_pool::Symbol;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    fs,
+    path::Path,
+};
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiRawData, SuiRawMovePackage};
+use sui_move_build::SuiPackageHooks;
+use sui_sdk::{SuiClientBuilder, SUI_DEVNET_URL, SUI_LOCAL_NETWORK_URL, SUI_TESTNET_URL};
+use sui_types::{
+    base_types::ObjectID, is_system_package, DEEPBOOK_ADDRESS, MOVE_STDLIB_ADDRESS,
+    SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_ADDRESS,
+};
+
+#[cfg(test)]
+#[path = "unit_tests/on_chain_deps_tests.rs"]
+mod on_chain_deps_tests;
+
+pub const SUI_MAINNET_URL: &str = "https://fullnode.mainnet.sui.io:443";
+
+/// Environment variable holding the URL of a source validation service to fetch the verified
+/// sources of on-chain dependencies from.
+pub const SOURCE_SERVICE_URL_ENV: &str = "SUI_SOURCE_SERVICE_URL";
+
+/// Directory of a fetched package that verified sources are written to. They are kept for
+/// reference only: the package is compiled from the generated interface stubs.
+pub const VERIFIED_SOURCES_DIR: &str = "verified_sources";
+
+const SUI_GIT_URL: &str = "https://github.com/MystenLabs/sui.git";
+
+/// Package hooks of the Sui CLI: those of `SuiPackageHooks`, plus support for fetching on-chain
+/// dependencies.
+pub struct OnChainPackageHooks;
+
+impl PackageHooks for OnChainPackageHooks {
+    fn custom_package_info_fields(&self) -> Vec<String> {
+        SuiPackageHooks.custom_package_info_fields()
+    }
+
+    fn custom_dependency_key(&self) -> Option<String> {
+        SuiPackageHooks.custom_dependency_key()
+    }
+
+    fn resolve_custom_dependency(
+        &self,
+        dep_name: Symbol,
+        info: &CustomDepInfo,
+    ) -> anyhow::Result<()> {
+        SuiPackageHooks.resolve_custom_dependency(dep_name, info)
+    }
+
+    fn custom_resolve_pkg_name(&self, manifest: &SourceManifest) -> anyhow::Result<Symbol> {
+        SuiPackageHooks.custom_resolve_pkg_name(manifest)
+    }
+
+    fn resolve_on_chain_dependency(
+        &self,
+        dep_name: Symbol,
+        info: &OnChainInfo,
+        dest: &Path,
+    ) -> anyhow::Result<()> {
+        let network = info.network.as_str();
+        let rpc_url = network_rpc_url(network)?;
+        let id = ObjectID::from_hex_literal(info.id.as_str())
+            .with_context(|| format!("Invalid on-chain package ID '{}'", info.id))?;
+        if is_system_package(id) {
+            bail!(
+                "{id} is a system package: depend on it from the framework's git repository \
+                 instead of on-chain"
+            );
+        }
+        let source_service_url = std::env::var(SOURCE_SERVICE_URL_ENV).ok();
+
+        // Dependencies are resolved synchronously, possibly from within the CLI's runtime, so the
+        // package is fetched from a separate thread with a runtime of its own.
+        let (package, verified_sources) = std::thread::scope(|s| {
+            s.spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(async {
+                        let package = fetch_package(rpc_url, id).await?;
+                        let verified_sources = match &source_service_url {
+                            Some(url) => {
+                                fetch_verified_sources(url, network, id, package.module_map.keys())
+                                    .await
+                            }
+                            None => BTreeMap::new(),
+                        };
+                        Ok::<_, anyhow::Error>((package, verified_sources))
+                    })
+            })
+            .join()
+            .map_err(|_| anyhow!("Fetching on-chain package {id} panicked"))?
+        })
+        .with_context(|| format!("Failed to fetch package {id} from '{network}'"))?;
+
+        write_package(dep_name, network, &package, &verified_sources, dest)
+    }
+}
+
+/// The URL of the fullnode to fetch packages published on `network` from: `network` is either the
+/// name of a well-known network, or the URL of a fullnode.
+pub fn network_rpc_url(network: &str) -> anyhow::Result<&str> {
+    Ok(match network {
+        "mainnet" => SUI_MAINNET_URL,
+        "testnet" => SUI_TESTNET_URL,
+        "devnet" => SUI_DEVNET_URL,
+        "localnet" => SUI_LOCAL_NETWORK_URL,
+        url if url.starts_with("http://") || url.starts_with("https://") => url,
+        _ => bail!(
+            "Unknown network '{network}', expected one of 'mainnet', 'testnet', 'devnet', \
+             'localnet' or the URL of a fullnode"
+        ),
+    })
+}
+
+async fn fetch_package(rpc_url: &str, id: ObjectID) -> anyhow::Result<SuiRawMovePackage> {
+    let client = SuiClientBuilder::default().build(rpc_url).await?;
+    let object = client
+        .read_api()
+        .get_object_with_options(id, SuiObjectDataOptions::new().with_bcs())
+        .await?
+        .into_object()?;
+    match object.bcs {
+        Some(SuiRawData::Package(package)) => Ok(package),
+        _ => bail!("Object {id} is not a package"),
+    }
+}
+
+#[derive(Deserialize)]
+struct SourceResponse {
+    source: String,
+}
+
+/// Fetches the verified sources of the modules of package `id` from a source validation service.
+/// Sources are only available for packages the service verified, so modules whose source cannot
+/// be fetched are skipped.
+async fn fetch_verified_sources(
+    service_url: &str,
+    network: &str,
+    id: ObjectID,
+    modules: impl Iterator<Item = &String>,
+) -> BTreeMap<String, String> {
+    let client = reqwest::Client::new();
+    let address = id.to_string();
+    let mut sources = BTreeMap::new();
+    for module in modules {
+        let response = client
+            .get(format!("{}/api", service_url.trim_end_matches('/')))
+            .query(&[
+                ("network", network),
+                ("address", address.as_str()),
+                ("module", module.as_str()),
+            ])
+            .send()
+            .await;
+        let Ok(response) = response.and_then(|r| r.error_for_status()) else {
+            continue;
+        };
+        if let Ok(SourceResponse { source }) = response.json().await {
+            sources.insert(module.clone(), source);
+        }
+    }
+    sources
+}
+
+/// Writes `package` as a source package named `dep_name` to `dest`: its interface stubs go to
+/// `sources/`, the verified sources, if any, to `verified_sources/`, and its manifest depends on
+/// the packages its modules refer to. The manifest is written last, so that a package that was
+/// only partially written is fetched again.
+pub fn write_package(
+    dep_name: Symbol,
+    network: &str,
+    package: &SuiRawMovePackage,
+    verified_sources: &BTreeMap<String, String>,
+    dest: &Path,
+) -> anyhow::Result<()> {
+    let modules = package
+        .module_map
+        .iter()
+        .map(|(name, bytes)| {
+            CompiledModule::deserialize_with_defaults(bytes)
+                .with_context(|| format!("Failed to deserialize module '{name}'"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let Some(original_id) = modules.first().map(|m| *m.self_id().address()) else {
+        bail!("Package {} has no modules", package.id);
+    };
+
+    let named_address = named_address(dep_name);
+    let stubs = interface_stubs(&named_address, &modules)?;
+    let manifest = package_manifest(
+        dep_name,
+        &named_address,
+        network,
+        package,
+        original_id,
+        &modules,
+    );
+
+    if dest.exists() {
+        fs::remove_dir_all(dest)?;
+    }
+    let sources_dir = dest.join("sources");
+    fs::create_dir_all(&sources_dir)?;
+    for (name, stub) in stubs {
+        fs::write(sources_dir.join(format!("{name}.move")), stub)?;
+    }
+    if !verified_sources.is_empty() {
+        let verified_dir = dest.join(VERIFIED_SOURCES_DIR);
+        fs::create_dir_all(&verified_dir)?;
+        for (name, source) in verified_sources {
+            fs::write(verified_dir.join(format!("{name}.move")), source)?;
+        }
+    }
+    fs::write(dest.join("Move.toml"), manifest)?;
+    Ok(())
+}
+
+/// The named address the modules of the package fetched for dependency `dep_name` are declared
+/// at.
+fn named_address(dep_name: Symbol) -> String {
+    dep_name.as_str().to_lowercase().replace('-', "_")
+}
+
+/// Generates the interface stubs of `modules` by module name, with the modules declared at
+/// `named_address`.
+pub fn interface_stubs(
+    named_address: &str,
+    modules: &[CompiledModule],
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let named_address_mapping: BTreeMap<ModuleId, &str> = modules
+        .iter()
+        .map(|m| (m.self_id(), named_address))
+        .collect();
+    modules
+        .iter()
+        .map(|m| {
+            let (id, stub) = write_module_to_string(&named_address_mapping, m)?;
+            Ok((id.name().to_string(), stub))
+        })
+        .collect()
+}
+
+/// Generates the manifest of the package fetched for dependency `dep_name`. Packages the modules
+/// refer to are dependencies: system packages from the framework's git repository at the
+/// revision matching `network`, and others as on-chain dependencies, at the version recorded in
+/// the package's linkage table.
+fn package_manifest(
+    dep_name: Symbol,
+    named_address: &str,
+    network: &str,
+    package: &SuiRawMovePackage,
+    original_id: AccountAddress,
+    modules: &[CompiledModule],
+) -> String {
+    let referenced: BTreeSet<AccountAddress> = modules
+        .iter()
+        .flat_map(|m| {
+            m.module_handles()
+                .iter()
+                .map(|h| *m.module_id_for_handle(h).address())
+        })
+        .filter(|addr| *addr != original_id)
+        .collect();
+
+    let rev = match network {
+        "mainnet" | "testnet" | "devnet" => format!("framework/{network}"),
+        _ => "framework/mainnet".to_string(),
+    };
+
+    let mut dependencies = BTreeMap::new();
+    for addr in referenced {
+        if is_system_package(addr) {
+            let (name, subdir) = system_package_source(addr);
+            dependencies.insert(
+                name.to_string(),
+                format!(
+                    "{{ git = \"{SUI_GIT_URL}\", subdir = \"crates/sui-framework/packages/{subdir}\", rev = \"{rev}\" }}"
+                ),
+            );
+        } else {
+            let id = package
+                .linkage_table
+                .get(&ObjectID::from(addr))
+                .map_or(ObjectID::from(addr), |info| info.upgraded_id);
+            dependencies.insert(
+                on_chain_dep_name(addr),
+                format!("{{ on-chain = \"{id}\", network = \"{network}\" }}"),
+            );
+        }
+    }
+
+    let mut manifest = String::new();
+    let _ = writeln!(manifest, "[package]");
+    let _ = writeln!(manifest, "name = \"{dep_name}\"");
+    let _ = writeln!(manifest, "published-at = \"{}\"", package.id);
+    let _ = writeln!(manifest);
+    let _ = writeln!(manifest, "[dependencies]");
+    for (name, dep) in dependencies {
+        let _ = writeln!(manifest, "{name} = {dep}");
+    }
+    let _ = writeln!(manifest);
+    let _ = writeln!(manifest, "[addresses]");
+    let _ = writeln!(
+        manifest,
+        "{named_address} = \"{}\"",
+        original_id.to_hex_literal()
+    );
+    manifest
+}
+
+/// The name and directory in the framework's repository of a system package.
+fn system_package_source(addr: AccountAddress) -> (&'static str, &'static str) {
+    match addr {
+        SUI_SYSTEM_ADDRESS => ("SuiSystem", "sui-system"),
+        DEEPBOOK_ADDRESS => ("DeepBook", "deepbook"),
+        // The standard library comes with the framework.
+        MOVE_STDLIB_ADDRESS | SUI_FRAMEWORK_ADDRESS => ("Sui", "sui-framework"),
+        _ => unreachable!("{addr} is not a system package"),
+    }
+}
+
+/// The name of an on-chain dependency on the package originally published at `addr`, for
+/// dependencies discovered while fetching another package.
+fn on_chain_dep_name(addr: AccountAddress) -> String {
+    format!("OnChain_{}", &addr.short_str_lossless())
+}
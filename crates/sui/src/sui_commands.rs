@@ -6,6 +6,7 @@ use crate::console::start_console;
 use crate::fire_drill::{run_fire_drill, FireDrill};
 use crate::genesis_ceremony::{run, Ceremony};
 use crate::keytool::KeyToolCommand;
+use crate::on_chain_deps::OnChainPackageHooks;
 use crate::validator_commands::SuiValidatorCommand;
 use anyhow::{anyhow, bail};
 use clap::*;
@@ -25,9 +26,10 @@ use sui_config::{
 use sui_config::{
     SUI_BENCHMARK_GENESIS_GAS_KEYSTORE_FILENAME, SUI_GENESIS_FILENAME, SUI_KEYSTORE_FILENAME,
 };
+use sui_keys::encrypted_keystore::EncryptedFileKeystore;
+use sui_keys::external_signer::{ExternalKeystore, ExternalSignerTransport};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_move::{self, execute_move_command};
-use sui_move_build::SuiPackageHooks;
 use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv};
 use sui_sdk::wallet_context::WalletContext;
 use sui_swarm::memory::Swarm;
@@ -93,6 +95,10 @@ pub enum SuiCommand {
     KeyTool {
         #[clap(long)]
         keystore_path: Option<PathBuf>,
+        /// Use an external signer instead of the keystore file: either a command line to spawn
+        /// for every request, or `unix:<path>` for a signer listening on a Unix domain socket.
+        #[clap(long, conflicts_with = "keystore_path")]
+        external_signer: Option<ExternalSignerTransport>,
         ///Return command outputs in json format
         #[clap(long, global = true)]
         json: bool,
@@ -120,6 +126,9 @@ pub enum SuiCommand {
         json: bool,
         #[clap(short = 'y', long = "yes")]
         accept_defaults: bool,
+        /// Do not dry run and print a preview of transactions before signing them.
+        #[clap(long, global = true)]
+        no_preview: bool,
     },
     /// A tool for validators and validator candidates.
     #[clap(name = "validator")]
@@ -159,7 +168,7 @@ pub enum SuiCommand {
 
 impl SuiCommand {
     pub async fn execute(self) -> Result<(), anyhow::Error> {
-        move_package::package_hooks::register_package_hooks(Box::new(SuiPackageHooks));
+        move_package::package_hooks::register_package_hooks(Box::new(OnChainPackageHooks));
         match self {
             SuiCommand::Start {
                 config,
@@ -261,12 +270,28 @@ impl SuiCommand {
             SuiCommand::GenesisCeremony(cmd) => run(cmd),
             SuiCommand::KeyTool {
                 keystore_path,
+                external_signer,
                 json,
-                cmd,
+                mut cmd,
             } => {
+                // Nobody is there to confirm signing when the output is read as JSON.
+                if let KeyToolCommand::MultiSigSignRequest { yes, .. } = &mut cmd {
+                    *yes |= json;
+                }
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+                let mut keystore = if let Some(transport) = external_signer {
+                    Keystore::from(ExternalKeystore::new(transport)?)
+                } else if EncryptedFileKeystore::is_encrypted(&keystore_path) {
+                    if let KeyToolCommand::Lock = cmd {
+                        // No need to unlock the keystore just to end its session.
+                        EncryptedFileKeystore::lock(&keystore_path)?;
+                        return Ok(());
+                    }
+                    Keystore::from(EncryptedFileKeystore::unlock(&keystore_path)?)
+                } else {
+                    Keystore::from(FileBasedKeystore::new(&keystore_path)?)
+                };
                 cmd.execute(&mut keystore).await?.print(!json);
                 Ok(())
             }
@@ -281,10 +306,13 @@ impl SuiCommand {
                 cmd,
                 json,
                 accept_defaults,
+                no_preview,
             } => {
                 let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
                 prompt_if_no_config(&config_path, accept_defaults).await?;
-                let mut context = WalletContext::new(&config_path, None, None).await?;
+                let mut context = WalletContext::new(&config_path, None, None)
+                    .await?
+                    .with_transaction_preview(!no_preview);
                 if let Some(cmd) = cmd {
                     cmd.execute(&mut context).await?.print(!json);
                 } else {
@@ -546,6 +574,7 @@ async fn genesis(
         alias: "localnet".to_string(),
         rpc: format!("http://{}", fullnode_config.json_rpc_address),
         ws: None,
+        ..Default::default()
     });
     client_config.add_env(SuiEnv::devnet());
 
@@ -570,6 +599,7 @@ async fn prompt_if_no_config(
                 alias: "custom".to_string(),
                 rpc: v.into_string().unwrap(),
                 ws: None,
+                ..Default::default()
             }),
             None => {
                 if accept_defaults {
@@ -605,6 +635,7 @@ async fn prompt_if_no_config(
                             alias,
                             rpc: url,
                             ws: None,
+                            ..Default::default()
                         }
                     })
                 } else {
@@ -0,0 +1,224 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::u256::U256;
+use sui_types::{
+    base_types::{ObjectID, SuiAddress},
+    gas::GasCostSummary,
+    parse_sui_type_tag,
+};
+
+use crate::client_ptb::{
+    estimate_gas_budget, parse_ptb, pure_value, ArgType, PtbCommand, PtbValue, Statement,
+};
+
+fn address(s: &str) -> SuiAddress {
+    ObjectID::from_hex_literal(&format!("0x{s}"))
+        .unwrap()
+        .into()
+}
+
+#[test]
+fn test_parse_builtin_commands() {
+    let statements = parse_ptb(
+        "
+        # split off two coins
+        coins = split_coins(gas, [1000, 2_000u64])
+        merge_coins(@0xc0, [coins.0, coins.1])
+        transfer_objects([coins.0, @0xc1,], sender)
+        v = make_move_vec<u64>([1, 2])
+        cap = publish(\"../pkg\")
+        upgrade(\"../pkg\", @0xca)
+        ",
+    )
+    .unwrap();
+    assert_eq!(
+        statements,
+        vec![
+            Statement {
+                name: Some("coins".to_string()),
+                command: PtbCommand::SplitCoins(
+                    PtbValue::Gas,
+                    vec![
+                        PtbValue::Number("1000".to_string(), None),
+                        PtbValue::Number("2000".to_string(), Some(ArgType::U64)),
+                    ]
+                ),
+            },
+            Statement {
+                name: None,
+                command: PtbCommand::MergeCoins(
+                    PtbValue::Address(address("c0")),
+                    vec![
+                        PtbValue::Result("coins".to_string(), Some(0)),
+                        PtbValue::Result("coins".to_string(), Some(1)),
+                    ]
+                ),
+            },
+            Statement {
+                name: None,
+                command: PtbCommand::TransferObjects(
+                    vec![
+                        PtbValue::Result("coins".to_string(), Some(0)),
+                        PtbValue::Address(address("c1")),
+                    ],
+                    PtbValue::Sender,
+                ),
+            },
+            Statement {
+                name: Some("v".to_string()),
+                command: PtbCommand::MakeMoveVec(
+                    Some(parse_sui_type_tag("u64").unwrap()),
+                    vec![
+                        PtbValue::Number("1".to_string(), None),
+                        PtbValue::Number("2".to_string(), None),
+                    ]
+                ),
+            },
+            Statement {
+                name: Some("cap".to_string()),
+                command: PtbCommand::Publish("../pkg".into()),
+            },
+            Statement {
+                name: None,
+                command: PtbCommand::Upgrade("../pkg".into(), PtbValue::Address(address("ca"))),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_move_call() {
+    let statements = parse_ptb(
+        "r = 0x2::coin::join<0x2::coin::Coin<0x2::sui::SUI>, u8>(\
+            c, [some(\"a \\\"b\\\"\"), none], true, @0x1)",
+    )
+    .unwrap();
+    assert_eq!(
+        statements,
+        vec![Statement {
+            name: Some("r".to_string()),
+            command: PtbCommand::MoveCall {
+                package: ObjectID::from_hex_literal("0x2").unwrap(),
+                module: "coin".to_string(),
+                function: "join".to_string(),
+                type_args: vec![
+                    parse_sui_type_tag("0x2::coin::Coin<0x2::sui::SUI>").unwrap(),
+                    parse_sui_type_tag("u8").unwrap(),
+                ],
+                args: vec![
+                    PtbValue::Result("c".to_string(), None),
+                    PtbValue::Vector(vec![
+                        PtbValue::OptionSome(Box::new(PtbValue::String("a \"b\"".to_string()))),
+                        PtbValue::OptionNone,
+                    ]),
+                    PtbValue::Bool(true),
+                    PtbValue::Address(address("1")),
+                ],
+            },
+        }]
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    for source in [
+        "split_coins(gas [1000])",
+        "split_coins(gas, [1000]",
+        "split_coins(gas, [1000]) extra",
+        "split_coins<u64>(gas, [1000])",
+        "send(gas, @0x1)",
+        "0x2::coin::value<0x2::sui::SUI(c)",
+        "transfer_objects([c], \"unterminated)",
+        "split_coins(gas, [10u7])",
+        "1x = split_coins(gas, [10])",
+    ] {
+        assert!(parse_ptb(source).is_err(), "{source}");
+    }
+}
+
+#[test]
+fn test_pure_value() {
+    let sender = address("5e");
+    let number = |n: &str| PtbValue::Number(n.to_string(), None);
+    let pure_bytes = |value: &PtbValue, ty: &ArgType| -> anyhow::Result<Vec<u8>> {
+        Ok(bcs::to_bytes(&pure_value(value, ty, sender)?)?)
+    };
+    let vector = |ty: ArgType| ArgType::Vector(Box::new(ty));
+
+    assert_eq!(pure_bytes(&number("7"), &ArgType::U8).unwrap(), vec![7]);
+    assert_eq!(
+        pure_bytes(&number("1000"), &ArgType::U64).unwrap(),
+        bcs::to_bytes(&1000u64).unwrap()
+    );
+    assert_eq!(
+        pure_bytes(&number("1000"), &ArgType::U256).unwrap(),
+        bcs::to_bytes(&U256::from(1000u64)).unwrap()
+    );
+    // the suffix gives the type when it cannot be inferred
+    assert_eq!(
+        pure_bytes(
+            &PtbValue::Number("7".to_string(), Some(ArgType::U16)),
+            &ArgType::Unknown
+        )
+        .unwrap(),
+        bcs::to_bytes(&7u16).unwrap()
+    );
+    assert_eq!(
+        pure_bytes(&PtbValue::Sender, &ArgType::Address).unwrap(),
+        bcs::to_bytes(&sender).unwrap()
+    );
+    assert_eq!(
+        pure_bytes(&PtbValue::String("sui".to_string()), &vector(ArgType::U8)).unwrap(),
+        bcs::to_bytes("sui").unwrap()
+    );
+    assert_eq!(
+        pure_bytes(
+            &PtbValue::Vector(vec![
+                PtbValue::OptionSome(Box::new(number("1"))),
+                PtbValue::OptionNone
+            ]),
+            &vector(ArgType::Option(Box::new(ArgType::U32)))
+        )
+        .unwrap(),
+        bcs::to_bytes(&vec![Some(1u32), None]).unwrap()
+    );
+    // lengths of more than one byte
+    let elements: Vec<u64> = (0..300).collect();
+    assert_eq!(
+        pure_bytes(
+            &PtbValue::Vector(elements.iter().map(|e| number(&e.to_string())).collect()),
+            &vector(ArgType::U64)
+        )
+        .unwrap(),
+        bcs::to_bytes(&elements).unwrap()
+    );
+
+    assert!(pure_bytes(&number("256"), &ArgType::U8).is_err());
+    assert!(pure_bytes(&number("1"), &ArgType::Unknown).is_err());
+    assert!(pure_bytes(&PtbValue::Bool(true), &ArgType::U64).is_err());
+    assert!(pure_bytes(&PtbValue::Gas, &ArgType::Address).is_err());
+    // suffixes must agree with the expected type
+    assert!(pure_bytes(
+        &PtbValue::Number("7".to_string(), Some(ArgType::U16)),
+        &ArgType::U64
+    )
+    .is_err());
+    assert!(pure_bytes(
+        &PtbValue::Vector(vec![
+            PtbValue::Number("1".to_string(), Some(ArgType::U8)),
+            number("2")
+        ]),
+        &vector(ArgType::U64)
+    )
+    .is_err());
+}
+
+#[test]
+fn test_estimate_gas_budget() {
+    let gas_used = GasCostSummary::new(2_000_000, 3_000_000, 1_000_000, 0);
+    assert_eq!(estimate_gas_budget(&gas_used, 1000), 5_000_000);
+    // the rebate cannot bring the budget below the computation cost
+    let gas_used = GasCostSummary::new(2_000_000, 0, 9_000_000, 0);
+    assert_eq!(estimate_gas_budget(&gas_used, 1000), 3_000_000);
+}
@@ -6,8 +6,10 @@ use std::str::FromStr;
 use crate::key_identity::KeyIdentity;
 use crate::keytool::read_authority_keypair_from_file;
 use crate::keytool::read_keypair_from_file;
+use crate::multisig_request::MultiSigSigningRequest;
 
 use super::write_keypair_to_file;
+use super::CommandOutput;
 use super::KeyToolCommand;
 use anyhow::Ok;
 use fastcrypto::encoding::Base64;
@@ -15,7 +17,9 @@ use fastcrypto::encoding::Encoding;
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use shared_crypto::intent::Intent;
+use shared_crypto::intent::IntentMessage;
 use shared_crypto::intent::IntentScope;
+use sui_keys::encrypted_keystore::{EncryptedFileKeystore, KdfParams};
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
 use sui_types::base_types::ObjectDigest;
 use sui_types::base_types::ObjectID;
@@ -32,6 +36,8 @@ use sui_types::crypto::Signature;
 use sui_types::crypto::SignatureScheme;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::SuiSignatureInner;
+use sui_types::multisig::MultiSigPublicKey;
+use sui_types::signature::{AuthenticatorTrait, VerifyParams};
 use sui_types::transaction::TransactionData;
 use sui_types::transaction::TEST_ONLY_GAS_UNIT_FOR_TRANSFER;
 use tempfile::TempDir;
@@ -526,3 +532,161 @@ async fn test_sign_command() -> Result<(), anyhow::Error> {
     .await?;
     Ok(())
 }
+
+#[test]
+async fn test_encrypted_keystore_commands() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let kdf_params = KdfParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+    let mut keystore = Keystore::from(EncryptedFileKeystore::new(
+        &keystore_path,
+        "passphrase",
+        kdf_params,
+    )?);
+    keystore.add_key(None, SuiKeyPair::Ed25519(get_key_pair().1))?;
+
+    let output = KeyToolCommand::Unlock { timeout_secs: 60 }
+        .execute(&mut keystore)
+        .await?;
+    assert!(EncryptedFileKeystore::session_expiry(&keystore_path).is_some());
+    let CommandOutput::Keystore(status) = output else {
+        panic!("Expected a keystore status");
+    };
+    let secret = serde_json::to_value(&status)?["sessionSecret"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(
+        keystore.addresses(),
+        EncryptedFileKeystore::open_session(&keystore_path, &secret)?.addresses()
+    );
+    KeyToolCommand::Lock.execute(&mut keystore).await?;
+    assert!(EncryptedFileKeystore::session_expiry(&keystore_path).is_none());
+
+    let export_path = temp_dir.path().join("exported.keystore");
+    KeyToolCommand::ExportKeystore {
+        path: export_path.clone(),
+    }
+    .execute(&mut keystore)
+    .await?;
+    assert_eq!(
+        keystore.addresses(),
+        FileBasedKeystore::new(&export_path)?.addresses()
+    );
+
+    // Plaintext keystores have no session or passphrase.
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(1));
+    assert!(KeyToolCommand::Unlock { timeout_secs: 60 }
+        .execute(&mut keystore)
+        .await
+        .is_err());
+    assert!(KeyToolCommand::ChangePassphrase
+        .execute(&mut keystore)
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[test]
+async fn test_multisig_request_workflow() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(3));
+    let outsider = SuiKeyPair::Ed25519(get_key_pair().1);
+    let outsider_address = SuiAddress::from(&outsider.public());
+    let pks = keystore.keys();
+    let members = keystore.addresses();
+    let weights = vec![1, 1, 1];
+    let multisig_pk = MultiSigPublicKey::new(pks.clone(), weights.clone(), 2)?;
+    let multisig_address = SuiAddress::from(&multisig_pk);
+    keystore.add_key(None, outsider)?;
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let tx_data = TransactionData::new_pay_sui(
+        multisig_address,
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        1,
+    )
+    .unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let request_path = temp_dir.path().join("request.json");
+    KeyToolCommand::MultiSigCreateRequest {
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+        pks: pks.clone(),
+        weights: weights.clone(),
+        threshold: 2,
+        output: request_path.clone(),
+    }
+    .execute(&mut keystore)
+    .await?;
+    let status = MultiSigSigningRequest::read(&request_path)?.status()?;
+    assert_eq!(multisig_address, status.transaction.sender);
+    assert_eq!(0, status.signed_weight);
+    assert!(!status.ready);
+
+    // A transaction that is not sent from the MultiSig address is refused.
+    let other_tx_data = TransactionData::new_pay_sui(
+        members[0],
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        1,
+    )
+    .unwrap();
+    assert!(MultiSigSigningRequest::new(&other_tx_data, pks, weights, 2).is_err());
+
+    let sign = |address: SuiAddress| KeyToolCommand::MultiSigSignRequest {
+        request: request_path.clone(),
+        address: KeyIdentity::Address(address),
+        yes: true,
+    };
+    sign(members[0]).execute(&mut keystore).await?;
+    // Signing twice with the same member does not add weight.
+    sign(members[0]).execute(&mut keystore).await?;
+    let request = MultiSigSigningRequest::read(&request_path)?;
+    assert_eq!(1, request.status()?.signed_weight);
+    assert!(request.combine().is_err());
+    assert!(KeyToolCommand::MultiSigCombineRequest {
+        request: request_path.clone(),
+    }
+    .execute(&mut keystore)
+    .await
+    .is_err());
+
+    // Only members can sign.
+    assert!(sign(outsider_address).execute(&mut keystore).await.is_err());
+
+    sign(members[2]).execute(&mut keystore).await?;
+    let request = MultiSigSigningRequest::read(&request_path)?;
+    let status = request.status()?;
+    assert_eq!(2, status.signed_weight);
+    assert!(status.ready);
+    assert!(status.members[0].signed && !status.members[1].signed && status.members[2].signed);
+
+    let multisig = request.combine()?;
+    multisig.verify_authenticator(
+        &IntentMessage::new(Intent::sui_transaction(), tx_data),
+        multisig_address,
+        None,
+        &VerifyParams::default(),
+    )?;
+    KeyToolCommand::MultiSigCombineRequest {
+        request: request_path,
+    }
+    .execute(&mut keystore)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,72 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::BTreeMap, fs};
+
+use move_symbol_pool::Symbol;
+use sui_framework::BuiltInFramework;
+use sui_json_rpc_types::SuiRawMovePackage;
+use sui_sdk::SUI_TESTNET_URL;
+use sui_types::SUI_SYSTEM_PACKAGE_ID;
+
+use crate::on_chain_deps::{network_rpc_url, write_package, VERIFIED_SOURCES_DIR};
+
+fn sui_system_package() -> SuiRawMovePackage {
+    BuiltInFramework::genesis_move_packages()
+        .find(|p| p.id() == SUI_SYSTEM_PACKAGE_ID)
+        .unwrap()
+        .into()
+}
+
+#[test]
+fn test_network_rpc_url() {
+    assert_eq!(network_rpc_url("testnet").unwrap(), SUI_TESTNET_URL);
+    assert_eq!(
+        network_rpc_url("http://127.0.0.1:9123").unwrap(),
+        "http://127.0.0.1:9123"
+    );
+    assert!(network_rpc_url("moonnet").is_err());
+}
+
+#[test]
+fn test_write_package() {
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("onchain_testnet_0x3_SystemStubs");
+    let package = sui_system_package();
+    let verified_sources = BTreeMap::from([(
+        "validator".to_string(),
+        "module sui_system::validator {}".to_string(),
+    )]);
+
+    write_package(
+        Symbol::from("SystemStubs"),
+        "testnet",
+        &package,
+        &verified_sources,
+        &dest,
+    )
+    .unwrap();
+
+    let manifest = fs::read_to_string(dest.join("Move.toml")).unwrap();
+    assert!(manifest.contains("name = \"SystemStubs\""));
+    assert!(manifest.contains(&format!("published-at = \"{}\"", package.id)));
+    // The standard library and the framework both come from the framework's package.
+    assert!(manifest.contains(
+        "Sui = { git = \"https://github.com/MystenLabs/sui.git\", \
+         subdir = \"crates/sui-framework/packages/sui-framework\", rev = \"framework/testnet\" }"
+    ));
+    assert_eq!(manifest.matches(" = { ").count(), 1);
+    assert!(manifest.contains("systemstubs = \"0x3\""));
+
+    let stub = fs::read_to_string(dest.join("sources").join("sui_system.move")).unwrap();
+    assert!(stub.starts_with("module systemstubs::sui_system {"));
+    assert!(stub.contains("native public entry fun request_add_stake("));
+    assert_eq!(
+        fs::read_dir(dest.join("sources")).unwrap().count(),
+        package.module_map.len()
+    );
+    assert!(dest
+        .join(VERIFIED_SOURCES_DIR)
+        .join("validator.move")
+        .exists());
+}
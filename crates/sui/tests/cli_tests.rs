@@ -8,6 +8,7 @@ use std::str::FromStr;
 use std::{fmt::Write, fs::read_dir, path::PathBuf, str, thread, time::Duration};
 
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 use move_package::BuildConfig as MoveBuildConfig;
 use serde_json::json;
 use sui::key_identity::{get_identity_address, KeyIdentity};
@@ -22,7 +23,7 @@ use tokio::time::sleep;
 
 use sui::client_commands::SwitchResponse;
 use sui::{
-    client_commands::{SuiClientCommandResult, SuiClientCommands},
+    client_commands::{EnvSettings, SuiClientCommandResult, SuiClientCommands},
     sui_commands::SuiCommand,
 };
 use sui_config::{
@@ -31,8 +32,9 @@ use sui_config::{
 };
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    OwnedObjectRef, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI,
+    ObjectChange, OwnedObjectRef, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI,
 };
 use sui_keys::keystore::AccountKeystore;
 use sui_macros::sim_test;
@@ -46,11 +48,23 @@ use sui_types::crypto::{
     Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
-use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
+use sui_types::{
+    base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin, SUI_FRAMEWORK_PACKAGE_ID,
+};
 use test_cluster::TestClusterBuilder;
 
 const TEST_DATA_DIR: &str = "tests/data/";
 
+/// The bindings of the `docgen` test package, behind a license header. `test_bindgen` checks that
+/// they are up to date, and including them here checks that the generated code compiles.
+/// Regenerate them with `sui client bindgen tests/data/docgen`, keeping the header.
+const DOCGEN_BINDINGS: &str = include_str!("data/docgen_bindings.rs");
+
+#[allow(dead_code)]
+mod docgen_bindings {
+    include!("data/docgen_bindings.rs");
+}
+
 #[sim_test]
 async fn test_genesis() -> Result<(), anyhow::Error> {
     let temp_dir = tempfile::tempdir()?;
@@ -349,7 +363,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
         to: KeyIdentity::Address(SuiAddress::random_for_testing_only()),
         object_id: object_to_send,
         gas: Some(object_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -398,7 +412,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_PUBLISH * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_PUBLISH * rgp),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -481,7 +495,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args,
         gas: None,
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -520,7 +534,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -546,7 +560,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -574,7 +588,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -619,7 +633,7 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -686,7 +700,7 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -720,7 +734,7 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         function: "start".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
@@ -742,7 +756,7 @@ async fn test_delete_shared_object() -> Result<(), anyhow::Error> {
         function: "delete".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![SuiJsonValue::from_str(&shared_id.to_string()).unwrap()],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
@@ -793,7 +807,7 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -827,7 +841,7 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         function: "start".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
@@ -865,7 +879,7 @@ async fn test_receive_argument() -> Result<(), anyhow::Error> {
         function: "receiver".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![
             SuiJsonValue::from_str(&parent.object_id.to_string()).unwrap(),
             SuiJsonValue::from_str(&child.object_id.to_string()).unwrap(),
@@ -919,7 +933,7 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -953,7 +967,7 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         function: "start".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
@@ -991,7 +1005,7 @@ async fn test_receive_argument_by_immut_ref() -> Result<(), anyhow::Error> {
         function: "invalid_call_immut_ref".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![
             SuiJsonValue::from_str(&parent.object_id.to_string()).unwrap(),
             SuiJsonValue::from_str(&child.object_id.to_string()).unwrap(),
@@ -1045,7 +1059,7 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -1079,7 +1093,7 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         function: "start".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![],
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
@@ -1117,7 +1131,7 @@ async fn test_receive_argument_by_mut_ref() -> Result<(), anyhow::Error> {
         function: "invalid_call_mut_ref".to_string(),
         type_args: vec![],
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         args: vec![
             SuiJsonValue::from_str(&parent.object_id.to_string()).unwrap(),
             SuiJsonValue::from_str(&child.object_id.to_string()).unwrap(),
@@ -1173,7 +1187,7 @@ async fn test_package_publish_command_with_unpublished_dependency_succeeds(
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
@@ -1241,7 +1255,7 @@ async fn test_package_publish_command_with_unpublished_dependency_fails(
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
@@ -1287,7 +1301,7 @@ async fn test_package_publish_command_non_zero_unpublished_dep_fails() -> Result
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
@@ -1342,7 +1356,7 @@ async fn test_package_publish_command_failure_invalid() -> Result<(), anyhow::Er
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
@@ -1384,7 +1398,7 @@ async fn test_package_publish_nonexistent_dependency() -> Result<(), anyhow::Err
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -1427,7 +1441,7 @@ async fn test_package_publish_test_flag() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -1482,7 +1496,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         package_path: package_path.clone(),
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -1554,7 +1568,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         upgrade_capability: cap.reference.object_id,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -1618,7 +1632,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         gas: Some(gas_obj_id),
         to: KeyIdentity::Address(recipient),
         object_id: obj_id,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -1723,7 +1737,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         gas: None,
         to: KeyIdentity::Address(recipient),
         object_id: obj_id,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -2038,7 +2052,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         primary_coin,
         coin_to_merge,
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -2093,7 +2107,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         primary_coin,
         coin_to_merge,
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -2125,6 +2139,87 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_ptb() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let coin = context
+        .gas_objects(address)
+        .await?
+        .first()
+        .unwrap()
+        .1
+        .object_id;
+
+    let commands = vec![
+        "coins = split_coins(gas, [1000, 2000])".to_string(),
+        "merge_coins(coins.0, [coins.1])".to_string(),
+        format!(
+            "0x2::transfer::public_transfer<0x2::coin::Coin<0x2::sui::SUI>>(coins.0, @{recipient})"
+        ),
+        format!("transfer_objects([@{coin}], @{recipient})"),
+    ];
+    let ptb = |dry_run| SuiClientCommands::Ptb {
+        commands: commands.clone(),
+        file: None,
+        gas: None,
+        gas_budget: None,
+        dry_run,
+        profile_gas: None,
+        skip_dependency_verification: false,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    };
+
+    // A dry run estimates the gas budget, without executing the transaction
+    let SuiClientCommandResult::PtbDryRun {
+        gas_budget,
+        response,
+    } = ptb(true).execute(context).await?
+    else {
+        panic!("Expected a dry run response");
+    };
+    assert!(response.effects.status().is_ok());
+    assert!(gas_budget > 0);
+    assert_eq!(context.get_object_owner(&coin).await?, address);
+
+    let SuiClientCommandResult::Ptb(response) = ptb(false).execute(context).await? else {
+        panic!("Expected a transaction response");
+    };
+    assert!(
+        response.status_ok().unwrap(),
+        "Command failed: {response:?}"
+    );
+    assert_eq!(context.get_object_owner(&coin).await?, recipient);
+    let created = response.effects.as_ref().unwrap().created().to_vec();
+    assert_eq!(created.len(), 1);
+    let new_coin = get_object(created[0].reference.object_id, context)
+        .await
+        .unwrap();
+    assert_eq!(get_gas_value(&new_coin), 3000);
+    assert_eq!(created[0].owner, Owner::AddressOwner(recipient));
+
+    // Unknown results and ill-typed arguments are reported before executing anything
+    let result = SuiClientCommands::Ptb {
+        commands: vec!["transfer_objects([coins.0], sender)".to_string()],
+        file: None,
+        gas: None,
+        gas_budget: None,
+        dry_run: false,
+        profile_gas: None,
+        skip_dependency_verification: false,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_split_coin() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -2156,7 +2251,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test with gas specified
     let resp = SuiClientCommands::SplitCoin {
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
@@ -2224,7 +2319,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test split coin into equal parts
     let resp = SuiClientCommands::SplitCoin {
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: None,
         count: Some(3),
@@ -2295,7 +2390,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test with no gas specified
     let resp = SuiClientCommands::SplitCoin {
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
@@ -2406,7 +2501,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     SuiClientCommands::TransferSui {
         to: KeyIdentity::Address(address1),
         sui_coin_object_id: coin,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         amount: Some(1),
         serialize_unsigned_transaction: true,
         serialize_signed_transaction: false,
@@ -2417,7 +2512,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     SuiClientCommands::TransferSui {
         to: KeyIdentity::Address(address1),
         sui_coin_object_id: coin,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         amount: Some(1),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: true,
@@ -2429,7 +2524,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     SuiClientCommands::TransferSui {
         to: KeyIdentity::Alias(alias1),
         sui_coin_object_id: coin,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         amount: Some(1),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: true,
@@ -2439,6 +2534,67 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_preview_tx() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?
+        .data;
+
+    let resp = SuiClientCommands::SplitCoin {
+        gas: Some(coins[0].coin_object_id),
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
+        coin_id: coins[1].coin_object_id,
+        amounts: Some(vec![1000, 10]),
+        count: None,
+        serialize_unsigned_transaction: true,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) = resp else {
+        panic!("Expected SerializedUnsignedTransaction, got {resp:?}");
+    };
+
+    let resp = SuiClientCommands::PreviewTx {
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::PreviewTx(preview) = resp else {
+        panic!("Expected PreviewTx, got {resp:?}");
+    };
+    assert!(preview.status.is_ok());
+    assert_eq!(preview.sender, address);
+    assert_eq!(preview.move_calls.len(), 1);
+    let call = &preview.move_calls[0];
+    assert_eq!(call.module, "pay");
+    assert_eq!(call.function, "split_vec");
+    assert_eq!(call.type_arguments, vec!["0x2::sui::SUI"]);
+    // The TxContext parameter is not part of the preview.
+    assert_eq!(
+        call.parameters,
+        vec!["&mut 0x2::coin::Coin<0x2::sui::SUI>", "vector<u64>"]
+    );
+    // The two new coins are created, the split coin and the gas coin are mutated.
+    let created = preview
+        .object_changes
+        .iter()
+        .filter(|change| matches!(change, ObjectChange::Created { .. }))
+        .count();
+    assert_eq!(created, 2);
+    assert!(preview.gas.estimate > 0);
+    assert!(!preview.gas.budget_too_low());
+    assert!(preview.to_string().contains("Transaction Preview"));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_stake_with_none_amount() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -2644,6 +2800,54 @@ async fn test_linter_suppression_stats() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_move_docgen() -> Result<(), anyhow::Error> {
+    let temp_dir = tempfile::tempdir()?;
+    let output_dir = temp_dir.path().to_str().unwrap();
+    for format in ["html", "markdown"] {
+        let mut cmd = assert_cmd::Command::cargo_bin("sui").unwrap();
+        let args = vec![
+            "move",
+            "docgen",
+            "--path",
+            "tests/data/docgen",
+            "--output-directory",
+            output_dir,
+            "--format",
+            format,
+        ];
+        let output = cmd
+            .args(&args)
+            .output()
+            .expect("failed to run 'sui move docgen'");
+        assert!(output.status.success());
+    }
+
+    let page = std::fs::read_to_string(temp_dir.path().join("0x0/counter.html"))?;
+    assert!(page.contains("<span class=\"badge\">shared</span>"));
+    assert!(page.contains("<span class=\"badge\">entry</span>"));
+    assert!(page.contains("Emitted by"));
+    assert!(page.contains("The current value of the counter."));
+
+    let page = std::fs::read_to_string(temp_dir.path().join("0x0/counter.md"))?;
+    assert!(page.contains("Object: `shared`"));
+    assert!(page.contains("Emits"));
+
+    // dependencies are documented and linked to
+    let index = std::fs::read_to_string(temp_dir.path().join("index.html"))?;
+    assert!(index.contains("0x2/object.html"));
+
+    let search_index: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        temp_dir.path().join("search-index.json"),
+    )?)?;
+    assert!(search_index
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|e| e["name"] == "0x0::counter::increment" && e["kind"] == "function"));
+    Ok(())
+}
+
 #[tokio::test]
 async fn key_identity_test() {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -2674,3 +2878,189 @@ async fn key_identity_test() {
         get_identity_address(None, context).unwrap()
     );
 }
+
+#[sim_test]
+async fn test_bindgen() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let context = &mut test_cluster.wallet;
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("docgen");
+
+    let resp = SuiClientCommands::Bindgen {
+        package_path,
+        build_config: BuildConfig::new_for_testing().config,
+        package: None,
+        published_at: None,
+        output: None,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Bindgen { source, .. } = resp else {
+        panic!("Expected Bindgen, got {resp:?}");
+    };
+    let (_license, expected) = DOCGEN_BINDINGS.split_once("\n\n").unwrap();
+    assert_eq!(
+        source, expected,
+        "tests/data/docgen_bindings.rs is out of date"
+    );
+
+    // Bindings of an on-chain package, written to a file.
+    let temp_dir = tempfile::tempdir()?;
+    let output = temp_dir.path().join("sui_framework.rs");
+    SuiClientCommands::Bindgen {
+        package_path: PathBuf::from("."),
+        build_config: BuildConfig::new_for_testing().config,
+        package: Some(SUI_FRAMEWORK_PACKAGE_ID),
+        published_at: None,
+        output: Some(output.clone()),
+    }
+    .execute(context)
+    .await?;
+    let source = std::fs::read_to_string(output)?;
+    assert!(source.contains("pub mod coin {"));
+    assert!(source.contains("pub balance: super::balance::Balance,"));
+    // Pure arguments take their Rust type.
+    assert!(source.contains("arg1: u64,"));
+    assert!(source.contains("type_args: [sui_sdk::types::TypeTag; 1],"));
+    Ok(())
+}
+
+#[sim_test]
+async fn test_env_settings() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let recipient = SuiAddress::random_for_testing_only();
+    let context = &mut test_cluster.wallet;
+    let coin = context
+        .gas_objects(address)
+        .await?
+        .first()
+        .map(|(_, coin)| coin.object_id)
+        .unwrap();
+
+    // Without a default gas budget, the gas budget is required.
+    let result = SuiClientCommands::TransferSui {
+        to: KeyIdentity::Address(recipient),
+        sui_coin_object_id: coin,
+        gas_budget: None,
+        amount: Some(1),
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await;
+    assert!(result.is_err());
+
+    let resp = SuiClientCommands::UpdateEnv {
+        alias: None,
+        rpc: None,
+        ws: None,
+        settings: EnvSettings {
+            faucet: Some("http://127.0.0.1:9123/gas".to_string()),
+            gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+            ..Default::default()
+        },
+        add_address_alias: vec![("bob".to_string(), recipient)],
+        remove_address_alias: vec![],
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::UpdateEnv(env) = resp else {
+        panic!("Expected UpdateEnv, got {resp:?}");
+    };
+    assert_eq!(env.alias, "localnet");
+    assert_eq!(env.faucet.as_deref(), Some("http://127.0.0.1:9123/gas"));
+
+    // The env's gas budget and address alias are used.
+    SuiClientCommands::TransferSui {
+        to: KeyIdentity::Alias("bob".to_string()),
+        sui_coin_object_id: coin,
+        gas_budget: None,
+        amount: Some(1),
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let balance = context
+        .get_client()
+        .await?
+        .coin_read_api()
+        .get_balance(recipient, None)
+        .await?;
+    assert_eq!(balance.total_balance, 1);
+
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("envs.json");
+    SuiClientCommands::ExportEnvs {
+        output: path.clone(),
+        envs: vec!["localnet".to_string()],
+        encrypt: false,
+    }
+    .execute(context)
+    .await?;
+
+    // Importing into a config that already has the env skips it, unless overwriting.
+    context
+        .config
+        .get_env_mut("localnet")?
+        .address_aliases
+        .clear();
+    let resp = SuiClientCommands::ImportEnvs {
+        path: path.clone(),
+        overwrite: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ImportEnvs(import) = resp else {
+        panic!("Expected ImportEnvs, got {resp:?}");
+    };
+    assert_eq!(import.skipped, vec!["localnet"]);
+    SuiClientCommands::ImportEnvs {
+        path,
+        overwrite: true,
+    }
+    .execute(context)
+    .await?;
+    assert_eq!(
+        context.config.get_active_env()?.address_aliases["bob"],
+        recipient
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bindgen_output_builds_calls() {
+    use sui_types::transaction::{Argument, Command};
+
+    let mut builder =
+        sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder::new();
+    let counter = Argument::Input(0);
+    docgen_bindings::counter::increment(&mut builder, counter).unwrap();
+    let pt = builder.finish();
+    let Command::MoveCall(call) = &pt.commands[0] else {
+        panic!("Expected a move call, got {:?}", pt.commands[0]);
+    };
+    assert_eq!(call.package, docgen_bindings::PACKAGE_ID);
+    assert_eq!(call.module.as_str(), "counter");
+    assert_eq!(call.function.as_str(), "increment");
+    assert!(call.type_arguments.is_empty());
+    assert_eq!(call.arguments, vec![counter]);
+
+    let tag = docgen_bindings::counter::Counter::struct_tag();
+    assert_eq!(ObjectID::from(tag.address), ObjectID::ZERO);
+    assert_eq!(tag.module.as_str(), "counter");
+    assert_eq!(tag.name.as_str(), "Counter");
+
+    // Event contents deserialize into the generated structs.
+    let event = docgen_bindings::counter::Incremented {
+        value: 1,
+        by: SuiAddress::ZERO,
+    };
+    let bytes = bcs::to_bytes(&event).unwrap();
+    assert_eq!(
+        bcs::from_bytes::<docgen_bindings::counter::Incremented>(&bytes).unwrap(),
+        event
+    );
+}
@@ -0,0 +1,48 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A shared counter that anyone can increment.
+module docgen::counter {
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+    use sui::event;
+
+    /// The counter cannot be decremented below zero.
+    const EUnderflow: u64 = 0;
+
+    /// A counter shared between all users.
+    struct Counter has key {
+        id: UID,
+        /// The current value of the counter.
+        value: u64,
+    }
+
+    /// Emitted whenever the counter is incremented.
+    struct Incremented has copy, drop {
+        /// The value of the counter after the increment.
+        value: u64,
+        by: address,
+    }
+
+    fun init(ctx: &mut TxContext) {
+        transfer::share_object(Counter { id: object::new(ctx), value: 0 })
+    }
+
+    /// Increments the counter by one.
+    public entry fun increment(counter: &mut Counter, ctx: &TxContext) {
+        counter.value = counter.value + 1;
+        event::emit(Incremented { value: counter.value, by: tx_context::sender(ctx) })
+    }
+
+    /// Decrements the counter by one, aborting if it is already zero.
+    public fun decrement(counter: &mut Counter) {
+        assert!(counter.value > 0, EUnderflow);
+        counter.value = counter.value - 1;
+    }
+
+    /// The current value of the counter.
+    public fun value(counter: &Counter): u64 {
+        counter.value
+    }
+}
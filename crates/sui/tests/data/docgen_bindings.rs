@@ -0,0 +1,103 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+// Generated by `sui client bindgen` for package 0x0000000000000000000000000000000000000000000000000000000000000000, do not edit.
+
+/// The package the functions are called on.
+pub const PACKAGE_ID: sui_sdk::types::base_types::ObjectID = sui_sdk::types::base_types::ObjectID::new([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+/// The package the types are defined in, its first version.
+pub const ORIGINAL_PACKAGE_ID: sui_sdk::types::base_types::ObjectID = sui_sdk::types::base_types::ObjectID::new([0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+pub mod counter {
+    pub const MODULE_NAME: &str = "counter";
+
+    #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Counter {
+        pub id: sui_sdk::types::id::UID,
+        pub value: u64,
+    }
+
+    impl Counter {
+        pub const STRUCT_NAME: &'static str = "Counter";
+
+        pub fn struct_tag() -> move_core_types::language_storage::StructTag {
+            move_core_types::language_storage::StructTag {
+                address: super::ORIGINAL_PACKAGE_ID.into(),
+                module: sui_sdk::types::Identifier::new(MODULE_NAME).unwrap(),
+                name: sui_sdk::types::Identifier::new(Self::STRUCT_NAME).unwrap(),
+                type_params: vec![],
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Incremented {
+        pub value: u64,
+        pub by: sui_sdk::types::base_types::SuiAddress,
+    }
+
+    impl Incremented {
+        pub const STRUCT_NAME: &'static str = "Incremented";
+
+        pub fn struct_tag() -> move_core_types::language_storage::StructTag {
+            move_core_types::language_storage::StructTag {
+                address: super::ORIGINAL_PACKAGE_ID.into(),
+                module: sui_sdk::types::Identifier::new(MODULE_NAME).unwrap(),
+                name: sui_sdk::types::Identifier::new(Self::STRUCT_NAME).unwrap(),
+                type_params: vec![],
+            }
+        }
+    }
+
+    /// `public fun decrement(&mut 0x0::counter::Counter)`
+    #[allow(clippy::too_many_arguments, clippy::vec_init_then_push)]
+    pub fn decrement(
+        builder: &mut sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder,
+        arg0: sui_sdk::types::transaction::Argument,
+    ) -> anyhow::Result<sui_sdk::types::transaction::Argument> {
+        let mut arguments = vec![];
+        arguments.push(arg0);
+        Ok(builder.programmable_move_call(
+            super::PACKAGE_ID,
+            sui_sdk::types::Identifier::new(MODULE_NAME).unwrap(),
+            sui_sdk::types::Identifier::new("decrement").unwrap(),
+            vec![],
+            arguments,
+        ))
+    }
+
+    /// `public entry fun increment(&mut 0x0::counter::Counter)`
+    #[allow(clippy::too_many_arguments, clippy::vec_init_then_push)]
+    pub fn increment(
+        builder: &mut sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder,
+        arg0: sui_sdk::types::transaction::Argument,
+    ) -> anyhow::Result<sui_sdk::types::transaction::Argument> {
+        let mut arguments = vec![];
+        arguments.push(arg0);
+        Ok(builder.programmable_move_call(
+            super::PACKAGE_ID,
+            sui_sdk::types::Identifier::new(MODULE_NAME).unwrap(),
+            sui_sdk::types::Identifier::new("increment").unwrap(),
+            vec![],
+            arguments,
+        ))
+    }
+
+    /// `public fun value(&0x0::counter::Counter)`
+    #[allow(clippy::too_many_arguments, clippy::vec_init_then_push)]
+    pub fn value(
+        builder: &mut sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder,
+        arg0: sui_sdk::types::transaction::Argument,
+    ) -> anyhow::Result<sui_sdk::types::transaction::Argument> {
+        let mut arguments = vec![];
+        arguments.push(arg0);
+        Ok(builder.programmable_move_call(
+            super::PACKAGE_ID,
+            sui_sdk::types::Identifier::new(MODULE_NAME).unwrap(),
+            sui_sdk::types::Identifier::new("value").unwrap(),
+            vec![],
+            arguments,
+        ))
+    }
+}
@@ -7,8 +7,9 @@ use clap::Parser;
 use crossbeam::channel::{bounded, select};
 use lsp_server::{Connection, Message, Notification, Request, Response};
 use lsp_types::{
-    notification::Notification as _, request::Request as _, CompletionOptions, Diagnostic,
-    HoverProviderCapability, OneOf, SaveOptions, TextDocumentSyncCapability, TextDocumentSyncKind,
+    notification::Notification as _, request::Request as _, CodeActionProviderCapability,
+    CompletionOptions, Diagnostic, HoverProviderCapability, OneOf, RenameOptions, SaveOptions,
+    SignatureHelpOptions, TextDocumentSyncCapability, TextDocumentSyncKind,
     TextDocumentSyncOptions, TypeDefinitionProviderCapability, WorkDoneProgressOptions,
 };
 use std::{
@@ -19,8 +20,10 @@ use std::{
 };
 
 use move_analyzer::{
+    code_action::on_code_action_request,
     completion::on_completion_request,
     context::Context,
+    inlay_hints::{on_inlay_hint_request, INLAY_HINT_METHOD},
     symbols,
     vfs::{on_text_document_sync_notification, VirtualFileSystem},
 };
@@ -61,7 +64,7 @@ fn main() {
         .initialize_start()
         .expect("could not start connection initialization");
 
-    let capabilities = serde_json::to_value(lsp_types::ServerCapabilities {
+    let mut capabilities = serde_json::to_value(lsp_types::ServerCapabilities {
         // The server receives notifications from the client as users open, close,
         // and modify documents.
         text_document_sync: Some(TextDocumentSyncCapability::Options(
@@ -107,9 +110,28 @@ fn main() {
         )),
         references_provider: Some(OneOf::Left(symbols::DEFS_AND_REFS_SUPPORT)),
         document_symbol_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        })),
+        // The server shows the signature of the called function when its arguments are being
+        // typed.
+        signature_help_provider: Some(SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+            retrigger_characters: None,
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
         ..Default::default()
     })
     .expect("could not serialize server capabilities");
+    // Inlay hints are not supported by the version of `lsp-types` in use (see the
+    // 'move_analyzer::inlay_hints' module for details).
+    capabilities["inlayHintProvider"] = serde_json::Value::Bool(true);
 
     let (diag_sender, diag_receiver) = bounded::<Result<BTreeMap<Symbol, Vec<Diagnostic>>>>(0);
     let mut symbolicator_runner = symbols::SymbolicatorRunner::idle();
@@ -275,6 +297,21 @@ fn on_request(context: &Context, request: &Request, shutdown_request_received: b
         lsp_types::request::DocumentSymbolRequest::METHOD => {
             symbols::on_document_symbol_request(context, request, &context.symbols.lock().unwrap());
         }
+        lsp_types::request::PrepareRenameRequest::METHOD => {
+            symbols::on_prepare_rename_request(context, request, &context.symbols.lock().unwrap());
+        }
+        lsp_types::request::Rename::METHOD => {
+            symbols::on_rename_request(context, request, &context.symbols.lock().unwrap());
+        }
+        lsp_types::request::SignatureHelpRequest::METHOD => {
+            symbols::on_signature_help_request(context, request, &context.symbols.lock().unwrap());
+        }
+        lsp_types::request::CodeActionRequest::METHOD => {
+            on_code_action_request(context, request, &context.symbols.lock().unwrap());
+        }
+        INLAY_HINT_METHOD => {
+            on_inlay_hint_request(context, request, &context.symbols.lock().unwrap());
+        }
         lsp_types::request::Shutdown::METHOD => {
             eprintln!("Shutdown request received");
             let response =
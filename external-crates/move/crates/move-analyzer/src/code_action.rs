@@ -0,0 +1,473 @@
+//! Code actions for warnings reported by the Sui linters. The linters point out code patterns that
+//! are usually (but not always) wrong rather than compute a replacement. When a warning has a
+//! mechanical rewrite, a quick fix applying it is offered:
+//! - a private `transfer`, `share_object`, `freeze_object` or `receive` call on a type with the
+//!   `store` ability (`custom_state_change`) is replaced by its public variant, which behaves the
+//!   same for such types and makes it explicit that no custom policy is enforced;
+//! - an object transferred to the sender at the end of a function returning nothing
+//!   (`self_transfer`) is returned from the function instead.
+//!
+//! Every lint warning can also be suppressed in the enclosing definition with the `lint_allow`
+//! attribute.
+
+use crate::{context::Context, symbols::Symbols, utils::file_contents};
+use lsp_server::Request;
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, Diagnostic, NumberOrString,
+    Position, Range, TextEdit, Url, WorkspaceEdit,
+};
+use move_compiler::{
+    diagnostics::codes::WarningFilter,
+    sui_mode::linters::{
+        known_filters, ALLOW_ATTR_NAME, CUSTOM_STATE_CHANGE_FILTER_NAME, FREEZE_FUN,
+        LINT_WARNING_PREFIX, RECEIVE_FUN, SELF_TRANSFER_FILTER_NAME, SHARE_FUN, TRANSFER_FUN,
+        TRANSFER_MOD_NAME,
+    },
+};
+use std::collections::HashMap;
+
+/// Handles code action request of the language server
+pub fn on_code_action_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<CodeActionParams>(request.params.clone())
+        .expect("could not deserialize code action request");
+
+    let uri = parameters.text_document.uri;
+    let fpath = uri.to_file_path().unwrap();
+    let mut actions = vec![];
+    if let Some(contents) = file_contents(&context.files, &fpath) {
+        for diag in parameters.context.diagnostics {
+            let def_start = symbols.enclosing_def_start(&fpath, diag.range.start);
+            let local_type = |pos| symbols.local_type(&fpath, pos);
+            actions.extend(
+                lint_actions(&uri, &contents, diag, def_start, &local_type)
+                    .into_iter()
+                    .map(CodeActionOrCommand::CodeAction),
+            );
+        }
+    }
+
+    eprintln!("about to send code action response");
+    let response =
+        lsp_server::Response::new_ok(request.id.clone(), serde_json::to_value(actions).unwrap());
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send code action response: {:?}", err);
+    }
+}
+
+/// Computes the code actions for a lint warning in a file: the fix of the warning if it has one,
+/// followed by its suppression in the definition starting at `def_start`. The type of a local at a
+/// given position is obtained with `local_type`.
+fn lint_actions(
+    uri: &Url,
+    contents: &str,
+    diag: Diagnostic,
+    def_start: Option<Position>,
+    local_type: &dyn Fn(Position) -> Option<String>,
+) -> Vec<CodeAction> {
+    let Some(filter_name) = diag.code.as_ref().and_then(lint_filter_name) else {
+        return vec![];
+    };
+    let action = |title: String, edits: Vec<TextEdit>, is_preferred: bool| CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diag.clone()]),
+        edit: Some(WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)]))),
+        is_preferred: Some(is_preferred),
+        ..Default::default()
+    };
+
+    let mut actions = vec![];
+    let fix = match filter_name {
+        CUSTOM_STATE_CHANGE_FILTER_NAME => public_variant_fix(uri, contents, &diag),
+        SELF_TRANSFER_FILTER_NAME => return_object_fix(contents, &diag, local_type),
+        _ => None,
+    };
+    if let Some((title, edits)) = fix {
+        actions.push(action(title, edits, true));
+    }
+    if let Some(edit) =
+        def_start.and_then(|start| lint_allow_edit(contents, start.line, filter_name))
+    {
+        actions.push(action(
+            format!("Suppress with #[{ALLOW_ATTR_NAME}({filter_name})]"),
+            vec![edit],
+            false,
+        ));
+    }
+    actions
+}
+
+/// Fixes a `custom_state_change` warning by calling the public variant of the private transfer
+/// function. The name of the called function is labeled in the related information of the warning.
+fn public_variant_fix(
+    uri: &Url,
+    contents: &str,
+    diag: &Diagnostic,
+) -> Option<(String, Vec<TextEdit>)> {
+    diag.related_information
+        .iter()
+        .flatten()
+        .filter(|info| &info.location.uri == uri)
+        .find_map(|info| {
+            let range = info.location.range;
+            let text = text_in_range(contents, range)?;
+            let name = text.rsplit("::").next()?;
+            if ![TRANSFER_FUN, SHARE_FUN, FREEZE_FUN, RECEIVE_FUN].contains(&name) {
+                return None;
+            }
+            // only the function name is replaced, in case the label covers the module as well
+            let start = Position::new(range.end.line, range.end.character - name.len() as u32);
+            Some((
+                format!("Use {TRANSFER_MOD_NAME}::public_{name}"),
+                vec![TextEdit::new(
+                    Range::new(start, range.end),
+                    format!("public_{name}"),
+                )],
+            ))
+        })
+}
+
+/// Fixes a `self_transfer` warning by returning the transferred object from the function instead.
+/// This is only done when the transfer is the last statement of a function without a return type
+/// (whose return type is then labeled at the function name) and when the type of the object is
+/// known, i.e., the object is a local or a struct instance.
+fn return_object_fix(
+    contents: &str,
+    diag: &Diagnostic,
+    local_type: &dyn Fn(Position) -> Option<String>,
+) -> Option<(String, Vec<TextEdit>)> {
+    let call_start = offset_at(contents, diag.range.start)?;
+    let call_end = offset_at(contents, diag.range.end)?;
+    let call = contents.get(call_start..call_end)?;
+
+    // the object is the first argument of the call
+    let args_start = call.find('(')? + 1;
+    let mut depth = 0;
+    let arg_len = call[args_start..].find(|c| {
+        match c {
+            '(' | '{' | '<' => depth += 1,
+            ')' | '}' | '>' => depth -= 1,
+            _ => (),
+        }
+        c == ',' && depth == 0
+    })?;
+    let arg = call[args_start..args_start + arg_len].trim();
+    let arg_offset = call_start + args_start + call[args_start..].find(arg)?;
+    let type_str = if arg.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        local_type(position_at(contents, arg_offset))?
+    } else {
+        // a struct instance, whose type is named before the fields (unless it has type arguments)
+        let (name, _) = arg.split_once('{')?;
+        let name = name.trim();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == ':')
+        {
+            return None;
+        }
+        name.to_string()
+    };
+
+    // the call must be the last statement of the function body
+    let rest = &contents[call_end..];
+    let after_stmt = rest
+        .trim_start()
+        .strip_prefix(';')
+        .unwrap_or(rest.trim_start());
+    if !after_stmt.trim_start().starts_with('}') {
+        return None;
+    }
+    let stmt_end = contents.len() - after_stmt.len();
+
+    // the function must not have a return type, in which case the name of the function is labeled
+    let params_end = diag.related_information.iter().flatten().find_map(|info| {
+        let name_start = offset_at(contents, info.location.range.start)?;
+        let name_end = offset_at(contents, info.location.range.end)?;
+        let name = contents.get(name_start..name_end)?;
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        let signature = &contents[name_end..call_start];
+        if !contents[..name_start].trim_end().ends_with("fun") {
+            return None;
+        }
+        let params_start = signature.find('(')?;
+        let mut depth = 0;
+        let params_len = signature[params_start..].find(|c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => (),
+            }
+            depth == 0
+        })?;
+        let params_end = name_end + params_start + params_len + 1;
+        // nothing but whitespace may separate the parameters from the body
+        contents[params_end..]
+            .trim_start()
+            .starts_with('{')
+            .then_some(params_end)
+    })?;
+
+    Some((
+        "Return the object instead of transferring it to the sender".to_string(),
+        vec![
+            TextEdit::new(
+                Range::new(
+                    position_at(contents, params_end),
+                    position_at(contents, params_end),
+                ),
+                format!(": {type_str}"),
+            ),
+            TextEdit::new(
+                Range::new(diag.range.start, position_at(contents, stmt_end)),
+                arg.to_string(),
+            ),
+        ],
+    ))
+}
+
+/// Returns the text of a file within a range.
+fn text_in_range(contents: &str, range: Range) -> Option<&str> {
+    contents.get(offset_at(contents, range.start)?..offset_at(contents, range.end)?)
+}
+
+/// Returns the byte offset of a position in a file.
+fn offset_at(contents: &str, pos: Position) -> Option<usize> {
+    let mut line_start = 0;
+    for (line, text) in contents.split('\n').enumerate() {
+        if line == pos.line as usize {
+            return text
+                .char_indices()
+                .map(|(offset, _)| offset)
+                .chain(std::iter::once(text.len()))
+                .nth(pos.character as usize)
+                .map(|offset| line_start + offset);
+        }
+        line_start += text.len() + 1;
+    }
+    None
+}
+
+/// Returns the position of a byte offset in a file.
+fn position_at(contents: &str, offset: usize) -> Position {
+    let before = &contents[..offset];
+    let line = before.matches('\n').count();
+    let character = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count();
+    Position::new(line as u32, character as u32)
+}
+
+/// Returns the name of the filter suppressing a lint warning with a given diagnostic code (e.g.,
+/// `Lint W03001`), or `None` if the code does not belong to a known lint.
+pub fn lint_filter_name(code: &NumberOrString) -> Option<&'static str> {
+    let NumberOrString::String(code) = code else {
+        return None;
+    };
+    // the rest of the code is rendered as `{severity}{category:02}{code:03}`
+    let code = code.strip_prefix(LINT_WARNING_PREFIX)?.strip_prefix('W')?;
+    if code.len() != 5 {
+        return None;
+    }
+    let diag_category: u8 = code.get(..2)?.parse().ok()?;
+    let diag_code: u8 = code.get(2..)?.parse().ok()?;
+    let (_, filters) = known_filters();
+    filters.into_iter().find_map(|filter| match filter {
+        WarningFilter::Code {
+            category,
+            code: filter_code,
+            name: Some(name),
+            ..
+        } if category == diag_category && filter_code == diag_code => Some(name),
+        _ => None,
+    })
+}
+
+/// Computes the edit suppressing a lint warning in the definition whose name is on a given line:
+/// the filter is added to the `lint_allow` attribute of the definition if it already has one, and
+/// a new attribute is inserted right above the definition otherwise.
+fn lint_allow_edit(contents: &str, def_line: u32, filter_name: &str) -> Option<TextEdit> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let def_text = lines.get(def_line as usize)?;
+
+    let attr_start = format!("#[{ALLOW_ATTR_NAME}(");
+    for (line, text) in lines[..def_line as usize].iter().enumerate().rev() {
+        let attr = text.trim();
+        if !attr.starts_with("#[") {
+            // past the attributes of the definition
+            break;
+        }
+        if attr.starts_with(&attr_start) && attr.ends_with(")]") {
+            // insert right before the closing `)]`
+            let col = text.trim_end().chars().count() - 2;
+            let pos = Position::new(line as u32, col as u32);
+            return Some(TextEdit::new(
+                Range::new(pos, pos),
+                format!(", {filter_name}"),
+            ));
+        }
+    }
+
+    let indent: String = def_text.chars().take_while(|c| c.is_whitespace()).collect();
+    let pos = Position::new(def_line, 0);
+    Some(TextEdit::new(
+        Range::new(pos, pos),
+        format!("{indent}#[{ALLOW_ATTR_NAME}({filter_name})]\n"),
+    ))
+}
+
+#[test]
+fn lint_filter_name_test() {
+    let code = |s: &str| NumberOrString::String(s.to_string());
+    assert_eq!(lint_filter_name(&code("Lint W00001")), Some("share_owned"));
+    assert_eq!(lint_filter_name(&code("Lint W03001")), Some("coin_field"));
+    // not a lint
+    assert_eq!(lint_filter_name(&code("W09002")), None);
+    // unknown lint
+    assert_eq!(lint_filter_name(&code("Lint W99001")), None);
+    assert_eq!(lint_filter_name(&NumberOrString::Number(1)), None);
+}
+
+#[test]
+fn lint_allow_edit_test() {
+    let contents = "module 0x42::m {\n    #[test_only]\n    struct S { c: Coin<SUI> }\n\n    #[lint_allow(share_owned)]\n    public fun f() {}\n}\n";
+
+    // new attribute
+    let edit = lint_allow_edit(contents, 2, "coin_field").unwrap();
+    assert_eq!(
+        edit.range,
+        Range::new(Position::new(2, 0), Position::new(2, 0))
+    );
+    assert_eq!(edit.new_text, "    #[lint_allow(coin_field)]\n");
+
+    // existing attribute
+    let edit = lint_allow_edit(contents, 5, "self_transfer").unwrap();
+    assert_eq!(
+        edit.range,
+        Range::new(Position::new(4, 28), Position::new(4, 28))
+    );
+    assert_eq!(edit.new_text, ", self_transfer");
+}
+
+#[cfg(test)]
+fn lint_diagnostic(code: &str, range: Range, related: Vec<(Range, &str)>) -> Diagnostic {
+    let uri = Url::parse("file:///m.move").unwrap();
+    Diagnostic {
+        range,
+        code: Some(NumberOrString::String(code.to_string())),
+        related_information: Some(
+            related
+                .into_iter()
+                .map(|(range, message)| lsp_types::DiagnosticRelatedInformation {
+                    location: lsp_types::Location::new(uri.clone(), range),
+                    message: message.to_string(),
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+fn action_edits(action: &CodeAction) -> Vec<TextEdit> {
+    let uri = Url::parse("file:///m.move").unwrap();
+    action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri].clone()
+}
+
+#[test]
+fn public_variant_fix_test() {
+    let uri = Url::parse("file:///m.move").unwrap();
+    let contents = "module 0x42::m {\n    struct S has key, store { id: UID }\n\n    public fun give(s: S, to: address) {\n        transfer::transfer(s, to)\n    }\n}\n";
+    let diag = lint_diagnostic(
+        "Lint W02001",
+        Range::new(Position::new(3, 15), Position::new(3, 19)),
+        vec![(
+            Range::new(Position::new(4, 18), Position::new(4, 26)),
+            "Instances of a type with a store ability can be transferred",
+        )],
+    );
+
+    let actions = lint_actions(&uri, contents, diag, Some(Position::new(3, 4)), &|_| None);
+    assert_eq!(actions.len(), 2);
+    assert_eq!(actions[0].title, "Use transfer::public_transfer");
+    assert_eq!(actions[0].is_preferred, Some(true));
+    assert_eq!(
+        action_edits(&actions[0]),
+        vec![TextEdit::new(
+            Range::new(Position::new(4, 18), Position::new(4, 26)),
+            "public_transfer".to_string(),
+        )]
+    );
+    // suppression is still offered
+    assert_eq!(
+        actions[1].title,
+        "Suppress with #[lint_allow(custom_state_change)]"
+    );
+    assert_eq!(
+        action_edits(&actions[1]),
+        vec![TextEdit::new(
+            Range::new(Position::new(3, 0), Position::new(3, 0)),
+            "    #[lint_allow(custom_state_change)]\n".to_string(),
+        )]
+    );
+}
+
+#[test]
+fn return_object_fix_test() {
+    let uri = Url::parse("file:///m.move").unwrap();
+    let contents = "module 0x42::m {\n    struct S has key, store { id: UID }\n    public fun make(ctx: &mut TxContext) {\n        let s = S { id: object::new(ctx) };\n        transfer::public_transfer(s, tx_context::sender(ctx));\n    }\n}\n";
+    let diag = lint_diagnostic(
+        "Lint W01001",
+        Range::new(Position::new(4, 8), Position::new(4, 61)),
+        vec![(
+            Range::new(Position::new(2, 15), Position::new(2, 19)),
+            "Returning an object from a function, allows a caller to use the object",
+        )],
+    );
+    let local_type =
+        |pos: Position| (pos == Position::new(4, 34)).then(|| "0x42::m::S".to_string());
+
+    let actions = lint_actions(&uri, contents, diag.clone(), None, &local_type);
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        actions[0].title,
+        "Return the object instead of transferring it to the sender"
+    );
+    assert_eq!(
+        action_edits(&actions[0]),
+        vec![
+            TextEdit::new(
+                Range::new(Position::new(2, 40), Position::new(2, 40)),
+                ": 0x42::m::S".to_string(),
+            ),
+            TextEdit::new(
+                Range::new(Position::new(4, 8), Position::new(4, 62)),
+                "s".to_string(),
+            ),
+        ]
+    );
+
+    // no fix if the type of the object is unknown
+    assert!(lint_actions(&uri, contents, diag, None, &|_| None).is_empty());
+
+    // no fix if the transfer is not the last statement
+    let contents = contents.replace("(ctx));\n", "(ctx));\n        foo();\n");
+    let diag = lint_diagnostic(
+        "Lint W01001",
+        Range::new(Position::new(4, 8), Position::new(4, 61)),
+        vec![(
+            Range::new(Position::new(2, 15), Position::new(2, 19)),
+            "Returning an object from a function, allows a caller to use the object",
+        )],
+    );
+    assert!(lint_actions(&uri, &contents, diag, None, &local_type).is_empty());
+}
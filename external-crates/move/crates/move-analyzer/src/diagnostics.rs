@@ -3,8 +3,11 @@
 
 use crate::utils::get_loc;
 use codespan_reporting::{diagnostic::Severity, files::SimpleFiles};
-use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Range};
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Range,
+};
 use move_command_line_common::files::FileHash;
+use move_compiler::diagnostics::codes::DiagnosticInfo;
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
 use std::collections::{BTreeMap, HashMap};
@@ -14,7 +17,7 @@ use url::Url;
 pub fn lsp_diagnostics(
     diagnostics: &Vec<(
         codespan_reporting::diagnostic::Severity,
-        &'static str,
+        DiagnosticInfo,
         (Loc, String),
         Vec<(Loc, String)>,
         Vec<String>,
@@ -24,7 +27,7 @@ pub fn lsp_diagnostics(
     file_name_mapping: &BTreeMap<FileHash, Symbol>,
 ) -> BTreeMap<Symbol, Vec<Diagnostic>> {
     let mut lsp_diagnostics = BTreeMap::new();
-    for (s, _, (loc, msg), labels, _) in diagnostics {
+    for (s, info, (loc, msg), labels, _) in diagnostics {
         let fpath = file_name_mapping.get(&loc.file_hash()).unwrap();
         if let Some(start) = get_loc(&loc.file_hash(), loc.start(), files, file_id_mapping) {
             if let Some(end) = get_loc(&loc.file_hash(), loc.end(), files, file_id_mapping) {
@@ -63,7 +66,9 @@ pub fn lsp_diagnostics(
                     .push(Diagnostic::new(
                         range,
                         Some(severity(*s)),
-                        None,
+                        // the code (e.g., `Lint W01001`) is needed to identify linter warnings when
+                        // computing code actions
+                        Some(NumberOrString::String(info.clone().render().0)),
                         None,
                         msg.to_string(),
                         related_info_opt,
@@ -0,0 +1,94 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inlay hints displaying the inferred types of locals declared without a type annotation.
+//!
+//! Inlay hints were introduced in version 3.17 of the Language Server Protocol, which is newer
+//! than the one supported by the version of `lsp-types` used here, so the request and response
+//! types are defined in this module.
+
+use crate::{
+    context::Context,
+    symbols::{inferred_let_types, Symbols},
+    utils::file_contents,
+};
+use lsp_server::Request;
+use lsp_types::{Position, Range, TextDocumentIdentifier};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Method name of the inlay hint request
+pub const INLAY_HINT_METHOD: &str = "textDocument/inlayHint";
+
+/// Parameters of the inlay hint request
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintParams {
+    /// The document the hints are requested for
+    pub text_document: TextDocumentIdentifier,
+    /// The visible range of the document the hints are requested for
+    pub range: Range,
+}
+
+/// Kind of an inlay hint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct InlayHintKind(u32);
+
+impl InlayHintKind {
+    pub const TYPE: InlayHintKind = InlayHintKind(1);
+    pub const PARAMETER: InlayHintKind = InlayHintKind(2);
+}
+
+/// A hint displayed inline in the editor
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+    /// Position of the hint
+    pub position: Position,
+    /// Label of the hint
+    pub label: String,
+    /// Kind of the hint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<InlayHintKind>,
+}
+
+/// Handles inlay hint request of the language server
+pub fn on_inlay_hint_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<InlayHintParams>(request.params.clone())
+        .expect("could not deserialize inlay hint request");
+
+    let fpath = parameters.text_document.uri.to_file_path().unwrap();
+    let hints = match file_contents(&context.files, &fpath) {
+        Some(contents) => inlay_hints(symbols, &fpath, &contents, parameters.range),
+        None => vec![],
+    };
+
+    eprintln!("about to send inlay hint response");
+    let response =
+        lsp_server::Response::new_ok(request.id.clone(), serde_json::to_value(hints).unwrap());
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send inlay hint response: {:?}", err);
+    }
+}
+
+/// Computes type hints for the locals declared without a type annotation in a given range of a file
+pub fn inlay_hints(
+    symbols: &Symbols,
+    fpath: &PathBuf,
+    contents: &str,
+    range: Range,
+) -> Vec<InlayHint> {
+    inferred_let_types(symbols, fpath, contents, range)
+        .into_iter()
+        .map(|(position, type_str)| InlayHint {
+            position,
+            label: format!(": {type_str}"),
+            kind: Some(InlayHintKind::TYPE),
+        })
+        .collect()
+}
@@ -57,7 +57,8 @@
 use crate::{
     context::Context,
     diagnostics::{lsp_diagnostics, lsp_empty_diagnostics},
-    utils::get_loc,
+    utils::{file_contents, get_loc, line_text},
+    vfs::VirtualFileSystem,
 };
 use anyhow::{anyhow, Result};
 use codespan_reporting::files::SimpleFiles;
@@ -67,8 +68,11 @@ use im::ordmap::OrdMap;
 use lsp_server::{Request, RequestId};
 use lsp_types::{
     request::GotoTypeDefinitionParams, Diagnostic, DocumentSymbol, DocumentSymbolParams,
-    GotoDefinitionParams, Hover, HoverContents, HoverParams, Location, MarkupContent, MarkupKind,
-    Position, Range, ReferenceParams, SymbolKind,
+    Documentation, GotoDefinitionParams, Hover, HoverContents, HoverParams, Location,
+    MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, Position,
+    PrepareRenameResponse, Range, ReferenceParams, RenameParams, SignatureHelp,
+    SignatureHelpParams, SignatureInformation, SymbolKind, TextDocumentPositionParams, TextEdit,
+    WorkspaceEdit,
 };
 
 use std::{
@@ -87,7 +91,10 @@ use move_compiler::{
     editions::Flavor,
     expansion::ast::{Fields, ModuleIdent, ModuleIdent_, Value, Value_, Visibility},
     naming::ast::{StructDefinition, StructFields, TParam, Type, TypeName_, Type_},
-    parser::ast::{self as P, StructName},
+    parser::{
+        ast::{self as P, StructName},
+        keywords::KEYWORDS,
+    },
     shared::Identifier,
     typing::ast::{
         BuiltinFunction_, Exp, ExpListItem, Function, FunctionBody_, LValue, LValueList, LValue_,
@@ -942,6 +949,36 @@ impl Symbols {
     pub fn file_mods(&self) -> &BTreeMap<PathBuf, BTreeSet<ModuleDefs>> {
         &self.file_mods
     }
+
+    /// Returns the start of the closest struct or function definition preceding a given position in
+    /// a file, which for positions within definitions is the definition the position belongs to
+    /// (if there is no such definition, the start of the module is returned instead).
+    pub fn enclosing_def_start(&self, fpath: &PathBuf, pos: Position) -> Option<Position> {
+        let mods = self.file_mods.get(fpath)?;
+        let mut starts = vec![];
+        for mod_defs in mods {
+            starts.push(mod_defs.start);
+            starts.extend(mod_defs.structs.values().map(|s| s.name_start));
+            starts.extend(mod_defs.functions.values().map(|f| f.start));
+        }
+        starts.into_iter().filter(|s| s.line <= pos.line).max()
+    }
+
+    /// Returns the type of the local used or defined at a given position in a file, if it is known.
+    pub fn local_type(&self, fpath: &PathBuf, pos: Position) -> Option<String> {
+        let uses = self.file_use_defs.get(fpath)?.0.get(&pos.line)?;
+        uses.iter().find_map(|u| match &u.on_hover {
+            IdentOnHover::Local(_, t, _)
+                if u.col_start <= pos.character && pos.character < u.col_end =>
+            {
+                match t.value {
+                    Type_::Anything | Type_::Var(_) | Type_::UnresolvedError => None,
+                    _ => Some(type_to_ide_string(t)),
+                }
+            }
+            _ => None,
+        })
+    }
 }
 
 /// Main driver to get symbols for the whole package. Returned symbols is an option as only the
@@ -3068,22 +3105,10 @@ pub fn on_use_request(
     id: RequestId,
     use_def_action: impl Fn(&UseDef) -> Option<serde_json::Value>,
 ) {
-    let mut result = None;
-
-    let mut use_def_found = false;
-    if let Some(mod_symbols) = symbols.file_use_defs.get(use_fpath) {
-        if let Some(uses) = mod_symbols.get(use_line) {
-            for u in uses {
-                if use_col >= u.col_start && use_col <= u.col_end {
-                    result = use_def_action(&u);
-                    use_def_found = true;
-                }
-            }
-        }
-    }
-    if !use_def_found {
-        result = Some(serde_json::to_value(Option::<lsp_types::Location>::None).unwrap());
-    }
+    let result = match find_use_def(symbols, use_fpath, use_line, use_col) {
+        Some(u) => use_def_action(&u),
+        None => Some(serde_json::to_value(Option::<lsp_types::Location>::None).unwrap()),
+    };
 
     eprintln!("about to send use response");
     // unwrap will succeed based on the logic above which the compiler is unable to figure out
@@ -3098,6 +3123,440 @@ pub fn on_use_request(
     }
 }
 
+/// Finds the use of an identifier at a given position in a file
+fn find_use_def(
+    symbols: &Symbols,
+    use_fpath: &PathBuf,
+    use_line: u32,
+    use_col: u32,
+) -> Option<UseDef> {
+    let uses = symbols.file_use_defs.get(use_fpath)?.get(use_line)?;
+    uses.into_iter()
+        .filter(|u| use_col >= u.col_start && use_col <= u.col_end)
+        .last()
+}
+
+/// Sends a response to a request that is handled without the help of `on_use_request`
+fn send_response(context: &Context, response: lsp_server::Response) {
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send response: {:?}", err);
+    }
+}
+
+/// Handles prepare-rename request of the language server
+pub fn on_prepare_rename_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<TextDocumentPositionParams>(request.params.clone())
+        .expect("could not deserialize prepare rename request");
+
+    let fpath = parameters.text_document.uri.to_file_path().unwrap();
+    let loc = parameters.position;
+    let line = loc.line;
+    let col = loc.character;
+
+    on_use_request(
+        context,
+        symbols,
+        &fpath,
+        line,
+        col,
+        request.id.clone(),
+        |u| {
+            if let IdentOnHover::Module(_) = u.on_hover {
+                return Some(serde_json::to_value(Option::<PrepareRenameResponse>::None).unwrap());
+            }
+            let range = Range {
+                start: Position {
+                    line,
+                    character: u.col_start,
+                },
+                end: Position {
+                    line,
+                    character: u.col_end,
+                },
+            };
+            Some(serde_json::to_value(PrepareRenameResponse::Range(range)).unwrap())
+        },
+    );
+}
+
+/// Handles rename request of the language server
+pub fn on_rename_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<RenameParams>(request.params.clone())
+        .expect("could not deserialize rename request");
+
+    let fpath = parameters
+        .text_document_position
+        .text_document
+        .uri
+        .to_file_path()
+        .unwrap();
+    let loc = parameters.text_document_position.position;
+
+    eprintln!("about to send rename response");
+    let response = match rename_edits(symbols, &context.files, &fpath, loc, &parameters.new_name) {
+        Ok(edit) => lsp_server::Response::new_ok(request.id.clone(), edit),
+        Err(msg) => lsp_server::Response::new_err(
+            request.id.clone(),
+            lsp_server::ErrorCode::InvalidParams as i32,
+            msg,
+        ),
+    };
+    send_response(context, response);
+}
+
+/// Computes the edits renaming the identifier at a given position, as well as its definition and
+/// all its other uses in the package, to a new name. Returns an error message if the identifier
+/// cannot be renamed or if the new name is not valid for it.
+pub fn rename_edits(
+    symbols: &Symbols,
+    files: &VirtualFileSystem,
+    fpath: &PathBuf,
+    loc: Position,
+    new_name: &str,
+) -> Result<Option<WorkspaceEdit>, String> {
+    let Some(u) = find_use_def(symbols, fpath, loc.line, loc.character) else {
+        return Ok(None);
+    };
+    check_new_name(&u.on_hover, new_name)?;
+    let Some(ref_locs) = symbols.references.get(&u.def_loc) else {
+        return Ok(None);
+    };
+
+    let mut contents = BTreeMap::new();
+    for ref_loc in ref_locs {
+        if contents.contains_key(&ref_loc.fhash) {
+            continue;
+        }
+        if let Some(path) = symbols.file_name_mapping.get(&ref_loc.fhash) {
+            if let Some(c) = file_contents(files, &PathBuf::from(path.as_str())) {
+                contents.insert(ref_loc.fhash, c);
+            }
+        }
+    }
+    let ref_text = |ref_loc: &UseLoc| {
+        contents.get(&ref_loc.fhash).and_then(|c| {
+            line_text(
+                c,
+                ref_loc.start.line,
+                ref_loc.start.character,
+                ref_loc.col_end,
+            )
+        })
+    };
+
+    // Uses of aliases (e.g., `use Symbols::M2::some_fun as other_fun;`) are recorded as references
+    // of the aliased definition but they should keep using the alias - only references spelled
+    // the same way as the definition are renamed.
+    let Some(def_name) = ref_locs
+        .iter()
+        .find(|l| l.fhash == u.def_loc.fhash && l.start == u.def_loc.start)
+        .and_then(ref_text)
+    else {
+        return Ok(None);
+    };
+
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for ref_loc in ref_locs {
+        if ref_text(ref_loc).as_ref() != Some(&def_name) {
+            continue;
+        }
+        let path = symbols.file_name_mapping.get(&ref_loc.fhash).unwrap();
+        let range = Range {
+            start: ref_loc.start,
+            end: Position {
+                line: ref_loc.start.line,
+                character: ref_loc.col_end,
+            },
+        };
+        changes
+            .entry(Url::from_file_path(path.as_str()).unwrap())
+            .or_default()
+            .push(TextEdit::new(range, new_name.to_string()));
+    }
+    Ok(Some(WorkspaceEdit::new(changes)))
+}
+
+/// Checks if an identifier can be renamed to a new name, following the naming rules enforced by
+/// the compiler for the kind of the identifier
+fn check_new_name(on_hover: &IdentOnHover, new_name: &str) -> Result<(), String> {
+    let is_identifier = new_name
+        .chars()
+        .next()
+        .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && new_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_identifier || KEYWORDS.contains(&new_name) {
+        return Err(format!("'{new_name}' is not a valid Move identifier"));
+    }
+    match on_hover {
+        IdentOnHover::Module(_) => Err("Renaming modules is not supported".to_string()),
+        IdentOnHover::Local(..)
+            if !new_name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_') =>
+        {
+            Err(format!(
+                "Invalid local variable name '{new_name}'. Local variable names must start with \
+                 'a'..'z' (or '_')"
+            ))
+        }
+        IdentOnHover::Function(..) if new_name.starts_with('_') => Err(format!(
+            "Invalid function name '{new_name}'. Function names cannot start with '_'"
+        )),
+        IdentOnHover::Struct(..) | IdentOnHover::Const(..)
+            if !new_name.starts_with(|c: char| c.is_ascii_uppercase()) =>
+        {
+            Err(format!(
+                "Invalid name '{new_name}'. Struct and constant names must start with 'A'..'Z'"
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Handles signature help request of the language server
+pub fn on_signature_help_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<SignatureHelpParams>(request.params.clone())
+        .expect("could not deserialize signature help request");
+
+    let fpath = parameters
+        .text_document_position_params
+        .text_document
+        .uri
+        .to_file_path()
+        .unwrap();
+    let loc = parameters.text_document_position_params.position;
+
+    let help = file_contents(&context.files, &fpath)
+        .and_then(|contents| signature_help(symbols, &fpath, &contents, loc));
+    eprintln!("about to send signature help response");
+    let response =
+        lsp_server::Response::new_ok(request.id.clone(), serde_json::to_value(help).unwrap());
+    send_response(context, response);
+}
+
+/// Function call whose arguments contain a given position
+struct CallSite {
+    /// Start of the called function's name
+    name_start: Position,
+    /// Name of the called function
+    name: String,
+    /// Module the function is called from (if the call is qualified)
+    module: Option<String>,
+    /// Index of the argument containing the position
+    arg_idx: u32,
+}
+
+/// Computes the signature of the function whose call arguments contain a given position, together
+/// with the argument that the position is in.
+pub fn signature_help(
+    symbols: &Symbols,
+    fpath: &PathBuf,
+    contents: &str,
+    loc: Position,
+) -> Option<SignatureHelp> {
+    let call = find_call_site(contents, loc)?;
+    // The call is resolved using the (last computed) symbols, unless the code was modified since
+    // then and the call is new (which is likely when the signature is requested) - in that case
+    // the function is looked up by name instead.
+    let (on_hover, doc_string) = match find_use_def(
+        symbols,
+        fpath,
+        call.name_start.line,
+        call.name_start.character,
+    ) {
+        Some(u) => (u.on_hover, u.doc_string),
+        None => (find_fun_by_name(symbols, fpath, &call)?, None),
+    };
+    let IdentOnHover::Function(_, _, _, _, arg_names, arg_types, _) = &on_hover else {
+        return None;
+    };
+
+    let parameters = arg_names
+        .iter()
+        .zip(arg_types.iter())
+        .map(|(n, t)| ParameterInformation {
+            label: ParameterLabel::Simple(format!("{}: {}", n, type_to_ide_string(t))),
+            documentation: None,
+        })
+        .collect();
+    let signature = SignatureInformation {
+        label: on_hover.to_string(),
+        documentation: doc_string.map(Documentation::String),
+        parameters: Some(parameters),
+        active_parameter: None,
+    };
+    Some(SignatureHelp {
+        signatures: vec![signature],
+        active_signature: Some(0),
+        active_parameter: Some(call.arg_idx),
+    })
+}
+
+/// Finds the function call whose arguments contain a given position by scanning the source
+/// backwards for an unmatched opening parenthesis
+fn find_call_site(contents: &str, loc: Position) -> Option<CallSite> {
+    let chars: Vec<(Position, char)> = contents
+        .lines()
+        .take(loc.line as usize + 1)
+        .enumerate()
+        .flat_map(|(line, text)| {
+            text.chars()
+                .enumerate()
+                .map(move |(col, c)| (Position::new(line as u32, col as u32), c))
+        })
+        .filter(|(pos, _)| *pos < loc)
+        .collect();
+
+    let mut idx = chars.len();
+    let mut depth = 0;
+    let mut arg_idx = 0;
+    loop {
+        idx = idx.checked_sub(1)?;
+        match chars[idx].1 {
+            ')' | ']' | '}' => depth += 1,
+            '(' | '[' | '{' if depth > 0 => depth -= 1,
+            '(' => break,
+            // outside of any call
+            '[' | '{' => return None,
+            ';' if depth == 0 => return None,
+            ',' if depth == 0 => arg_idx += 1,
+            _ => (),
+        }
+    }
+
+    let skip_whitespace = |mut idx: usize| {
+        while idx > 0 && chars[idx - 1].1.is_whitespace() {
+            idx -= 1;
+        }
+        idx
+    };
+    // skip type arguments between the function name and the arguments
+    let mut name_end = skip_whitespace(idx);
+    if name_end > 0 && chars[name_end - 1].1 == '>' {
+        let mut depth = 0;
+        loop {
+            name_end = name_end.checked_sub(1)?;
+            match chars[name_end].1 {
+                '>' => depth += 1,
+                '<' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                ';' | '{' | '}' => return None,
+                _ => (),
+            }
+        }
+        name_end = skip_whitespace(name_end);
+    }
+
+    let ident_before = |end: usize| {
+        let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let mut start = end;
+        while start > 0
+            && is_ident_char(chars[start - 1].1)
+            && chars[start - 1].0.line == chars[end - 1].0.line
+        {
+            start -= 1;
+        }
+        let ident: String = chars[start..end].iter().map(|(_, c)| c).collect();
+        (start, ident)
+    };
+    if name_end == 0 {
+        return None;
+    }
+    let (name_start, name) = ident_before(name_end);
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let module =
+        if name_start > 2 && chars[name_start - 1].1 == ':' && chars[name_start - 2].1 == ':' {
+            Some(ident_before(name_start - 2).1).filter(|m| !m.is_empty())
+        } else {
+            None
+        };
+    Some(CallSite {
+        name_start: chars[name_start].0,
+        name,
+        module,
+        arg_idx,
+    })
+}
+
+/// Looks up a called function by its name in the module it is called from (if the call is
+/// qualified) or in the modules of the file containing the call (otherwise)
+fn find_fun_by_name(symbols: &Symbols, fpath: &PathBuf, call: &CallSite) -> Option<IdentOnHover> {
+    let name = Symbol::from(call.name.as_str());
+    let fun_def = match &call.module {
+        Some(module) => symbols
+            .file_mods
+            .values()
+            .flatten()
+            .filter(|m| m.ident.module.value().as_str() == module.as_str())
+            .find_map(|m| m.functions.get(&name)),
+        None => symbols
+            .file_mods
+            .get(fpath)?
+            .iter()
+            .find_map(|m| m.functions.get(&name)),
+    }?;
+    Some(fun_def.on_hover.clone())
+}
+
+/// Returns the (inferred) types of the locals declared with `let` without a type annotation in a
+/// given range of a file, along with the positions right after the names of the locals.
+pub fn inferred_let_types(
+    symbols: &Symbols,
+    fpath: &PathBuf,
+    contents: &str,
+    range: Range,
+) -> Vec<(Position, String)> {
+    let mut types = vec![];
+    let Some(mod_symbols) = symbols.file_use_defs.get(fpath) else {
+        return types;
+    };
+    if range.start > range.end {
+        return types;
+    }
+    for (line, uses) in mod_symbols.0.range(range.start.line..=range.end.line) {
+        let Some(text) = contents.lines().nth(*line as usize) else {
+            continue;
+        };
+        for u in uses {
+            let IdentOnHover::Local(_, t, true) = &u.on_hover else {
+                continue;
+            };
+            // only the definition of a local (and not its uses) is considered
+            if u.def_loc.start.line != *line || u.def_loc.start.character != u.col_start {
+                continue;
+            }
+            if let Type_::Anything | Type_::Var(_) | Type_::UnresolvedError = t.value {
+                continue;
+            }
+            // the type is annotated if there is a `:` between the name and the assignment (or
+            // the end of the declaration)
+            let annotated = text
+                .chars()
+                .skip(u.col_end as usize)
+                .take_while(|c| *c != '=' && *c != ';')
+                .any(|c| c == ':');
+            let end = Position {
+                line: *line,
+                character: u.col_end,
+            };
+            if !annotated && range.start <= end && end <= range.end {
+                types.push((end, type_to_ide_string(t)));
+            }
+        }
+    }
+    types
+}
+
 /// Handles document symbol request of the language server
 #[allow(deprecated)]
 pub fn on_document_symbol_request(context: &Context, request: &Request, symbols: &Symbols) {
@@ -5475,3 +5934,153 @@ fn pretype_error_with_deps_test() {
         None,
     );
 }
+
+#[test]
+/// Tests if renaming an identifier produces edits for its definition and all its uses.
+fn rename_test() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) = get_symbols(path.as_path(), false).unwrap();
+    let symbols = symbols_opt.unwrap();
+    let files = VirtualFileSystem::default();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M1.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+
+    let edit_ranges = |loc: Position, new_name: &str| {
+        let edit = rename_edits(&symbols, &files, &cpath, loc, new_name)
+            .unwrap()
+            .unwrap();
+        let mut ranges = BTreeMap::new();
+        for (uri, edits) in edit.changes.unwrap() {
+            let fname = uri.path_segments().unwrap().last().unwrap().to_string();
+            for e in edits {
+                assert_eq!(e.new_text, new_name);
+                ranges
+                    .entry(fname.clone())
+                    .or_insert_with(BTreeSet::new)
+                    .insert((
+                        e.range.start.line,
+                        e.range.start.character,
+                        e.range.end.character,
+                    ));
+            }
+        }
+        ranges
+    };
+
+    // local (renamed from its use)
+    assert_eq!(
+        edit_ranges(Position::new(16, 9), "res"),
+        BTreeMap::from([(
+            "M1.move".to_string(),
+            BTreeSet::from([(15, 12, 15), (16, 8, 11)])
+        )])
+    );
+    // function called from another module
+    assert_eq!(
+        edit_ranges(Position::new(40, 12), "sum"),
+        BTreeMap::from([
+            ("M1.move".to_string(), BTreeSet::from([(40, 12, 21)])),
+            ("M2.move".to_string(), BTreeSet::from([(10, 15, 24)])),
+        ])
+    );
+
+    // invalid names
+    assert!(rename_edits(&symbols, &files, &cpath, Position::new(16, 9), "Res").is_err());
+    assert!(rename_edits(&symbols, &files, &cpath, Position::new(16, 9), "let").is_err());
+    assert!(rename_edits(&symbols, &files, &cpath, Position::new(16, 9), "1res").is_err());
+    // struct names must start with an upper case letter
+    assert!(rename_edits(&symbols, &files, &cpath, Position::new(2, 11), "some").is_err());
+    // modules cannot be renamed
+    assert!(rename_edits(&symbols, &files, &cpath, Position::new(40, 8), "M").is_err());
+    // no identifier
+    assert_eq!(
+        rename_edits(&symbols, &files, &cpath, Position::new(1, 0), "x"),
+        Ok(None)
+    );
+}
+
+#[test]
+/// Tests if the signature of a called function is found for positions within call arguments.
+fn signature_help_test() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) = get_symbols(path.as_path(), false).unwrap();
+    let symbols = symbols_opt.unwrap();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M1.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+    let contents = std::fs::read_to_string(&cpath).unwrap();
+
+    let signature = |contents: &str, loc: Position| {
+        let help = signature_help(&symbols, &cpath, contents, loc)?;
+        Some((help.signatures[0].label.clone(), help.active_parameter))
+    };
+
+    let multi_arg = "public fun Symbols::M2::multi_arg(p1: u64, p2: u64): u64".to_string();
+    // first argument
+    assert_eq!(
+        signature(&contents, Position::new(40, 22)),
+        Some((multi_arg.clone(), Some(0)))
+    );
+    // second argument
+    assert_eq!(
+        signature(&contents, Position::new(40, 34)),
+        Some((multi_arg.clone(), Some(1)))
+    );
+    // outside of the call
+    assert_eq!(signature(&contents, Position::new(40, 8)), None);
+    // the call was not symbolicated yet
+    let modified = contents.replace(
+        "        M2::multi_arg(SOME_CONST, SOME_CONST)",
+        "        let x = 7;\n        M2::multi_arg(cp(x), ",
+    );
+    assert_eq!(
+        signature(&modified, Position::new(41, 29)),
+        Some((multi_arg, Some(1)))
+    );
+    assert_eq!(
+        signature(&modified, Position::new(41, 25)),
+        Some(("fun Symbols::M1::cp(value: u64): u64".to_string(), Some(0)))
+    );
+}
+
+#[test]
+/// Tests if types are inferred for the locals declared without a type annotation.
+fn inferred_let_types_test() {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) = get_symbols(path.as_path(), false).unwrap();
+    let symbols = symbols_opt.unwrap();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M1.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+    let contents = std::fs::read_to_string(&cpath).unwrap();
+
+    let types = inferred_let_types(
+        &symbols,
+        &cpath,
+        &contents,
+        Range::new(Position::new(9, 0), Position::new(60, 0)),
+    );
+    assert_eq!(
+        types,
+        vec![
+            (Position::new(15, 15), "u64".to_string()),
+            (Position::new(20, 15), "Symbols::M1::SomeStruct".to_string()),
+            (Position::new(44, 13), "Symbols::M1::SomeStruct".to_string()),
+            (Position::new(55, 15), "u64".to_string()),
+            (Position::new(56, 13), "&mut u64".to_string()),
+        ]
+    );
+}
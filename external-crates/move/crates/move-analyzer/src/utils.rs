@@ -1,12 +1,13 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::vfs::VirtualFileSystem;
 use codespan_reporting::files::{Files, SimpleFiles};
 use lsp_types::Position;
 use move_command_line_common::files::FileHash;
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 /// Converts a location from the byte index format to the line/character (Position) format, where
 /// line/character are 0-based.
@@ -29,3 +30,26 @@ pub fn get_loc(
         Err(_) => None,
     }
 }
+
+/// Returns the contents of a file, taking it from the editor's buffer if the file is open (and
+/// possibly modified) and reading it from disk otherwise.
+pub fn file_contents(files: &VirtualFileSystem, fpath: &PathBuf) -> Option<String> {
+    match files.get(fpath) {
+        Some(buffer) => Some(buffer.to_string()),
+        None => std::fs::read_to_string(fpath).ok(),
+    }
+}
+
+/// Returns the text between two (0-based) columns of a given (0-based) line.
+pub fn line_text(contents: &str, line: u32, col_start: u32, col_end: u32) -> Option<String> {
+    let text = contents.lines().nth(line as usize)?;
+    if col_start > col_end || col_end as usize > text.chars().count() {
+        return None;
+    }
+    Some(
+        text.chars()
+            .skip(col_start as usize)
+            .take((col_end - col_start) as usize)
+            .collect(),
+    )
+}
@@ -14,7 +14,7 @@ use move_compiler::{
 };
 use move_coverage::coverage_map::{output_map_to_file, CoverageMap};
 use move_package::{compilation::build_plan::BuildPlan, BuildConfig};
-use move_unit_test::UnitTestingConfig;
+use move_unit_test::{test_reporter::ReportFormat, UnitTestingConfig};
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
     collections::HashMap,
@@ -58,6 +58,10 @@ pub struct Test {
     /// Report test statistics at the end of testing. CSV report generated if 'csv' passed
     #[clap(name = "report-statistics", short = 's', long = "statistics")]
     pub report_statistics: Option<Option<String>>,
+    /// Write a machine-readable report of the test results to a file, as `junit=<path>` or
+    /// `json=<path>`. Can be passed multiple times.
+    #[clap(name = "report", long = "report", value_name = "FORMAT=PATH")]
+    pub reports: Vec<ReportFormat>,
 
     /// Use the stackless bytecode interpreter to run the tests and cross check its results with
     /// the execution result from Move VM.
@@ -69,6 +73,23 @@ pub struct Test {
     /// Collect coverage information for later use with the various `move coverage` subcommands. Currently supported only in debug builds.
     #[clap(long = "coverage")]
     pub compute_coverage: bool,
+    /// Seed for the arguments generated for `#[random_test]` tests. A random seed is used if none
+    /// is given; the seed of a failing test is reported so the failure can be reproduced.
+    #[clap(long = "seed")]
+    pub seed: Option<u64>,
+    /// Number of times each `#[random_test]` is run with freshly generated arguments.
+    #[clap(long = "rand-num-iters")]
+    pub rand_num_iters: Option<u64>,
+    /// Write a gas profile of each test, in the speedscope format (https://www.speedscope.app), to
+    /// the given directory or to the current one. Currently supported only in debug builds.
+    #[clap(
+        long = "profile-gas",
+        value_name = "DIR",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "."
+    )]
+    pub profile_gas: Option<PathBuf>,
 }
 
 impl Test {
@@ -105,9 +126,13 @@ impl Test {
             list,
             num_threads,
             report_statistics,
+            reports,
             check_stackless_vm,
             verbose_mode,
             compute_coverage: _,
+            seed,
+            rand_num_iters,
+            profile_gas,
         } = self;
         UnitTestingConfig {
             gas_limit,
@@ -115,8 +140,12 @@ impl Test {
             list,
             num_threads,
             report_statistics,
+            reports,
             check_stackless_vm,
             verbose: verbose_mode,
+            seed,
+            rand_num_iters,
+            profile_gas,
             ..UnitTestingConfig::default_with_bound(None)
         }
     }
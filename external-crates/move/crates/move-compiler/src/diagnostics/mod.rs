@@ -299,7 +299,7 @@ impl Diagnostics {
         self,
     ) -> Vec<(
         codespan_reporting::diagnostic::Severity,
-        &'static str,
+        DiagnosticInfo,
         (Loc, String),
         Vec<(Loc, String)>,
         Vec<String>,
@@ -314,7 +314,7 @@ impl Diagnostics {
             } = diag;
             let csr_diag = (
                 info.severity().into_codespan_severity(),
-                info.message(),
+                info,
                 primary_label,
                 secondary_labels,
                 notes,
@@ -505,6 +505,8 @@ impl Attributes {
             known_attributes::TestingAttribute::TestOnly,
         ))) || self.contains_key_(&AttributeName_::Known(KnownAttribute::Testing(
             known_attributes::TestingAttribute::Test,
+        ))) || self.contains_key_(&AttributeName_::Known(KnownAttribute::Testing(
+            known_attributes::TestingAttribute::RandTest,
         )))
     }
 }
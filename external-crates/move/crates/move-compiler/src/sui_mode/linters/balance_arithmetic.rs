@@ -0,0 +1,194 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags arithmetic on values of sui::balance::Balance or sui::coin::Coin (as
+//! returned by their respective `value` functions) that may abort with an arithmetic error:
+//! subtractions in functions that never compare a balance value (and so never check that it is
+//! large enough), and multiplications, which may overflow u64 for large balances.
+
+use std::collections::BTreeSet;
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::ModuleIdent,
+    naming::ast as N,
+    parser::ast::{self as P, FunctionName},
+    shared::{program_info::TypingProgramInfo, CompilationEnv, Identifier},
+    typing::{
+        ast as T,
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+use move_ir_types::location::*;
+
+use super::{
+    LinterDiagCategory, BALANCE_MOD_NAME, COIN_MOD_NAME, LINTER_DEFAULT_DIAG_CODE,
+    LINT_WARNING_PREFIX, SUI_PKG_NAME, VALUE_FUN,
+};
+
+const BALANCE_ARITHMETIC_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::BalanceArithmetic as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "unchecked balance arithmetic",
+);
+
+const VALUE_FUNCTIONS: &[(&str, &str, &str)] = &[
+    (SUI_PKG_NAME, BALANCE_MOD_NAME, VALUE_FUN),
+    (SUI_PKG_NAME, COIN_MOD_NAME, VALUE_FUN),
+];
+
+pub struct BalanceArithmeticVisitor;
+
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+    /// Locals of the function being visited holding balance values
+    balance_vars: BTreeSet<N::Var_>,
+    /// Has the function being visited compared a balance value
+    compared: bool,
+    /// Locations of subtractions involving balance values in the function being visited
+    subtractions: Vec<Loc>,
+}
+
+impl TypingVisitorConstructor for BalanceArithmeticVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        _program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context {
+            env,
+            balance_vars: BTreeSet::new(),
+            compared: false,
+            subtractions: vec![],
+        }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_module_custom(&mut self, _ident: ModuleIdent, mdef: &mut T::ModuleDefinition) -> bool {
+        // skips if true
+        mdef.attributes.is_test_or_test_only()
+    }
+
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        if fdef.attributes.is_test_or_test_only() {
+            return true;
+        }
+        let T::FunctionBody_::Defined(seq) = &mut fdef.body.value else {
+            return true;
+        };
+
+        self.balance_vars.clear();
+        self.compared = false;
+        self.subtractions.clear();
+        self.visit_seq(seq);
+        // subtractions are only reported once the whole function has been visited, as the check
+        // may come after the subtraction in the source (e.g., in a loop)
+        if !self.compared {
+            for loc in std::mem::take(&mut self.subtractions) {
+                let msg = "Subtraction involving a balance value that is never checked to be \
+                           large enough, which may abort with an arithmetic underflow";
+                let mut d = diag!(BALANCE_ARITHMETIC_DIAG, (loc, msg));
+                d.add_note(
+                    "Compare the balance value with the amount subtracted first, to abort with \
+                     a meaningful error code",
+                );
+                self.env.add_diag(d);
+            }
+        }
+        true
+    }
+
+    fn visit_seq_item(&mut self, sp!(_, seq_item): &mut T::SequenceItem) {
+        use T::SequenceItem_ as SI;
+        match seq_item {
+            SI::Seq(e) => self.visit_exp(e),
+            SI::Declare(_) => (),
+            SI::Bind(lvalues, _, e) => {
+                self.visit_exp(e);
+                self.track_binding(lvalues, e);
+            }
+        }
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::Assign(lvalues, _, rhs) => self.track_binding(lvalues, rhs),
+            E::BinopExp(e1, op, _, e2) => {
+                if !self.is_balance_value(e1) && !self.is_balance_value(e2) {
+                    return false;
+                }
+                match op.value {
+                    P::BinOp_::Sub => self.subtractions.push(op.loc),
+                    P::BinOp_::Mul => {
+                        let msg = "Multiplication involving a balance value, which may abort \
+                                   with an arithmetic overflow";
+                        let mut d = diag!(BALANCE_ARITHMETIC_DIAG, (op.loc, msg));
+                        d.add_note(
+                            "Cast the operands to 'u128' before multiplying, and check that the \
+                             result fits in 'u64'",
+                        );
+                        self.env.add_diag(d);
+                    }
+                    P::BinOp_::Lt | P::BinOp_::Le | P::BinOp_::Gt | P::BinOp_::Ge => {
+                        self.compared = true
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+        // always return false to process operands
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
+
+impl Context<'_> {
+    /// Records (or forgets) that a local holds a balance value when it is assigned.
+    fn track_binding(&mut self, sp!(_, lvalues): &T::LValueList, rhs: &T::Exp) {
+        let [sp!(_, T::LValue_::Var { var, .. })] = lvalues.as_slice() else {
+            return;
+        };
+        if self.is_balance_value(rhs) {
+            self.balance_vars.insert(var.value);
+        } else {
+            self.balance_vars.remove(&var.value);
+        }
+    }
+
+    fn is_balance_value(&self, exp: &T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::ModuleCall(fun) => VALUE_FUNCTIONS.iter().any(|(addr, module, fname)| {
+                fun.module.value.is(*addr, *module) && fun.name.value().as_str() == *fname
+            }),
+            E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) => {
+                self.balance_vars.contains(&var.value)
+            }
+            E::Annotate(e, _) => self.is_balance_value(e),
+            _ => false,
+        }
+    }
+}
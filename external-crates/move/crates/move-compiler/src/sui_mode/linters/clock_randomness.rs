@@ -0,0 +1,173 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags uses of timestamps (from sui::clock::Clock or sui::tx_context::TxContext)
+//! as a source of randomness, i.e., reducing them modulo some value. Timestamps are predictable
+//! and sui::random should be used instead.
+
+use std::collections::BTreeSet;
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::ModuleIdent,
+    naming::ast as N,
+    parser::ast::{self as P, FunctionName},
+    shared::{program_info::TypingProgramInfo, CompilationEnv, Identifier},
+    typing::{
+        ast as T,
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+
+use super::{
+    LinterDiagCategory, CLOCK_MOD_NAME, EPOCH_TIMESTAMP_MS_FUN, LINTER_DEFAULT_DIAG_CODE,
+    LINT_WARNING_PREFIX, SUI_PKG_NAME, TIMESTAMP_MS_FUN, TX_CONTEXT_MOD_NAME,
+};
+
+const CLOCK_RANDOMNESS_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::ClockRandomness as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "timestamp used as randomness",
+);
+
+const TIMESTAMP_FUNCTIONS: &[(&str, &str, &str)] = &[
+    (SUI_PKG_NAME, CLOCK_MOD_NAME, TIMESTAMP_MS_FUN),
+    (SUI_PKG_NAME, TX_CONTEXT_MOD_NAME, EPOCH_TIMESTAMP_MS_FUN),
+];
+
+pub struct ClockRandomnessVisitor;
+
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+    /// Locals of the function being visited holding values derived from a timestamp
+    timestamp_vars: BTreeSet<N::Var_>,
+}
+
+impl TypingVisitorConstructor for ClockRandomnessVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        _program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context {
+            env,
+            timestamp_vars: BTreeSet::new(),
+        }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_module_custom(&mut self, _ident: ModuleIdent, mdef: &mut T::ModuleDefinition) -> bool {
+        // skips if true
+        mdef.attributes.is_test_or_test_only()
+    }
+
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        self.timestamp_vars.clear();
+        // skips if true
+        fdef.attributes.is_test_or_test_only()
+    }
+
+    fn visit_seq_item(&mut self, sp!(_, seq_item): &mut T::SequenceItem) {
+        use T::SequenceItem_ as SI;
+        match seq_item {
+            SI::Seq(e) => self.visit_exp(e),
+            SI::Declare(_) => (),
+            SI::Bind(lvalues, _, e) => {
+                self.visit_exp(e);
+                self.track_binding(lvalues, e);
+            }
+        }
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        use T::UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::Assign(lvalues, _, rhs) => self.track_binding(lvalues, rhs),
+            E::BinopExp(e1, op, _, e2)
+                if op.value == P::BinOp_::Mod
+                    && (self.is_timestamp(e1) || self.is_timestamp(e2)) =>
+            {
+                let msg = "The result of this operation is derived from a timestamp and is \
+                           predictable";
+                let mut d = diag!(CLOCK_RANDOMNESS_DIAG, (op.loc, msg));
+                d.add_note(
+                    "Timestamps can be anticipated by users and validators. Use 'sui::random' \
+                     as a source of randomness instead",
+                );
+                self.env.add_diag(d);
+                // the operands are not visited to report a single warning for nested operations
+                return true;
+            }
+            _ => (),
+        }
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
+
+impl Context<'_> {
+    /// Records (or forgets) that a local holds a value derived from a timestamp when it is
+    /// assigned.
+    fn track_binding(&mut self, sp!(_, lvalues): &T::LValueList, rhs: &T::Exp) {
+        let [sp!(_, T::LValue_::Var { var, .. })] = lvalues.as_slice() else {
+            return;
+        };
+        if self.is_timestamp(rhs) {
+            self.timestamp_vars.insert(var.value);
+        } else {
+            self.timestamp_vars.remove(&var.value);
+        }
+    }
+
+    /// Is the value of the expression derived from a timestamp.
+    fn is_timestamp(&self, exp: &T::Exp) -> bool {
+        use P::BinOp_ as B;
+        use T::UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::ModuleCall(fun) => TIMESTAMP_FUNCTIONS.iter().any(|(addr, module, fname)| {
+                fun.module.value.is(*addr, *module) && fun.name.value().as_str() == *fname
+            }),
+            E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) => {
+                self.timestamp_vars.contains(&var.value)
+            }
+            E::BinopExp(e1, op, _, e2) => {
+                matches!(
+                    op.value,
+                    B::Add
+                        | B::Sub
+                        | B::Mul
+                        | B::Div
+                        | B::Mod
+                        | B::BitOr
+                        | B::BitAnd
+                        | B::Xor
+                        | B::Shl
+                        | B::Shr
+                ) && (self.is_timestamp(e1) || self.is_timestamp(e2))
+            }
+            E::Cast(e, _) | E::Annotate(e, _) => self.is_timestamp(e),
+            _ => false,
+        }
+    }
+}
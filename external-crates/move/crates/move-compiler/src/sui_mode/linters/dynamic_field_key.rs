@@ -0,0 +1,126 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags accesses to dynamic fields (via sui::dynamic_field or
+//! sui::dynamic_object_field) in public and entry functions, where the type of the key is a type
+//! parameter of the function. Callers can then choose the key type and access or overwrite any
+//! dynamic field stored under a key they can construct.
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::{ModuleIdent, Visibility},
+    naming::ast as N,
+    parser::ast::FunctionName,
+    shared::{program_info::TypingProgramInfo, CompilationEnv, Identifier},
+    typing::{
+        ast as T,
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+
+use super::{
+    base_type, LinterDiagCategory, DYNAMIC_FIELD_MOD_NAME, DYNAMIC_OBJECT_FIELD_MOD_NAME,
+    LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX, SUI_PKG_NAME,
+};
+
+const DYNAMIC_FIELD_KEY_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::DynamicFieldKey as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "dynamic field keyed by a caller-chosen type",
+);
+
+const DYNAMIC_FIELD_MODULES: &[(&str, &str)] = &[
+    (SUI_PKG_NAME, DYNAMIC_FIELD_MOD_NAME),
+    (SUI_PKG_NAME, DYNAMIC_OBJECT_FIELD_MOD_NAME),
+];
+
+/// Functions of the dynamic field modules whose first type argument is the type of the key.
+const KEYED_FUNCTIONS: &[&str] = &[
+    "add",
+    "borrow",
+    "borrow_mut",
+    "remove",
+    "exists_",
+    "exists_with_type",
+    "remove_if_exists",
+];
+
+pub struct DynamicFieldKeyVisitor;
+
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+}
+
+impl TypingVisitorConstructor for DynamicFieldKeyVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        _program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context { env }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_module_custom(&mut self, _ident: ModuleIdent, mdef: &mut T::ModuleDefinition) -> bool {
+        // skips if true
+        mdef.attributes.is_test_or_test_only()
+    }
+
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        _function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        // skips if true, i.e., for functions that cannot be called from other packages or
+        // transactions
+        fdef.attributes.is_test_or_test_only()
+            || (!matches!(fdef.visibility, Visibility::Public(_)) && fdef.entry.is_none())
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        let T::UnannotatedExp_::ModuleCall(fun) = &exp.exp.value else {
+            return false;
+        };
+        if !DYNAMIC_FIELD_MODULES
+            .iter()
+            .any(|(addr, module)| fun.module.value.is(*addr, *module))
+            || !KEYED_FUNCTIONS.contains(&fun.name.value().as_str())
+        {
+            return false;
+        }
+        let Some(sp!(_, N::Type_::Param(tparam))) = fun.type_arguments.first().and_then(base_type)
+        else {
+            return false;
+        };
+        let msg = format!(
+            "The type of the key of this dynamic field is the type parameter '{}'",
+            tparam.user_specified_name.value
+        );
+        let mut d = diag!(DYNAMIC_FIELD_KEY_DIAG, (exp.exp.loc, msg));
+        d.add_note(
+            "Callers can choose the key type, and so access or overwrite dynamic fields stored \
+             under keys they can construct. Use a key type defined in this module instead",
+        );
+        self.env.add_diag(d);
+        // process arguments of the call
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags public and entry functions that take a mutable reference to an object and
+//! a mutable reference to sui::tx_context::TxContext, but neither check the sender of the
+//! transaction (via sui::tx_context::sender) nor require a capability argument. Such functions can
+//! typically be called by anyone to modify the object (if it is shared).
+
+use crate::{
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        WarningFilters,
+    },
+    expansion::ast::{ModuleIdent, Visibility},
+    naming::ast as N,
+    parser::ast::{Ability_, FunctionName},
+    shared::{program_info::TypingProgramInfo, CompilationEnv, Identifier},
+    sui_mode::INIT_FUNCTION_NAME,
+    typing::{
+        ast as T,
+        visitor::{TypingVisitorConstructor, TypingVisitorContext},
+    },
+};
+use move_symbol_pool::Symbol;
+
+use super::{
+    is_capability_type, LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX,
+    SENDER_FUN, SUI_PKG_NAME, TX_CONTEXT_MOD_NAME, TX_CONTEXT_STRUCT_NAME,
+};
+
+const MISSING_SENDER_CHECK_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::MissingSenderCheck as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "unchecked transaction sender",
+);
+
+pub struct MissingSenderCheckVisitor;
+
+pub struct Context<'a> {
+    env: &'a mut CompilationEnv,
+    program_info: &'a TypingProgramInfo,
+    /// Set when the function being visited calls sui::tx_context::sender
+    sender_checked: bool,
+}
+
+impl TypingVisitorConstructor for MissingSenderCheckVisitor {
+    type Context<'a> = Context<'a>;
+
+    fn context<'a>(
+        env: &'a mut CompilationEnv,
+        program_info: &'a TypingProgramInfo,
+        _program: &T::Program_,
+    ) -> Self::Context<'a> {
+        Context {
+            env,
+            program_info,
+            sender_checked: false,
+        }
+    }
+}
+
+impl TypingVisitorContext for Context<'_> {
+    fn visit_module_custom(&mut self, _ident: ModuleIdent, mdef: &mut T::ModuleDefinition) -> bool {
+        // skips if true
+        mdef.attributes.is_test_or_test_only()
+    }
+
+    fn visit_function_custom(
+        &mut self,
+        _module: ModuleIdent,
+        function_name: FunctionName,
+        fdef: &mut T::Function,
+    ) -> bool {
+        if fdef.attributes.is_test_or_test_only() || function_name.value() == INIT_FUNCTION_NAME {
+            return true;
+        }
+        if !matches!(fdef.visibility, Visibility::Public(_)) && fdef.entry.is_none() {
+            return true;
+        }
+        let params = &fdef.signature.parameters;
+        if !params.iter().any(|(_, _, t)| is_mut_tx_context(t)) {
+            return true;
+        }
+        if params.iter().any(|(_, _, t)| is_capability_type(t)) {
+            // access is already restricted to holders of the capability
+            return true;
+        }
+        let Some((param, sname)) = params
+            .iter()
+            .find_map(|(_, v, t)| self.mut_object(t).map(|sname| (v.value.name, sname)))
+        else {
+            return true;
+        };
+        let T::FunctionBody_::Defined(seq) = &mut fdef.body.value else {
+            return true;
+        };
+
+        self.sender_checked = false;
+        self.visit_seq(seq);
+        if !self.sender_checked {
+            let msg = format!(
+                "Function '{function_name}' modifies the object '{param}' of type '{sname}' \
+                 without checking the sender of the transaction"
+            );
+            let mut d = diag!(MISSING_SENDER_CHECK_DIAG, (function_name.loc(), msg));
+            d.add_note(
+                "Check the sender with 'sui::tx_context::sender', or require a capability \
+                 argument, to restrict who can call this function",
+            );
+            self.env.add_diag(d);
+        }
+        true
+    }
+
+    fn visit_exp_custom(&mut self, exp: &mut T::Exp) -> bool {
+        if let T::UnannotatedExp_::ModuleCall(fun) = &exp.exp.value {
+            if fun.module.value.is(SUI_PKG_NAME, TX_CONTEXT_MOD_NAME)
+                && fun.name.value().as_str() == SENDER_FUN
+            {
+                self.sender_checked = true;
+            }
+        }
+        // always return false to process arguments of the call
+        false
+    }
+
+    fn add_warning_filter_scope(&mut self, filter: WarningFilters) {
+        self.env.add_warning_filter_scope(filter)
+    }
+
+    fn pop_warning_filter_scope(&mut self) {
+        self.env.pop_warning_filter_scope()
+    }
+}
+
+impl Context<'_> {
+    /// Returns the name of the struct if `t` is a mutable reference to an object.
+    fn mut_object(&self, sp!(_, t): &N::Type) -> Option<Symbol> {
+        let N::Type_::Ref(true, inner) = t else {
+            return None;
+        };
+        let N::Type_::Apply(_, sp!(_, N::TypeName_::ModuleType(mident, sname)), _) = &inner.value
+        else {
+            return None;
+        };
+        self.program_info
+            .struct_declared_abilities(mident, sname)
+            .has_ability_(Ability_::Key)
+            .then(|| sname.value())
+    }
+}
+
+fn is_mut_tx_context(sp!(_, t): &N::Type) -> bool {
+    match t {
+        N::Type_::Ref(true, inner) => {
+            inner
+                .value
+                .is(SUI_PKG_NAME, TX_CONTEXT_MOD_NAME, TX_CONTEXT_STRUCT_NAME)
+        }
+        _ => false,
+    }
+}
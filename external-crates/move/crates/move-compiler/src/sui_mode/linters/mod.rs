@@ -8,14 +8,20 @@ use crate::{
     expansion::ast as E,
     hlir::ast::{BaseType_, SingleType, SingleType_},
     naming::ast as N,
+    shared::Identifier,
     typing::visitor::TypingVisitor,
 };
 use move_ir_types::location::Loc;
 
+pub mod balance_arithmetic;
+pub mod clock_randomness;
 pub mod coin_field;
 pub mod collection_equality;
 pub mod custom_state_change;
+pub mod dynamic_field_key;
 pub mod freeze_wrapped;
+pub mod missing_sender_check;
+pub mod public_capability;
 pub mod self_transfer;
 pub mod share_owned;
 
@@ -58,6 +64,23 @@ pub const VEC_MAP_STRUCT_NAME: &str = "VecMap";
 pub const VEC_SET_MOD_NAME: &str = "vec_set";
 pub const VEC_SET_STRUCT_NAME: &str = "VecSet";
 
+pub const TX_CONTEXT_MOD_NAME: &str = "tx_context";
+pub const TX_CONTEXT_STRUCT_NAME: &str = "TxContext";
+pub const SENDER_FUN: &str = "sender";
+pub const EPOCH_TIMESTAMP_MS_FUN: &str = "epoch_timestamp_ms";
+
+pub const BALANCE_MOD_NAME: &str = "balance";
+pub const VALUE_FUN: &str = "value";
+
+pub const CLOCK_MOD_NAME: &str = "clock";
+pub const TIMESTAMP_MS_FUN: &str = "timestamp_ms";
+
+pub const DYNAMIC_FIELD_MOD_NAME: &str = "dynamic_field";
+pub const DYNAMIC_OBJECT_FIELD_MOD_NAME: &str = "dynamic_object_field";
+
+/// Suffix of the names of structs treated as capabilities.
+pub const CAPABILITY_SUFFIX: &str = "Cap";
+
 pub const ALLOW_ATTR_NAME: &str = "lint_allow";
 pub const LINT_WARNING_PREFIX: &str = "Lint ";
 
@@ -67,6 +90,11 @@ pub const CUSTOM_STATE_CHANGE_FILTER_NAME: &str = "custom_state_change";
 pub const COIN_FIELD_FILTER_NAME: &str = "coin_field";
 pub const FREEZE_WRAPPED_FILTER_NAME: &str = "freeze_wrapped";
 pub const COLLECTION_EQUALITY_FILTER_NAME: &str = "collection_equality";
+pub const MISSING_SENDER_CHECK_FILTER_NAME: &str = "missing_sender_check";
+pub const BALANCE_ARITHMETIC_FILTER_NAME: &str = "balance_arithmetic";
+pub const CLOCK_RANDOMNESS_FILTER_NAME: &str = "clock_randomness";
+pub const PUBLIC_CAPABILITY_FILTER_NAME: &str = "public_capability";
+pub const DYNAMIC_FIELD_KEY_FILTER_NAME: &str = "dynamic_field_key";
 
 pub const INVALID_LOC: Loc = Loc::invalid();
 
@@ -77,6 +105,11 @@ pub enum LinterDiagCategory {
     CoinField,
     FreezeWrapped,
     CollectionEquality,
+    MissingSenderCheck,
+    BalanceArithmetic,
+    ClockRandomness,
+    PublicCapability,
+    DynamicFieldKey,
 }
 
 /// A default code for each linter category (as long as only one code per category is used, no other
@@ -124,6 +157,36 @@ pub fn known_filters() -> (E::AttributeName_, Vec<WarningFilter>) {
                 LINTER_DEFAULT_DIAG_CODE,
                 Some(COLLECTION_EQUALITY_FILTER_NAME),
             ),
+            WarningFilter::code(
+                Some(LINT_WARNING_PREFIX),
+                LinterDiagCategory::MissingSenderCheck as u8,
+                LINTER_DEFAULT_DIAG_CODE,
+                Some(MISSING_SENDER_CHECK_FILTER_NAME),
+            ),
+            WarningFilter::code(
+                Some(LINT_WARNING_PREFIX),
+                LinterDiagCategory::BalanceArithmetic as u8,
+                LINTER_DEFAULT_DIAG_CODE,
+                Some(BALANCE_ARITHMETIC_FILTER_NAME),
+            ),
+            WarningFilter::code(
+                Some(LINT_WARNING_PREFIX),
+                LinterDiagCategory::ClockRandomness as u8,
+                LINTER_DEFAULT_DIAG_CODE,
+                Some(CLOCK_RANDOMNESS_FILTER_NAME),
+            ),
+            WarningFilter::code(
+                Some(LINT_WARNING_PREFIX),
+                LinterDiagCategory::PublicCapability as u8,
+                LINTER_DEFAULT_DIAG_CODE,
+                Some(PUBLIC_CAPABILITY_FILTER_NAME),
+            ),
+            WarningFilter::code(
+                Some(LINT_WARNING_PREFIX),
+                LinterDiagCategory::DynamicFieldKey as u8,
+                LINTER_DEFAULT_DIAG_CODE,
+                Some(DYNAMIC_FIELD_KEY_FILTER_NAME),
+            ),
         ],
     )
 }
@@ -136,6 +199,11 @@ pub fn linter_visitors() -> Vec<Visitor> {
         coin_field::CoinFieldVisitor.visitor(),
         freeze_wrapped::FreezeWrappedVisitor.visitor(),
         collection_equality::CollectionEqualityVisitor.visitor(),
+        missing_sender_check::MissingSenderCheckVisitor.visitor(),
+        balance_arithmetic::BalanceArithmeticVisitor.visitor(),
+        clock_randomness::ClockRandomnessVisitor.visitor(),
+        public_capability::PublicCapabilityVisitor.visitor(),
+        dynamic_field_key::DynamicFieldKeyVisitor.visitor(),
     ]
 }
 
@@ -148,6 +216,18 @@ pub fn base_type(t: &N::Type) -> Option<&N::Type> {
     }
 }
 
+/// Returns true if the struct type `t` (possibly behind a reference) is named like a capability.
+pub fn is_capability_type(t: &N::Type) -> bool {
+    base_type(t)
+        .and_then(|bt| bt.value.type_name())
+        .is_some_and(|tn| match &tn.value {
+            N::TypeName_::ModuleType(_, sname) => {
+                sname.value().as_str().ends_with(CAPABILITY_SUFFIX)
+            }
+            N::TypeName_::Builtin(_) | N::TypeName_::Multiple(_) => false,
+        })
+}
+
 /// Returns abilities of a given type, if any.
 pub fn type_abilities(sp!(_, st_): &SingleType) -> Option<E::AbilitySet> {
     let sp!(_, bt_) = match st_ {
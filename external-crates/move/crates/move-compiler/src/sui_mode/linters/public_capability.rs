@@ -0,0 +1,107 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags public functions returning capabilities (structs whose name ends with "Cap")
+//! defined in the same module and having the store ability, without requiring another capability
+//! as an argument. Anyone can call such functions to obtain the capability and, since it has
+//! store, transfer it freely.
+
+use crate::{
+    diag,
+    diagnostics::codes::{custom, DiagnosticInfo, Severity},
+    expansion::ast::{ModuleIdent, Visibility},
+    naming::ast as N,
+    parser::ast::Ability_,
+    shared::{program_info::TypingProgramInfo, CompilationEnv, Identifier},
+    typing::{ast as T, visitor::TypingVisitor},
+};
+use move_symbol_pool::Symbol;
+
+use super::{
+    is_capability_type, LinterDiagCategory, CAPABILITY_SUFFIX, LINTER_DEFAULT_DIAG_CODE,
+    LINT_WARNING_PREFIX,
+};
+
+const PUBLIC_CAPABILITY_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::PublicCapability as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "capability returned by public function",
+);
+
+pub struct PublicCapabilityVisitor;
+
+impl TypingVisitor for PublicCapabilityVisitor {
+    fn visit(
+        &mut self,
+        env: &mut CompilationEnv,
+        program_info: &TypingProgramInfo,
+        program: &mut T::Program_,
+    ) {
+        for (mident, mdef) in program.modules.key_cloned_iter() {
+            if mdef.attributes.is_test_or_test_only() {
+                continue;
+            }
+            env.add_warning_filter_scope(mdef.warning_filter.clone());
+            mdef.functions
+                .iter()
+                .filter(|(_, _, fdef)| !fdef.attributes.is_test_or_test_only())
+                .for_each(|(_, fname, fdef)| function(env, program_info, mident, *fname, fdef));
+            env.pop_warning_filter_scope();
+        }
+    }
+}
+
+fn function(
+    env: &mut CompilationEnv,
+    program_info: &TypingProgramInfo,
+    mident: ModuleIdent,
+    fname: Symbol,
+    fdef: &T::Function,
+) {
+    if !matches!(fdef.visibility, Visibility::Public(_)) || fdef.entry.is_some() {
+        // entry functions cannot return capabilities to the caller
+        return;
+    }
+    if fdef
+        .signature
+        .parameters
+        .iter()
+        .any(|(_, _, t)| is_capability_type(t))
+    {
+        // access is already restricted to holders of another capability
+        return;
+    }
+
+    env.add_warning_filter_scope(fdef.warning_filter.clone());
+    let return_type = &fdef.signature.return_type;
+    let returned = match &return_type.value {
+        N::Type_::Apply(_, sp!(_, N::TypeName_::Multiple(_)), tys) => tys.iter().collect(),
+        _ => vec![return_type],
+    };
+    for t in returned {
+        let N::Type_::Apply(_, sp!(_, N::TypeName_::ModuleType(m, sname)), _) = &t.value else {
+            continue;
+        };
+        if m.value != mident.value
+            || !sname.value().as_str().ends_with(CAPABILITY_SUFFIX)
+            || !program_info
+                .struct_declared_abilities(m, sname)
+                .has_ability_(Ability_::Store)
+        {
+            continue;
+        }
+        let msg = format!(
+            "Public function '{fname}' returns the capability '{sname}', which has the 'store' \
+             ability"
+        );
+        let mut d = diag!(PUBLIC_CAPABILITY_DIAG, (t.loc, msg));
+        d.add_note(
+            "Anyone can call this function to obtain the capability and transfer it freely. \
+             Restrict the visibility of the function, or require a capability argument",
+        );
+        env.add_diag(d);
+    }
+    env.pop_warning_filter_scope();
+}
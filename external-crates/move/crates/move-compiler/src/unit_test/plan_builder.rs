@@ -8,18 +8,22 @@ use crate::{
     expansion::ast::{
         self as E, Address, Attribute, AttributeValue, ModuleAccess_, ModuleIdent, ModuleIdent_,
     },
-    hlir::translate::display_var,
+    hlir::{ast as H, translate::display_var},
+    naming::ast as N,
     parser::ast::ConstantName,
     shared::{
         known_attributes::{KnownAttribute, TestingAttribute},
         unique_map::UniqueMap,
         CompilationEnv, Identifier, NumericalAddress,
     },
-    unit_test::{ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestCase},
+    unit_test::{ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestArgument, TestCase},
 };
 use move_core_types::{
-    account_address::AccountAddress as MoveAddress, language_storage::ModuleId,
-    runtime_value::MoveValue, u256::U256, vm_status::StatusCode,
+    account_address::AccountAddress as MoveAddress,
+    language_storage::ModuleId,
+    runtime_value::{MoveTypeLayout, MoveValue},
+    u256::U256,
+    vm_status::StatusCode,
 };
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
@@ -125,15 +129,16 @@ fn build_test_info<'func>(
     const IN_THIS_TEST_MSG: &str = "Error found in this test";
 
     let test_attribute_opt = get_attrs(TestingAttribute::Test);
+    let rand_test_attribute_opt = get_attrs(TestingAttribute::RandTest);
     let abort_attribute_opt = get_attrs(TestingAttribute::ExpectedFailure);
     let test_only_attribute_opt = get_attrs(TestingAttribute::TestOnly);
 
-    let test_attribute = match test_attribute_opt {
-        None => {
+    let (test_attribute, is_random_test) = match (test_attribute_opt, rand_test_attribute_opt) {
+        (None, None) => {
             // expected failures cannot be annotated on non-#[test] functions
             if let Some(abort_attribute) = abort_attribute_opt {
-                let fn_msg = "Only functions defined as a test with #[test] can also have an \
-                              #[expected_failure] attribute";
+                let fn_msg = "Only functions defined as a test with #[test] or #[random_test] can \
+                              also have an #[expected_failure] attribute";
                 let abort_msg = "Attributed as #[expected_failure] here";
                 context.env.add_diag(diag!(
                     Attributes::InvalidUsage,
@@ -143,13 +148,33 @@ fn build_test_info<'func>(
             }
             return None;
         }
-        Some(test_attribute) => test_attribute,
+        (Some(test_attribute), None) => (test_attribute, false),
+        (None, Some(rand_test_attribute)) => (rand_test_attribute, true),
+        (Some(test_attribute), Some(rand_test_attribute)) => {
+            // A function cannot be both a #[test] and a #[random_test]
+            let msg = "Function annotated as both #[test(...)] and #[random_test(...)]. You need \
+                       to declare it as either one or the other";
+            context.env.add_diag(diag!(
+                Attributes::InvalidUsage,
+                (rand_test_attribute.loc, msg),
+                (test_attribute.loc, PREVIOUSLY_ANNOTATED_MSG),
+                (fn_loc, IN_THIS_TEST_MSG),
+            ));
+            return None;
+        }
     };
 
     // A #[test] function cannot also be annotated #[test_only]
     if let Some(test_only_attribute) = test_only_attribute_opt {
-        let msg = "Function annotated as both #[test(...)] and #[test_only]. You need to declare \
-                   it as either one or the other";
+        let msg = format!(
+            "Function annotated as both #[{}(...)] and #[test_only]. You need to declare \
+             it as either one or the other",
+            if is_random_test {
+                TestingAttribute::RAND_TEST
+            } else {
+                TestingAttribute::TEST
+            }
+        );
         context.env.add_diag(diag!(
             Attributes::InvalidUsage,
             (test_only_attribute.loc, msg),
@@ -160,14 +185,29 @@ fn build_test_info<'func>(
 
     let test_annotation_params = parse_test_attribute(context, test_attribute, 0);
     let mut arguments = Vec::new();
-    for (var, _) in &function.signature.parameters {
+    for (var, ty) in &function.signature.parameters {
         let sp!(vloc, var_) = var.0;
         let var_ = match display_var(var_) {
             crate::hlir::translate::DisplayVar::Orig(s) => s.into(),
             crate::hlir::translate::DisplayVar::Tmp => panic!("ICE temp as parameter"),
         };
         match test_annotation_params.get(&var_) {
-            Some(value) => arguments.push(value.clone()),
+            Some(value) => arguments.push(TestArgument::Value(value.clone())),
+            // Parameters of a #[random_test] that are not assigned are generated by the runner
+            None if is_random_test => match random_test_argument_layout(ty) {
+                Some(generator) => arguments.push(TestArgument::Generate { generator }),
+                None => {
+                    let msg = "Unsupported type for a randomly generated test parameter. Only \
+                               'bool', integer, 'address', and vectors of these types can be \
+                               generated";
+                    context.env.add_diag(diag!(
+                        Attributes::InvalidTest,
+                        (ty.loc, msg),
+                        (vloc, "Corresponding to this parameter"),
+                        (fn_loc, IN_THIS_TEST_MSG),
+                    ))
+                }
+            },
             None => {
                 let missing_param_msg = "Missing test parameter assignment in test. Expected a \
                                          parameter to be assigned in this attribute";
@@ -214,7 +254,7 @@ fn parse_test_attribute(
         }
         EA::Name(nm) => {
             assert!(
-                nm.value.as_str() == TestingAttribute::Test.name() && depth == 0,
+                is_test_attribute_name(nm.value.as_str()) && depth == 0,
                 "ICE: We should only be parsing a raw test attribute"
             );
             BTreeMap::new()
@@ -246,7 +286,7 @@ fn parse_test_attribute(
         }
         EA::Parameterized(nm, attributes) => {
             assert!(
-                nm.value.as_str() == TestingAttribute::Test.name() && depth == 0,
+                is_test_attribute_name(nm.value.as_str()) && depth == 0,
                 "ICE: We should only be parsing a raw test attribute"
             );
             attributes
@@ -257,6 +297,41 @@ fn parse_test_attribute(
     }
 }
 
+fn is_test_attribute_name(name: &str) -> bool {
+    name == TestingAttribute::Test.name() || name == TestingAttribute::RandTest.name()
+}
+
+// The layout of values generated for a #[random_test] parameter of type `ty`, if values of that
+// type can be generated
+fn random_test_argument_layout(ty: &H::SingleType) -> Option<MoveTypeLayout> {
+    match &ty.value {
+        H::SingleType_::Base(bt) => random_test_base_type_layout(bt),
+        H::SingleType_::Ref(_, _) => None,
+    }
+}
+
+fn random_test_base_type_layout(sp!(_, bt_): &H::BaseType) -> Option<MoveTypeLayout> {
+    use N::BuiltinTypeName_ as B;
+    let (sp!(_, b_), ty_args) = match bt_ {
+        H::BaseType_::Apply(_, sp!(_, H::TypeName_::Builtin(b)), ty_args) => (b, ty_args),
+        _ => return None,
+    };
+    Some(match (b_, &ty_args[..]) {
+        (B::Bool, []) => MoveTypeLayout::Bool,
+        (B::U8, []) => MoveTypeLayout::U8,
+        (B::U16, []) => MoveTypeLayout::U16,
+        (B::U32, []) => MoveTypeLayout::U32,
+        (B::U64, []) => MoveTypeLayout::U64,
+        (B::U128, []) => MoveTypeLayout::U128,
+        (B::U256, []) => MoveTypeLayout::U256,
+        (B::Address, []) => MoveTypeLayout::Address,
+        (B::Vector, [elem]) => {
+            MoveTypeLayout::Vector(Box::new(random_test_base_type_layout(elem)?))
+        }
+        _ => return None,
+    })
+}
+
 const BAD_ABORT_VALUE_WARNING: &str = "WARNING: passes for an abort from any module.";
 const INVALID_VALUE: &str = "Invalid value in attribute assignment";
 
@@ -0,0 +1,15 @@
+// parameters of a #[random_test] that are not assigned in the attribute must have a type that
+// can be randomly generated, and a function cannot be both a #[test] and a #[random_test]
+address 0x1 {
+module M {
+    #[random_test]
+    fun bad_signer(_s: signer) { }
+
+    #[random_test]
+    fun bad_ref(_x: &u64) { }
+
+    #[test]
+    #[random_test]
+    fun both() { }
+}
+}
@@ -0,0 +1,81 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    use sui::balance::{Self, Balance};
+    use sui::coin::{Self, Coin};
+    use sui::sui::SUI;
+
+    const EInsufficientBalance: u64 = 0;
+
+    public fun remaining_bad(b: &Balance<SUI>, amount: u64): u64 {
+        balance::value(b) - amount
+    }
+
+    public fun remaining_local_bad(c: &Coin<SUI>, amount: u64): u64 {
+        let v = coin::value(c);
+        v - amount
+    }
+
+    public fun remaining_checked_ok(b: &Balance<SUI>, amount: u64): u64 {
+        let v = balance::value(b);
+        assert!(v >= amount, EInsufficientBalance);
+        v - amount
+    }
+
+    public fun scaled_bad(b: &Balance<SUI>, rate: u64): u64 {
+        balance::value(b) * rate
+    }
+
+    public fun scaled_wide_ok(b: &Balance<SUI>, rate: u64): u128 {
+        (balance::value(b) as u128) * (rate as u128)
+    }
+
+    public fun sum_ok(b: &Balance<SUI>, c: &Coin<SUI>): u64 {
+        balance::value(b) + coin::value(c)
+    }
+
+    public fun reassigned_ok(b: &Balance<SUI>, amount: u64): u64 {
+        let v = balance::value(b);
+        let total = v + 1;
+        v = amount;
+        total - v
+    }
+
+    #[lint_allow(balance_arithmetic)]
+    public fun remaining_bad_suppressed(b: &Balance<SUI>, amount: u64): u64 {
+        balance::value(b) - amount
+    }
+}
+
+module sui::sui {
+    struct SUI has drop {}
+}
+
+module sui::balance {
+    struct Balance<phantom T> has store {
+        value: u64,
+    }
+    public fun value<T>(_: &Balance<T>): u64 {
+        abort 0
+    }
+}
+
+module sui::coin {
+    use sui::object::UID;
+    use sui::balance::Balance;
+
+    struct Coin<phantom T> has key, store {
+        id: UID,
+        balance: Balance<T>,
+    }
+    public fun value<T>(_: &Coin<T>): u64 {
+        abort 0
+    }
+}
+
+module sui::object {
+    struct UID has store {
+        id: address,
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    use sui::clock::{Self, Clock};
+    use sui::tx_context::{Self, TxContext};
+
+    public fun deadline_ok(clock: &Clock, start: u64): bool {
+        clock::timestamp_ms(clock) - start > 1000
+    }
+
+    public fun modulo_ok(n: u64): u64 {
+        n % 10
+    }
+
+    public fun pick_winner_bad(clock: &Clock, players: u64): u64 {
+        clock::timestamp_ms(clock) % players
+    }
+
+    public fun coin_flip_bad(ctx: &TxContext): bool {
+        let seed = tx_context::epoch_timestamp_ms(ctx);
+        let shifted = (seed >> 3) + 7;
+        (shifted as u8) % 2 == 0
+    }
+
+    public fun nested_bad(clock: &Clock): u64 {
+        (clock::timestamp_ms(clock) % 100) % 10
+    }
+
+    public fun reassigned_ok(clock: &Clock, n: u64): u64 {
+        let x = clock::timestamp_ms(clock);
+        let elapsed = x + 1;
+        x = n;
+        (x % 10) + elapsed
+    }
+
+    #[lint_allow(clock_randomness)]
+    public fun pick_winner_bad_suppressed(clock: &Clock, players: u64): u64 {
+        clock::timestamp_ms(clock) % players
+    }
+}
+
+module sui::clock {
+    struct Clock has key {
+        id: sui::object::UID,
+        timestamp_ms: u64,
+    }
+    public fun timestamp_ms(_: &Clock): u64 {
+        abort 0
+    }
+}
+
+module sui::tx_context {
+    struct TxContext has drop {}
+    public fun epoch_timestamp_ms(_: &TxContext): u64 {
+        abort 0
+    }
+}
+
+module sui::object {
+    struct UID has store {
+        id: address,
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    use sui::dynamic_field as df;
+    use sui::dynamic_object_field as dof;
+    use sui::object::UID;
+
+    struct Registry has key {
+        id: UID,
+    }
+
+    struct Key has copy, drop, store {}
+
+    public fun add_bad<K: copy + drop + store>(r: &mut Registry, k: K, v: u64) {
+        df::add(&mut r.id, k, v)
+    }
+
+    entry fun remove_bad<K: copy + drop + store>(r: &mut Registry, k: K) {
+        let _: u64 = df::remove(&mut r.id, k);
+    }
+
+    public fun borrow_bad<K: copy + drop + store, V: key + store>(r: &Registry, k: K): &V {
+        dof::borrow(&r.id, k)
+    }
+
+    public fun add_ok(r: &mut Registry, v: u64) {
+        df::add(&mut r.id, Key {}, v)
+    }
+
+    public fun add_value_ok<V: store>(r: &mut Registry, v: V) {
+        df::add(&mut r.id, Key {}, v)
+    }
+
+    fun add_private_ok<K: copy + drop + store>(r: &mut Registry, k: K, v: u64) {
+        df::add(&mut r.id, k, v)
+    }
+
+    #[lint_allow(dynamic_field_key)]
+    public fun add_bad_suppressed<K: copy + drop + store>(r: &mut Registry, k: K, v: u64) {
+        df::add(&mut r.id, k, v)
+    }
+}
+
+module sui::object {
+    struct UID has store {
+        id: address,
+    }
+}
+
+module sui::dynamic_field {
+    use sui::object::UID;
+
+    public fun add<Name: copy + drop + store, Value: store>(_: &mut UID, _: Name, _: Value) {
+        abort 0
+    }
+    public fun remove<Name: copy + drop + store, Value: store>(_: &mut UID, _: Name): Value {
+        abort 0
+    }
+}
+
+module sui::dynamic_object_field {
+    use sui::object::UID;
+
+    public fun borrow<Name: copy + drop + store, Value: key + store>(_: &UID, _: Name): &Value {
+        abort 0
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    use sui::object::UID;
+    use sui::tx_context::{Self, TxContext};
+
+    struct Vault has key {
+        id: UID,
+        owner: address,
+        value: u64,
+    }
+
+    struct AdminCap has key {
+        id: UID,
+    }
+
+    public fun withdraw_bad(vault: &mut Vault, _ctx: &mut TxContext) {
+        vault.value = 0;
+    }
+
+    entry fun reset_bad(vault: &mut Vault, _ctx: &mut TxContext) {
+        vault.value = 0;
+    }
+
+    public fun withdraw_checked_ok(vault: &mut Vault, ctx: &mut TxContext) {
+        assert!(tx_context::sender(ctx) == vault.owner, 0);
+        vault.value = 0;
+    }
+
+    public fun withdraw_with_cap_ok(_: &AdminCap, vault: &mut Vault, _ctx: &mut TxContext) {
+        vault.value = 0;
+    }
+
+    public fun read_ok(vault: &Vault, _ctx: &mut TxContext): u64 {
+        vault.value
+    }
+
+    public fun no_ctx_ok(vault: &mut Vault) {
+        vault.value = 0;
+    }
+
+    fun private_ok(vault: &mut Vault, _ctx: &mut TxContext) {
+        vault.value = 0;
+    }
+
+    #[lint_allow(missing_sender_check)]
+    public fun withdraw_bad_suppressed(vault: &mut Vault, _ctx: &mut TxContext) {
+        vault.value = 0;
+    }
+}
+
+module sui::object {
+    struct UID has store {
+        id: address,
+    }
+}
+
+module sui::tx_context {
+    struct TxContext has drop {}
+    public fun sender(_: &TxContext): address {
+        @0
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module a::test {
+    use sui::object::{Self, UID};
+    use sui::tx_context::TxContext;
+
+    struct AdminCap has key, store {
+        id: UID,
+    }
+
+    struct MintCap has key {
+        id: UID,
+    }
+
+    struct Receipt has key, store {
+        id: UID,
+    }
+
+    public fun new_admin_cap_bad(ctx: &mut TxContext): AdminCap {
+        AdminCap { id: object::new(ctx) }
+    }
+
+    public fun new_caps_bad(ctx: &mut TxContext): (u64, AdminCap) {
+        (0, AdminCap { id: object::new(ctx) })
+    }
+
+    public fun new_admin_cap_with_cap_ok(_: &AdminCap, ctx: &mut TxContext): AdminCap {
+        AdminCap { id: object::new(ctx) }
+    }
+
+    public fun new_mint_cap_ok(ctx: &mut TxContext): MintCap {
+        MintCap { id: object::new(ctx) }
+    }
+
+    public fun new_receipt_ok(ctx: &mut TxContext): Receipt {
+        Receipt { id: object::new(ctx) }
+    }
+
+    public(friend) fun new_admin_cap_friend_ok(ctx: &mut TxContext): AdminCap {
+        AdminCap { id: object::new(ctx) }
+    }
+
+    fun new_admin_cap_private_ok(ctx: &mut TxContext): AdminCap {
+        AdminCap { id: object::new(ctx) }
+    }
+
+    #[lint_allow(public_capability)]
+    public fun new_admin_cap_bad_suppressed(ctx: &mut TxContext): AdminCap {
+        AdminCap { id: object::new(ctx) }
+    }
+}
+
+module sui::object {
+    struct UID has store {
+        id: address,
+    }
+    public fun new(_: &mut sui::tx_context::TxContext): UID {
+        abort 0
+    }
+}
+
+module sui::tx_context {
+    struct TxContext has drop {}
+}
@@ -352,10 +352,10 @@ fn add_move_lang_diagnostics(env: &mut GlobalEnv, diags: Diagnostics) {
         let loc = env.to_loc(&loc);
         Label::new(style, loc.file_id(), loc.span()).with_message(msg)
     };
-    for (severity, msg, primary_label, secondary_labels, notes) in diags.into_codespan_format() {
+    for (severity, info, primary_label, secondary_labels, notes) in diags.into_codespan_format() {
         let diag = Diagnostic::new(severity)
             .with_labels(vec![mk_label(true, primary_label)])
-            .with_message(msg.to_string())
+            .with_message(info.message().to_string())
             .with_labels(
                 secondary_labels
                     .into_iter()
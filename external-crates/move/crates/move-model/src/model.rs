@@ -3119,6 +3119,38 @@ impl<'env> FunctionEnv<'env> {
 
         events
     }
+
+    /// Returns the types for which a `sui::display::Display` may be created by this function
+    /// If `transitive` is false, only return types of displays directly created by this function
+    /// If `transitive` is true, return types of displays created by both this function and its
+    /// transitive callees
+    pub fn get_display_types(&'env self, transitive: bool) -> BTreeSet<Type> {
+        let mut displayed = BTreeSet::new();
+        if transitive {
+            let callees = self.get_transitive_closure_of_called_functions();
+            for callee in callees {
+                let fenv = self.module_env.env.get_function(callee);
+                displayed.extend(fenv.get_display_types(false));
+            }
+        } else {
+            let module = &self.module_env.data.module;
+            for b in self.get_bytecode() {
+                if let Bytecode::CallGeneric(fi_idx) = b {
+                    let FunctionInstantiation {
+                        handle,
+                        type_parameters,
+                    } = module.function_instantiation_at(*fi_idx);
+                    let f_ref = FunctionRef::from_idx(module, handle);
+                    if is_framework_function(&f_ref, "display", vec!["new", "new_with_fields"]) {
+                        let type_params = module.signature_at(*type_parameters);
+                        displayed.insert(self.module_env.globalize_signature(&type_params.0[0]));
+                    }
+                }
+            }
+        }
+
+        displayed
+    }
 }
 
 // =================================================================================================
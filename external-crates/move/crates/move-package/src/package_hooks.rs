@@ -1,11 +1,11 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::source_package::parsed_manifest::{CustomDepInfo, SourceManifest};
+use crate::source_package::parsed_manifest::{CustomDepInfo, OnChainInfo, SourceManifest};
 use anyhow::bail;
 use move_symbol_pool::Symbol;
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use std::{path::Path, sync::Mutex};
 
 // TODO: remove static hooks and refactor this crate for better customizability
 
@@ -29,6 +29,24 @@ pub trait PackageHooks {
     ) -> anyhow::Result<()>;
 
     fn custom_resolve_pkg_name(&self, manifest: &SourceManifest) -> anyhow::Result<Symbol>;
+
+    /// A resolver for on-chain dependencies in the manifest. This is called to fetch the package
+    /// `info.id` from `info.network` and write it as a source package (with a manifest, and
+    /// sources or interface stubs to compile against) to `dest`. Published packages are
+    /// immutable, so this is only called if `dest` does not contain a manifest yet, and the
+    /// manifest should be written last so that an interrupted fetch is retried.
+    fn resolve_on_chain_dependency(
+        &self,
+        dep_name: Symbol,
+        info: &OnChainInfo,
+        dest: &Path,
+    ) -> anyhow::Result<()> {
+        let _ = (info, dest);
+        bail!(
+            "on-chain dependency '{}' is not supported by this package system",
+            dep_name
+        )
+    }
 }
 static HOOKS: Lazy<Mutex<Option<Box<dyn PackageHooks + Send + Sync>>>> =
     Lazy::new(|| Mutex::new(None));
@@ -50,6 +68,20 @@ pub(crate) fn resolve_custom_dependency(
     }
 }
 
+/// Calls any registered hook to fetch an on-chain dependency into `dest`. Bails if none is
+/// registered.
+pub(crate) fn resolve_on_chain_dependency(
+    dep_name: Symbol,
+    info: &OnChainInfo,
+    dest: &Path,
+) -> anyhow::Result<()> {
+    if let Some(hooks) = &*HOOKS.lock().unwrap() {
+        hooks.resolve_on_chain_dependency(dep_name, info, dest)
+    } else {
+        bail!("use of unsupported on-chain dependency in package manifest")
+    }
+}
+
 pub(crate) fn custom_dependency_key() -> Option<String> {
     if let Some(hooks) = &*HOOKS.lock().unwrap() {
         hooks.custom_dependency_key()
@@ -13,7 +13,10 @@ use std::{
 
 use crate::{
     package_hooks,
-    source_package::parsed_manifest::{DependencyKind, GitInfo, PackageName},
+    source_package::{
+        layout::SourcePackageLayout,
+        parsed_manifest::{DependencyKind, GitInfo, PackageName},
+    },
 };
 
 use super::repository_path;
@@ -56,6 +59,34 @@ impl DependencyCache {
                 package_hooks::resolve_custom_dependency(dep_name, node_info)
             }
 
+            DependencyKind::OnChain(on_chain_info) => {
+                let repository_path = repository_path(kind);
+                // check if a give dependency type has already been fetched
+                if !self.fetched_deps.insert(repository_path.clone()) {
+                    return Ok(());
+                }
+                // Published packages never change, so a package fetched once can be reused
+                // without going back to the network.
+                if repository_path
+                    .join(SourcePackageLayout::Manifest.path())
+                    .exists()
+                {
+                    return Ok(());
+                }
+                writeln!(
+                    progress_output,
+                    "{} {} ({})",
+                    "FETCHING ON-CHAIN DEPENDENCY".bold().green(),
+                    on_chain_info.id,
+                    on_chain_info.network,
+                )?;
+                package_hooks::resolve_on_chain_dependency(
+                    dep_name,
+                    on_chain_info,
+                    &repository_path,
+                )
+            }
+
             DependencyKind::Git(GitInfo {
                 git_url,
                 git_rev,
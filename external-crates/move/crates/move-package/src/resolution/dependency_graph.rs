@@ -1379,6 +1379,18 @@ impl fmt::Display for Package {
                 write!(f, ", subdir = ")?;
                 f.write_str(&path_escape(subdir)?)?;
             }
+
+            PM::DependencyKind::OnChain(PM::OnChainInfo {
+                id,
+                network,
+                package_name: _,
+            }) => {
+                write!(f, "on-chain = ")?;
+                f.write_str(&str_escape(id.as_str())?)?;
+
+                write!(f, ", network = ")?;
+                f.write_str(&str_escape(network.as_str())?)?;
+            }
         }
 
         Ok(())
@@ -360,8 +360,9 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         table.remove("subdir"),
         table.remove("git"),
         custom_key_opt.as_ref().and_then(|k| table.remove(k)),
+        table.remove("on-chain"),
     ) {
-        (Some(local), subdir, None, None) => {
+        (Some(local), subdir, None, None, None) => {
             if subdir.is_some() {
                 bail!("'subdir' not supported for local dependencies");
             }
@@ -376,7 +377,7 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             )
         }
 
-        (None, subdir, Some(git_url), None) => {
+        (None, subdir, Some(git_url), None, None) => {
             let Some(git_rev) = table.remove("rev") else {
                 bail!("Git revision not supplied for dependency")
             };
@@ -404,7 +405,7 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             })
         }
 
-        (None, subdir, None, Some(custom_key)) => {
+        (None, subdir, None, Some(custom_key), None) => {
             let Some(package_address) = table.remove("address") else {
                 bail!("Address not supplied for 'node' dependency");
             };
@@ -435,8 +436,34 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             })
         }
 
+        (None, subdir, None, None, Some(id)) => {
+            if subdir.is_some() {
+                bail!("'subdir' not supported for on-chain dependencies");
+            }
+
+            let Some(id) = id.as_str().map(Symbol::from) else {
+                bail!("On-chain package ID not a string")
+            };
+
+            let Some(network) = table.remove("network") else {
+                bail!("Network not supplied for on-chain dependency")
+            };
+
+            let Some(network) = network.as_str().map(Symbol::from) else {
+                bail!("Network not a string")
+            };
+
+            let package_name = Symbol::from(dep_name);
+
+            PM::DependencyKind::OnChain(PM::OnChainInfo {
+                id,
+                network,
+                package_name,
+            })
+        }
+
         _ => {
-            let mut keys = vec!["'local'", "'git'", "'resolver'"];
+            let mut keys = vec!["'local'", "'git'", "'on-chain'", "'resolver'"];
             let quoted_custom_key = custom_key_opt.as_ref().map(|k| format!("'{}'", k));
             if let Some(k) = &quoted_custom_key {
                 keys.push(k.as_str())
@@ -64,6 +64,7 @@ pub enum DependencyKind {
     Local(PathBuf),
     Git(GitInfo),
     Custom(CustomDepInfo),
+    OnChain(OnChainInfo),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -90,6 +91,17 @@ pub struct CustomDepInfo {
     pub subdir: PathBuf,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct OnChainInfo {
+    /// The ID of the package on chain. The representation depends on the registered package
+    /// hooks.
+    pub id: Symbol,
+    /// The network the package is published on -- e.g., 'mainnet' or the url of a node
+    pub network: Symbol,
+    /// The package's name (i.e. the dependency name).
+    pub package_name: Symbol,
+}
+
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct BuildInfo {
     pub language_version: Option<Version>,
@@ -113,10 +125,13 @@ impl DependencyKind {
         let mut parent = parent.clone();
 
         match (&mut parent, &self) {
-            // If `self` is a git or custom dependency kind, it does not need to be re-rooted
-            // because its URI is already absolute. (i.e. the location of an absolute URI does not
-            // change if referenced relative to some other URI).
-            (_, DependencyKind::Git(_) | DependencyKind::Custom(_)) => return Ok(()),
+            // If `self` is a git, custom or on-chain dependency kind, it does not need to be
+            // re-rooted because its URI is already absolute. (i.e. the location of an absolute URI
+            // does not change if referenced relative to some other URI).
+            (
+                _,
+                DependencyKind::Git(_) | DependencyKind::Custom(_) | DependencyKind::OnChain(_),
+            ) => return Ok(()),
 
             (DependencyKind::Local(parent), DependencyKind::Local(subdir)) => {
                 parent.push(subdir);
@@ -132,6 +147,13 @@ impl DependencyKind {
                 custom.subdir.push(subdir);
                 custom.subdir = normalize_path(&custom.subdir, /* allow_cwd_parent */ false)?;
             }
+
+            // Packages fetched from chain only contain the package itself, so there is nothing
+            // for a local dependency to point to.
+            (DependencyKind::OnChain(on_chain), DependencyKind::Local(_)) => bail!(
+                "Local dependency of on-chain package '{}' is not supported",
+                on_chain.package_name,
+            ),
         };
 
         *self = parent;
@@ -13,7 +13,7 @@ use move_package::{
     package_hooks,
     package_hooks::PackageHooks,
     resolution::resolution_graph::Package,
-    source_package::parsed_manifest::{CustomDepInfo, PackageDigest, SourceManifest},
+    source_package::parsed_manifest::{CustomDepInfo, OnChainInfo, PackageDigest, SourceManifest},
     BuildConfig, ModelConfig,
 };
 use move_symbol_pool::Symbol;
@@ -220,6 +220,21 @@ impl PackageHooks for TestHooks {
         )
     }
 
+    fn resolve_on_chain_dependency(
+        &self,
+        dep_name: Symbol,
+        info: &OnChainInfo,
+        _dest: &Path,
+    ) -> anyhow::Result<()> {
+        bail!(
+            "TestHooks resolve on-chain dep {:?} = {:?} {:?} {:?}",
+            dep_name,
+            info.id,
+            info.network,
+            info.package_name,
+        )
+    }
+
     fn custom_resolve_pkg_name(&self, manifest: &SourceManifest) -> anyhow::Result<Symbol> {
         let name = manifest.package.name.to_string();
         if name.ends_with("-rename") {
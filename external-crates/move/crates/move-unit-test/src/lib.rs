@@ -4,10 +4,11 @@
 
 pub mod cargo_runner;
 pub mod extensions;
+mod random_test;
 pub mod test_reporter;
 pub mod test_runner;
 
-use crate::test_runner::TestRunner;
+use crate::{test_reporter::ReportFormat, test_runner::TestRunner};
 use clap::*;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
@@ -24,12 +25,16 @@ use std::{
     collections::BTreeMap,
     io::{Result, Write},
     marker::Send,
+    path::PathBuf,
     sync::Mutex,
 };
 
 /// The default value bounding the amount of gas consumed in a test.
 const DEFAULT_EXECUTION_BOUND: u64 = 1_000_000;
 
+/// The default number of times a `#[random_test]` is run with freshly generated arguments.
+const DEFAULT_RAND_NUM_ITERS: u64 = 10;
+
 #[derive(Debug, Parser, Clone)]
 #[clap(author, version, about)]
 pub struct UnitTestingConfig {
@@ -68,6 +73,11 @@ pub struct UnitTestingConfig {
     #[clap(name = "report-statistics", short = 's', long = "statistics")]
     pub report_statistics: Option<Option<String>>,
 
+    /// Write a machine-readable report of the test results to a file, as `junit=<path>` or
+    /// `json=<path>`. Can be passed multiple times.
+    #[clap(name = "report", long = "report", value_name = "FORMAT=PATH")]
+    pub reports: Vec<ReportFormat>,
+
     #[clap(
         name = "report_stacktrace_on_abort",
         short = 'r',
@@ -100,6 +110,27 @@ pub struct UnitTestingConfig {
     /// Verbose mode
     #[clap(short = 'v', long = "verbose")]
     pub verbose: bool,
+
+    /// Seed for the arguments generated for `#[random_test]` tests. A random seed is used if none
+    /// is given; the seed of a failing test is reported so the failure can be reproduced.
+    #[clap(name = "seed", long = "seed")]
+    pub seed: Option<u64>,
+
+    /// Number of times each `#[random_test]` is run with freshly generated arguments.
+    #[clap(name = "rand-num-iters", long = "rand-num-iters")]
+    pub rand_num_iters: Option<u64>,
+
+    /// Write a gas profile of each test, in the speedscope format (https://www.speedscope.app), to
+    /// the given directory or to the current one. Only available in debug builds.
+    #[clap(
+        name = "profile-gas",
+        long = "profile-gas",
+        value_name = "DIR",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "."
+    )]
+    pub profile_gas: Option<PathBuf>,
 }
 
 fn format_module_id(module_id: &ModuleId) -> String {
@@ -118,6 +149,7 @@ impl UnitTestingConfig {
             filter: None,
             num_threads: 8,
             report_statistics: None,
+            reports: vec![],
             report_stacktrace_on_abort: false,
             source_files: vec![],
             dep_files: vec![],
@@ -125,6 +157,9 @@ impl UnitTestingConfig {
             verbose: false,
             list: false,
             named_address_values: vec![],
+            seed: None,
+            rand_num_iters: None,
+            profile_gas: None,
         }
     }
 
@@ -214,6 +249,9 @@ impl UnitTestingConfig {
             native_function_table,
             cost_table,
             verify_and_create_named_address_mapping(self.named_address_values.clone()).unwrap(),
+            self.seed.unwrap_or_else(rand::random),
+            self.rand_num_iters.unwrap_or(DEFAULT_RAND_NUM_ITERS),
+            self.profile_gas.clone(),
         )
         .unwrap();
 
@@ -225,6 +263,9 @@ impl UnitTestingConfig {
         if let Some(report_type) = &self.report_statistics {
             test_results.report_statistics(&shared_writer, report_type)?;
         }
+        for report in &self.reports {
+            test_results.write_report(report)?;
+        }
 
         let ok = test_results.summarize(&shared_writer)?;
 
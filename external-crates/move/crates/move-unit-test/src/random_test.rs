@@ -0,0 +1,159 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation and shrinking of the arguments of `#[random_test]` tests.
+
+use move_compiler::unit_test::TestArgument;
+use move_core_types::{
+    account_address::AccountAddress,
+    runtime_value::{MoveTypeLayout, MoveValue},
+    u256::U256,
+};
+use rand::{rngs::StdRng, Rng};
+
+/// The maximum length of a randomly generated vector.
+const MAX_VECTOR_LENGTH: usize = 16;
+
+/// The maximum number of elements removed one at a time when shrinking a vector.
+const MAX_VECTOR_REMOVALS: usize = 32;
+
+/// Instantiates the arguments of a test, generating a fresh value for each argument that is not
+/// assigned in the test attribute.
+pub(crate) fn generate_arguments(rng: &mut StdRng, arguments: &[TestArgument]) -> Vec<MoveValue> {
+    arguments
+        .iter()
+        .map(|arg| match arg {
+            TestArgument::Value(value) => value.clone(),
+            TestArgument::Generate { generator } => generate_value(rng, generator),
+        })
+        .collect()
+}
+
+fn generate_value(rng: &mut StdRng, layout: &MoveTypeLayout) -> MoveValue {
+    match layout {
+        MoveTypeLayout::Bool => MoveValue::Bool(rng.gen()),
+        MoveTypeLayout::U8 => MoveValue::U8(generate_uint(rng, 8).unchecked_as_u8()),
+        MoveTypeLayout::U16 => MoveValue::U16(generate_uint(rng, 16).unchecked_as_u16()),
+        MoveTypeLayout::U32 => MoveValue::U32(generate_uint(rng, 32).unchecked_as_u32()),
+        MoveTypeLayout::U64 => MoveValue::U64(generate_uint(rng, 64).unchecked_as_u64()),
+        MoveTypeLayout::U128 => MoveValue::U128(generate_uint(rng, 128).unchecked_as_u128()),
+        MoveTypeLayout::U256 => MoveValue::U256(generate_uint(rng, 256)),
+        MoveTypeLayout::Address => {
+            // Favor the zero address and small addresses like the framework's, which are more
+            // likely to be special cased than uniformly random ones.
+            let mut bytes: [u8; AccountAddress::LENGTH] = rng.gen();
+            if rng.gen_ratio(1, 4) {
+                bytes = [0; AccountAddress::LENGTH];
+                bytes[AccountAddress::LENGTH - 1] = rng.gen_range(0..=16);
+            }
+            MoveValue::Address(AccountAddress::new(bytes))
+        }
+        MoveTypeLayout::Vector(elem) => {
+            let len = rng.gen_range(0..=MAX_VECTOR_LENGTH);
+            MoveValue::Vector((0..len).map(|_| generate_value(rng, elem)).collect())
+        }
+        MoveTypeLayout::Struct(_) | MoveTypeLayout::Signer => {
+            unreachable!("ICE: only primitive and vector test arguments can be generated")
+        }
+    }
+}
+
+/// Generates an unsigned integer of `bits` bits. Edge cases and small values are generated more
+/// often than their share of the full range, as those are where most bugs are found.
+fn generate_uint(rng: &mut StdRng, bits: u32) -> U256 {
+    let max = U256::max_value() >> (256 - bits) as u8;
+    match rng.gen_range(0..8) {
+        0 => U256::zero(),
+        1 => U256::one(),
+        2 => max,
+        3 | 4 => U256::from(rng.gen_range(0..=u8::MAX)),
+        _ => U256::from_le_bytes(&rng.gen()) & max,
+    }
+}
+
+/// Values that are strictly simpler than `value` and that the failing test should be retried with,
+/// from the most to the least aggressive simplification.
+pub(crate) fn shrink_value(value: &MoveValue) -> Vec<MoveValue> {
+    match value {
+        MoveValue::Bool(b) => {
+            if *b {
+                vec![MoveValue::Bool(false)]
+            } else {
+                vec![]
+            }
+        }
+        MoveValue::U8(n) => shrink_uint(U256::from(*n), |n| MoveValue::U8(n.unchecked_as_u8())),
+        MoveValue::U16(n) => shrink_uint(U256::from(*n), |n| MoveValue::U16(n.unchecked_as_u16())),
+        MoveValue::U32(n) => shrink_uint(U256::from(*n), |n| MoveValue::U32(n.unchecked_as_u32())),
+        MoveValue::U64(n) => shrink_uint(U256::from(*n), |n| MoveValue::U64(n.unchecked_as_u64())),
+        MoveValue::U128(n) => {
+            shrink_uint(U256::from(*n), |n| MoveValue::U128(n.unchecked_as_u128()))
+        }
+        MoveValue::U256(n) => shrink_uint(*n, MoveValue::U256),
+        MoveValue::Address(a) => {
+            if *a == AccountAddress::ZERO {
+                vec![]
+            } else {
+                vec![MoveValue::Address(AccountAddress::ZERO)]
+            }
+        }
+        MoveValue::Vector(elems) => shrink_vector(elems),
+        // Only generated values are shrunk, and those are never signers or structs.
+        MoveValue::Signer(_) | MoveValue::Struct(_) => vec![],
+    }
+}
+
+fn shrink_uint(n: U256, to_value: impl Fn(U256) -> MoveValue) -> Vec<MoveValue> {
+    if n == U256::zero() {
+        return vec![];
+    }
+    // Zero, then values approaching `n` from below (`n/2`, `3n/4`, `7n/8`, ..., `n - 1`), so the
+    // smallest failing value is found in a logarithmic number of steps.
+    let mut candidates = vec![U256::zero()];
+    for shift in 1..=u8::MAX {
+        let delta = n >> shift;
+        if delta == U256::zero() {
+            break;
+        }
+        candidates.push(n - delta);
+    }
+    let pred = n - U256::one();
+    if !candidates.contains(&pred) {
+        candidates.push(pred);
+    }
+    candidates.dedup();
+    candidates.into_iter().map(to_value).collect()
+}
+
+fn shrink_vector(elems: &[MoveValue]) -> Vec<MoveValue> {
+    let mut candidates = vec![];
+    if elems.is_empty() {
+        return candidates;
+    }
+
+    // Shorter vectors: empty, each half, and the vector with one element removed.
+    candidates.push(vec![]);
+    let mid = elems.len() / 2;
+    if mid > 0 {
+        candidates.push(elems[..mid].to_vec());
+        candidates.push(elems[mid..].to_vec());
+    }
+    if elems.len() > 1 {
+        for i in 0..elems.len().min(MAX_VECTOR_REMOVALS) {
+            let mut shorter = elems.to_vec();
+            shorter.remove(i);
+            candidates.push(shorter);
+        }
+    }
+
+    // Vectors of the same length with one simpler element.
+    for (i, elem) in elems.iter().enumerate() {
+        for simpler in shrink_value(elem) {
+            let mut shrunk = elems.to_vec();
+            shrunk[i] = simpler;
+            candidates.push(shrunk);
+        }
+    }
+
+    candidates.into_iter().map(MoveValue::Vector).collect()
+}
@@ -14,12 +14,19 @@ use move_compiler::{
     diagnostics::{self, Diagnostic, Diagnostics},
     unit_test::{ModuleTestPlan, TestName, TestPlan},
 };
-use move_core_types::{language_storage::ModuleId, vm_status::StatusType};
+use move_core_types::{
+    language_storage::ModuleId,
+    vm_status::{StatusCode, StatusType},
+};
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
-    io::{Result, Write},
+    fs::{self, File},
+    io::{BufWriter, Result, Write},
+    path::PathBuf,
+    str::FromStr,
     sync::Mutex,
     time::Duration,
 };
@@ -52,6 +59,16 @@ pub struct TestFailure {
     pub test_run_info: TestRunInfo,
     pub vm_error: Option<VMError>,
     pub failure_reason: FailureReason,
+    pub random_input: Option<RandomTestInput>,
+}
+
+/// The input a `#[random_test]` failed with.
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Serialize)]
+pub struct RandomTestInput {
+    /// The seed the arguments of the test were generated from.
+    pub seed: u64,
+    /// The arguments of the failing test, after shrinking, formatted as Move values.
+    pub arguments: Vec<String>,
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
@@ -74,6 +91,95 @@ pub struct TestResults {
     test_plan: TestPlan,
 }
 
+/// A machine-readable report of the test results, written to a file, parsed from
+/// `<format>=<path>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// JUnit XML, as understood by most CI systems.
+    Junit(PathBuf),
+    /// JSON, serialized from a `TestReport`.
+    Json(PathBuf),
+}
+
+/// The results of a test run, in the form they are written to machine-readable reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct TestReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub tests: Vec<TestCaseReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestCaseReport {
+    /// The module the test is defined in, as `<address>::<module>`.
+    pub module: String,
+    pub function: String,
+    pub status: TestStatus,
+    pub duration_secs: f64,
+    pub gas_used: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<TestFailureReport>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TestFailureReport {
+    /// Why the test failed, e.g. `unexpected_error` or `timeout`.
+    pub kind: &'static str,
+    /// The failure as it is rendered in the human-readable output, without colors.
+    pub message: String,
+    /// The abort code, if the test aborted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abort_code: Option<u64>,
+    /// Where the test aborted or errored, if it happened in Move code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abort_location: Option<AbortLocation>,
+    /// The minimal input a `#[random_test]` failed with, and the seed to reproduce it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random_input: Option<RandomTestInput>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AbortLocation {
+    pub module: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_offset: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// The line, or range of lines (`<start>-<end>`), of the source that errored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<String>,
+}
+
+/// Source files of a test plan, indexed for looking up line numbers.
+struct SourceFiles<'a> {
+    files: SimpleFiles<Symbol, &'a str>,
+    file_mapping: HashMap<FileHash, usize>,
+}
+
+impl<'a> SourceFiles<'a> {
+    fn new(test_plan: &'a TestPlan) -> Self {
+        let mut files = SimpleFiles::new();
+        let mut file_mapping = HashMap::new();
+        for (fhash, (fname, source)) in &test_plan.files {
+            let id = files.add(*fname, source.as_str());
+            file_mapping.insert(*fhash, id);
+        }
+        Self {
+            files,
+            file_mapping,
+        }
+    }
+}
+
 impl TestRunInfo {
     pub fn new(function_ident: String, elapsed_time: Duration, instructions_executed: u64) -> Self {
         Self {
@@ -126,6 +232,19 @@ impl FailureReason {
     pub fn property(details: String) -> Self {
         FailureReason::Property(details)
     }
+
+    /// A short identifier of the kind of failure, for machine-readable reports.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FailureReason::NoError(_) => "no_error",
+            FailureReason::WrongError(..) => "wrong_error",
+            FailureReason::WrongAbortDEPRECATED(..) => "wrong_abort",
+            FailureReason::UnexpectedError(..) => "unexpected_error",
+            FailureReason::Timeout(_) => "timeout",
+            FailureReason::Mismatch { .. } => "mismatch",
+            FailureReason::Property(_) => "property",
+        }
+    }
 }
 
 impl TestFailure {
@@ -138,10 +257,37 @@ impl TestFailure {
             test_run_info,
             vm_error,
             failure_reason,
+            random_input: None,
         }
     }
 
+    /// Records the input a `#[random_test]` failed with, to be reported along with the failure.
+    pub fn with_random_input(mut self, random_input: RandomTestInput) -> Self {
+        self.random_input = Some(random_input);
+        self
+    }
+
     pub fn render_error(&self, test_plan: &TestPlan) -> String {
+        self.render_error_impl(test_plan, control::SHOULD_COLORIZE.should_colorize())
+    }
+
+    fn render_error_impl(&self, test_plan: &TestPlan, colorize: bool) -> String {
+        let message = self.render_failure_reason(test_plan, colorize);
+        match &self.random_input {
+            None => message,
+            Some(RandomTestInput { seed, arguments }) => format!(
+                "{}\nThis test uses randomly generated inputs. It failed with the minimal input\n\
+                 \t{}({})\n\
+                 Run the tests with `--seed {}` to reproduce this failure.\n",
+                message.trim_end(),
+                self.test_run_info.function_ident,
+                arguments.join(", "),
+                seed,
+            ),
+        }
+    }
+
+    fn render_failure_reason(&self, test_plan: &TestPlan, colorize: bool) -> String {
         match &self.failure_reason {
             FailureReason::NoError(message) => message.to_string(),
             FailureReason::Timeout(message) => message.to_string(),
@@ -151,7 +297,7 @@ impl TestFailure {
                     expected.verbiage(/* is_past_tense */ false),
                     actual.verbiage(/* is_past_tense */ true),
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::WrongAbortDEPRECATED(message, expected_code, actual) => {
                 let base_message = format!(
@@ -161,7 +307,7 @@ impl TestFailure {
                     expected_code,
                     actual.verbiage(/* is_past_tense */ true),
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::UnexpectedError(message, error) => {
                 let prefix = match error.0.status_type() {
@@ -185,7 +331,7 @@ impl TestFailure {
                     message,
                     error.verbiage(/* is_past_tense */ true)
                 );
-                Self::report_error_with_location(test_plan, base_message, &self.vm_error)
+                Self::report_error_with_location(test_plan, base_message, &self.vm_error, colorize)
             }
             FailureReason::Mismatch {
                 move_vm_return_values,
@@ -203,6 +349,71 @@ impl TestFailure {
         }
     }
 
+    /// This failure, in the form it is written to machine-readable reports.
+    fn report(&self, test_plan: &TestPlan, source_files: &SourceFiles) -> TestFailureReport {
+        let abort_code = self
+            .vm_error
+            .as_ref()
+            .filter(|vm_error| vm_error.major_status() == StatusCode::ABORTED)
+            .and_then(|vm_error| vm_error.sub_status());
+        TestFailureReport {
+            kind: self.failure_reason.kind(),
+            message: self.render_error_impl(test_plan, /* colorize */ false),
+            abort_code,
+            abort_location: self.abort_location(test_plan, source_files),
+            random_input: self.random_input.clone(),
+        }
+    }
+
+    fn abort_location(
+        &self,
+        test_plan: &TestPlan,
+        source_files: &SourceFiles,
+    ) -> Option<AbortLocation> {
+        let vm_error = self.vm_error.as_ref()?;
+        let Location::Module(module_id) = vm_error.location() else {
+            return None;
+        };
+        let mut location = AbortLocation {
+            module: format_module_id(module_id),
+            function: None,
+            code_offset: None,
+            file: None,
+            lines: None,
+        };
+        let Some((fdef_idx, offset)) = vm_error.offsets().first() else {
+            return Some(location);
+        };
+        location.code_offset = Some(*offset);
+        let Some(named_module) = test_plan.module_info.get(module_id) else {
+            return Some(location);
+        };
+
+        let module = &named_module.module;
+        location.function = module.function_defs().get(fdef_idx.0 as usize).map(|def| {
+            let fn_handle = module.function_handle_at(def.function);
+            module.identifier_at(fn_handle.name).to_string()
+        });
+        let loc = named_module
+            .source_map
+            .get_function_source_map(*fdef_idx)
+            .ok()
+            .and_then(|function_source_map| function_source_map.get_code_location(*offset));
+        if let Some(loc) = loc {
+            location.file = test_plan
+                .files
+                .get(&loc.file_hash())
+                .map(|(fname, _)| fname.to_string());
+            location.lines = Self::get_line_number_internal(
+                &loc,
+                &source_files.files,
+                &source_files.file_mapping,
+            )
+            .ok();
+        }
+        Some(location)
+    }
+
     fn get_line_number(
         loc: &Loc,
         files: &SimpleFiles<Symbol, &str>,
@@ -236,12 +447,10 @@ impl TestFailure {
         let mut buf = String::new();
         if !stack_trace.is_empty() {
             buf.push_str("stack trace\n");
-            let mut files = SimpleFiles::new();
-            let mut file_mapping = HashMap::new();
-            for (fhash, (fname, source)) in &test_plan.files {
-                let id = files.add(*fname, source.as_str());
-                file_mapping.insert(*fhash, id);
-            }
+            let SourceFiles {
+                files,
+                file_mapping,
+            } = SourceFiles::new(test_plan);
 
             for frame in stack_trace {
                 let module_id = &frame.0;
@@ -282,8 +491,9 @@ impl TestFailure {
         test_plan: &TestPlan,
         base_message: String,
         vm_error: &Option<VMError>,
+        colorize: bool,
     ) -> String {
-        let report_diagnostics = if control::SHOULD_COLORIZE.should_colorize() {
+        let report_diagnostics = if colorize {
             diagnostics::report_diagnostics_to_color_buffer
         } else {
             diagnostics::report_diagnostics_to_buffer
@@ -531,6 +741,62 @@ impl TestResults {
         writeln!(writer.lock().unwrap())
     }
 
+    /// The results, in the form they are written to machine-readable reports.
+    pub fn test_report(&self) -> TestReport {
+        let source_files = SourceFiles::new(&self.test_plan);
+        let mut tests = vec![];
+        let mut passed = 0;
+        let mut failed = 0;
+        for (module_id, test_results) in &self.final_statistics.passed {
+            for test_result in test_results {
+                passed += 1;
+                tests.push(TestCaseReport {
+                    module: format_module_id(module_id),
+                    function: test_result.function_ident.clone(),
+                    status: TestStatus::Passed,
+                    duration_secs: test_result.elapsed_time.as_secs_f64(),
+                    gas_used: test_result.instructions_executed,
+                    failure: None,
+                });
+            }
+        }
+        for (module_id, test_failures) in &self.final_statistics.failed {
+            for test_failure in test_failures {
+                failed += 1;
+                let test_run_info = &test_failure.test_run_info;
+                tests.push(TestCaseReport {
+                    module: format_module_id(module_id),
+                    function: test_run_info.function_ident.clone(),
+                    status: TestStatus::Failed,
+                    duration_secs: test_run_info.elapsed_time.as_secs_f64(),
+                    gas_used: test_run_info.instructions_executed,
+                    failure: Some(test_failure.report(&self.test_plan, &source_files)),
+                });
+            }
+        }
+        tests.sort_by(|a, b| (&a.module, &a.function).cmp(&(&b.module, &b.function)));
+        TestReport {
+            passed,
+            failed,
+            tests,
+        }
+    }
+
+    /// Write a machine-readable report of the results to the file named in `format`.
+    pub fn write_report(&self, format: &ReportFormat) -> Result<()> {
+        let (ReportFormat::Junit(path) | ReportFormat::Json(path)) = format;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        let report = self.test_report();
+        match format {
+            ReportFormat::Junit(_) => report.write_junit(&mut writer)?,
+            ReportFormat::Json(_) => serde_json::to_writer_pretty(&mut writer, &report)?,
+        }
+        writer.flush()
+    }
+
     /// Returns `true` if all tests passed, `false` if there was a test failure/timeout
     pub fn summarize<W: Write>(self, writer: &Mutex<W>) -> Result<bool> {
         let num_failed_tests = self
@@ -584,3 +850,135 @@ impl TestResults {
         Ok(num_failed_tests == 0)
     }
 }
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let Some((format, path)) = s.split_once('=') else {
+            return Err(format!(
+                "Expected a report of the form <format>=<path>, got '{s}'"
+            ));
+        };
+        if path.is_empty() {
+            return Err(format!("Missing path for '{format}' report"));
+        }
+        match format {
+            "junit" => Ok(ReportFormat::Junit(path.into())),
+            "json" => Ok(ReportFormat::Json(path.into())),
+            _ => Err(format!(
+                "Unknown report format '{format}', expected 'junit' or 'json'"
+            )),
+        }
+    }
+}
+
+impl TestReport {
+    /// Write the report as JUnit XML, with a test suite per module.
+    pub fn write_junit<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut suites: BTreeMap<&str, Vec<&TestCaseReport>> = BTreeMap::new();
+        for test in &self.tests {
+            suites.entry(&test.module).or_default().push(test);
+        }
+
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<testsuites name="move-unit-tests" tests="{}" failures="{}" time="{:.6}">"#,
+            self.passed + self.failed,
+            self.failed,
+            self.tests
+                .iter()
+                .map(|test| test.duration_secs)
+                .sum::<f64>(),
+        )?;
+        for (module, tests) in suites {
+            let failures = tests
+                .iter()
+                .filter(|test| test.status == TestStatus::Failed)
+                .count();
+            writeln!(
+                writer,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" time="{:.6}">"#,
+                xml_escape(module),
+                tests.len(),
+                failures,
+                tests.iter().map(|test| test.duration_secs).sum::<f64>(),
+            )?;
+            for test in tests {
+                writeln!(
+                    writer,
+                    r#"    <testcase name="{}" classname="{}" time="{:.6}">"#,
+                    xml_escape(&test.function),
+                    xml_escape(module),
+                    test.duration_secs,
+                )?;
+                writeln!(writer, "      <properties>")?;
+                writeln!(
+                    writer,
+                    r#"        <property name="gas_used" value="{}"/>"#,
+                    test.gas_used
+                )?;
+                writeln!(writer, "      </properties>")?;
+                if let Some(failure) = &test.failure {
+                    let summary = failure.message.lines().next().unwrap_or_default();
+                    let mut body = failure.message.clone();
+                    if let Some(code) = failure.abort_code {
+                        body.push_str(&format!("\nabort code: {code}"));
+                    }
+                    if let Some(location) = &failure.abort_location {
+                        body.push_str(&format!("\nabort location: {location}"));
+                    }
+                    writeln!(
+                        writer,
+                        r#"      <failure type="{}" message="{}">{}</failure>"#,
+                        failure.kind,
+                        xml_escape(summary),
+                        xml_escape(&body),
+                    )?;
+                }
+                writeln!(writer, "    </testcase>")?;
+            }
+            writeln!(writer, "  </testsuite>")?;
+        }
+        writeln!(writer, "</testsuites>")
+    }
+}
+
+impl std::fmt::Display for AbortLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.module)?;
+        if let Some(function) = &self.function {
+            write!(f, "::{function}")?;
+        }
+        if let Some(offset) = self.code_offset {
+            write!(f, " (code offset {offset})")?;
+        }
+        if let Some(file) = &self.file {
+            write!(f, " at {file}")?;
+            if let Some(lines) = &self.lines {
+                write!(f, ":{lines}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escape `s` for use in XML text and attribute values, dropping the control characters that
+/// XML 1.0 doesn't allow.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\r' | '\t' => escaped.push(c),
+            c if c.is_control() => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    extensions, format_module_id,
+    extensions, format_module_id, random_test,
     test_reporter::{
-        FailureReason, MoveError, TestFailure, TestResults, TestRunInfo, TestStatistics,
+        FailureReason, MoveError, RandomTestInput, TestFailure, TestResults, TestRunInfo,
+        TestStatistics,
     },
 };
 use anyhow::Result;
@@ -15,16 +16,22 @@ use codespan_reporting::{
 };
 use colored::*;
 
-use move_binary_format::{errors::VMResult, file_format::CompiledModule};
+use move_binary_format::{
+    errors::{VMError, VMResult},
+    file_format::CompiledModule,
+};
 use move_bytecode_utils::Modules;
 use move_compiler::{
     diagnostics::WarningFilters,
     shared::{Flags, NumericalAddress, PackagePaths},
-    unit_test::{ExpectedFailure, ModuleTestPlan, TestCase, TestPlan},
+    unit_test::{ExpectedFailure, ModuleTestPlan, TestArgument, TestCase, TestPlan},
 };
 use move_core_types::{
-    account_address::AccountAddress, effects::ChangeSet, identifier::IdentStr,
-    runtime_value::serialize_values, vm_status::StatusCode,
+    account_address::AccountAddress,
+    effects::ChangeSet,
+    identifier::IdentStr,
+    runtime_value::{serialize_values, MoveValue},
+    vm_status::StatusCode,
 };
 use move_model::{
     model::GlobalEnv, options::ModelBuilderOptions,
@@ -36,6 +43,8 @@ use move_stackless_bytecode_interpreter::{
     StacklessBytecodeInterpreter,
 };
 #[cfg(debug_assertions)]
+use move_vm_config::runtime::VMProfilerConfig;
+#[cfg(debug_assertions)]
 use move_vm_profiler::GasProfiler;
 use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
 use move_vm_test_utils::{
@@ -44,11 +53,22 @@ use move_vm_test_utils::{
 };
 #[cfg(debug_assertions)]
 use move_vm_types::gas::GasMeter;
+use rand::{rngs::StdRng, SeedableRng};
 use rayon::prelude::*;
-use std::{collections::BTreeMap, io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    marker::Send,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use move_vm_runtime::native_extensions::NativeContextExtensions;
 
+/// The maximum number of times a failing `#[random_test]` is rerun while shrinking its input.
+const MAX_SHRINK_RUNS: usize = 1000;
+
 /// Test state common to all tests
 pub struct SharedTestingConfig {
     report_stacktrace_on_abort: bool,
@@ -60,6 +80,10 @@ pub struct SharedTestingConfig {
     named_address_values: BTreeMap<String, NumericalAddress>,
     check_stackless_vm: bool,
     verbose: bool,
+    seed: u64,
+    rand_num_iters: u64,
+    #[cfg_attr(not(debug_assertions), allow(dead_code))]
+    profile_gas: Option<PathBuf>,
 }
 
 pub struct TestRunner {
@@ -100,6 +124,9 @@ impl TestRunner {
         native_function_table: Option<NativeFunctionTable>,
         cost_table: Option<CostTable>,
         named_address_values: BTreeMap<String, NumericalAddress>,
+        seed: u64,
+        rand_num_iters: u64,
+        profile_gas: Option<PathBuf>,
     ) -> Result<Self> {
         let source_files = tests
             .files
@@ -130,6 +157,9 @@ impl TestRunner {
                 check_stackless_vm,
                 verbose,
                 named_address_values,
+                seed,
+                rand_num_iters,
+                profile_gas,
             },
             num_threads,
             tests,
@@ -172,6 +202,39 @@ impl TestRunner {
     }
 }
 
+/// The outcome of running a test once.
+enum TestOutcome {
+    Pass(TestRunInfo),
+    Fail(Box<TestFailure>),
+}
+
+impl TestOutcome {
+    fn failure(
+        failure_reason: FailureReason,
+        test_run_info: TestRunInfo,
+        vm_error: Option<VMError>,
+    ) -> Self {
+        TestOutcome::Fail(Box::new(TestFailure::new(
+            failure_reason,
+            test_run_info,
+            vm_error,
+        )))
+    }
+}
+
+/// Whether two failures of a test are for the same reason, so that an input causing the second is
+/// a valid simplification of the input causing the first.
+fn is_same_failure(original: &TestFailure, shrunk: &TestFailure) -> bool {
+    let status = |failure: &TestFailure| {
+        failure
+            .vm_error
+            .as_ref()
+            .map(|err| (err.major_status(), err.sub_status()))
+    };
+    original.failure_reason.kind() == shrunk.failure_reason.kind()
+        && status(original) == status(shrunk)
+}
+
 // TODO: do not expose this to backend implementations
 struct TestOutput<'a, 'b, W> {
     test_plan: &'a ModuleTestPlan,
@@ -218,7 +281,7 @@ impl SharedTestingConfig {
         &self,
         test_plan: &ModuleTestPlan,
         function_name: &str,
-        test_info: &TestCase,
+        arguments: &[MoveValue],
     ) -> (
         VMResult<ChangeSet>,
         VMResult<NativeContextExtensions>,
@@ -231,8 +294,16 @@ impl SharedTestingConfig {
             move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
         let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
         #[cfg(debug_assertions)]
-        gas_meter.set_profiler(GasProfiler::init_default_cfg(
-            function_name.to_owned(),
+        gas_meter.set_profiler(GasProfiler::init(
+            &VMProfilerConfig {
+                enabled: self.profile_gas.is_some(),
+                base_path: self
+                    .profile_gas
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from(".")),
+                ..VMProfilerConfig::default()
+            },
+            format!("{}_{}", test_plan.module_id.name(), function_name),
             self.execution_bound,
         ));
 
@@ -243,7 +314,7 @@ impl SharedTestingConfig {
             &test_plan.module_id,
             IdentStr::new(function_name).unwrap(),
             vec![], // no ty args, at least for now
-            serialize_values(test_info.arguments.iter()),
+            serialize_values(arguments),
             &mut gas_meter,
         );
         let mut return_result = serialized_return_values_result.map(|res| {
@@ -278,7 +349,7 @@ impl SharedTestingConfig {
         env: &GlobalEnv,
         test_plan: &ModuleTestPlan,
         function_name: &str,
-        test_info: &TestCase,
+        arguments: &[MoveValue],
     ) -> (VMResult<Vec<Vec<u8>>>, TestRunInfo, Option<String>) {
         let now = Instant::now();
 
@@ -297,7 +368,7 @@ impl SharedTestingConfig {
             &test_plan.module_id,
             IdentStr::new(function_name).unwrap(),
             &[], // no ty args, at least for now
-            &test_info.arguments,
+            arguments,
             &global_state,
         );
         let prop_check_result = interpreter.report_property_checking_results();
@@ -355,136 +426,231 @@ impl SharedTestingConfig {
         let mut stats = TestStatistics::new();
 
         for (function_name, test_info) in &test_plan.tests {
-            let (_cs_result, _ext_result, exec_result, test_run_info) =
-                self.execute_via_move_vm(test_plan, function_name, test_info);
-
-            if self.check_stackless_vm {
-                let (stackless_vm_result, _, prop_check_result) = self.execute_via_stackless_vm(
-                    stackless_model.as_ref().unwrap(),
+            let outcome = if test_info.is_random_test() {
+                self.exec_random_test(
+                    stackless_model.as_ref(),
+                    test_plan,
+                    function_name,
+                    test_info,
+                )
+            } else {
+                let arguments = test_info
+                    .arguments
+                    .iter()
+                    .map(|arg| match arg {
+                        TestArgument::Value(value) => value.clone(),
+                        TestArgument::Generate { .. } => unreachable!(),
+                    })
+                    .collect::<Vec<_>>();
+                self.exec_test(
+                    stackless_model.as_ref(),
                     test_plan,
                     function_name,
                     test_info,
+                    &arguments,
+                )
+            };
+
+            match outcome {
+                TestOutcome::Pass(test_run_info) => {
+                    output.pass(function_name);
+                    stats.test_success(test_run_info, test_plan);
+                }
+                TestOutcome::Fail(failure) => {
+                    if matches!(failure.failure_reason, FailureReason::Timeout(_)) {
+                        output.timeout(function_name);
+                    } else {
+                        output.fail(function_name);
+                    }
+                    stats.test_failure(*failure, test_plan);
+                }
+            }
+        }
+
+        stats
+    }
+
+    /// Runs a single execution of the test `function_name` with `arguments`, and checks its result
+    /// against what the test expects.
+    fn exec_test(
+        &self,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        arguments: &[MoveValue],
+    ) -> TestOutcome {
+        let (_cs_result, _ext_result, exec_result, test_run_info) =
+            self.execute_via_move_vm(test_plan, function_name, arguments);
+
+        if let Some(stackless_model) = stackless_model {
+            let (stackless_vm_result, _, prop_check_result) =
+                self.execute_via_stackless_vm(stackless_model, test_plan, function_name, arguments);
+            let move_vm_result = adapt_move_vm_result(exec_result.clone());
+            if stackless_vm_result != move_vm_result {
+                return TestOutcome::failure(
+                    FailureReason::mismatch(move_vm_result, stackless_vm_result),
+                    test_run_info,
+                    None,
+                );
+            }
+            if let Some(prop_failure) = prop_check_result {
+                return TestOutcome::failure(
+                    FailureReason::property(prop_failure),
+                    test_run_info,
+                    None,
                 );
-                let move_vm_result = adapt_move_vm_result(exec_result.clone());
-                if stackless_vm_result != move_vm_result {
-                    output.fail(function_name);
-                    stats.test_failure(
-                        TestFailure::new(
-                            FailureReason::mismatch(move_vm_result, stackless_vm_result),
+            }
+        }
+
+        match exec_result {
+            Err(err) => {
+                let actual_err =
+                    MoveError(err.major_status(), err.sub_status(), err.location().clone());
+                assert!(err.major_status() != StatusCode::EXECUTED);
+                match test_info.expected_failure.as_ref() {
+                    Some(ExpectedFailure::Expected) => TestOutcome::Pass(test_run_info),
+                    Some(ExpectedFailure::ExpectedWithError(expected_err))
+                        if expected_err == &actual_err =>
+                    {
+                        TestOutcome::Pass(test_run_info)
+                    }
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
+                        if actual_err.0 == StatusCode::ABORTED
+                            && actual_err.1.is_some()
+                            && actual_err.1.unwrap() == *code =>
+                    {
+                        TestOutcome::Pass(test_run_info)
+                    }
+                    // incorrect cases
+                    Some(ExpectedFailure::ExpectedWithError(expected_err)) => TestOutcome::failure(
+                        FailureReason::wrong_error(expected_err.clone(), actual_err),
+                        test_run_info,
+                        Some(err),
+                    ),
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
+                        TestOutcome::failure(
+                            FailureReason::wrong_abort_deprecated(*expected_code, actual_err),
                             test_run_info,
-                            None,
-                        ),
-                        test_plan,
-                    );
-                    continue;
+                            Some(err),
+                        )
+                    }
+                    None if err.major_status() == StatusCode::OUT_OF_GAS => {
+                        // Ran out of ticks, report a test timeout and log a test failure
+                        TestOutcome::failure(FailureReason::timeout(), test_run_info, Some(err))
+                    }
+                    None => TestOutcome::failure(
+                        FailureReason::unexpected_error(actual_err),
+                        test_run_info,
+                        Some(err),
+                    ),
                 }
-                if let Some(prop_failure) = prop_check_result {
-                    output.fail(function_name);
-                    stats.test_failure(
-                        TestFailure::new(
-                            FailureReason::property(prop_failure),
-                            test_run_info,
-                            None,
-                        ),
+            }
+            Ok(_) => {
+                // Expected the test to fail, but it executed
+                if test_info.expected_failure.is_some() {
+                    TestOutcome::failure(FailureReason::no_error(), test_run_info, None)
+                } else {
+                    // Expected the test to execute fully and it did
+                    TestOutcome::Pass(test_run_info)
+                }
+            }
+        }
+    }
+
+    /// Runs a `#[random_test]` `rand_num_iters` times, with arguments generated from `seed`. The
+    /// first failing input is shrunk, and the test fails with the smallest input that still fails
+    /// the same way.
+    fn exec_random_test(
+        &self,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+    ) -> TestOutcome {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut total_run_info = TestRunInfo::new(function_name.to_string(), Duration::ZERO, 0);
+        for _ in 0..self.rand_num_iters {
+            let arguments = random_test::generate_arguments(&mut rng, &test_info.arguments);
+            match self.exec_test(
+                stackless_model,
+                test_plan,
+                function_name,
+                test_info,
+                &arguments,
+            ) {
+                TestOutcome::Pass(test_run_info) => {
+                    total_run_info.elapsed_time += test_run_info.elapsed_time;
+                    total_run_info.instructions_executed += test_run_info.instructions_executed;
+                }
+                TestOutcome::Fail(failure) => {
+                    let (arguments, failure) = self.shrink_random_test_failure(
+                        stackless_model,
                         test_plan,
+                        function_name,
+                        test_info,
+                        arguments,
+                        *failure,
                     );
-                    continue;
+                    let random_input = RandomTestInput {
+                        seed: self.seed,
+                        arguments: arguments.iter().map(|arg| arg.to_string()).collect(),
+                    };
+                    return TestOutcome::Fail(Box::new(failure.with_random_input(random_input)));
                 }
             }
+        }
+        TestOutcome::Pass(total_run_info)
+    }
 
-            match exec_result {
-                Err(err) => {
-                    let actual_err =
-                        MoveError(err.major_status(), err.sub_status(), err.location().clone());
-                    assert!(err.major_status() != StatusCode::EXECUTED);
-                    match test_info.expected_failure.as_ref() {
-                        Some(ExpectedFailure::Expected) => {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        Some(ExpectedFailure::ExpectedWithError(expected_err))
-                            if expected_err == &actual_err =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
-                            if actual_err.0 == StatusCode::ABORTED
-                                && actual_err.1.is_some()
-                                && actual_err.1.unwrap() == *code =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        // incorrect cases
-                        Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_error(expected_err.clone(), actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_abort_deprecated(
-                                        *expected_code,
-                                        actual_err,
-                                    ),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        None if err.major_status() == StatusCode::OUT_OF_GAS => {
-                            // Ran out of ticks, report a test timeout and log a test failure
-                            output.timeout(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::timeout(),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        None => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::unexpected_error(actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
+    /// Greedily simplifies the generated arguments of a failing `#[random_test]` for as long as
+    /// the test keeps failing in the same way, returning the simplest failing arguments found.
+    fn shrink_random_test_failure(
+        &self,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        mut arguments: Vec<MoveValue>,
+        mut failure: TestFailure,
+    ) -> (Vec<MoveValue>, TestFailure) {
+        let generated: Vec<_> = test_info
+            .arguments
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| matches!(arg, TestArgument::Generate { .. }))
+            .map(|(idx, _)| idx)
+            .collect();
+
+        let mut runs = 0;
+        'shrink: loop {
+            for idx in &generated {
+                for candidate in random_test::shrink_value(&arguments[*idx]) {
+                    if runs == MAX_SHRINK_RUNS {
+                        break 'shrink;
                     }
-                }
-                Ok(_) => {
-                    // Expected the test to fail, but it executed
-                    if test_info.expected_failure.is_some() {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(FailureReason::no_error(), test_run_info, None),
-                            test_plan,
-                        )
-                    } else {
-                        // Expected the test to execute fully and it did
-                        output.pass(function_name);
-                        stats.test_success(test_run_info, test_plan);
+                    runs += 1;
+
+                    let mut shrunk = arguments.clone();
+                    shrunk[*idx] = candidate;
+                    if let TestOutcome::Fail(shrunk_failure) = self.exec_test(
+                        stackless_model,
+                        test_plan,
+                        function_name,
+                        test_info,
+                        &shrunk,
+                    ) {
+                        if is_same_failure(&failure, &shrunk_failure) {
+                            arguments = shrunk;
+                            failure = *shrunk_failure;
+                            continue 'shrink;
+                        }
                     }
                 }
             }
+            break;
         }
-
-        stats
+        (arguments, failure)
     }
 
     // TODO: comparison of results via different backends
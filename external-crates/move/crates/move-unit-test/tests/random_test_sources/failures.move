@@ -0,0 +1,21 @@
+address 0x1 {
+module M {
+    use std::signer;
+    use std::vector;
+
+    #[random_test]
+    fun less_than_100(x: u64) {
+        assert!(x < 100, 0);
+    }
+
+    #[random_test]
+    fun short_vector(v: vector<u8>) {
+        assert!(vector::length(&v) < 3, 1);
+    }
+
+    #[random_test(s = @0x2)]
+    fun assigned_not_shrunk(s: signer, x: u8) {
+        assert!(signer::address_of(&s) == @0x2 && x < 10, 2);
+    }
+}
+}
@@ -0,0 +1,59 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(debug_assertions)]
+
+use move_unit_test::{self, UnitTestingConfig};
+use std::{fs, path::PathBuf};
+
+#[test]
+fn test_profile_gas() {
+    let dir = tempfile::tempdir().unwrap();
+    // Missing directories are created.
+    let profile_dir = dir.path().join("profiles");
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/test_sources/construct_data.move");
+    let config = UnitTestingConfig {
+        num_threads: 1,
+        source_files: vec![path.to_str().unwrap().to_owned()],
+        dep_files: move_stdlib::move_stdlib_files(),
+        named_address_values: move_stdlib::move_stdlib_named_addresses()
+            .into_iter()
+            .collect(),
+        filter: Some("make_sure_number_matches".to_string()),
+        profile_gas: Some(profile_dir.clone()),
+        ..UnitTestingConfig::default_with_bound(None)
+    };
+    let test_plan = config.build_test_plan().unwrap();
+    let (_, ok) = config
+        .run_and_report_unit_tests(test_plan, None, None, vec![])
+        .unwrap();
+    assert!(ok);
+
+    let profiles: Vec<_> = fs::read_dir(&profile_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(profiles.len(), 1);
+    let file_name = profiles[0].file_name().unwrap().to_str().unwrap();
+    assert!(file_name.starts_with("gas_profile_M_make_sure_number_matches_"));
+
+    let profile: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&profiles[0]).unwrap()).unwrap();
+    assert_eq!(
+        profile["$schema"],
+        "https://www.speedscope.app/file-format-schema.json"
+    );
+    // Gas is attributed to each function called by the test.
+    let frames: Vec<_> = profile["shared"]["frames"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|frame| frame["name"].as_str().unwrap())
+        .collect();
+    assert!(frames.contains(&"construct_with_number"));
+    assert!(frames.contains(&"get_struct_x_field"));
+    let events = profile["profiles"][0]["events"].as_array().unwrap();
+    assert!(!events.is_empty());
+    assert!(profile["profiles"][0]["endValue"].as_u64().unwrap() > 0);
+}
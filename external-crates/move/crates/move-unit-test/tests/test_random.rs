@@ -0,0 +1,76 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_unit_test::{self, test_reporter::ReportFormat, UnitTestingConfig};
+use std::{fs, path::PathBuf};
+
+const SEED: u64 = 42;
+
+fn run_random_tests(rand_num_iters: u64, report: PathBuf) -> (String, bool) {
+    std::env::set_var("NO_COLOR", "1");
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/random_test_sources/failures.move");
+    let config = UnitTestingConfig {
+        num_threads: 1,
+        source_files: vec![path.to_str().unwrap().to_owned()],
+        dep_files: move_stdlib::move_stdlib_files(),
+        named_address_values: move_stdlib::move_stdlib_named_addresses()
+            .into_iter()
+            .collect(),
+        reports: vec![ReportFormat::Json(report)],
+        seed: Some(SEED),
+        rand_num_iters: Some(rand_num_iters),
+        ..UnitTestingConfig::default_with_bound(None)
+    };
+    let test_plan = config.build_test_plan().unwrap();
+    let (output, ok) = config
+        .run_and_report_unit_tests(test_plan, None, None, vec![])
+        .unwrap();
+    (String::from_utf8(output).unwrap(), ok)
+}
+
+fn random_input(report: &serde_json::Value, function: &str) -> serde_json::Value {
+    report["tests"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|test| test["function"] == function)
+        .unwrap()["failure"]["random_input"]
+        .clone()
+}
+
+#[test]
+fn test_failing_inputs_are_shrunk() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.json");
+    let (output, ok) = run_random_tests(100, path.clone());
+    assert!(!ok);
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(report["failed"], 3);
+
+    let input = random_input(&report, "less_than_100");
+    assert_eq!(input["seed"], SEED);
+    assert_eq!(input["arguments"], serde_json::json!(["100u64"]));
+
+    let input = random_input(&report, "short_vector");
+    assert_eq!(
+        input["arguments"],
+        serde_json::json!(["vector[0u8, 0u8, 0u8]"])
+    );
+
+    // Values assigned in the attribute are kept as they are.
+    let input = random_input(&report, "assigned_not_shrunk");
+    assert_eq!(input["arguments"], serde_json::json!(["0x2", "10u8"]));
+
+    assert!(output.contains("less_than_100(100u64)"));
+    assert!(output.contains(&format!("`--seed {SEED}`")));
+}
+
+#[test]
+fn test_no_iterations_pass() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_, ok) = run_random_tests(0, dir.path().join("report.json"));
+    assert!(ok);
+}
@@ -0,0 +1,102 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_unit_test::{self, test_reporter::ReportFormat, UnitTestingConfig};
+use std::{fs, path::PathBuf, str::FromStr};
+
+fn run_with_reports(source: &str, reports: Vec<ReportFormat>) -> bool {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(source);
+    let config = UnitTestingConfig {
+        num_threads: 1,
+        source_files: vec![path.to_str().unwrap().to_owned()],
+        dep_files: move_stdlib::move_stdlib_files(),
+        named_address_values: move_stdlib::move_stdlib_named_addresses()
+            .into_iter()
+            .collect(),
+        reports,
+        ..UnitTestingConfig::default_with_bound(None)
+    };
+    let test_plan = config.build_test_plan().unwrap();
+    let (_, ok) = config
+        .run_and_report_unit_tests(test_plan, None, None, vec![])
+        .unwrap();
+    ok
+}
+
+#[test]
+fn test_json_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("report.json");
+    let ok = run_with_reports(
+        "tests/test_sources/unexpected_abort.move",
+        vec![ReportFormat::Json(path.clone())],
+    );
+    assert!(!ok);
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    assert_eq!(report["passed"], 2);
+    assert_eq!(report["failed"], 4);
+
+    let tests = report["tests"].as_array().unwrap();
+    assert_eq!(tests.len(), 6);
+    let passed = tests
+        .iter()
+        .find(|test| test["function"] == "correct_abort_code")
+        .unwrap();
+    assert_eq!(passed["module"], "0x1::M");
+    assert_eq!(passed["status"], "passed");
+    assert!(passed["gas_used"].as_u64().is_some());
+    assert!(passed.get("failure").is_none());
+
+    let failed = tests
+        .iter()
+        .find(|test| test["function"] == "unexpected_abort")
+        .unwrap();
+    assert_eq!(failed["status"], "failed");
+    let failure = &failed["failure"];
+    assert_eq!(failure["kind"], "unexpected_error");
+    assert_eq!(failure["abort_code"], 0);
+    assert_eq!(failure["abort_location"]["module"], "0x1::M");
+    assert_eq!(failure["abort_location"]["function"], "unexpected_abort");
+    assert_eq!(failure["abort_location"]["lines"], "5");
+    assert!(failure["message"]
+        .as_str()
+        .unwrap()
+        .contains("Test was not expected to error"));
+}
+
+#[test]
+fn test_junit_report() {
+    let dir = tempfile::tempdir().unwrap();
+    // Missing parent directories are created.
+    let path = dir.path().join("reports").join("junit.xml");
+    let ok = run_with_reports(
+        "tests/test_sources/unexpected_abort.move",
+        vec![ReportFormat::Junit(path.clone())],
+    );
+    assert!(!ok);
+
+    let report = fs::read_to_string(path).unwrap();
+    assert!(report.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+    assert!(report.contains(r#"<testsuite name="0x1::M" tests="6" failures="4""#));
+    assert!(report.contains(r#"<testcase name="unexpected_abort" classname="0x1::M""#));
+    assert!(report.contains(r#"<failure type="unexpected_error""#));
+    assert!(report.contains(r#"<property name="gas_used""#));
+    assert_eq!(report.matches("<failure ").count(), 4);
+}
+
+#[test]
+fn test_report_format_parsing() {
+    assert_eq!(
+        ReportFormat::from_str("junit=out/report.xml").unwrap(),
+        ReportFormat::Junit("out/report.xml".into())
+    );
+    assert_eq!(
+        ReportFormat::from_str("json=report.json").unwrap(),
+        ReportFormat::Json("report.json".into())
+    );
+    assert!(ReportFormat::from_str("junit").is_err());
+    assert!(ReportFormat::from_str("json=").is_err());
+    assert!(ReportFormat::from_str("xml=report.xml").is_err());
+}
@@ -0,0 +1,43 @@
+address 0x1 {
+module M {
+    use std::signer;
+    use std::vector;
+
+    #[random_test]
+    fun addition_commutes(a: u64, b: u64) {
+        let (a, b) = ((a as u128), (b as u128));
+        assert!(a + b == b + a, 0);
+    }
+
+    #[random_test]
+    fun all_generated_types(
+        _b: bool,
+        _x8: u8,
+        _x16: u16,
+        _x32: u32,
+        _x64: u64,
+        _x128: u128,
+        _x256: u256,
+        _a: address,
+        _v: vector<u8>,
+        _vv: vector<vector<address>>,
+    ) { }
+
+    #[random_test(s = @0x1)]
+    fun assigned_and_generated(s: signer, v: vector<u64>) {
+        assert!(signer::address_of(&s) == @0x1, 0);
+        let w = copy v;
+        vector::push_back(&mut w, 0);
+        assert!(vector::length(&w) == vector::length(&v) + 1, 1);
+    }
+
+    #[random_test]
+    #[expected_failure(abort_code = 7, location = Self)]
+    fun always_aborts(_x: u256) {
+        abort 7
+    }
+
+    #[random_test]
+    fun no_generated_arguments() { }
+}
+}
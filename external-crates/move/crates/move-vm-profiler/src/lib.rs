@@ -125,6 +125,12 @@ impl GasProfiler {
         s.split("::").last().unwrap_or(s).to_string()
     }
 
+    /// Whether events are recorded, either because the config asks for it or because the
+    /// `MOVE_VM_PROFILE` environment variable is set.
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled || *PROFILER_ENABLED
+    }
+
     fn is_metered(&self) -> bool {
         (self.profiles[0].end_value != 0) && (self.start_gas != 0)
     }
@@ -154,7 +160,7 @@ impl GasProfiler {
     }
 
     pub fn open_frame(&mut self, frame_name: String, metadata: String, gas_start: u64) {
-        if !*PROFILER_ENABLED || self.start_gas == 0 {
+        if !self.is_enabled() || self.start_gas == 0 {
             return;
         }
 
@@ -169,7 +175,7 @@ impl GasProfiler {
     }
 
     pub fn close_frame(&mut self, frame_name: String, metadata: String, gas_end: u64) {
-        if !*PROFILER_ENABLED || self.start_gas == 0 {
+        if !self.is_enabled() || self.start_gas == 0 {
             return;
         }
         let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata);
@@ -184,7 +190,7 @@ impl GasProfiler {
     }
 
     pub fn to_file(&self) {
-        if !*PROFILER_ENABLED || !self.is_metered() {
+        if !self.is_enabled() || !self.is_metered() {
             return;
         }
         // Get the unix timestamp
@@ -193,6 +199,7 @@ impl GasProfiler {
             .expect("Clock may have gone backwards")
             .as_nanos();
 
+        std::fs::create_dir_all(&self.config.base_path).expect("Unable to create directory");
         let mut p = self.config.base_path.clone();
         p.push(format!("gas_profile_{}_{}.json", self.profile_name(), now));
         let path_str = p.as_os_str().to_string_lossy().to_string();
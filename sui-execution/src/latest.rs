@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
 use move_binary_format::CompiledModule;
 use move_vm_config::verifier::VerifierConfig;
@@ -45,10 +45,15 @@ pub(crate) struct Verifier<'m> {
 }
 
 impl Executor {
-    pub(crate) fn new(protocol_config: &ProtocolConfig, silent: bool) -> Result<Self, SuiError> {
+    pub(crate) fn new(
+        protocol_config: &ProtocolConfig,
+        silent: bool,
+        enable_profiler: Option<PathBuf>,
+    ) -> Result<Self, SuiError> {
         Ok(Executor(Arc::new(new_move_vm(
             all_natives(silent),
             protocol_config,
+            enable_profiler,
         )?)))
     }
 }
@@ -3,7 +3,7 @@
 
 // DO NOT MODIFY, Generated by ./scripts/execution-layer
 
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use sui_protocol_config::ProtocolConfig;
 use sui_types::{error::SuiResult, metrics::BytecodeVerifierMetrics};
@@ -26,16 +26,25 @@ pub const NEXT_VM: u64 = u64::MAX;
 pub fn executor(
     protocol_config: &ProtocolConfig,
     silent: bool,
+    enable_profiler: Option<PathBuf>,
 ) -> SuiResult<Arc<dyn Executor + Send + Sync>> {
     let version = protocol_config.execution_version_as_option().unwrap_or(0);
     Ok(match version {
-        0 => Arc::new(v0::Executor::new(protocol_config, silent)?),
+        0 => Arc::new(v0::Executor::new(protocol_config, silent, enable_profiler)?),
 
-        1 => Arc::new(v1::Executor::new(protocol_config, silent)?),
+        1 => Arc::new(v1::Executor::new(protocol_config, silent, enable_profiler)?),
 
-        2 => Arc::new(latest::Executor::new(protocol_config, silent)?),
+        2 => Arc::new(latest::Executor::new(
+            protocol_config,
+            silent,
+            enable_profiler,
+        )?),
 
-        NEXT_VM => Arc::new(next_vm::Executor::new(protocol_config, silent)?),
+        NEXT_VM => Arc::new(next_vm::Executor::new(
+            protocol_config,
+            silent,
+            enable_profiler,
+        )?),
 
         v => panic!("Unsupported execution version {v}"),
     })
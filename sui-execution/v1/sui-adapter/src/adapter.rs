@@ -5,13 +5,15 @@ pub use checked::*;
 
 #[sui_macros::with_checked_arithmetic]
 mod checked {
-    use std::{collections::BTreeMap, sync::Arc};
+    use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
     use anyhow::Result;
     use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
     use move_bytecode_verifier::meter::Meter;
     use move_bytecode_verifier::verify_module_with_config_metered;
     use move_core_types::account_address::AccountAddress;
+    #[cfg(debug_assertions)]
+    use move_vm_config::runtime::VMProfilerConfig;
     use move_vm_config::{
         runtime::{VMConfig, VMRuntimeLimitsConfig},
         verifier::VerifierConfig,
@@ -84,7 +86,10 @@ mod checked {
     pub fn new_move_vm(
         natives: NativeFunctionTable,
         protocol_config: &ProtocolConfig,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<MoveVM, SuiError> {
+        #[cfg(not(debug_assertions))]
+        let _ = enable_profiler;
         MoveVM::new_with_config(
             natives,
             VMConfig {
@@ -103,7 +108,11 @@ mod checked {
                 check_no_extraneous_bytes_during_deserialization: protocol_config
                     .no_extraneous_module_bytes(),
                 #[cfg(debug_assertions)]
-                profiler_config: std::default::Default::default(),
+                profiler_config: VMProfilerConfig {
+                    enabled: enable_profiler.is_some(),
+                    base_path: enable_profiler.unwrap_or_else(|| PathBuf::from(".")),
+                    ..Default::default()
+                },
                 // Don't augment errors with execution state on-chain
                 error_execution_state: false,
             },